@@ -0,0 +1,38 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct CrtSettings {
+    scanline_intensity: f32,
+    scanline_count: f32,
+    curvature: f32,
+    aberration: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+@group(0) @binding(2) var<uniform> settings: CrtSettings;
+
+fn curve(uv: vec2<f32>) -> vec2<f32> {
+    let centered = uv * 2.0 - 1.0;
+    let bent = centered + centered * (centered.yx * centered.yx) * settings.curvature;
+    return bent * 0.5 + 0.5;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let uv = curve(in.uv);
+    let shift = (uv - 0.5) * settings.aberration;
+
+    let red = textureSample(screen_texture, texture_sampler, uv + shift).r;
+    let green = textureSample(screen_texture, texture_sampler, uv).g;
+    let blue = textureSample(screen_texture, texture_sampler, uv - shift).b;
+    var color = vec3<f32>(red, green, blue);
+
+    let scan = 0.5 + 0.5 * sin(uv.y * settings.scanline_count * 6.2831853);
+    color *= 1.0 - settings.scanline_intensity * (1.0 - scan);
+
+    let edge = uv * (vec2<f32>(1.0) - uv.yx);
+    let vignette = clamp(pow(max(edge.x * edge.y * 15.0, 0.0), 0.25), 0.0, 1.0);
+    let inside = step(0.0, uv.x) * step(uv.x, 1.0) * step(0.0, uv.y) * step(uv.y, 1.0);
+
+    return vec4<f32>(color * vignette * inside, 1.0);
+}
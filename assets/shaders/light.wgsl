@@ -0,0 +1,11 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(2) @binding(0) var<uniform> color: vec4<f32>;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let distance_from_center = distance(in.uv, vec2<f32>(0.5, 0.5)) * 2.0;
+    let falloff = clamp(1.0 - distance_from_center, 0.0, 1.0);
+    let intensity = falloff * falloff * color.a;
+    return vec4<f32>(color.rgb * intensity, intensity);
+}
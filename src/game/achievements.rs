@@ -0,0 +1,302 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    boss::BossEvent,
+    collisions::EnemyDestroyedEvent,
+    highscores::today,
+    player::{BombEvent, PlayerHitEvent, PlayerWeaponState, WeaponMode},
+    powerups::PowerUpKind,
+    practice::PracticeRun,
+    profiles::CurrentProfile,
+    spawn::WaveStartedEvent,
+    states::{AppState, InRun},
+    storage::{load_json, save_json},
+    typography::{TextSize, UiFont},
+};
+
+pub const ACHIEVEMENTS_FILE: &str = "achievements.json";
+const LASER_KILL_TARGET: u32 = 100;
+const TOAST_WIDTH: f32 = 380.0;
+const TOAST_MARGIN: f32 = 16.0;
+const TOAST_SLIDE_SECONDS: f32 = 0.35;
+const TOAST_HOLD_SECONDS: f32 = 3.0;
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(Achievements::load(&profile))
+            .init_resource::<AchievementTracker>()
+            .init_resource::<ToastQueue>()
+            .add_event::<AchievementUnlockedEvent>()
+            .add_systems(OnEnter(InRun), reset_achievement_tracker)
+            .add_systems(OnExit(InRun), save_achievement_progress)
+            .add_systems(
+                Update,
+                track_achievements.run_if(
+                    in_state(AppState::Playing)
+                        .and_then(not(resource_exists::<DemoPilot>))
+                        .and_then(not(resource_exists::<PracticeRun>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    queue_toasts.run_if(on_event::<AchievementUnlockedEvent>()),
+                    show_next_toast,
+                    slide_toasts,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    NoMissLevel,
+    LaserCenturion,
+    BossWithoutBombs,
+}
+
+impl Achievement {
+    pub fn label(self) -> &'static str {
+        match self {
+            Achievement::NoMissLevel => "Untouchable",
+            Achievement::LaserCenturion => "Laser Centurion",
+            Achievement::BossWithoutBombs => "Steady Hands",
+        }
+    }
+
+    // Stable name for platform achievement APIs, which key them by string.
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::NoMissLevel => "NO_MISS_LEVEL",
+            Achievement::LaserCenturion => "LASER_CENTURION",
+            Achievement::BossWithoutBombs => "BOSS_WITHOUT_BOMBS",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::NoMissLevel => "Clear a level without taking a hit",
+            Achievement::LaserCenturion => "Destroy 100 enemies with the laser",
+            Achievement::BossWithoutBombs => "Beat a boss without using a bomb",
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AchievementUnlockedEvent(pub Achievement);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    pub date: String,
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct Achievements {
+    pub unlocked: Vec<UnlockedAchievement>,
+    pub laser_kills: u32,
+}
+
+impl Achievements {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        load_json(&profile.data_path(ACHIEVEMENTS_FILE))
+    }
+
+    pub fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.data_path(ACHIEVEMENTS_FILE), self);
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked
+            .iter()
+            .any(|entry| entry.achievement == achievement)
+    }
+
+    fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(achievement) {
+            return false;
+        }
+        self.unlocked.push(UnlockedAchievement {
+            achievement,
+            date: today(),
+        });
+        true
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct AchievementTracker {
+    hit_this_level: bool,
+    bombed_this_boss: bool,
+}
+
+#[derive(Resource, Default)]
+struct ToastQueue(VecDeque<Achievement>);
+
+#[derive(Component)]
+struct AchievementToast {
+    age: f32,
+}
+
+fn reset_achievement_tracker(mut tracker: ResMut<AchievementTracker>) {
+    *tracker = AchievementTracker::default();
+}
+
+// Laser kills accumulate across runs, so flush the counter whenever a run ends.
+fn save_achievement_progress(achievements: Res<Achievements>, profile: Res<CurrentProfile>) {
+    if achievements.is_changed() {
+        achievements.save(&profile);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn track_achievements(
+    mut achievements: ResMut<Achievements>,
+    mut tracker: ResMut<AchievementTracker>,
+    weapon_state: Res<PlayerWeaponState>,
+    mut waves: EventReader<WaveStartedEvent>,
+    mut hits: EventReader<PlayerHitEvent>,
+    mut bombs: EventReader<BombEvent>,
+    mut kills: EventReader<EnemyDestroyedEvent>,
+    mut boss_events: EventReader<BossEvent>,
+    mut unlocked: EventWriter<AchievementUnlockedEvent>,
+    profile: Res<CurrentProfile>,
+) {
+    if waves.read().any(|wave| wave.wave == 1) {
+        tracker.hit_this_level = false;
+    }
+    if hits.read().count() > 0 {
+        tracker.hit_this_level = true;
+    }
+    if bombs.read().count() > 0 {
+        tracker.bombed_this_boss = true;
+    }
+
+    let mut earned = Vec::new();
+    let laser_kills = kills
+        .read()
+        .filter(|kill| !kill.by_bomb && weapon_state.mode == WeaponMode::Laser)
+        .count() as u32;
+    if laser_kills > 0 {
+        achievements.laser_kills += laser_kills;
+        if achievements.laser_kills >= LASER_KILL_TARGET {
+            earned.push(Achievement::LaserCenturion);
+        }
+    }
+
+    for event in boss_events.read() {
+        match event {
+            BossEvent::Spawned => tracker.bombed_this_boss = false,
+            BossEvent::Defeated => {
+                if !tracker.hit_this_level {
+                    earned.push(Achievement::NoMissLevel);
+                }
+                if !tracker.bombed_this_boss {
+                    earned.push(Achievement::BossWithoutBombs);
+                }
+            }
+            BossEvent::PhaseChanged(_) => {}
+        }
+    }
+
+    let mut dirty = false;
+    for achievement in earned {
+        if achievements.unlock(achievement) {
+            unlocked.send(AchievementUnlockedEvent(achievement));
+            dirty = true;
+        }
+    }
+    if dirty {
+        achievements.save(&profile);
+    }
+}
+
+fn queue_toasts(mut events: EventReader<AchievementUnlockedEvent>, mut queue: ResMut<ToastQueue>) {
+    queue.0.extend(events.read().map(|event| event.0));
+}
+
+fn show_next_toast(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    font: Res<UiFont>,
+    active: Query<(), With<AchievementToast>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if !active.is_empty() {
+        return;
+    }
+    let Some(achievement) = queue.0.pop_front() else {
+        return;
+    };
+
+    // Reuse the disruptor fanfare as the unlock jingle.
+    audio.send(AudioCue::new(Sfx::Pickup(PowerUpKind::Invincibility)));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(96.0),
+                    right: Val::Px(-TOAST_WIDTH),
+                    width: Val::Px(TOAST_WIDTH),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    border: UiRect::left(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.05, 0.08, 0.15, 0.85)),
+                border_color: BorderColor(Color::srgb(1.0, 0.85, 0.4)),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            AchievementToast { age: 0.0 },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "ACHIEVEMENT UNLOCKED",
+                font.style(TextSize::Caption, Color::srgb(1.0, 0.85, 0.4)),
+            ));
+            parent.spawn(TextBundle::from_section(
+                achievement.label(),
+                font.style(TextSize::Body, Color::WHITE),
+            ));
+            parent.spawn(TextBundle::from_section(
+                achievement.description(),
+                font.style(TextSize::Caption, Color::srgb(0.8, 0.85, 1.0)),
+            ));
+        });
+}
+
+fn slide_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut AchievementToast, &mut Style)>,
+) {
+    let visible = TOAST_SLIDE_SECONDS + TOAST_HOLD_SECONDS;
+    for (entity, mut toast, mut style) in &mut toasts {
+        toast.age += time.delta_seconds();
+        let shown = if toast.age < TOAST_SLIDE_SECONDS {
+            toast.age / TOAST_SLIDE_SECONDS
+        } else if toast.age < visible {
+            1.0
+        } else {
+            1.0 - (toast.age - visible) / TOAST_SLIDE_SECONDS
+        };
+        if shown <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let eased = 1.0 - (1.0 - shown).powi(3);
+        style.right = Val::Px(-TOAST_WIDTH + (TOAST_WIDTH + TOAST_MARGIN) * eased);
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::{input::touch::Touches, prelude::*, time::Fixed};
+
+use super::{
+    input_device::DevicePrompt,
+    player::{Player, PlayerLifeLostEvent},
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+    weapons::EnemyProjectile,
+};
+
+const IDLE_SECONDS: f32 = 20.0;
+const DEMO_SECONDS: f32 = 45.0;
+const DEMO_CRUISE_Y: f32 = -240.0;
+const DEMO_WEAVE_WIDTH: f32 = 380.0;
+const DEMO_DODGE_RADIUS: f32 = 150.0;
+
+pub struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TitleIdle>()
+            .add_systems(OnEnter(AppState::Title), reset_title_idle)
+            .add_systems(Update, watch_title_idle.run_if(in_state(AppState::Title)))
+            .add_systems(
+                OnEnter(AppState::Playing),
+                spawn_demo_overlay.run_if(resource_exists::<DemoPilot>),
+            )
+            .add_systems(
+                FixedUpdate,
+                steer_demo_pilot
+                    .run_if(in_state(AppState::Playing).and_then(resource_exists::<DemoPilot>)),
+            )
+            .add_systems(
+                Update,
+                (end_demo, blink_demo_overlay)
+                    .run_if(in_state(AppState::Playing).and_then(resource_exists::<DemoPilot>)),
+            )
+            .add_systems(OnExit(InRun), finish_demo);
+    }
+}
+
+#[derive(Resource)]
+pub struct DemoPilot {
+    pub direction: Vec2,
+    pub firing: bool,
+    elapsed: f32,
+}
+
+#[derive(Resource, Default)]
+struct TitleIdle(f32);
+
+#[derive(Component)]
+struct DemoOverlay;
+
+fn any_input_pressed(
+    keys: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    buttons: &ButtonInput<GamepadButton>,
+    touches: &Touches,
+) -> bool {
+    keys.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || buttons.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+}
+
+fn reset_title_idle(mut idle: ResMut<TitleIdle>) {
+    idle.0 = 0.0;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn watch_title_idle(
+    mut commands: Commands,
+    mut idle: ResMut<TitleIdle>,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    touches: Res<Touches>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if any_input_pressed(&keys, &mouse, &buttons, &touches) {
+        idle.0 = 0.0;
+        return;
+    }
+    idle.0 += time.delta_seconds();
+    if idle.0 >= IDLE_SECONDS {
+        commands.insert_resource(DemoPilot {
+            direction: Vec2::ZERO,
+            firing: true,
+            elapsed: 0.0,
+        });
+        next_state.set(AppState::Playing);
+    }
+}
+
+fn steer_demo_pilot(
+    mut pilot: ResMut<DemoPilot>,
+    time: Res<Time<Fixed>>,
+    player: Query<&Transform, With<Player>>,
+    threats: Query<&Transform, With<EnemyProjectile>>,
+) {
+    pilot.elapsed += time.delta_seconds();
+    let Ok(player) = player.get_single() else {
+        pilot.direction = Vec2::ZERO;
+        return;
+    };
+    let position = player.translation.truncate();
+    let cruise = Vec2::new(
+        (pilot.elapsed * 0.6).sin() * DEMO_WEAVE_WIDTH,
+        DEMO_CRUISE_Y,
+    );
+
+    let mut steer = (cruise - position) / 120.0;
+    for threat in &threats {
+        let offset = position - threat.translation.truncate();
+        let distance = offset.length();
+        if distance < DEMO_DODGE_RADIUS && offset.y < 40.0 {
+            let sideways = if offset.x >= 0.0 { 1.0 } else { -1.0 };
+            steer.x += sideways * (1.0 - distance / DEMO_DODGE_RADIUS) * 3.0;
+        }
+    }
+    pilot.direction = steer.clamp_length_max(1.0);
+}
+
+fn spawn_demo_overlay(mut commands: Commands, font: Res<UiFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(96.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            DemoOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    font.style(TextSize::Heading, Color::srgb(1.0, 0.85, 0.4)),
+                ),
+                DevicePrompt::new("DEMO - PRESS ANY KEY", "DEMO - PRESS START"),
+            ));
+        });
+}
+
+fn blink_demo_overlay(
+    pilot: Res<DemoPilot>,
+    mut overlays: Query<&mut Visibility, With<DemoOverlay>>,
+) {
+    let visible = (pilot.elapsed * 2.0).fract() < 0.6;
+    for mut visibility in &mut overlays {
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn end_demo(
+    pilot: Res<DemoPilot>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    touches: Res<Touches>,
+    mut life_events: EventReader<PlayerLifeLostEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let life_lost = life_events.read().count() > 0;
+    if any_input_pressed(&keys, &mouse, &buttons, &touches)
+        || life_lost
+        || pilot.elapsed >= DEMO_SECONDS
+    {
+        next_state.set(AppState::Title);
+    }
+}
+
+fn finish_demo(mut commands: Commands, overlays: Query<Entity, With<DemoOverlay>>) {
+    for entity in &overlays {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<DemoPilot>();
+}
@@ -1,117 +1,457 @@
-use std::{f32::consts::PI, sync::Arc};
+use std::{collections::HashMap, f32::consts::PI, path::Path};
 
-use bevy::{
-    audio::{AudioBundle, AudioSink, AudioSource, PlaybackSettings, Volume},
-    prelude::*,
+use bevy::{asset::io::AssetSourceId, prelude::*, tasks::block_on};
+
+use super::{
+    boss::{BossEvent, BossState},
+    config::GameRng,
+    enemies::Enemy,
+    player::{PlayerLifeLostEvent, PlayerStats},
+    powerups::PowerUpKind,
+    spawn::WaveDirector,
+    states::{AppState, InRun},
 };
 
-use super::{config::GameSettings, states::AppState};
+const AUDIO_DIR: &str = "audio";
+const MUSIC_LAYER_FADE_SECONDS: f32 = 1.5;
+const MUSIC_DUCK_LEVEL: f32 = 0.35;
+const MUSIC_DUCK_FADE_SECONDS: f32 = 0.4;
+const MUSIC_RESTORE_SECONDS: f32 = 1.5;
+const BOSS_WARNING_DUCK_SECONDS: f32 = 2.5;
+const LIFE_LOST_DUCK_SECONDS: f32 = 1.6;
+const MUSIC_LOOP_SECONDS: f32 = 8.0;
+const MUSIC_BEAT_SECONDS: f32 = 0.5;
+const MUSIC_ROOTS: [f32; 4] = [55.0, 43.65, 49.0, 41.2];
+const MUSIC_CHORDS: [[f32; 4]; 4] = [
+    [220.0, 261.6, 329.6, 440.0],
+    [174.6, 220.0, 261.6, 349.2],
+    [196.0, 246.9, 293.7, 392.0],
+    [164.8, 207.7, 246.9, 329.6],
+];
+pub(super) const PICKUP_KINDS: [PowerUpKind; 6] = [
+    PowerUpKind::Spread,
+    PowerUpKind::Rapid,
+    PowerUpKind::Shield,
+    PowerUpKind::Health,
+    PowerUpKind::Invincibility,
+    PowerUpKind::Reflect,
+];
+pub(super) const LEVEL_TRACKS: [&str; 3] = ["level1", "level2", "level3"];
+// Share of the SFX volume the low-health alarm plays at.
+pub(super) const ALARM_VOLUME: f32 = 0.6;
 
-pub struct AudioPlugin;
+// The part of the mix both backends share: which cues get a voice, the layer gains,
+// ducking, and whether the low-health alarm sounds are decided here. Each backend adds
+// this plugin and plays the result from systems ordered after `AudioMixSet`.
+pub struct AudioMixPlugin;
 
-impl Plugin for AudioPlugin {
+impl Plugin for AudioMixPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<AudioCue>()
-            .init_resource::<AudioAssets>()
-            .init_resource::<MusicState>()
-            .add_systems(Startup, setup_audio_assets)
-            .add_systems(OnEnter(AppState::Title), start_title_music)
-            .add_systems(Update, (handle_audio_cues, apply_music_volume));
+            .add_event::<VoiceTrigger>()
+            .init_resource::<VoiceLimiter>()
+            .init_resource::<MusicIntensity>()
+            .init_resource::<MusicDuck>()
+            .init_resource::<LowHealthAlarm>()
+            .add_systems(
+                Update,
+                (
+                    limit_voices,
+                    (
+                        update_music_duck,
+                        update_music_intensity,
+                        update_layer_gains,
+                    )
+                        .chain(),
+                    update_low_health_alarm,
+                )
+                    .in_set(AudioMixSet),
+            );
     }
 }
 
-#[derive(Resource, Default)]
-pub struct AudioAssets {
-    pub music: Handle<AudioSource>,
-    pub shoot: Handle<AudioSource>,
-    pub hit: Handle<AudioSource>,
-    pub explosion: Handle<AudioSource>,
-    pub pickup: Handle<AudioSource>,
-    pub ui: Handle<AudioSource>,
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AudioMixSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MusicTrack {
+    Title,
+    Level(usize),
+    Boss,
 }
 
-#[derive(Resource, Default)]
-struct MusicState {
-    entity: Option<Entity>,
+impl MusicTrack {
+    pub(super) fn wanted(
+        in_run: bool,
+        boss: &BossState,
+        director: &WaveDirector,
+        level_tracks: usize,
+    ) -> Self {
+        match in_run {
+            false => MusicTrack::Title,
+            true if boss.active => MusicTrack::Boss,
+            true => MusicTrack::Level(director.level_index % level_tracks.max(1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MusicLayer {
+    Bass,
+    Arpeggio,
+    Percussion,
+}
+
+impl MusicLayer {
+    pub(super) const ALL: [MusicLayer; 3] = [
+        MusicLayer::Bass,
+        MusicLayer::Arpeggio,
+        MusicLayer::Percussion,
+    ];
+
+    pub(super) fn sound_name(self) -> &'static str {
+        match self {
+            MusicLayer::Bass => "bass",
+            MusicLayer::Arpeggio => "arpeggio",
+            MusicLayer::Percussion => "percussion",
+        }
+    }
+
+    fn threshold(self) -> f32 {
+        match self {
+            MusicLayer::Bass => 0.2,
+            MusicLayer::Arpeggio => 0.45,
+            MusicLayer::Percussion => 0.65,
+        }
+    }
+
+    fn mix(self) -> f32 {
+        match self {
+            MusicLayer::Bass => 0.9,
+            MusicLayer::Arpeggio => 0.55,
+            MusicLayer::Percussion => 0.75,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct MusicIntensity {
+    pub level: f32,
+    gains: [f32; 3],
+}
+
+impl MusicIntensity {
+    // Each layer's share of the music volume, its mix level already applied.
+    pub(super) fn layer_volumes(&self) -> impl Iterator<Item = (MusicLayer, f32)> + '_ {
+        MusicLayer::ALL
+            .into_iter()
+            .zip(self.gains)
+            .map(|(layer, gain)| (layer, layer.mix() * gain))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DuckPhase {
+    Open,
+    Timed(f32),
+    Held,
+}
+
+// How far the music is pulled down for the boss warning, a lost life, or game over.
+#[derive(Resource, Debug)]
+pub struct MusicDuck {
+    phase: DuckPhase,
+    pub gain: f32,
+}
+
+impl Default for MusicDuck {
+    fn default() -> Self {
+        Self {
+            phase: DuckPhase::Open,
+            gain: 1.0,
+        }
+    }
+}
+
+// Whether the low-health alarm should be sounding.
+#[derive(Resource, Debug, Default)]
+pub struct LowHealthAlarm {
+    pub active: bool,
 }
 
 #[derive(Event, Clone, Copy, Debug)]
-pub enum AudioCue {
+pub struct AudioCue {
+    pub sfx: Sfx,
+    pub position: Option<Vec2>,
+}
+
+impl AudioCue {
+    pub fn new(sfx: Sfx) -> Self {
+        Self {
+            sfx,
+            position: None,
+        }
+    }
+
+    pub fn at(sfx: Sfx, position: Vec2) -> Self {
+        Self {
+            sfx,
+            position: Some(position),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
     Shoot,
     Hit,
     Explosion,
-    Pickup,
+    Pickup(PowerUpKind),
     UiSelect,
 }
 
-fn setup_audio_assets(mut assets: ResMut<Assets<AudioSource>>, mut store: ResMut<AudioAssets>) {
-    store.music = assets.add(build_pad_source(220.0, 280.0, 12.0));
-    store.shoot = assets.add(build_tone_source(760.0, 0.08, 0.3));
-    store.hit = assets.add(build_tone_source(260.0, 0.15, 0.35));
-    store.explosion = assets.add(build_noise_burst(0.25, 0.45));
-    store.pickup = assets.add(build_tone_source(980.0, 0.18, 0.4));
-    store.ui = assets.add(build_tone_source(440.0, 0.12, 0.25));
+impl Sfx {
+    pub fn all() -> impl Iterator<Item = Sfx> {
+        [Sfx::Shoot, Sfx::Hit, Sfx::Explosion, Sfx::UiSelect]
+            .into_iter()
+            .chain(PICKUP_KINDS.into_iter().map(Sfx::Pickup))
+    }
+
+    pub fn max_voices(self) -> usize {
+        match self {
+            Sfx::Shoot => 4,
+            Sfx::Hit => 3,
+            Sfx::Explosion => 6,
+            Sfx::Pickup(_) => 2,
+            Sfx::UiSelect => 2,
+        }
+    }
+
+    fn pitch_jitter(self) -> f32 {
+        match self {
+            Sfx::Shoot => 0.08,
+            Sfx::Hit => 0.06,
+            Sfx::Explosion => 0.1,
+            Sfx::Pickup(_) | Sfx::UiSelect => 0.0,
+        }
+    }
+
+    fn min_interval(self) -> f64 {
+        match self {
+            Sfx::Shoot => 0.05,
+            Sfx::Hit => 0.06,
+            Sfx::Explosion => 0.04,
+            Sfx::Pickup(_) => 0.08,
+            Sfx::UiSelect => 0.05,
+        }
+    }
+}
+
+// A cue that got through the limiter, to be played on voice `voice` of its sound
+// (below `Sfx::max_voices`), cutting off whatever that voice was still playing.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct VoiceTrigger {
+    pub sfx: Sfx,
+    pub voice: usize,
+    pub position: Option<Vec2>,
+    pub speed: f32,
+}
+
+// Each sound's voices are re-triggered in turn, so the next one is always the voice
+// that started longest ago.
+#[derive(Resource, Default)]
+struct VoiceLimiter {
+    next: HashMap<Sfx, usize>,
+    last_trigger: HashMap<Sfx, f64>,
 }
 
-fn start_title_music(
-    mut commands: Commands,
-    assets: Res<AudioAssets>,
-    settings: Res<GameSettings>,
-    mut state: ResMut<MusicState>,
+fn limit_voices(
+    mut cues: EventReader<AudioCue>,
+    mut triggers: EventWriter<VoiceTrigger>,
+    time: Res<Time<Real>>,
+    mut limiter: ResMut<VoiceLimiter>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if state.entity.is_some() {
-        return;
+    let now = time.elapsed_seconds_f64();
+    for cue in cues.read() {
+        let sfx = cue.sfx;
+        if let Some(last) = limiter.last_trigger.get(&sfx)
+            && now - last < sfx.min_interval()
+        {
+            continue;
+        }
+        limiter.last_trigger.insert(sfx, now);
+
+        let next = limiter.next.entry(sfx).or_default();
+        let voice = *next;
+        *next = (voice + 1) % sfx.max_voices();
+        triggers.send(VoiceTrigger {
+            sfx,
+            voice,
+            position: cue.position,
+            speed: 1.0 + rng.signed() * sfx.pitch_jitter(),
+        });
     }
+}
 
-    let entity = commands
-        .spawn((AudioBundle {
-            source: assets.music.clone(),
-            settings: PlaybackSettings::LOOP.with_volume(Volume::new(settings.music_volume)),
-            ..default()
-        },))
-        .id();
-    state.entity = Some(entity);
+fn update_music_duck(
+    app_state: Res<State<AppState>>,
+    time: Res<Time<Real>>,
+    mut boss_events: EventReader<BossEvent>,
+    mut life_events: EventReader<PlayerLifeLostEvent>,
+    mut duck: ResMut<MusicDuck>,
+) {
+    let delta = time.delta_seconds();
+    let mut hold_for = 0.0f32;
+    if boss_events.read().any(|event| *event == BossEvent::Spawned) {
+        hold_for = hold_for.max(BOSS_WARNING_DUCK_SECONDS);
+    }
+    if life_events.read().count() > 0 {
+        hold_for = hold_for.max(LIFE_LOST_DUCK_SECONDS);
+    }
+
+    duck.phase = match (*app_state.get(), duck.phase) {
+        (AppState::GameOver | AppState::NameEntry, _) => DuckPhase::Held,
+        (_, DuckPhase::Held) => DuckPhase::Open,
+        (_, DuckPhase::Timed(remaining)) if remaining.max(hold_for) > delta => {
+            DuckPhase::Timed(remaining.max(hold_for) - delta)
+        }
+        (_, DuckPhase::Open) if hold_for > 0.0 => DuckPhase::Timed(hold_for),
+        _ => DuckPhase::Open,
+    };
+
+    let (target, seconds) = match duck.phase {
+        DuckPhase::Open => (1.0, MUSIC_RESTORE_SECONDS),
+        DuckPhase::Timed(_) | DuckPhase::Held => (MUSIC_DUCK_LEVEL, MUSIC_DUCK_FADE_SECONDS),
+    };
+    let step = delta * (1.0 - MUSIC_DUCK_LEVEL) / seconds;
+    duck.gain += (target - duck.gain).clamp(-step, step);
 }
 
-fn handle_audio_cues(
-    mut commands: Commands,
-    mut reader: EventReader<AudioCue>,
-    assets: Res<AudioAssets>,
-    settings: Res<GameSettings>,
+fn update_music_intensity(
+    in_run: Option<Res<State<InRun>>>,
+    boss: Res<BossState>,
+    stats: Res<PlayerStats>,
+    enemies: Query<(), With<Enemy>>,
+    time: Res<Time<Real>>,
+    mut intensity: ResMut<MusicIntensity>,
 ) {
-    if reader.is_empty() {
+    let target = if in_run.is_some() {
+        let density = (enemies.iter().count() as f32 / 12.0).min(1.0) * 0.4;
+        let boss = if boss.active { 0.4 } else { 0.0 };
+        let danger = if stats.is_critical() { 0.2 } else { 0.0 };
+        (0.25 + density + boss + danger).min(1.0)
+    } else {
+        0.0
+    };
+    let step = time.delta_seconds() / MUSIC_LAYER_FADE_SECONDS;
+    intensity.level += (target - intensity.level).clamp(-step, step);
+}
+
+fn update_layer_gains(time: Res<Time<Real>>, mut intensity: ResMut<MusicIntensity>) {
+    let step = time.delta_seconds() / MUSIC_LAYER_FADE_SECONDS;
+    let level = intensity.level;
+    for (layer, gain) in MusicLayer::ALL.into_iter().zip(&mut intensity.gains) {
+        let target = ((level - layer.threshold()) / 0.15).clamp(0.0, 1.0);
+        *gain += (target - *gain).clamp(-step, step);
+    }
+}
+
+fn update_low_health_alarm(
+    stats: Res<PlayerStats>,
+    app_state: Res<State<AppState>>,
+    mut alarm: ResMut<LowHealthAlarm>,
+) {
+    if !stats.is_changed() && !app_state.is_changed() {
         return;
     }
+    let wanted = *app_state.get() == AppState::Playing && stats.is_critical();
+    if alarm.active != wanted {
+        alarm.active = wanted;
+    }
+}
 
-    for cue in reader.read() {
-        let handle = match cue {
-            AudioCue::Shoot => &assets.shoot,
-            AudioCue::Hit => &assets.hit,
-            AudioCue::Explosion => &assets.explosion,
-            AudioCue::Pickup => &assets.pickup,
-            AudioCue::UiSelect => &assets.ui,
-        };
-        commands.spawn(AudioBundle {
-            source: handle.clone(),
-            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.sfx_volume)),
-            ..default()
-        });
+// Asks the default asset source rather than the filesystem, so the check follows the
+// same asset root the loader will use instead of the working directory.
+pub(super) fn audio_file(asset_server: &AssetServer, name: &str) -> Option<String> {
+    let path = format!("{AUDIO_DIR}/{name}.ogg");
+    let source = asset_server.get_source(AssetSourceId::Default).ok()?;
+    let found = block_on(source.reader().read(Path::new(&path))).is_ok();
+    found.then_some(path)
+}
+
+pub(super) fn pickup_sound_name(kind: PowerUpKind) -> &'static str {
+    match kind {
+        PowerUpKind::Spread => "pickup_spread",
+        PowerUpKind::Rapid => "pickup_rapid",
+        PowerUpKind::Shield => "pickup_shield",
+        PowerUpKind::Health => "pickup_health",
+        PowerUpKind::Invincibility => "pickup_invincibility",
+        PowerUpKind::Reflect => "pickup_reflect",
     }
 }
 
-fn apply_music_volume(
-    settings: Res<GameSettings>,
-    state: Res<MusicState>,
-    sinks: Query<&AudioSink>,
-) {
-    if let Some(entity) = state.entity {
-        if let Ok(sink) = sinks.get(entity) {
-            sink.set_volume(settings.music_volume);
-        }
+pub(super) fn synthesize(name: &str) -> Vec<u8> {
+    match name {
+        "level1" => build_pad_source(196.0, 247.0, 12.0),
+        "level2" => build_pad_source(174.6, 233.1, 10.0),
+        "level3" => build_pad_source(164.8, 261.6, 9.0),
+        "boss" => build_pad_source(110.0, 146.8, 6.0),
+        "bass" => build_bass_loop(),
+        "arpeggio" => build_arpeggio_loop(),
+        "percussion" => build_percussion_loop(),
+        "shoot" => build_tone_source(760.0, 0.08, 0.3),
+        "hit" => build_tone_source(260.0, 0.15, 0.35),
+        "explosion" => build_noise_burst(0.25, 0.45),
+        "pickup_spread" => build_jingle(
+            &[
+                (523.3, 0.0, 0.1),
+                (659.3, 0.06, 0.1),
+                (784.0, 0.12, 0.1),
+                (1046.5, 0.18, 0.2),
+            ],
+            0.3,
+        ),
+        "pickup_rapid" => build_jingle(
+            &[
+                (659.3, 0.0, 0.07),
+                (784.0, 0.045, 0.07),
+                (987.8, 0.09, 0.07),
+                (1318.5, 0.135, 0.16),
+            ],
+            0.28,
+        ),
+        "pickup_shield" => build_jingle(&[(392.0, 0.0, 0.14), (587.3, 0.12, 0.26)], 0.32),
+        "pickup_health" => build_jingle(
+            &[(261.6, 0.0, 0.5), (329.6, 0.0, 0.5), (392.0, 0.0, 0.5)],
+            0.2,
+        ),
+        "pickup_invincibility" => build_jingle(
+            &[
+                (392.0, 0.0, 0.09),
+                (392.0, 0.11, 0.09),
+                (523.3, 0.22, 0.4),
+                (659.3, 0.22, 0.4),
+                (784.0, 0.22, 0.4),
+            ],
+            0.2,
+        ),
+        "pickup_reflect" => build_jingle(
+            &[
+                (880.0, 0.0, 0.08),
+                (1174.7, 0.06, 0.08),
+                (987.8, 0.12, 0.08),
+                (1318.5, 0.18, 0.18),
+            ],
+            0.24,
+        ),
+        "ui" => build_tone_source(440.0, 0.12, 0.25),
+        "warning" => build_beep_loop(880.0, 0.14, 0.9, 0.18),
+        _ => build_pad_source(220.0, 280.0, 12.0),
     }
 }
 
-fn build_pad_source(freq_a: f32, freq_b: f32, seconds: f32) -> AudioSource {
+fn build_pad_source(freq_a: f32, freq_b: f32, seconds: f32) -> Vec<u8> {
     let sample_rate = 44_100;
     let sample_count = (seconds * sample_rate as f32) as usize;
     let mut samples = Vec::with_capacity(sample_count);
@@ -126,7 +466,75 @@ fn build_pad_source(freq_a: f32, freq_b: f32, seconds: f32) -> AudioSource {
     make_wav(samples, sample_rate)
 }
 
-fn build_tone_source(freq: f32, seconds: f32, amplitude: f32) -> AudioSource {
+fn build_bass_loop() -> Vec<u8> {
+    build_loop(|t| {
+        let bar = (t / (MUSIC_LOOP_SECONDS / 4.0)) as usize % 4;
+        let step_time = t % (MUSIC_BEAT_SECONDS * 0.5);
+        let freq = MUSIC_ROOTS[bar];
+        let env = (step_time * 200.0).min(1.0) * (-step_time * 6.0).exp();
+        let phase = (freq * t).fract();
+        let tone = (2.0 * PI * freq * t).sin() * 0.7 + (phase * 2.0 - 1.0) * 0.3;
+        tone * env * 0.32
+    })
+}
+
+fn build_arpeggio_loop() -> Vec<u8> {
+    let step_seconds = MUSIC_BEAT_SECONDS * 0.25;
+    build_loop(|t| {
+        let bar = (t / (MUSIC_LOOP_SECONDS / 4.0)) as usize % 4;
+        let step = (t / step_seconds) as usize;
+        let step_time = t % step_seconds;
+        let freq = MUSIC_CHORDS[bar][step % 4];
+        let env = (step_time * 400.0).min(1.0) * (-step_time * 18.0).exp();
+        let square = if (2.0 * PI * freq * t).sin() >= 0.0 {
+            1.0
+        } else {
+            -1.0
+        };
+        square * env * 0.09
+    })
+}
+
+fn build_percussion_loop() -> Vec<u8> {
+    let half_beat = MUSIC_BEAT_SECONDS * 0.5;
+    build_loop(|t| {
+        let beat = (t / MUSIC_BEAT_SECONDS) as usize;
+        let beat_time = t % MUSIC_BEAT_SECONDS;
+        let kick_sweep = 45.0 + 90.0 * (-beat_time * 30.0).exp();
+        let kick = (2.0 * PI * kick_sweep * beat_time).sin() * (-beat_time * 14.0).exp() * 0.5;
+        let snare = if beat % 2 == 1 {
+            noise_sample(t) * (-beat_time * 22.0).exp() * 0.22
+        } else {
+            0.0
+        };
+        let hat_time = (t + half_beat) % MUSIC_BEAT_SECONDS;
+        let hat = if hat_time < half_beat {
+            noise_sample(t * 1.37) * (-hat_time * 90.0).exp() * 0.1
+        } else {
+            0.0
+        };
+        kick + snare + hat
+    })
+}
+
+fn build_loop(sample: impl Fn(f32) -> f32) -> Vec<u8> {
+    let sample_rate = 44_100;
+    let sample_count = (MUSIC_LOOP_SECONDS * sample_rate as f32) as usize;
+    let samples = (0..sample_count)
+        .map(|i| sample(i as f32 / sample_rate as f32))
+        .collect();
+    make_wav(samples, sample_rate)
+}
+
+fn noise_sample(t: f32) -> f32 {
+    let mut value = (t * 44_100.0) as u32;
+    value = value.wrapping_mul(747796405).wrapping_add(2891336453);
+    value ^= value >> 15;
+    value = value.wrapping_mul(0x2c1b_3c6d);
+    ((value >> 9) as f32 / (1u32 << 23) as f32) * 2.0 - 1.0
+}
+
+fn build_tone_source(freq: f32, seconds: f32, amplitude: f32) -> Vec<u8> {
     let sample_rate = 44_100;
     let sample_count = (seconds * sample_rate as f32) as usize;
     let mut samples = Vec::with_capacity(sample_count);
@@ -138,7 +546,47 @@ fn build_tone_source(freq: f32, seconds: f32, amplitude: f32) -> AudioSource {
     make_wav(samples, sample_rate)
 }
 
-fn build_noise_burst(seconds: f32, amplitude: f32) -> AudioSource {
+fn build_jingle(notes: &[(f32, f32, f32)], amplitude: f32) -> Vec<u8> {
+    let sample_rate = 44_100;
+    let seconds = notes
+        .iter()
+        .map(|(_, start, length)| start + length)
+        .fold(0.0, f32::max);
+    let sample_count = (seconds * sample_rate as f32) as usize;
+    let mut samples = vec![0.0; sample_count];
+    for &(freq, start, length) in notes {
+        let first = (start * sample_rate as f32) as usize;
+        let count = (length * sample_rate as f32) as usize;
+        for i in 0..count.min(sample_count.saturating_sub(first)) {
+            let t = i as f32 / sample_rate as f32;
+            let progress = i as f32 / count as f32;
+            let env = (progress * 30.0).min(1.0) * (1.0 - progress).powf(1.8);
+            let tone = (2.0 * PI * freq * t).sin() + 0.25 * (4.0 * PI * freq * t).sin();
+            samples[first + i] += tone * amplitude * env;
+        }
+    }
+    make_wav(samples, sample_rate)
+}
+
+fn build_beep_loop(freq: f32, beep_seconds: f32, period: f32, amplitude: f32) -> Vec<u8> {
+    let sample_rate = 44_100;
+    let sample_count = (period * sample_rate as f32) as usize;
+    let beep_count = (beep_seconds * sample_rate as f32) as usize;
+    let mut samples = Vec::with_capacity(sample_count);
+    for i in 0..sample_count {
+        if i >= beep_count {
+            samples.push(0.0);
+            continue;
+        }
+        let t = i as f32 / sample_rate as f32;
+        let progress = i as f32 / beep_count as f32;
+        let env = (progress * 12.0).min(1.0) * (1.0 - progress).powf(1.5);
+        samples.push((2.0 * PI * freq * t).sin() * amplitude * env);
+    }
+    make_wav(samples, sample_rate)
+}
+
+fn build_noise_burst(seconds: f32, amplitude: f32) -> Vec<u8> {
     let sample_rate = 44_100;
     let sample_count = (seconds * sample_rate as f32) as usize;
     let mut samples = Vec::with_capacity(sample_count);
@@ -152,7 +600,7 @@ fn build_noise_burst(seconds: f32, amplitude: f32) -> AudioSource {
     make_wav(samples, sample_rate)
 }
 
-fn make_wav(samples: Vec<f32>, sample_rate: u32) -> AudioSource {
+fn make_wav(samples: Vec<f32>, sample_rate: u32) -> Vec<u8> {
     let channels = 1u16;
     let bits_per_sample = 16u16;
     let bytes_per_sample = (bits_per_sample / 8) as u32;
@@ -177,7 +625,5 @@ fn make_wav(samples: Vec<f32>, sample_rate: u32) -> AudioSource {
         buffer.extend_from_slice(&clamped.to_le_bytes());
     }
 
-    AudioSource {
-        bytes: Arc::from(buffer.into_boxed_slice()),
-    }
+    buffer
 }
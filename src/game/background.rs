@@ -1,21 +1,177 @@
-use bevy::{prelude::*, time::Fixed};
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    sprite::ImageScaleMode,
+    time::Fixed,
+};
 
-use super::config::GameConfig;
+use super::{
+    boss::{BossEvent, BossPhase},
+    config::{GameConfig, GameRng},
+    loading::LoadingAssets,
+    ship_sprites::{ShipRow, ShipSpriteAssets, ShipSpriteId},
+    spawn::{LevelBackground, Storyboard, WaveDirector},
+    states::{AppState, InRun},
+};
+
+const BACKDROP_TEXTURE_SIZE: u32 = 128;
+const BOSS_TINT: Color = Color::srgb(1.0, 0.25, 0.2);
+const BOSS_MOOD_RATE: f32 = 1.5;
+// How quickly the scroll speed eases toward its target, per second.
+const SCROLL_EASE_RATE: f32 = 0.8;
+const MAX_SCROLL_SPEED: f32 = 2.5;
+// The drift the stars settle into on the level-complete screen.
+const LEVEL_COMPLETE_SCROLL_SPEED: f32 = 0.35;
+const LEVEL_TILE_FADE_SECONDS: f32 = 1.5;
+// Beneath the nebulae and planets as well as the stars.
+const LEVEL_TILE_Z: f32 = -30.0;
+// Seconds between ambient set pieces.
+const AMBIENT_MIN_INTERVAL: f32 = 20.0;
+const AMBIENT_MAX_INTERVAL: f32 = 45.0;
 
 pub struct BackgroundPlugin;
 
 impl Plugin for BackgroundPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_starfield)
-            .add_systems(FixedUpdate, scroll_stars);
+        app.init_resource::<BackgroundMood>()
+            .init_resource::<AmbientEvents>()
+            .init_resource::<ScrollSpeed>()
+            .add_systems(
+                Startup,
+                (spawn_starfield, spawn_backdrop, load_level_backgrounds),
+            )
+            .add_systems(
+                Update,
+                (
+                    react_to_boss_events.run_if(in_state(AppState::Playing)),
+                    apply_background_mood,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    (update_scroll_speed, scroll_stars).chain(),
+                    scroll_backdrop,
+                    (fade_level_tiles, scroll_level_tiles).chain(),
+                    (
+                        spawn_ambient_events.run_if(in_state(AppState::Playing)),
+                        move_ambient_pieces,
+                    )
+                        .chain(),
+                ),
+            )
+            .add_systems(OnEnter(InRun), reset_ambient_events)
+            .add_systems(OnExit(InRun), reset_background_mood);
     }
 }
 
+#[derive(Resource, Debug, Default)]
+struct BackgroundMood {
+    target: f32,
+    level: f32,
+}
+
 #[derive(Component)]
 struct StarLayer {
     speed: f32,
+    color: Color,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BackdropKind {
+    Nebula,
+    Planet,
+}
+
+#[derive(Component)]
+struct BackdropLayer {
+    kind: BackdropKind,
+    speed: f32,
+    color: Color,
+}
+
+// The current level's tiled background, or one fading out after a level change.
+#[derive(Component, Debug)]
+pub struct LevelTile {
+    pub level: usize,
+    pub background: LevelBackground,
+    pub alpha: f32,
+    pub leaving: bool,
+    scroll: f32,
+}
+
+// A multiplier on the stars' scroll speed that tracks the action: it climbs as the wave
+// difficulty ramps up and through a boss fight's later phases, and eases off on the
+// level-complete screen.
+#[derive(Resource, Debug)]
+pub struct ScrollSpeed {
+    pub target: f32,
+    pub current: f32,
+}
+
+impl Default for ScrollSpeed {
+    fn default() -> Self {
+        Self {
+            target: 1.0,
+            current: 1.0,
+        }
+    }
+}
+
+// Counts down to the next purely cosmetic set piece: a meteor shower, a capital ship
+// passing in the distance, or a field of drifting debris.
+#[derive(Resource, Debug)]
+pub struct AmbientEvents {
+    pub countdown: f32,
+}
+
+impl Default for AmbientEvents {
+    fn default() -> Self {
+        Self {
+            countdown: AMBIENT_MIN_INTERVAL,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct AmbientPiece {
+    pub velocity: Vec2,
+    spin: f32,
+}
+
+struct BackdropTheme {
+    nebula: [Color; 2],
+    planet: [Color; 2],
 }
 
+const LEVEL_THEMES: [BackdropTheme; 3] = [
+    BackdropTheme {
+        nebula: [
+            Color::srgba(0.35, 0.25, 0.75, 0.28),
+            Color::srgba(0.15, 0.4, 0.8, 0.24),
+        ],
+        planet: [Color::srgb(0.55, 0.7, 0.95), Color::srgb(0.8, 0.6, 0.45)],
+    },
+    BackdropTheme {
+        nebula: [
+            Color::srgba(0.75, 0.25, 0.45, 0.26),
+            Color::srgba(0.9, 0.45, 0.2, 0.2),
+        ],
+        planet: [Color::srgb(0.95, 0.55, 0.4), Color::srgb(0.7, 0.75, 0.55)],
+    },
+    BackdropTheme {
+        nebula: [
+            Color::srgba(0.2, 0.65, 0.55, 0.24),
+            Color::srgba(0.3, 0.35, 0.85, 0.22),
+        ],
+        planet: [Color::srgb(0.6, 0.9, 0.8), Color::srgb(0.85, 0.8, 0.95)],
+    },
+];
+
 fn spawn_starfield(mut commands: Commands, config: Res<GameConfig>) {
     let layers = [
         (72, Color::srgb(0.4, 0.6, 1.0), 28.0, 0.45),
@@ -42,18 +198,183 @@ fn spawn_starfield(mut commands: Commands, config: Res<GameConfig>) {
                     },
                     ..default()
                 },
-                StarLayer { speed },
+                StarLayer { speed, color },
             ));
         }
     }
 }
 
+fn spawn_backdrop(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let nebula = images.add(build_nebula_image(BACKDROP_TEXTURE_SIZE));
+    let planet = images.add(build_planet_image(BACKDROP_TEXTURE_SIZE));
+    let theme = &LEVEL_THEMES[0];
+
+    let pieces = [
+        (BackdropKind::Nebula, 0, -320.0, 80.0, 720.0, 7.0, -20.0),
+        (BackdropKind::Nebula, 1, 360.0, -260.0, 620.0, 9.0, -19.5),
+        (BackdropKind::Planet, 0, 420.0, 220.0, 160.0, 14.0, -15.0),
+    ];
+    for (kind, tint, x, y, size, speed, z) in pieces {
+        let (texture, color) = match kind {
+            BackdropKind::Nebula => (nebula.clone(), theme.nebula[tint]),
+            BackdropKind::Planet => (planet.clone(), theme.planet[tint]),
+        };
+        commands.spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_xyz(x, y, z),
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+                ..default()
+            },
+            BackdropLayer { kind, speed, color },
+        ));
+    }
+}
+
+// The loading screen keeps the handles, so the images stay loaded for the session.
+fn load_level_backgrounds(
+    asset_server: Res<AssetServer>,
+    storyboard: Res<Storyboard>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    for path in storyboard.background_images() {
+        loading.track(asset_server.load::<Image>(path.to_string()));
+    }
+}
+
+// Crossfades to the current level's background as the level changes. Images the
+// storyboard editor adds mid-session load on demand.
+fn fade_level_tiles(
+    mut commands: Commands,
+    director: Res<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time<Fixed>>,
+    mut tiles: Query<(Entity, &mut LevelTile, &mut Sprite)>,
+) {
+    let wanted = storyboard
+        .level(director.level_index)
+        .and_then(|level| level.background.as_ref());
+    let step = time.delta_seconds() / LEVEL_TILE_FADE_SECONDS;
+    let mut showing = false;
+    for (entity, mut tile, mut sprite) in &mut tiles {
+        if tile.level != director.level_index || Some(&tile.background) != wanted {
+            tile.leaving = true;
+        }
+        tile.alpha = if tile.leaving {
+            tile.alpha - step
+        } else {
+            tile.alpha + step
+        }
+        .clamp(0.0, 1.0);
+        if tile.leaving && tile.alpha <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if sprite.color.alpha() != tile.alpha {
+            sprite.color.set_alpha(tile.alpha);
+        }
+        showing |= !tile.leaving;
+    }
+
+    let Some(background) = wanted.filter(|_| !showing) else {
+        return;
+    };
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load(background.image.clone()),
+            transform: Transform::from_xyz(0.0, 0.0, LEVEL_TILE_Z),
+            sprite: Sprite {
+                color: Color::WHITE.with_alpha(0.0),
+                ..default()
+            },
+            ..default()
+        },
+        ImageScaleMode::Tiled {
+            tile_x: true,
+            tile_y: true,
+            stretch_value: 1.0,
+        },
+        LevelTile {
+            level: director.level_index,
+            background: background.clone(),
+            alpha: 0.0,
+            leaving: false,
+            scroll: 0.0,
+        },
+    ));
+}
+
+fn react_to_boss_events(mut mood: ResMut<BackgroundMood>, mut events: EventReader<BossEvent>) {
+    for event in events.read() {
+        mood.target = match event {
+            BossEvent::PhaseChanged(BossPhase::Second) => 0.5,
+            BossEvent::PhaseChanged(BossPhase::Final) => 1.0,
+            BossEvent::Spawned
+            | BossEvent::PhaseChanged(BossPhase::Entry)
+            | BossEvent::Defeated => 0.0,
+        };
+    }
+}
+
+fn apply_background_mood(
+    mut mood: ResMut<BackgroundMood>,
+    time: Res<Time>,
+    mut stars: Query<(&StarLayer, &mut Sprite), Without<BackdropLayer>>,
+    mut backdrop: Query<(&BackdropLayer, &mut Sprite), Without<StarLayer>>,
+) {
+    let step = BOSS_MOOD_RATE * time.delta_seconds();
+    let previous = mood.level;
+    mood.level += (mood.target - mood.level).clamp(-step, step);
+    if mood.level == previous && mood.level == 0.0 {
+        return;
+    }
+
+    let level = mood.level;
+    for (layer, mut sprite) in &mut stars {
+        sprite.color = layer.color.mix(&BOSS_TINT, level * 0.6);
+    }
+    for (layer, mut sprite) in &mut backdrop {
+        let tinted = layer.color.mix(&BOSS_TINT, level * 0.5);
+        sprite.color = tinted.with_alpha(layer.color.alpha());
+    }
+}
+
+fn reset_background_mood(mut mood: ResMut<BackgroundMood>) {
+    mood.target = 0.0;
+}
+
+// Difficulty starts near 1 and climbs a step each wave; the boss mood adds up to 1.5.
+fn update_scroll_speed(
+    mut speed: ResMut<ScrollSpeed>,
+    director: Res<WaveDirector>,
+    mood: Res<BackgroundMood>,
+    state: Res<State<AppState>>,
+    time: Res<Time<Fixed>>,
+) {
+    speed.target = match state.get() {
+        AppState::LevelComplete => LEVEL_COMPLETE_SCROLL_SPEED,
+        AppState::Playing | AppState::Dialogue | AppState::Paused => {
+            let ramp = ((director.difficulty - 1.0) * 0.5).max(0.0);
+            (1.0 + ramp + mood.level * 1.5).min(MAX_SCROLL_SPEED)
+        }
+        _ => 1.0,
+    };
+    let step = SCROLL_EASE_RATE * time.delta_seconds();
+    speed.current += (speed.target - speed.current).clamp(-step, step);
+}
+
 fn scroll_stars(
     mut query: Query<(&StarLayer, &mut Transform)>,
     time: Res<Time<Fixed>>,
     config: Res<GameConfig>,
+    speed: Res<ScrollSpeed>,
 ) {
-    let delta = time.delta_seconds();
+    let delta = time.delta_seconds() * speed.current;
     let reset_y = config.logical_height * 0.5 + 40.0;
     let bottom = -config.logical_height * 0.5 - 40.0;
 
@@ -65,6 +386,274 @@ fn scroll_stars(
     }
 }
 
+fn scroll_backdrop(
+    mut query: Query<(&mut BackdropLayer, &mut Transform, &mut Sprite)>,
+    time: Res<Time<Fixed>>,
+    config: Res<GameConfig>,
+    director: Res<WaveDirector>,
+    mut rng: ResMut<GameRng>,
+) {
+    let delta = time.delta_seconds();
+    let half_height = config.logical_height * 0.5;
+    let half_width = config.logical_width * 0.5;
+    let theme = &LEVEL_THEMES[director.level_index % LEVEL_THEMES.len()];
+
+    for (mut layer, mut transform, mut sprite) in &mut query {
+        transform.translation.y -= layer.speed * delta;
+        let size = sprite.custom_size.unwrap_or(Vec2::ONE).y;
+        if transform.translation.y + size * 0.5 >= -half_height {
+            continue;
+        }
+
+        let (new_size, color) = match layer.kind {
+            BackdropKind::Nebula => {
+                let tint = usize::from(rng.next_f32() > 0.5);
+                (rng.range(520.0, 820.0), theme.nebula[tint])
+            }
+            BackdropKind::Planet => {
+                let tint = usize::from(rng.next_f32() > 0.5);
+                (rng.range(90.0, 220.0), theme.planet[tint])
+            }
+        };
+        sprite.custom_size = Some(Vec2::splat(new_size));
+        layer.color = color;
+        sprite.color = color;
+        transform.translation.x = rng.range(-half_width, half_width);
+        transform.translation.y = half_height + new_size * 0.5 + rng.range(40.0, 360.0);
+    }
+}
+
+fn reset_ambient_events(mut events: ResMut<AmbientEvents>) {
+    *events = AmbientEvents::default();
+}
+
+fn spawn_ambient_events(
+    mut commands: Commands,
+    mut events: ResMut<AmbientEvents>,
+    time: Res<Time<Fixed>>,
+    config: Res<GameConfig>,
+    sprites: Res<ShipSpriteAssets>,
+    mut rng: ResMut<GameRng>,
+) {
+    events.countdown -= time.delta_seconds();
+    if events.countdown > 0.0 {
+        return;
+    }
+    events.countdown = rng.range(AMBIENT_MIN_INTERVAL, AMBIENT_MAX_INTERVAL);
+    let half = Vec2::new(config.logical_width, config.logical_height) * 0.5;
+    match rng.next_u32() % 3 {
+        0 => spawn_meteor_shower(&mut commands, &mut rng, half),
+        1 => spawn_capital_ship(&mut commands, &mut rng, &sprites, half),
+        _ => spawn_debris_field(&mut commands, &mut rng, half),
+    }
+}
+
+// Thin streaks raking down across the screen from one side, just behind the stars.
+fn spawn_meteor_shower(commands: &mut Commands, rng: &mut GameRng, half: Vec2) {
+    let side = if rng.next_f32() > 0.5 { 1.0 } else { -1.0 };
+    let heading = Vec2::new(-side * rng.range(0.4, 0.7), -1.0).normalize();
+    let count = 6 + rng.next_u32() % 7;
+    for _ in 0..count {
+        let speed = rng.range(420.0, 620.0);
+        let length = rng.range(18.0, 40.0);
+        let start = Vec2::new(
+            side * rng.range(0.0, half.x),
+            half.y + length + rng.range(0.0, half.y),
+        );
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(start.extend(-11.0))
+                    .with_rotation(Quat::from_rotation_arc_2d(Vec2::Y, heading)),
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 0.85, 0.6, rng.range(0.35, 0.7)),
+                    custom_size: Some(Vec2::new(2.0, length)),
+                    ..default()
+                },
+                ..default()
+            },
+            AmbientPiece {
+                velocity: heading * speed,
+                spin: 0.0,
+            },
+        ));
+    }
+}
+
+// A darkened, oversized boss hull sliding sideways across the far background.
+fn spawn_capital_ship(
+    commands: &mut Commands,
+    rng: &mut GameRng,
+    sprites: &ShipSpriteAssets,
+    half: Vec2,
+) {
+    let data = sprites.data(ShipSpriteId::Boss);
+    let size = data.frame_size * data.scale * 1.6;
+    let side = if rng.next_f32() > 0.5 { 1.0 } else { -1.0 };
+    let start = Vec2::new(
+        -side * (half.x + size.max_element()),
+        rng.range(-half.y * 0.3, half.y * 0.6),
+    );
+    commands.spawn((
+        SpriteBundle {
+            texture: data.texture.clone(),
+            transform: Transform::from_translation(start.extend(-17.0))
+                .with_rotation(Quat::from_rotation_z(side * std::f32::consts::FRAC_PI_2)),
+            sprite: Sprite {
+                color: Color::srgba(0.25, 0.3, 0.4, 0.55),
+                custom_size: Some(size),
+                ..default()
+            },
+            ..default()
+        },
+        TextureAtlas {
+            layout: data.layout.clone(),
+            index: sprites.sequence(ShipSpriteId::Boss, ShipRow::Idle)[0],
+        },
+        AmbientPiece {
+            velocity: Vec2::new(side * rng.range(22.0, 34.0), -6.0),
+            spin: 0.0,
+        },
+    ));
+}
+
+// Tumbling hull fragments drifting down in a loose cluster.
+fn spawn_debris_field(commands: &mut Commands, rng: &mut GameRng, half: Vec2) {
+    let center = rng.range(-half.x * 0.6, half.x * 0.6);
+    let count = 8 + rng.next_u32() % 7;
+    for _ in 0..count {
+        let size = Vec2::new(rng.range(4.0, 14.0), rng.range(3.0, 9.0));
+        let shade = rng.range(0.3, 0.5);
+        let start = Vec2::new(
+            center + rng.range(-140.0, 140.0),
+            half.y + 20.0 + rng.range(0.0, 220.0),
+        );
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(start.extend(-14.0))
+                    .with_rotation(Quat::from_rotation_z(rng.range(0.0, std::f32::consts::TAU))),
+                sprite: Sprite {
+                    color: Color::srgba(shade, shade, shade * 1.1, 0.8),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            },
+            AmbientPiece {
+                velocity: Vec2::new(rng.signed() * 12.0, -rng.range(50.0, 80.0)),
+                spin: rng.signed() * 2.0,
+            },
+        ));
+    }
+}
+
+// Pieces start off screen, so one is only gone once it's outside and still heading away.
+fn move_ambient_pieces(
+    mut commands: Commands,
+    mut pieces: Query<(Entity, &AmbientPiece, &mut Transform, &Sprite)>,
+    time: Res<Time<Fixed>>,
+    config: Res<GameConfig>,
+) {
+    let delta = time.delta_seconds();
+    let half = Vec2::new(config.logical_width, config.logical_height) * 0.5;
+    for (entity, piece, mut transform, sprite) in &mut pieces {
+        transform.translation += (piece.velocity * delta).extend(0.0);
+        transform.rotate_z(piece.spin * delta);
+        let position = transform.translation.truncate();
+        let reach = half + Vec2::splat(sprite.custom_size.unwrap_or(Vec2::ONE).max_element());
+        let leaving_x = position.x.abs() > reach.x && position.x * piece.velocity.x > 0.0;
+        let leaving_y = position.y.abs() > reach.y && position.y * piece.velocity.y > 0.0;
+        if leaving_x || leaving_y {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// The sprite covers the playfield plus a spare row of tiles, and slides down by up to
+// one tile before snapping back, so the tiling never shows an edge.
+fn scroll_level_tiles(
+    mut tiles: Query<(&mut LevelTile, &mut Transform, &mut Sprite, &Handle<Image>)>,
+    images: Res<Assets<Image>>,
+    time: Res<Time<Fixed>>,
+    config: Res<GameConfig>,
+) {
+    for (mut tile, mut transform, mut sprite, handle) in &mut tiles {
+        let Some(image) = images.get(handle) else {
+            continue;
+        };
+        let tile_height = image.size_f32().y.max(1.0);
+        let rows = (config.logical_height / tile_height).ceil() + 1.0;
+        let size = Vec2::new(config.logical_width, rows * tile_height);
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+        tile.scroll = (tile.scroll + tile.background.speed * time.delta_seconds()) % tile_height;
+        transform.translation.y = (size.y - config.logical_height) * 0.5 - tile.scroll;
+    }
+}
+
+fn build_nebula_image(size: u32) -> Image {
+    let blobs = [
+        (0.5, 0.5, 0.34, 1.0),
+        (0.32, 0.4, 0.22, 0.7),
+        (0.68, 0.62, 0.24, 0.65),
+        (0.45, 0.72, 0.18, 0.5),
+        (0.62, 0.3, 0.16, 0.45),
+    ];
+    build_image(size, |u, v| {
+        let mut density = 0.0;
+        for (cx, cy, radius, weight) in blobs {
+            let dx = u - cx;
+            let dy = v - cy;
+            density += weight * (-(dx * dx + dy * dy) / (radius * radius)).exp();
+        }
+        let swirl = 0.85 + 0.15 * (u * 23.0 + (v * 17.0).sin() * 3.0).sin();
+        let alpha = ((density - 0.15) * 0.9 * swirl).clamp(0.0, 1.0);
+        [1.0, 1.0, 1.0, alpha]
+    })
+}
+
+fn build_planet_image(size: u32) -> Image {
+    let light = Vec3::new(-0.5, 0.6, 0.62).normalize();
+    build_image(size, |u, v| {
+        let x = u * 2.0 - 1.0;
+        let y = 1.0 - v * 2.0;
+        let distance_sq = x * x + y * y;
+        if distance_sq >= 1.0 {
+            return [0.0, 0.0, 0.0, 0.0];
+        }
+        let z = (1.0 - distance_sq).sqrt();
+        let diffuse = Vec3::new(x, y, z).dot(light).max(0.0);
+        let bands = 0.9 + 0.1 * (y * 14.0 + x * 2.0).sin();
+        let shade = (0.18 + 0.82 * diffuse) * bands;
+        let edge = ((1.0 - distance_sq.sqrt()) * size as f32 * 0.5).clamp(0.0, 1.0);
+        [shade, shade, shade, edge]
+    })
+}
+
+fn build_image(size: u32, mut texel: impl FnMut(f32, f32) -> [f32; 4]) -> Image {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let u = (x as f32 + 0.5) / size as f32;
+            let v = (y as f32 + 0.5) / size as f32;
+            for channel in texel(u, v) {
+                data.push((channel.clamp(0.0, 1.0) * 255.0) as u8);
+            }
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
 fn pseudo_random(seed: u32) -> f32 {
     let mut value = seed.wrapping_mul(1664525).wrapping_add(1013904223);
     value ^= value >> 13;
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use super::{
+    audio::{AudioCue, Sfx},
+    collisions::EnemyDestroyedEvent,
+    player::PlayerLifeLostEvent,
+    spawn::{
+        BonusProgress, BonusStageEvent, SecretCondition, Storyboard, WaveDefinition, WaveDirector,
+        WaveStartedEvent,
+    },
+    states::{AppState, InRun},
+    ui::ScoreBoard,
+};
+
+pub struct BonusStagePlugin;
+
+impl Plugin for BonusStagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SecretTracker>()
+            .add_systems(OnEnter(InRun), reset_secret_tracker)
+            .add_systems(
+                FixedUpdate,
+                track_secrets.run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+// Progress toward the current level's secret, cleared as each level's first wave starts.
+#[derive(Resource, Debug, Default)]
+struct SecretTracker {
+    formation_kills: u32,
+    life_lost: bool,
+}
+
+fn reset_secret_tracker(mut tracker: ResMut<SecretTracker>) {
+    *tracker = SecretTracker::default();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn track_secrets(
+    mut tracker: ResMut<SecretTracker>,
+    mut director: ResMut<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    scoreboard: Res<ScoreBoard>,
+    mut waves: EventReader<WaveStartedEvent>,
+    mut kills: EventReader<EnemyDestroyedEvent>,
+    mut deaths: EventReader<PlayerLifeLostEvent>,
+    mut bonus_events: EventWriter<BonusStageEvent>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if waves.read().any(|wave| wave.wave == 1) {
+        *tracker = SecretTracker::default();
+    }
+    if deaths.read().count() > 0 {
+        tracker.life_lost = true;
+    }
+
+    let level = storyboard.level(director.level_index);
+    let Some(stage) = level.and_then(|level| level.bonus_stage.as_ref()) else {
+        kills.clear();
+        return;
+    };
+    if director.bonus != BonusProgress::Hidden {
+        kills.clear();
+        return;
+    }
+
+    let found = match stage.secret {
+        // Enemies that slip off the bottom of the screen are never counted, so the
+        // formation has to be shot down in full.
+        SecretCondition::Formation { wave } => {
+            tracker.formation_kills +=
+                kills.read().filter(|kill| kill.wave == Some(wave)).count() as u32;
+            let size = level
+                .and_then(|level| level.waves.get(wave.saturating_sub(1) as usize))
+                .map_or(0, WaveDefinition::enemy_count) as u32;
+            size > 0 && tracker.formation_kills >= size
+        }
+        SecretCondition::Score { score } => {
+            kills.clear();
+            !tracker.life_lost && scoreboard.score >= score
+        }
+    };
+    if found {
+        director.bonus = BonusProgress::Found;
+        bonus_events.send(BonusStageEvent::SecretFound);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+}
@@ -3,31 +3,49 @@ use std::f32::consts::{PI, TAU};
 use bevy::{log::info, prelude::*, sprite::TextureAtlas, time::Fixed};
 
 use super::{
-    audio::AudioCue,
-    config::{GameConfig, GameSettings},
-    enemies::{Enemy, EnemyKind, new_enemy_shot},
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    challenge::ChallengeRun,
+    config::{GameConfig, GameSettings, RunDifficulty},
+    enemies::{ContactCooldown, Enemy, EnemyKind, new_enemy_shot},
+    interpolation::TransformInterpolation,
     player::Player,
-    ship_sprites::{ShipAnimation, ShipSpriteAssets, ShipSpriteId},
+    rank::Rank,
+    rules::RuleSet,
+    ship_sprites::{ShipAnimation, ShipRow, ShipSpriteAssets, ShipSpriteId},
     spawn::{Storyboard, WaveDirector, advance_level},
-    states::AppState,
+    states::{AppState, InRun},
     ui::ScoreBoard,
     weapons::EnemyFireEvent,
 };
 
+pub const BOSS_PHASE_THRESHOLDS: [f32; 2] = [0.65, 0.35];
+const BOSS_NAMES: [&str; 3] = ["DREADNOUGHT VEX", "HARBINGER", "THE WARDEN"];
+// Time for the boss explosion to play out before the level results take over.
+const LEVEL_OUTRO_SECONDS: f32 = 2.0;
+
 pub struct BossPlugin;
 
 impl Plugin for BossPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BossState>()
-            .add_systems(OnEnter(AppState::Playing), reset_boss_state)
+            .add_event::<BossEvent>()
+            .add_systems(OnEnter(InRun), reset_boss_state)
             .add_systems(
                 FixedUpdate,
+                // Chained so the tracker never sees a freshly triggered boss before
+                // its entity exists and mistakes it for a kill.
                 (
                     trigger_boss_spawn,
                     boss_movement_and_attacks,
                     boss_health_tracker,
                 )
+                    .chain()
                     .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                finish_level_outro.run_if(in_state(AppState::Playing)),
             );
     }
 }
@@ -39,6 +57,10 @@ pub struct BossState {
     pub max_health: f32,
     pub health: f32,
     pub spawn_score: u32,
+    pub name: &'static str,
+    pub phase: BossPhase,
+    // Running between a boss kill and the level-complete or victory screen.
+    outro: Option<Timer>,
 }
 
 impl Default for BossState {
@@ -49,86 +71,125 @@ impl Default for BossState {
             max_health: 0.0,
             health: 0.0,
             spawn_score: 2600,
+            name: BOSS_NAMES[0],
+            phase: BossPhase::Entry,
+            outro: None,
         }
     }
 }
 
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossEvent {
+    Spawned,
+    PhaseChanged(BossPhase),
+    Defeated,
+}
+
 #[derive(Component)]
 struct BossControl {
     phase: BossPhase,
     direction: f32,
+    // -1 in mirror mode, which flips the boss's sweeps; fixed when the boss spawns.
+    mirror: f32,
     elapsed: f32,
     fire_timer: f32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum BossPhase {
+pub enum BossPhase {
     Entry,
     Second,
     Final,
 }
 
+impl BossPhase {
+    pub fn number(self) -> u8 {
+        match self {
+            BossPhase::Entry => 1,
+            BossPhase::Second => 2,
+            BossPhase::Final => 3,
+        }
+    }
+}
+
 fn reset_boss_state(mut state: ResMut<BossState>) {
+    // Each run starts from the first threshold; practice and resumed runs move it afterwards.
+    state.spawn_score = BossState::default().spawn_score;
     state.active = false;
     state.entity = None;
     state.max_health = 0.0;
     state.health = 0.0;
+    state.outro = None;
 }
 
+#[allow(clippy::too_many_arguments)]
 fn trigger_boss_spawn(
     mut commands: Commands,
     scoreboard: Res<ScoreBoard>,
     mut state: ResMut<BossState>,
     mut director: ResMut<WaveDirector>,
     config: Res<GameConfig>,
+    settings: Res<GameSettings>,
     sprites: Res<ShipSpriteAssets>,
+    mut boss_events: EventWriter<BossEvent>,
 ) {
-    if state.active || scoreboard.score < state.spawn_score {
+    if state.active || state.outro.is_some() || scoreboard.score < state.spawn_score {
         return;
     }
 
     let max_health = 200.0;
+    let mirror = if settings.mirror_mode { -1.0 } else { 1.0 };
     let sprite_data = sprites.data(ShipSpriteId::Boss);
-    let sequence = sprites.sequence(ShipSpriteId::Boss, 0);
-    let entity = commands
-        .spawn((
-            SpriteBundle {
-                texture: sprite_data.texture.clone(),
-                transform: Transform::from_xyz(0.0, config.logical_height * 0.3, 6.0),
-                sprite: Sprite {
-                    color: Color::WHITE,
-                    custom_size: Some(sprite_data.frame_size * sprite_data.scale),
-                    ..default()
-                },
+    let sequence = sprites.sequence(ShipSpriteId::Boss, ShipRow::Idle);
+    let mut boss = commands.spawn((
+        SpriteBundle {
+            texture: sprite_data.texture.clone(),
+            transform: Transform::from_xyz(0.0, config.logical_height * 0.3, 6.0),
+            sprite: Sprite {
+                color: Color::WHITE,
+                custom_size: Some(sprite_data.frame_size * sprite_data.scale),
                 ..default()
             },
-            TextureAtlas {
-                layout: sprite_data.layout.clone(),
-                index: sequence[0],
-            },
-            Enemy {
-                kind: EnemyKind::Boss,
-                health: max_health as i32,
-                score: EnemyKind::Boss.score_value(),
-                damage: 1,
-            },
-            BossControl {
-                phase: BossPhase::Entry,
-                direction: 1.0,
-                elapsed: 0.0,
-                fire_timer: 1.0,
-            },
-            ShipAnimation::new(ShipSpriteId::Boss, 0, 0.12),
-        ))
-        .id();
+            ..default()
+        },
+        TextureAtlas {
+            layout: sprite_data.layout.clone(),
+            index: sequence[0],
+        },
+        Enemy {
+            kind: EnemyKind::Boss,
+            health: max_health as i32,
+            max_health: max_health as i32,
+            score: EnemyKind::Boss.score_value(),
+            damage: 1,
+            wave: None,
+        },
+        BossControl {
+            phase: BossPhase::Entry,
+            direction: mirror,
+            mirror,
+            elapsed: 0.0,
+            fire_timer: 1.0,
+        },
+        ShipAnimation::new(&sprites, ShipSpriteId::Boss, ShipRow::Idle),
+        TransformInterpolation::default(),
+    ));
+    if let Some(cooldown) = EnemyKind::Boss.contact_cooldown() {
+        boss.insert(ContactCooldown::new(cooldown));
+    }
+    let entity = boss.id();
 
     state.active = true;
     state.entity = Some(entity);
     state.max_health = max_health;
     state.health = max_health;
+    state.name = BOSS_NAMES[director.level_index % BOSS_NAMES.len()];
+    state.phase = BossPhase::Entry;
     director.boss_active = true;
+    boss_events.send(BossEvent::Spawned);
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn boss_movement_and_attacks(
     mut queries: ParamSet<(
         Query<(&mut Transform, &mut BossControl, &Enemy)>,
@@ -137,8 +198,11 @@ fn boss_movement_and_attacks(
     time: Res<Time<Fixed>>,
     config: Res<GameConfig>,
     mut fire_writer: EventWriter<EnemyFireEvent>,
-    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    rank: Res<Rank>,
+    rules: Res<RuleSet>,
     boss_state: Res<BossState>,
+    mut boss_events: EventWriter<BossEvent>,
 ) {
     let player_x = queries
         .p1()
@@ -160,11 +224,15 @@ fn boss_movement_and_attacks(
     } else {
         1.0
     };
-    if ratio < 0.35 {
+    let previous_phase = control.phase;
+    if ratio < BOSS_PHASE_THRESHOLDS[1] {
         control.phase = BossPhase::Final;
-    } else if ratio < 0.65 {
+    } else if ratio < BOSS_PHASE_THRESHOLDS[0] {
         control.phase = BossPhase::Second;
     }
+    if control.phase != previous_phase {
+        boss_events.send(BossEvent::PhaseChanged(control.phase));
+    }
 
     match control.phase {
         BossPhase::Entry => {
@@ -179,7 +247,7 @@ fn boss_movement_and_attacks(
         }
         BossPhase::Second => {
             let amplitude = config.logical_width * 0.25;
-            transform.translation.x = amplitude * (control.elapsed * 0.8).sin();
+            transform.translation.x = control.mirror * amplitude * (control.elapsed * 0.8).sin();
             transform.translation.y =
                 config.logical_height * 0.2 + (control.elapsed * 1.6).cos() * 40.0;
         }
@@ -198,7 +266,7 @@ fn boss_movement_and_attacks(
             control.phase,
             transform.translation.truncate(),
             &mut fire_writer,
-            settings.difficulty.enemy_bullet_factor(),
+            run_difficulty.knobs().bullet_speed * rank.bullet_speed() * rules.enemy_bullet_speed,
         );
         control.fire_timer = match control.phase {
             BossPhase::Entry => 1.35,
@@ -244,16 +312,15 @@ fn fire_boss_pattern(
 
 fn boss_health_tracker(
     mut state: ResMut<BossState>,
-    boss_query: Query<(&Enemy, Entity), With<BossControl>>,
-    mut director: ResMut<WaveDirector>,
-    storyboard: Res<Storyboard>,
-    settings: Res<GameSettings>,
+    boss_query: Query<(&Enemy, &BossControl, Entity)>,
     mut audio: EventWriter<AudioCue>,
+    mut boss_events: EventWriter<BossEvent>,
 ) {
     match boss_query.get_single() {
-        Ok((enemy, entity)) => {
+        Ok((enemy, control, entity)) => {
             state.entity = Some(entity);
             state.health = enemy.health.max(0) as f32;
+            state.phase = control.phase;
         }
         Err(_) => {
             if state.active {
@@ -261,15 +328,56 @@ fn boss_health_tracker(
                 state.entity = None;
                 state.health = 0.0;
                 state.max_health = 0.0;
-                director.boss_active = false;
                 state.spawn_score += 2600;
-                advance_level(&mut director, &storyboard, &settings);
-                info!(
-                    "Boss defeated; advancing to level {}",
-                    director.level_index + 1
-                );
-                audio.send(AudioCue::UiSelect);
+                // Waves stay held (`boss_active`) until the outro hands over.
+                state.outro = Some(Timer::from_seconds(LEVEL_OUTRO_SECONDS, TimerMode::Once));
+                audio.send(AudioCue::new(Sfx::UiSelect));
+                boss_events.send(BossEvent::Defeated);
             }
         }
     }
 }
+
+// Clearing the storyboard's last level waits on the player's choice to loop or finish
+// instead of advancing.
+#[allow(clippy::too_many_arguments)]
+fn finish_level_outro(
+    mut state: ResMut<BossState>,
+    mut director: ResMut<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    run_difficulty: Res<RunDifficulty>,
+    demo: Option<Res<DemoPilot>>,
+    challenge: Option<Res<ChallengeRun>>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(outro) = state.outro.as_mut() else {
+        return;
+    };
+    if !outro.tick(time.delta()).finished() {
+        return;
+    }
+    state.outro = None;
+    director.boss_active = false;
+    if demo.is_some() {
+        next_state.set(AppState::Title);
+    } else if challenge.is_some_and(|challenge| {
+        challenge
+            .mode
+            .ends_after_level(director.level_index, storyboard.level_count())
+    }) {
+        info!("Challenge level cleared");
+        next_state.set(AppState::Victory);
+    } else if director.level_index + 1 >= storyboard.level_count() {
+        // The level-complete screen offers another loop or ends the run in victory.
+        info!("Final boss defeated; offering New Game+");
+        next_state.set(AppState::LevelComplete);
+    } else {
+        advance_level(&mut director, &storyboard, &run_difficulty);
+        info!(
+            "Boss defeated; advancing to level {}",
+            director.level_index + 1
+        );
+        next_state.set(AppState::LevelComplete);
+    }
+}
@@ -1,17 +1,447 @@
-use bevy::{prelude::*, render::camera::ScalingMode};
+use std::f32::consts::FRAC_PI_2;
 
-use super::config::GameConfig;
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode, Viewport},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+    window::{PrimaryWindow, WindowRef, WindowResized},
+};
+
+use super::{
+    boss::BossEvent,
+    config::{GameConfig, GameSettings, MIN_LOGICAL_WIDTH, ScreenLayout},
+    effects::ExplosionEvent,
+    player::{BombEvent, Player, PlayerHitEvent, PlayerWeaponState, WeaponMode},
+    states::{AppState, InRun},
+    weapons::PlayerFireEvent,
+};
+
+const SHAKE_DECAY_PER_SECOND: f32 = 1.4;
+const SHAKE_MAX_OFFSET: f32 = 16.0;
+const SHAKE_MAX_ROLL: f32 = 0.035;
+// Recoil from a laser volley and from a bomb, pushing the view down.
+const LASER_KICK: f32 = 1.2;
+const BOMB_KICK: f32 = 5.0;
+const KICK_MAX_OFFSET: f32 = 6.0;
+// How fast a kick springs back; it halves in roughly 1/KICK_RECOVERY seconds.
+const KICK_RECOVERY: f32 = 14.0;
+// The most the camera drifts toward the ship at the edge of the playfield.
+const FOLLOW_MAX_OFFSET: f32 = 6.0;
+const FOLLOW_RATE: f32 = 3.0;
+// (projection scale, seconds held) for a boss's arrival and its death slow motion.
+// Above 1 pulls the view back.
+const BOSS_ENTRANCE_ZOOM: (f32, f32) = (1.12, 2.0);
+const BOSS_DEATH_ZOOM: (f32, f32) = (0.88, 0.8);
+const ZOOM_RATE: f32 = 3.0;
+// Nothing is drawn on this layer; the bar camera only clears the window to black.
+const LETTERBOX_LAYER: usize = 31;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_main_camera);
+        app.init_resource::<CameraShake>()
+            .init_resource::<PlayfieldViewport>()
+            .init_resource::<CameraMotion>()
+            .init_resource::<CameraZoom>()
+            .add_systems(Startup, spawn_main_camera)
+            .add_systems(PostUpdate, fit_camera_viewport)
+            .add_systems(
+                Update,
+                (
+                    feed_camera_shake,
+                    feed_camera_kick,
+                    apply_camera_motion,
+                    apply_camera_zoom,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(InRun), reset_camera_motion);
     }
 }
 
+#[derive(Component)]
+pub struct MainCamera;
+
+// Clears the window around the playfield, and draws the side panels and the turned
+// tate playfield.
+#[derive(Component)]
+pub struct LetterboxCamera;
+
+#[derive(Component)]
+struct TatePresenter;
+
+// Where the playfield sits in the window, in physical pixels, as it's drawn there (so on
+// its side in tate).
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct PlayfieldViewport {
+    pub window: UVec2,
+    pub position: UVec2,
+    pub size: UVec2,
+    pub scale_factor: f32,
+}
+
+// A brief change of framing that holds for a moment, then eases back to the standard
+// scale of 1.
+#[derive(Resource, Debug)]
+pub struct CameraZoom {
+    pub scale: f32,
+    target: f32,
+    hold: f32,
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            target: 1.0,
+            hold: 0.0,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct CameraShake {
+    pub trauma: f32,
+    elapsed: f32,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+// The calmer movements layered under the shake: recoil from heavy weapons, and a slight
+// lean toward the ship's side of the playfield.
+#[derive(Resource, Debug, Default)]
+pub struct CameraMotion {
+    pub kick: Vec2,
+    pub follow: f32,
+}
+
 fn spawn_main_camera(mut commands: Commands, config: Res<GameConfig>) {
     let mut camera = Camera2dBundle::default();
     camera.projection.scaling_mode = ScalingMode::FixedVertical(config.logical_height);
-    commands.spawn(camera);
+    commands.spawn((camera, MainCamera, IsDefaultUiCamera));
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -1,
+                clear_color: ClearColorConfig::Custom(Color::BLACK),
+                ..default()
+            },
+            ..default()
+        },
+        RenderLayers::layer(LETTERBOX_LAYER),
+        LetterboxCamera,
+    ));
+}
+
+// Fits the playfield to the window for the chosen layout: widening or narrowing it with
+// the window's aspect ratio within the supported range, or holding a fixed aspect, and
+// letterboxing anything beyond it. In tate the main camera draws to an image that the
+// letterbox camera shows turned a quarter round. The UI scales to match.
+#[allow(clippy::too_many_arguments)]
+fn fit_camera_viewport(
+    mut commands: Commands,
+    mut config: ResMut<GameConfig>,
+    settings: Res<GameSettings>,
+    mut resized: EventReader<WindowResized>,
+    windows: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Camera, (With<MainCamera>, Without<LetterboxCamera>)>,
+    mut letterbox: Query<&mut Camera, With<LetterboxCamera>>,
+    mut presenters: Query<(Entity, &mut Sprite, &Handle<Image>), With<TatePresenter>>,
+    mut images: ResMut<Assets<Image>>,
+    mut ui_scale: ResMut<UiScale>,
+    mut playfield: ResMut<PlayfieldViewport>,
+) {
+    let resized = resized.read().count() > 0;
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok(mut camera) = cameras.get_single_mut() else {
+        return;
+    };
+    let fitted = playfield.window != UVec2::ZERO;
+    if !resized && !window.is_changed() && !settings.is_changed() && fitted {
+        return;
+    }
+
+    let physical = UVec2::new(window.physical_width(), window.physical_height());
+    if physical.x == 0 || physical.y == 0 {
+        return;
+    }
+    let tate = settings.screen_layout == ScreenLayout::Tate;
+    let available = if tate { physical.yx() } else { physical }.as_vec2();
+    let width = settings
+        .screen_layout
+        .logical_width(config.logical_height, available.x / available.y);
+    if (config.logical_width - width).abs() > f32::EPSILON {
+        config.logical_width = width;
+    }
+    let aspect = config.logical_width / config.logical_height;
+    let size = if available.x / available.y > aspect {
+        Vec2::new(available.y * aspect, available.y)
+    } else {
+        Vec2::new(available.x, available.x / aspect)
+    }
+    .round()
+    .as_uvec2()
+    .max(UVec2::ONE);
+    let drawn = if tate { size.yx() } else { size };
+    let viewport = PlayfieldViewport {
+        window: physical,
+        position: (physical - drawn) / 2,
+        size: drawn,
+        scale_factor: window.scale_factor(),
+    };
+    if *playfield != viewport {
+        *playfield = viewport;
+    }
+
+    let order = if tate { 1 } else { -1 };
+    for mut camera in &mut letterbox {
+        if camera.order != order {
+            camera.order = order;
+        }
+    }
+    if tate {
+        let extent = Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        };
+        let logical = drawn.as_vec2() / window.scale_factor();
+        // The sprite is turned, so it's sized upright.
+        let sprite_size = logical.yx();
+        let handle = match presenters.get_single_mut() {
+            Ok((_, mut sprite, handle)) => {
+                if let Some(image) = images.get_mut(handle)
+                    && image.texture_descriptor.size != extent
+                {
+                    image.resize(extent);
+                }
+                if sprite.custom_size != Some(sprite_size) {
+                    sprite.custom_size = Some(sprite_size);
+                }
+                handle.clone()
+            }
+            Err(_) => {
+                let handle = images.add(build_playfield_image(extent));
+                commands.spawn((
+                    SpriteBundle {
+                        texture: handle.clone(),
+                        transform: Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+                        sprite: Sprite {
+                            custom_size: Some(sprite_size),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    RenderLayers::layer(LETTERBOX_LAYER),
+                    TatePresenter,
+                ));
+                handle
+            }
+        };
+        if camera.viewport.is_some() {
+            camera.viewport = None;
+        }
+        if !matches!(&camera.target, RenderTarget::Image(current) if *current == handle) {
+            camera.target = RenderTarget::Image(handle);
+        }
+    } else {
+        for (entity, ..) in &presenters {
+            commands.entity(entity).despawn_recursive();
+        }
+        if !matches!(camera.target, RenderTarget::Window(WindowRef::Primary)) {
+            camera.target = RenderTarget::Window(WindowRef::Primary);
+        }
+        if camera
+            .viewport
+            .as_ref()
+            .map(|current| (current.physical_position, current.physical_size))
+            != Some((viewport.position, viewport.size))
+        {
+            camera.viewport = Some(Viewport {
+                physical_position: viewport.position,
+                physical_size: viewport.size,
+                ..default()
+            });
+        }
+    }
+
+    // Images have no scale factor of their own. A playfield narrower than 4:3 keeps the
+    // UI at the 4:3 width so menus still fit across it.
+    let pixels = size.as_vec2() / if tate { 1.0 } else { window.scale_factor() };
+    let scale = (pixels.y / config.logical_height).min(pixels.x / MIN_LOGICAL_WIDTH);
+    if (ui_scale.0 - scale).abs() > f32::EPSILON {
+        ui_scale.0 = scale;
+    }
+}
+
+fn build_playfield_image(size: Extent3d) -> Image {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("tate_playfield"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    image
+}
+
+fn feed_camera_shake(
+    mut shake: ResMut<CameraShake>,
+    mut explosions: EventReader<ExplosionEvent>,
+    mut player_hits: EventReader<PlayerHitEvent>,
+    mut boss_events: EventReader<BossEvent>,
+) {
+    for event in explosions.read() {
+        if event.large {
+            shake.add_trauma(0.3);
+        }
+    }
+    for hit in player_hits.read() {
+        shake.add_trauma(0.25 + 0.1 * hit.damage as f32);
+    }
+    for event in boss_events.read() {
+        let amount = match event {
+            BossEvent::Spawned => 0.4,
+            BossEvent::PhaseChanged(_) => 0.6,
+            BossEvent::Defeated => 0.9,
+        };
+        shake.add_trauma(amount);
+    }
+}
+
+// A laser volley fires two shots at once, so kicks count volleys rather than shots.
+fn feed_camera_kick(
+    mut motion: ResMut<CameraMotion>,
+    mut shots: EventReader<PlayerFireEvent>,
+    mut bombs: EventReader<BombEvent>,
+    weapon: Res<PlayerWeaponState>,
+) {
+    let fired = shots.read().count() > 0;
+    let mut kick = 0.0;
+    if fired && weapon.mode == WeaponMode::Laser {
+        kick += LASER_KICK;
+    }
+    kick += BOMB_KICK * bombs.read().count() as f32;
+    motion.kick = (motion.kick - Vec2::Y * kick).clamp_length_max(KICK_MAX_OFFSET);
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_camera_motion(
+    mut shake: ResMut<CameraShake>,
+    mut motion: ResMut<CameraMotion>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    player: Query<&Transform, (With<Player>, Without<MainCamera>)>,
+    mut cameras: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Ok(mut transform) = cameras.get_single_mut() else {
+        return;
+    };
+
+    let delta = time.delta_seconds();
+    shake.elapsed += delta;
+    shake.trauma = (shake.trauma - SHAKE_DECAY_PER_SECOND * delta).max(0.0);
+    motion.kick *= (-KICK_RECOVERY * delta).exp();
+    let lean = player.get_single().map_or(0.0, |player| {
+        (player.translation.x / (config.logical_width * 0.5)).clamp(-1.0, 1.0)
+    });
+    motion.follow += (lean * FOLLOW_MAX_OFFSET - motion.follow) * (FOLLOW_RATE * delta).min(1.0);
+
+    if settings.reduce_motion {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        transform.rotation = Quat::IDENTITY;
+        return;
+    }
+
+    let strength = shake.trauma * shake.trauma * settings.screen_shake.factor();
+    let t = shake.elapsed;
+    let offset = Vec2::new(
+        (t * 47.0).sin() + (t * 83.0).sin() * 0.5,
+        (t * 59.0).cos() + (t * 97.0).sin() * 0.5,
+    ) / 1.5
+        * SHAKE_MAX_OFFSET
+        * strength;
+    let roll = (t * 71.0).sin() * SHAKE_MAX_ROLL * strength;
+
+    transform.translation.x = offset.x + motion.kick.x + motion.follow;
+    transform.translation.y = offset.y + motion.kick.y;
+    transform.rotation = Quat::from_rotation_z(roll);
+}
+
+// Runs on real time, so the death zoom isn't drawn out by the slow motion it frames.
+fn apply_camera_zoom(
+    mut zoom: ResMut<CameraZoom>,
+    mut boss_events: EventReader<BossEvent>,
+    settings: Res<GameSettings>,
+    time: Res<Time<Real>>,
+    mut cameras: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    for event in boss_events.read() {
+        let (target, hold) = match event {
+            BossEvent::Spawned => BOSS_ENTRANCE_ZOOM,
+            BossEvent::Defeated => BOSS_DEATH_ZOOM,
+            BossEvent::PhaseChanged(_) => continue,
+        };
+        zoom.target = target;
+        zoom.hold = hold;
+    }
+
+    let delta = time.delta_seconds();
+    zoom.hold = (zoom.hold - delta).max(0.0);
+    let target = if zoom.hold > 0.0 { zoom.target } else { 1.0 };
+    zoom.scale += (target - zoom.scale) * (ZOOM_RATE * delta).min(1.0);
+    if (zoom.scale - 1.0).abs() < 0.001 && zoom.hold <= 0.0 {
+        zoom.scale = 1.0;
+    }
+    let scale = if settings.reduce_motion {
+        1.0
+    } else {
+        zoom.scale
+    };
+    for mut projection in &mut cameras {
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+    }
+}
+
+fn reset_camera_motion(
+    mut shake: ResMut<CameraShake>,
+    mut motion: ResMut<CameraMotion>,
+    mut zoom: ResMut<CameraZoom>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    shake.trauma = 0.0;
+    *motion = CameraMotion::default();
+    *zoom = CameraZoom::default();
+    for (mut transform, mut projection) in &mut cameras {
+        projection.scale = 1.0;
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        transform.rotation = Quat::IDENTITY;
+    }
 }
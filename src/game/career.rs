@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    boss::BossEvent,
+    enemies::EnemyKind,
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    menu::{MenuActivated, MenuFocus, MenuSet, clear_menu_focus, spawn_text_button},
+    practice::PracticeRun,
+    profiles::CurrentProfile,
+    run_stats::RunStats,
+    savegame::PendingResume,
+    states::{AppState, InRun},
+    storage::{load_json, save_json},
+    typography::{TextSize, UiFont},
+    unlocks::{Unlocks, spawn_unlock_list},
+};
+
+pub const CAREER_FILE: &str = "career.json";
+
+pub struct CareerPlugin;
+
+impl Plugin for CareerPlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(CareerStats::load(&profile))
+            .add_systems(
+                OnEnter(InRun),
+                count_career_game.run_if(
+                    not(resource_exists::<DemoPilot>)
+                        .and_then(not(resource_exists::<PracticeRun>))
+                        .and_then(not(resource_exists::<PendingResume>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                count_career_bosses.run_if(
+                    in_state(AppState::Playing)
+                        .and_then(not(resource_exists::<DemoPilot>))
+                        .and_then(not(resource_exists::<PracticeRun>)),
+                ),
+            )
+            .add_systems(
+                OnExit(InRun),
+                record_career_run.run_if(
+                    not(resource_exists::<DemoPilot>).and_then(not(resource_exists::<PracticeRun>)),
+                ),
+            )
+            .add_systems(OnEnter(AppState::CareerStats), spawn_career_screen)
+            .add_systems(
+                Update,
+                career_screen_input
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::CareerStats)),
+            )
+            .add_systems(
+                OnExit(AppState::CareerStats),
+                (cleanup_career_screen, clear_menu_focus),
+            );
+    }
+}
+
+// Lifetime totals for the current profile, folded in as each run ends.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CareerStats {
+    pub games_played: u32,
+    pub kills: HashMap<EnemyKind, u32>,
+    pub bosses_defeated: u32,
+    pub play_time: f64,
+    pub best_combo: u32,
+}
+
+impl CareerStats {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        load_json(&profile.data_path(CAREER_FILE))
+    }
+
+    fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.data_path(CAREER_FILE), self);
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    fn summary(&self) -> Vec<(String, String)> {
+        let seconds = self.play_time as u64;
+        let mut rows = vec![
+            ("Games played".to_string(), self.games_played.to_string()),
+            (
+                "Play time".to_string(),
+                format!(
+                    "{}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                ),
+            ),
+            (
+                "Bosses defeated".to_string(),
+                self.bosses_defeated.to_string(),
+            ),
+            ("Best combo".to_string(), self.best_combo.to_string()),
+            (
+                "Enemies destroyed".to_string(),
+                self.total_kills().to_string(),
+            ),
+        ];
+        for kind in EnemyKind::ALL {
+            let count = self.kills.get(&kind).copied().unwrap_or(0);
+            rows.push((format!("  {}", kind.label()), count.to_string()));
+        }
+        rows
+    }
+}
+
+#[derive(Component)]
+struct CareerScreen;
+
+fn count_career_game(mut career: ResMut<CareerStats>) {
+    career.games_played += 1;
+}
+
+fn count_career_bosses(mut career: ResMut<CareerStats>, mut boss_events: EventReader<BossEvent>) {
+    let defeated = boss_events
+        .read()
+        .filter(|event| **event == BossEvent::Defeated)
+        .count();
+    if defeated > 0 {
+        career.bosses_defeated += defeated as u32;
+    }
+}
+
+// Runs however the run ends: game over, victory, restart, quit, or save & quit.
+fn record_career_run(
+    mut career: ResMut<CareerStats>,
+    run_stats: Res<RunStats>,
+    profile: Res<CurrentProfile>,
+) {
+    for (&kind, &count) in &run_stats.kills {
+        *career.kills.entry(kind).or_default() += count;
+    }
+    career.play_time += f64::from(run_stats.time_survived);
+    career.best_combo = career.best_combo.max(run_stats.max_combo);
+    career.save(&profile);
+}
+
+fn spawn_career_screen(
+    mut commands: Commands,
+    career: Res<CareerStats>,
+    unlocks: Res<Unlocks>,
+    profile: Res<CurrentProfile>,
+    font: Res<UiFont>,
+) {
+    commands.insert_resource(MenuFocus::new(1));
+    let row_style = font.style(TextSize::Body, Color::srgb(0.8, 0.9, 1.0));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ..default()
+            },
+            CareerScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("Career - {}", profile.label()),
+                font.style(TextSize::Heading, Color::WHITE),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(48.0),
+                        align_items: AlignItems::Start,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|columns| {
+                    columns.spawn(TextBundle::from_sections(career.summary().into_iter().map(
+                        |(label, value)| {
+                            TextSection::new(format!("{label:<20}{value:>10}\n"), row_style.clone())
+                        },
+                    )));
+                    spawn_unlock_list(columns, &unlocks, row_style.clone());
+                });
+            spawn_text_button(
+                parent,
+                0,
+                "Back",
+                280.0,
+                font.style(TextSize::Body, Color::WHITE),
+                (),
+            );
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    font.style(TextSize::Caption, Color::srgb(0.7, 0.8, 0.9)),
+                ),
+                DevicePrompt::new("Enter / Esc back", "A / B back"),
+            ));
+        });
+}
+
+fn career_screen_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut activated: EventReader<MenuActivated>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    let back = keys.just_pressed(KeyCode::Escape)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East);
+    if back {
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+    if back || activated.read().count() > 0 {
+        next_state.set(AppState::Title);
+    }
+}
+
+fn cleanup_career_screen(mut commands: Commands, query: Query<Entity, With<CareerScreen>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,739 @@
+use std::{cmp::Reverse, time::Duration};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    audio::{AudioCue, Sfx},
+    boss::{BossEvent, BossState},
+    config::RunDifficulty,
+    highscores::today,
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    menu::{MenuActivated, MenuFocus, MenuSet, clear_menu_focus, menu_button, spawn_text_button},
+    player::WeaponMode,
+    profiles::CurrentProfile,
+    rules::RuleSet,
+    run_timer::{RunTimer, format_split},
+    spawn::{Storyboard, WaveDirector, jump_to_wave},
+    states::{AppState, InRun},
+    storage::{load_json, save_json},
+    typography::{TextSize, UiFont},
+    ui::ScoreBoard,
+    unlocks::{LOCK_COLOR, Unlock, Unlocks, spawn_lock_icon},
+};
+
+pub const CHALLENGE_FILE: &str = "challenges.json";
+const SCORE_ATTACK_LIMIT: Duration = Duration::from_secs(180);
+const MAX_ENTRIES: usize = 10;
+// The countdown turns red for the last stretch of a score attack.
+const WARNING_SECONDS: u64 = 10;
+const TIMER_COLOR: Color = Color::srgb(1.0, 0.95, 0.7);
+const WARNING_COLOR: Color = Color::srgb(1.0, 0.35, 0.3);
+const TABLE_HEADING_COLOR: Color = Color::srgb(1.0, 0.85, 0.4);
+const BADGE_MISSING_COLOR: Color = Color::srgb(0.5, 0.55, 0.65);
+
+pub struct ChallengePlugin;
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(ChallengeScores::load(&profile))
+            .init_resource::<RuleSet>()
+            .add_systems(OnEnter(AppState::Challenges), spawn_challenge_menu)
+            .add_systems(
+                Update,
+                challenge_menu_input
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::Challenges)),
+            )
+            .add_systems(
+                OnExit(AppState::Challenges),
+                (cleanup_challenge_ui::<ChallengeMenu>, clear_menu_focus),
+            )
+            .add_systems(
+                OnEnter(InRun),
+                (rearm_challenge_run, spawn_challenge_timer)
+                    .run_if(resource_exists::<ChallengeRun>),
+            )
+            .add_systems(
+                Update,
+                (apply_challenge_run, run_challenge_clock)
+                    .chain()
+                    .run_if(in_state(AppState::Playing).and_then(resource_exists::<ChallengeRun>)),
+            )
+            .add_systems(
+                Update,
+                update_challenge_timer
+                    .run_if(in_state(InRun).and_then(resource_exists::<ChallengeRun>)),
+            )
+            .add_systems(
+                OnExit(InRun),
+                (
+                    record_challenge_result.run_if(resource_exists::<ChallengeRun>),
+                    cleanup_challenge_ui::<ChallengeTimer>,
+                ),
+            )
+            .add_systems(OnEnter(AppState::Title), end_challenge);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeMode {
+    // Three minutes on the clock; the score when it runs out is ranked.
+    ScoreAttack,
+    // Level 1 and its boss as fast as possible; the clear time is ranked.
+    TimeAttack,
+    // Level 1 and its boss under the mission's rules, for a badge.
+    Mission(Mission),
+    // Every level's boss, each warping in as its level starts; the total time is ranked.
+    BossRush,
+}
+
+impl ChallengeMode {
+    // The modes with a leaderboard.
+    const RANKED: [ChallengeMode; 3] = [
+        ChallengeMode::ScoreAttack,
+        ChallengeMode::TimeAttack,
+        ChallengeMode::BossRush,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChallengeMode::ScoreAttack => "Score Attack",
+            ChallengeMode::TimeAttack => "Time Attack",
+            ChallengeMode::Mission(mission) => mission.label(),
+            ChallengeMode::BossRush => "Boss Rush",
+        }
+    }
+
+    // Whether beating this level's boss ends the run.
+    pub fn ends_after_level(self, level_index: usize, level_count: usize) -> bool {
+        match self {
+            ChallengeMode::ScoreAttack => false,
+            ChallengeMode::BossRush => level_index + 1 >= level_count,
+            _ => true,
+        }
+    }
+
+    // Ranked by clear time, fastest first, rather than by score.
+    fn is_timed(self) -> bool {
+        matches!(self, ChallengeMode::TimeAttack | ChallengeMode::BossRush)
+    }
+
+    fn format(self, value: u64) -> String {
+        if self.is_timed() {
+            format_split(Duration::from_millis(value))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mission {
+    NoPowerups,
+    OneLife,
+    LaserOnly,
+    FastBullets,
+}
+
+impl Mission {
+    const ALL: [Mission; 4] = [
+        Mission::NoPowerups,
+        Mission::OneLife,
+        Mission::LaserOnly,
+        Mission::FastBullets,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mission::NoPowerups => "No Power-ups",
+            Mission::OneLife => "One Life",
+            Mission::LaserOnly => "Laser Only",
+            Mission::FastBullets => "Bullets 2x Speed",
+        }
+    }
+
+    pub fn rules(self) -> RuleSet {
+        let normal = RuleSet::default();
+        match self {
+            Mission::NoPowerups => RuleSet {
+                powerups: false,
+                ..normal
+            },
+            Mission::OneLife => RuleSet {
+                lives: Some(1),
+                ..normal
+            },
+            Mission::LaserOnly => RuleSet {
+                weapon: Some(WeaponMode::Laser),
+                ..normal
+            },
+            Mission::FastBullets => RuleSet {
+                enemy_bullet_speed: 2.0,
+                ..normal
+            },
+        }
+    }
+}
+
+// Present for the whole of a challenge session, restarts and Play Again included, and
+// cleared on returning to the title. Challenge runs rank on their own tables instead of
+// the high score table, and can't be suspended.
+#[derive(Resource, Debug)]
+pub struct ChallengeRun {
+    pub mode: ChallengeMode,
+    applied: bool,
+    timed_out: bool,
+    cleared: Option<Duration>,
+    // The ranked value and its place on the table, once the run has ended.
+    result: Option<(u64, Option<usize>)>,
+    // The last level a boss rush called its boss in on.
+    summoned: Option<usize>,
+}
+
+impl ChallengeRun {
+    fn new(mode: ChallengeMode) -> Self {
+        Self {
+            mode,
+            applied: false,
+            timed_out: false,
+            cleared: None,
+            result: None,
+            summoned: None,
+        }
+    }
+
+    pub fn headline(&self) -> &'static str {
+        match self.mode {
+            ChallengeMode::ScoreAttack if self.timed_out => "Time Up!",
+            ChallengeMode::TimeAttack if self.cleared.is_some() => "Level Clear!",
+            ChallengeMode::BossRush if self.cleared.is_some() => "Boss Rush Clear!",
+            ChallengeMode::Mission(_) if self.cleared.is_some() => "Mission Complete!",
+            ChallengeMode::Mission(_) => "Mission Failed",
+            _ => "Game Over",
+        }
+    }
+
+    pub fn result_line(&self) -> String {
+        if let ChallengeMode::Mission(mission) = self.mode {
+            return match self.cleared {
+                Some(_) => format!("{} badge earned", mission.label()),
+                None => format!("Clear level 1 to earn the {} badge", mission.label()),
+            };
+        }
+        match self.result {
+            Some((value, Some(rank))) => format!(
+                "{}: {} - #{} on the table",
+                self.mode.label(),
+                self.mode.format(value),
+                rank + 1
+            ),
+            Some((value, None)) => format!("{}: {}", self.mode.label(), self.mode.format(value)),
+            None => format!("{}: no clear", self.mode.label()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeEntry {
+    pub name: String,
+    // Score for score attack, clear time in milliseconds for time attack.
+    pub value: u64,
+    pub date: String,
+}
+
+// A profile's challenge leaderboards, kept apart from the main high score table, and
+// the missions it has completed.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChallengeScores {
+    pub score_attack: Vec<ChallengeEntry>,
+    pub time_attack: Vec<ChallengeEntry>,
+    pub boss_rush: Vec<ChallengeEntry>,
+    pub badges: Vec<Mission>,
+}
+
+impl ChallengeScores {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        let mut scores: Self = load_json(&profile.data_path(CHALLENGE_FILE));
+        for mode in ChallengeMode::RANKED {
+            scores.sort(mode);
+        }
+        scores
+    }
+
+    fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.data_path(CHALLENGE_FILE), self);
+    }
+
+    pub fn table(&self, mode: ChallengeMode) -> &[ChallengeEntry] {
+        match mode {
+            ChallengeMode::ScoreAttack => &self.score_attack,
+            ChallengeMode::TimeAttack => &self.time_attack,
+            ChallengeMode::BossRush => &self.boss_rush,
+            ChallengeMode::Mission(_) => &[],
+        }
+    }
+
+    fn table_mut(&mut self, mode: ChallengeMode) -> Option<&mut Vec<ChallengeEntry>> {
+        match mode {
+            ChallengeMode::ScoreAttack => Some(&mut self.score_attack),
+            ChallengeMode::TimeAttack => Some(&mut self.time_attack),
+            ChallengeMode::BossRush => Some(&mut self.boss_rush),
+            ChallengeMode::Mission(_) => None,
+        }
+    }
+
+    pub fn has_badge(&self, mission: Mission) -> bool {
+        self.badges.contains(&mission)
+    }
+
+    // Highest score first for score attack, fastest time first for the timed modes.
+    fn sort(&mut self, mode: ChallengeMode) {
+        let Some(table) = self.table_mut(mode) else {
+            return;
+        };
+        if mode.is_timed() {
+            table.sort_by_key(|entry| entry.value);
+        } else {
+            table.sort_by_key(|entry| Reverse(entry.value));
+        }
+        table.truncate(MAX_ENTRIES);
+    }
+
+    // Returns the entry's place if it made the table.
+    fn insert(&mut self, mode: ChallengeMode, entry: ChallengeEntry) -> Option<usize> {
+        let beats = |existing: &ChallengeEntry| {
+            if mode.is_timed() {
+                entry.value < existing.value
+            } else {
+                entry.value > existing.value
+            }
+        };
+        let table = self.table_mut(mode)?;
+        let rank = table.iter().position(beats).unwrap_or(table.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        table.insert(rank, entry);
+        table.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+// A leaderboard for the ranked modes, or the badge list for a mission.
+pub fn spawn_challenge_table(
+    parent: &mut ChildBuilder,
+    scores: &ChallengeScores,
+    mode: ChallengeMode,
+    rows: usize,
+    style: TextStyle,
+) {
+    if let ChallengeMode::Mission(_) = mode {
+        spawn_badge_list(parent, scores, style);
+        return;
+    }
+    let mut sections = vec![TextSection::new(
+        format!("{}\n", mode.label().to_uppercase()),
+        TextStyle {
+            color: TABLE_HEADING_COLOR,
+            ..style.clone()
+        },
+    )];
+    let table = scores.table(mode);
+    if table.is_empty() {
+        sections.push(TextSection::new("No entries yet", style));
+    } else {
+        for (index, entry) in table.iter().take(rows).enumerate() {
+            sections.push(TextSection::new(
+                format!(
+                    "{:>2}. {:<8} {:>9}  {}\n",
+                    index + 1,
+                    entry.name,
+                    mode.format(entry.value),
+                    entry.date
+                ),
+                style.clone(),
+            ));
+        }
+    }
+    parent.spawn(TextBundle::from_sections(sections));
+}
+
+fn spawn_badge_list(parent: &mut ChildBuilder, scores: &ChallengeScores, style: TextStyle) {
+    let mut sections = vec![TextSection::new(
+        "BADGES\n",
+        TextStyle {
+            color: TABLE_HEADING_COLOR,
+            ..style.clone()
+        },
+    )];
+    for mission in Mission::ALL {
+        let (mark, color) = if scores.has_badge(mission) {
+            ("[x]", TABLE_HEADING_COLOR)
+        } else {
+            ("[ ]", BADGE_MISSING_COLOR)
+        };
+        sections.push(TextSection::new(
+            format!("{} {}\n", mark, mission.label()),
+            TextStyle {
+                color,
+                ..style.clone()
+            },
+        ));
+    }
+    parent.spawn(TextBundle::from_sections(sections));
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum ChallengeOption {
+    Start(ChallengeMode),
+    Back,
+}
+
+#[derive(Component)]
+struct ChallengeMenu;
+
+#[derive(Component)]
+struct ChallengeTimer;
+
+fn spawn_challenge_menu(
+    mut commands: Commands,
+    scores: Res<ChallengeScores>,
+    unlocks: Res<Unlocks>,
+    font: Res<UiFont>,
+) {
+    let mut options = vec![
+        ChallengeOption::Start(ChallengeMode::ScoreAttack),
+        ChallengeOption::Start(ChallengeMode::TimeAttack),
+        ChallengeOption::Start(ChallengeMode::BossRush),
+    ];
+    options.extend(
+        Mission::ALL.map(|mission| ChallengeOption::Start(ChallengeMode::Mission(mission))),
+    );
+    options.push(ChallengeOption::Back);
+    commands.insert_resource(MenuFocus::new(options.len()));
+    let option_style = font.style(TextSize::Body, Color::WHITE);
+    let table_style = font.style(TextSize::Caption, Color::srgb(0.8, 0.85, 1.0));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ..default()
+            },
+            ChallengeMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Challenges",
+                font.style(TextSize::Heading, Color::WHITE),
+            ));
+            for (index, option) in options.into_iter().enumerate() {
+                let label = match option {
+                    ChallengeOption::Start(ChallengeMode::ScoreAttack) => {
+                        "Score Attack - 3 minutes, highest score".to_string()
+                    }
+                    ChallengeOption::Start(ChallengeMode::TimeAttack) => {
+                        "Time Attack - clear level 1 fastest".to_string()
+                    }
+                    ChallengeOption::Start(ChallengeMode::BossRush) => {
+                        if !unlocks.has(Unlock::BossRush) {
+                            spawn_locked_option(parent, index, option, &option_style);
+                            continue;
+                        }
+                        "Boss Rush - every boss back to back".to_string()
+                    }
+                    ChallengeOption::Start(ChallengeMode::Mission(mission)) => {
+                        let done = if scores.has_badge(mission) {
+                            " - Badge earned"
+                        } else {
+                            ""
+                        };
+                        format!("Mission: {}{}", mission.label(), done)
+                    }
+                    ChallengeOption::Back => "Back".to_string(),
+                };
+                spawn_text_button(parent, index, &label, 640.0, option_style.clone(), option);
+            }
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(64.0),
+                        align_items: AlignItems::Start,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|columns| {
+                    for mode in ChallengeMode::RANKED {
+                        spawn_challenge_table(columns, &scores, mode, 5, table_style.clone());
+                    }
+                    spawn_badge_list(columns, &scores, table_style.clone());
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    font.style(TextSize::Caption, Color::srgb(0.7, 0.8, 0.9)),
+                ),
+                DevicePrompt::new("Enter start  |  Esc back", "A start  |  B back"),
+            ));
+        });
+}
+
+// Still selectable for focus, but shows what it takes to unlock rather than starting.
+fn spawn_locked_option(
+    parent: &mut ChildBuilder,
+    index: usize,
+    option: ChallengeOption,
+    style: &TextStyle,
+) {
+    parent
+        .spawn((
+            menu_button(
+                index,
+                Style {
+                    width: Val::Px(640.0),
+                    padding: UiRect::vertical(Val::Px(6.0)),
+                    column_gap: Val::Px(10.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ),
+            option,
+        ))
+        .with_children(|button| {
+            spawn_lock_icon(button);
+            button.spawn(TextBundle::from_section(
+                format!("Boss Rush - {}", Unlock::BossRush.requirement()),
+                TextStyle {
+                    color: LOCK_COLOR,
+                    ..style.clone()
+                },
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn challenge_menu_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut activated: EventReader<MenuActivated>,
+    options: Query<&ChallengeOption>,
+    unlocks: Res<Unlocks>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if keys.just_pressed(KeyCode::Escape)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East)
+    {
+        audio.send(AudioCue::new(Sfx::UiSelect));
+        next_state.set(AppState::Title);
+        return;
+    }
+
+    let chosen = activated
+        .read()
+        .filter_map(|event| options.get(event.entity).ok().copied())
+        .last();
+    match chosen {
+        Some(ChallengeOption::Start(ChallengeMode::BossRush)) if !unlocks.has(Unlock::BossRush) => {
+        }
+        Some(ChallengeOption::Start(mode)) => {
+            if let ChallengeMode::Mission(mission) = mode {
+                commands.insert_resource(mission.rules());
+            }
+            commands.insert_resource(ChallengeRun::new(mode));
+            next_state.set(AppState::Playing);
+        }
+        Some(ChallengeOption::Back) => next_state.set(AppState::Title),
+        None => {}
+    }
+}
+
+fn rearm_challenge_run(mut challenge: ResMut<ChallengeRun>) {
+    *challenge = ChallengeRun::new(challenge.mode);
+}
+
+fn spawn_challenge_timer(mut commands: Commands, font: Res<UiFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(80.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            ChallengeTimer,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                font.style(TextSize::Heading, TIMER_COLOR),
+            ));
+        });
+}
+
+// Every challenge starts on the storyboard's first level, whatever `--level` says.
+fn apply_challenge_run(
+    mut challenge: ResMut<ChallengeRun>,
+    mut director: ResMut<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    run_difficulty: Res<RunDifficulty>,
+) {
+    if challenge.applied {
+        return;
+    }
+    challenge.applied = true;
+    jump_to_wave(&mut director, &storyboard, &run_difficulty, 0, 0);
+    info!("{} started", challenge.mode.label());
+}
+
+// Both challenges run on the run timer, so pauses and slow motion are treated the same
+// as in a speedrun split.
+#[allow(clippy::too_many_arguments)]
+fn run_challenge_clock(
+    mut challenge: ResMut<ChallengeRun>,
+    timer: Res<RunTimer>,
+    director: Res<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    scoreboard: Res<ScoreBoard>,
+    mut boss: ResMut<BossState>,
+    mut boss_events: EventReader<BossEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    match challenge.mode {
+        ChallengeMode::ScoreAttack => {
+            if timer.total >= SCORE_ATTACK_LIMIT {
+                challenge.timed_out = true;
+                next_state.set(AppState::GameOver);
+            }
+        }
+        ChallengeMode::TimeAttack | ChallengeMode::Mission(_) => {
+            let defeated = boss_events
+                .read()
+                .any(|event| *event == BossEvent::Defeated);
+            if defeated && director.level_index == 0 && challenge.cleared.is_none() {
+                challenge.cleared = Some(timer.total);
+            }
+        }
+        ChallengeMode::BossRush => {
+            // Called in once per level; lowering the threshold every tick would bring the
+            // boss straight back during its outro.
+            if challenge.summoned != Some(director.level_index) {
+                challenge.summoned = Some(director.level_index);
+                boss.spawn_score = scoreboard.score;
+            }
+            let defeated = boss_events
+                .read()
+                .any(|event| *event == BossEvent::Defeated);
+            let last_level = director.level_index + 1 >= storyboard.level_count();
+            if defeated && last_level && challenge.cleared.is_none() {
+                challenge.cleared = Some(timer.total);
+            }
+        }
+    }
+}
+
+fn update_challenge_timer(
+    challenge: Res<ChallengeRun>,
+    timer: Res<RunTimer>,
+    roots: Query<&Children, With<ChallengeTimer>>,
+    mut texts: Query<&mut Text>,
+) {
+    if !timer.is_changed() && !challenge.is_changed() {
+        return;
+    }
+    let (shown, warning) = match challenge.mode {
+        ChallengeMode::ScoreAttack => {
+            let left = SCORE_ATTACK_LIMIT.saturating_sub(timer.total);
+            (left, left.as_secs() < WARNING_SECONDS)
+        }
+        _ => (challenge.cleared.unwrap_or(timer.total), false),
+    };
+    for children in &roots {
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) {
+                let section = &mut text.sections[0];
+                section.value = format_split(shown);
+                section.style.color = if warning { WARNING_COLOR } else { TIMER_COLOR };
+            }
+        }
+    }
+}
+
+// Runs before the game-over screen is built, so the screen can show the placing. The
+// state has already moved on, which tells a finished run from an abandoned one.
+fn record_challenge_result(
+    state: Res<State<AppState>>,
+    mut challenge: ResMut<ChallengeRun>,
+    mut scores: ResMut<ChallengeScores>,
+    scoreboard: Res<ScoreBoard>,
+    profile: Res<CurrentProfile>,
+) {
+    if !matches!(state.get(), AppState::GameOver | AppState::Victory) {
+        return;
+    }
+    // A ship lost between the boss kill and the victory screen doesn't count as a clear.
+    if *state.get() != AppState::Victory {
+        challenge.cleared = None;
+    }
+    let value = match challenge.mode {
+        ChallengeMode::ScoreAttack => Some(u64::from(scoreboard.score)),
+        ChallengeMode::Mission(mission) => {
+            if challenge.cleared.is_some() && !scores.has_badge(mission) {
+                scores.badges.push(mission);
+                scores.save(&profile);
+            }
+            return;
+        }
+        ChallengeMode::TimeAttack | ChallengeMode::BossRush => {
+            challenge.cleared.map(|time| time.as_millis() as u64)
+        }
+    };
+    let Some(value) = value else {
+        challenge.result = None;
+        return;
+    };
+    let rank = scores.insert(
+        challenge.mode,
+        ChallengeEntry {
+            name: profile.label(),
+            value,
+            date: today(),
+        },
+    );
+    if rank.is_some() {
+        scores.save(&profile);
+    }
+    challenge.result = Some((value, rank));
+}
+
+fn cleanup_challenge_ui<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn end_challenge(mut commands: Commands, mut rules: ResMut<RuleSet>) {
+    commands.remove_resource::<ChallengeRun>();
+    *rules = RuleSet::default();
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+#[cfg(debug_assertions)]
+use super::typography::{TextSize, UiFont};
+
+#[cfg(debug_assertions)]
+const WATERMARK_COLOR: Color = Color::srgba(1.0, 0.3, 0.3, 0.85);
+
+pub struct CheatsPlugin;
+
+impl Plugin for CheatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cheats>();
+        #[cfg(debug_assertions)]
+        app.add_systems(Startup, spawn_cheat_watermark)
+            .add_systems(Update, (toggle_cheats, update_cheat_watermark).chain());
+    }
+}
+
+// Development toggles, only switchable in debug builds. They last until the game
+// closes and carry over between runs.
+#[derive(Resource, Debug, Default)]
+pub struct Cheats {
+    // Hits and rams pass straight through the ship.
+    pub god_mode: bool,
+    pub infinite_bombs: bool,
+    // Holds the weapon at the laser with the fire rate maxed.
+    pub max_weapon: bool,
+    // Stops the wave timer, so no new waves arrive.
+    pub freeze_spawns: bool,
+}
+
+impl Cheats {
+    #[cfg(debug_assertions)]
+    fn active_labels(&self) -> Vec<&'static str> {
+        [
+            (self.god_mode, "GOD MODE"),
+            (self.infinite_bombs, "INFINITE BOMBS"),
+            (self.max_weapon, "MAX WEAPON"),
+            (self.freeze_spawns, "SPAWNS FROZEN"),
+        ]
+        .into_iter()
+        .filter_map(|(on, label)| on.then_some(label))
+        .collect()
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Component)]
+struct CheatWatermark;
+
+#[cfg(debug_assertions)]
+fn spawn_cheat_watermark(mut commands: Commands, font: Res<UiFont>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(8.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(50),
+            ..default()
+        })
+        .with_children(|parent| {
+            let text = TextBundle::from_section("", font.style(TextSize::Caption, WATERMARK_COLOR));
+            parent.spawn((
+                TextBundle {
+                    visibility: Visibility::Hidden,
+                    ..text
+                },
+                CheatWatermark,
+            ));
+        });
+}
+
+// Debug builds only: Ctrl+G god mode, Ctrl+B infinite bombs, Ctrl+U max weapon, and
+// Ctrl+T freezes the spawn timer.
+#[cfg(debug_assertions)]
+fn toggle_cheats(keys: Res<ButtonInput<KeyCode>>, mut cheats: ResMut<Cheats>) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let cheats = &mut *cheats;
+    for (key, flag, name) in [
+        (KeyCode::KeyG, &mut cheats.god_mode, "god mode"),
+        (KeyCode::KeyB, &mut cheats.infinite_bombs, "infinite bombs"),
+        (KeyCode::KeyU, &mut cheats.max_weapon, "max weapon"),
+        (KeyCode::KeyT, &mut cheats.freeze_spawns, "frozen spawns"),
+    ] {
+        if keys.just_pressed(key) {
+            *flag = !*flag;
+            info!("Debug: {} {}", name, if *flag { "on" } else { "off" });
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+fn update_cheat_watermark(
+    cheats: Res<Cheats>,
+    mut watermark: Query<(&mut Text, &mut Visibility), With<CheatWatermark>>,
+) {
+    if !cheats.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut visibility)) = watermark.get_single_mut() else {
+        return;
+    };
+    let labels = cheats.active_labels();
+    *visibility = if labels.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    text.sections[0].value = format!("DEV CHEATS: {}", labels.join(" / "));
+}
@@ -1,37 +1,138 @@
 use bevy::{
+    ecs::system::SystemParam,
     log::{info, warn},
     math::Vec3Swizzles,
     prelude::*,
 };
 
 use super::{
-    audio::AudioCue,
-    effects::ExplosionEvent,
-    enemies::{Enemy, EnemyKind},
-    player::{PLAYER_HIT_INVULNERABILITY, Player, PlayerDefense, PlayerLifeLostEvent, PlayerStats},
+    audio::{AudioCue, Sfx},
+    cheats::Cheats,
+    effects::{
+        EnemyDeathEvent, ExplosionEvent, ParticleBurst, ParticleBurstEvent, ScorePopupEvent,
+    },
+    enemies::{ContactCooldown, Enemy, EnemyKind},
+    player::{
+        BombEvent, PLAYER_HIT_INVULNERABILITY, Player, PlayerDefense, PlayerHitEvent,
+        PlayerLifeLostEvent, PlayerStats, REFLECT_SHIELD_RADIUS,
+    },
+    pool::release,
     powerups::{DropsPowerUp, SpawnPowerUpEvent},
+    run_stats::RunStats,
     states::AppState,
     ui::ScoreBoard,
-    weapons::{EnemyProjectile, Projectile},
+    weapons::{EnemyProjectile, PlayerFireEvent, Projectile},
 };
 
+const BOMB_DAMAGE: i32 = 8;
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyDestroyedEvent {
+    pub kind: EnemyKind,
+    pub position: Vec2,
+    pub by_bomb: bool,
+    pub wave: Option<u32>,
+}
+
+// Sent for every hit an enemy survives, for hit flashes and damage states.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyHitEvent {
+    pub entity: Entity,
+    pub kind: EnemyKind,
+    pub health: i32,
+    pub max_health: i32,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollisionSet;
+
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            FixedUpdate,
-            (
-                projectile_enemy_collisions,
-                player_enemy_collisions,
-                enemy_projectile_player_collisions,
-            )
-                .run_if(in_state(AppState::Playing)),
-        );
+        app.add_event::<EnemyDestroyedEvent>()
+            .add_event::<EnemyHitEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    projectile_enemy_collisions,
+                    player_enemy_collisions,
+                    reflect_shield_collisions.before(enemy_projectile_player_collisions),
+                    enemy_projectile_player_collisions,
+                    bomb_detonations,
+                )
+                    .in_set(CollisionSet)
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+#[derive(SystemParam)]
+pub struct KillFeedback<'w> {
+    scoreboard: ResMut<'w, ScoreBoard>,
+    audio_events: EventWriter<'w, AudioCue>,
+    explosion_events: EventWriter<'w, ExplosionEvent>,
+    death_events: EventWriter<'w, EnemyDeathEvent>,
+    powerup_events: EventWriter<'w, SpawnPowerUpEvent>,
+    popup_events: EventWriter<'w, ScorePopupEvent>,
+    run_stats: ResMut<'w, RunStats>,
+    destroyed_events: EventWriter<'w, EnemyDestroyedEvent>,
+    hit_events: EventWriter<'w, EnemyHitEvent>,
+}
+
+impl KillFeedback<'_> {
+    fn enemy_hit(&mut self, entity: Entity, enemy: &Enemy) {
+        self.hit_events.send(EnemyHitEvent {
+            entity,
+            kind: enemy.kind,
+            health: enemy.health,
+            max_health: enemy.max_health,
+        });
+    }
+
+    fn enemy_destroyed(
+        &mut self,
+        enemy: &Enemy,
+        death: EnemyDeathEvent,
+        drop: Option<&DropsPowerUp>,
+        by_bomb: bool,
+    ) {
+        let position = death.position;
+        self.run_stats.record_kill(enemy.kind);
+        let score = enemy.score * self.run_stats.multiplier();
+        self.scoreboard.score += score;
+        self.destroyed_events.send(EnemyDestroyedEvent {
+            kind: enemy.kind,
+            position,
+            by_bomb,
+            wave: enemy.wave,
+        });
+        self.popup_events.send(ScorePopupEvent {
+            position,
+            value: score,
+        });
+        self.audio_events
+            .send(AudioCue::at(Sfx::Explosion, position));
+        if let Some(drop) = drop {
+            self.powerup_events.send(SpawnPowerUpEvent {
+                position,
+                kind: drop.kind,
+            });
+        }
+        self.death_events.send(death);
     }
 }
 
-fn projectile_enemy_collisions(
+// Buffers for `projectile_enemy_collisions`, kept between ticks so a crowded screen
+// doesn't allocate fresh ones every time.
+#[derive(Default)]
+pub struct CollisionScratch {
+    enemy_shapes: Vec<(Entity, Vec2, Vec2)>,
+    hits: Vec<(Entity, Entity, Vec2)>,
+}
+
+#[allow(clippy::type_complexity)]
+pub fn projectile_enemy_collisions(
     mut commands: Commands,
     bullets: Query<(Entity, &Transform, &Sprite), With<Projectile>>,
     mut enemies: Query<(
@@ -39,77 +140,149 @@ fn projectile_enemy_collisions(
         &mut Enemy,
         &Transform,
         &Sprite,
+        &TextureAtlas,
         Option<&DropsPowerUp>,
     )>,
-    mut scoreboard: ResMut<ScoreBoard>,
-    mut audio_events: EventWriter<AudioCue>,
-    mut explosion_events: EventWriter<ExplosionEvent>,
-    mut powerup_events: EventWriter<SpawnPowerUpEvent>,
+    mut particle_events: EventWriter<ParticleBurstEvent>,
+    mut kills: KillFeedback,
+    mut scratch: Local<CollisionScratch>,
 ) {
-    let mut enemy_shapes = Vec::new();
-    for (entity, enemy, transform, sprite, _) in enemies.iter_mut() {
-        enemy_shapes.push((
+    // Profiling spans (see the `trace` feature) carry entity counts so a slow tick can
+    // be matched to how crowded the screen was.
+    let _span = info_span!(
+        "projectile_enemy_collisions",
+        bullets = bullets.iter().len(),
+        enemies = enemies.iter().len()
+    )
+    .entered();
+    let CollisionScratch { enemy_shapes, hits } = &mut *scratch;
+    enemy_shapes.clear();
+    enemy_shapes.extend(enemies.iter().map(|(entity, _, transform, sprite, _, _)| {
+        (
             entity,
-            enemy.kind,
             transform.translation.xy(),
             sprite_half_extents(sprite),
-        ));
-    }
+        )
+    }));
 
-    let mut hits: Vec<(Entity, Entity)> = Vec::new();
+    hits.clear();
     for (bullet_entity, bullet_transform, bullet_sprite) in &bullets {
         let bullet_half = sprite_half_extents(bullet_sprite);
         let bullet_center = bullet_transform.translation.xy();
-        for (enemy_entity, _, enemy_center, enemy_half) in &enemy_shapes {
-            if overlaps(*enemy_center, *enemy_half, bullet_center, bullet_half) {
-                hits.push((bullet_entity, *enemy_entity));
-                break;
-            }
+        if let Some((enemy_entity, _, _)) = enemy_shapes
+            .iter()
+            .find(|(_, center, half)| overlaps(*center, *half, bullet_center, bullet_half))
+        {
+            hits.push((bullet_entity, *enemy_entity, bullet_center));
         }
     }
 
-    for (bullet_entity, enemy_entity) in hits {
-        despawn_with_check(&mut commands, bullet_entity, "player bullet");
-        if let Ok((entity, mut enemy, transform, _, drop)) = enemies.get_mut(enemy_entity) {
+    for &(bullet_entity, enemy_entity, impact) in hits.iter() {
+        release::<Projectile>(&mut commands, bullet_entity);
+        kills.run_stats.shots_hit += 1;
+        particle_events.send(ParticleBurstEvent {
+            position: impact,
+            burst: ParticleBurst::Sparks,
+        });
+        if let Ok((entity, mut enemy, transform, sprite, atlas, drop)) =
+            enemies.get_mut(enemy_entity)
+        {
             enemy.health -= 1;
             if enemy.health <= 0 {
                 despawn_with_check(&mut commands, entity, "enemy (bullet collision)");
-                scoreboard.score += enemy.score;
-                audio_events.send(AudioCue::Explosion);
-                if let Some(drop) = drop {
-                    powerup_events.send(SpawnPowerUpEvent {
-                        position: transform.translation.xy(),
-                        kind: drop.kind,
-                    });
-                }
-                explosion_events.send(ExplosionEvent {
-                    position: transform.translation.xy(),
-                    large: matches!(enemy.kind, EnemyKind::Tank | EnemyKind::Boss),
-                });
+                let death =
+                    EnemyDeathEvent::new(enemy.kind, transform.translation.xy(), sprite, atlas);
+                kills.enemy_destroyed(&enemy, death, drop, false);
+            } else {
+                kills.enemy_hit(entity, &enemy);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn bomb_detonations(
+    mut commands: Commands,
+    mut bombs: EventReader<BombEvent>,
+    projectiles: Query<(Entity, &Transform), With<EnemyProjectile>>,
+    mut enemies: Query<(
+        Entity,
+        &mut Enemy,
+        &Transform,
+        &Sprite,
+        &TextureAtlas,
+        Option<&DropsPowerUp>,
+    )>,
+    mut particle_events: EventWriter<ParticleBurstEvent>,
+    mut kills: KillFeedback,
+) {
+    for bomb in bombs.read() {
+        kills.explosion_events.send(ExplosionEvent {
+            position: bomb.position,
+            large: true,
+        });
+        for (entity, transform) in &projectiles {
+            release::<EnemyProjectile>(&mut commands, entity);
+            particle_events.send(ParticleBurstEvent {
+                position: transform.translation.xy(),
+                burst: ParticleBurst::Sparks,
+            });
+        }
+        for (entity, mut enemy, transform, sprite, atlas, drop) in &mut enemies {
+            if enemy.health <= 0 {
+                continue;
+            }
+            enemy.health -= BOMB_DAMAGE;
+            if enemy.health <= 0 {
+                despawn_with_check(&mut commands, entity, "enemy (bomb)");
+                let death =
+                    EnemyDeathEvent::new(enemy.kind, transform.translation.xy(), sprite, atlas);
+                kills.enemy_destroyed(&enemy, death, drop, true);
+            } else {
+                kills.enemy_hit(entity, &enemy);
             }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn player_enemy_collisions(
     mut commands: Commands,
     mut player_query: Query<(&Transform, &Sprite, &mut PlayerDefense), With<Player>>,
-    enemies: Query<(Entity, &Enemy, &Transform, &Sprite, Option<&DropsPowerUp>)>,
+    mut enemies: Query<(
+        Entity,
+        &Enemy,
+        &Transform,
+        &Sprite,
+        &TextureAtlas,
+        Option<&DropsPowerUp>,
+        Option<&mut ContactCooldown>,
+    )>,
     mut stats: ResMut<PlayerStats>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut audio_events: EventWriter<AudioCue>,
-    mut explosion_events: EventWriter<ExplosionEvent>,
-    mut powerup_events: EventWriter<SpawnPowerUpEvent>,
+    mut kills: KillFeedback,
     mut life_events: EventWriter<PlayerLifeLostEvent>,
+    mut hit_events: EventWriter<PlayerHitEvent>,
+    cheats: Res<Cheats>,
 ) {
+    // God mode lets rams pass straight through the ship.
+    if cheats.god_mode {
+        return;
+    }
     let Ok((player_transform, player_sprite, mut defense)) = player_query.get_single_mut() else {
         return;
     };
 
+    let _span = info_span!("player_enemy_collisions", enemies = enemies.iter().len()).entered();
     let player_half = sprite_half_extents(player_sprite);
     let player_center = player_transform.translation.xy();
 
-    for (enemy_entity, enemy, enemy_transform, enemy_sprite, drop) in &enemies {
+    for (enemy_entity, enemy, enemy_transform, enemy_sprite, atlas, drop, mut contact) in
+        &mut enemies
+    {
+        if contact.as_ref().is_some_and(|cooldown| !cooldown.ready()) {
+            continue;
+        }
         let enemy_half = sprite_half_extents(enemy_sprite);
         let enemy_center = enemy_transform.translation.xy();
         if overlaps(player_center, player_half, enemy_center, enemy_half)
@@ -118,22 +291,24 @@ fn player_enemy_collisions(
                 &mut defense,
                 &mut next_state,
                 enemy.damage,
-                &mut audio_events,
+                &mut kills.audio_events,
                 &mut life_events,
+                &mut hit_events,
             )
         {
-            despawn_with_check(&mut commands, enemy_entity, "enemy (ram)");
-            if let Some(drop) = drop {
-                powerup_events.send(SpawnPowerUpEvent {
-                    position: enemy_center,
-                    kind: drop.kind,
+            if let Some(cooldown) = contact.as_mut() {
+                cooldown.trigger();
+                kills.explosion_events.send(ExplosionEvent {
+                    position: player_center,
+                    large: false,
                 });
+                break;
             }
-            explosion_events.send(ExplosionEvent {
-                position: enemy_center,
-                large: matches!(enemy.kind, EnemyKind::Tank | EnemyKind::Boss),
-            });
-            explosion_events.send(ExplosionEvent {
+
+            despawn_with_check(&mut commands, enemy_entity, "enemy (ram)");
+            let death = EnemyDeathEvent::new(enemy.kind, enemy_center, enemy_sprite, atlas);
+            kills.enemy_destroyed(enemy, death, drop, false);
+            kills.explosion_events.send(ExplosionEvent {
                 position: player_center,
                 large: true,
             });
@@ -142,6 +317,38 @@ fn player_enemy_collisions(
     }
 }
 
+fn reflect_shield_collisions(
+    mut commands: Commands,
+    projectiles: Query<(Entity, &Transform, &Sprite, &EnemyProjectile)>,
+    player_query: Query<(&Transform, &PlayerDefense), With<Player>>,
+    mut fire_events: EventWriter<PlayerFireEvent>,
+) {
+    let Ok((player_transform, defense)) = player_query.get_single() else {
+        return;
+    };
+    if defense.reflect_shield <= 0.0 {
+        return;
+    }
+
+    let player_center = player_transform.translation.xy();
+    for (entity, transform, sprite, projectile) in &projectiles {
+        let center = transform.translation.xy();
+        if center.distance(player_center) > REFLECT_SHIELD_RADIUS {
+            continue;
+        }
+        release::<EnemyProjectile>(&mut commands, entity);
+        let velocity = Vec2::new(projectile.velocity.x, projectile.velocity.y.abs());
+        let speed = velocity.length().max(420.0);
+        fire_events.send(PlayerFireEvent {
+            origin: center,
+            velocity: velocity.normalize_or(Vec2::Y) * speed,
+            size: sprite.custom_size.unwrap_or(Vec2::splat(16.0)) / 1.6,
+            lifetime: 1.8,
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn enemy_projectile_player_collisions(
     mut commands: Commands,
     projectiles: Query<(Entity, &Transform, &Sprite, &EnemyProjectile)>,
@@ -151,11 +358,21 @@ fn enemy_projectile_player_collisions(
     mut audio_events: EventWriter<AudioCue>,
     mut explosion_events: EventWriter<ExplosionEvent>,
     mut life_events: EventWriter<PlayerLifeLostEvent>,
+    mut hit_events: EventWriter<PlayerHitEvent>,
+    cheats: Res<Cheats>,
 ) {
+    if cheats.god_mode {
+        return;
+    }
     let Ok((player_transform, player_sprite, mut defense)) = player_query.get_single_mut() else {
         return;
     };
 
+    let _span = info_span!(
+        "enemy_projectile_player_collisions",
+        shots = projectiles.iter().len()
+    )
+    .entered();
     let player_half = sprite_half_extents(player_sprite);
     let player_center = player_transform.translation.xy();
 
@@ -174,8 +391,9 @@ fn enemy_projectile_player_collisions(
             projectile.damage,
             &mut audio_events,
             &mut life_events,
+            &mut hit_events,
         ) {
-            despawn_with_check(&mut commands, projectile_entity, "enemy projectile");
+            release::<EnemyProjectile>(&mut commands, projectile_entity);
             explosion_events.send(ExplosionEvent {
                 position: player_center,
                 large: false,
@@ -192,6 +410,7 @@ fn handle_player_hit(
     damage: u8,
     audio_events: &mut EventWriter<AudioCue>,
     life_events: &mut EventWriter<PlayerLifeLostEvent>,
+    hit_events: &mut EventWriter<PlayerHitEvent>,
 ) -> bool {
     if defense.invulnerability > 0.0 {
         info!(
@@ -211,7 +430,8 @@ fn handle_player_hit(
         current = stats.health,
         lives = stats.lives
     );
-    audio_events.send(AudioCue::Hit);
+    audio_events.send(AudioCue::new(Sfx::Hit));
+    hit_events.send(PlayerHitEvent { damage });
     if stats.health == 0 {
         if stats.lives > 1 {
             stats.lives -= 1;
@@ -242,7 +462,7 @@ fn despawn_with_check(commands: &mut Commands, entity: Entity, context: &str) {
     }
 }
 
-fn sprite_half_extents(sprite: &Sprite) -> Vec2 {
+pub fn sprite_half_extents(sprite: &Sprite) -> Vec2 {
     sprite.custom_size.unwrap_or(Vec2::splat(32.0)) * 0.5
 }
 
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+
+use super::{
+    run_stats::RunStats,
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+};
+
+const METER_WIDTH: f32 = 160.0;
+const PULSE_SECONDS: f32 = 0.35;
+const PULSE_SCALE: f32 = 0.6;
+const BREAK_SECONDS: f32 = 0.6;
+const MULTIPLIER_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const BROKEN_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+
+pub struct ComboMeterPlugin;
+
+impl Plugin for ComboMeterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(InRun), spawn_combo_meter)
+            .add_systems(
+                Update,
+                update_combo_meter.run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(InRun), cleanup_combo_meter);
+    }
+}
+
+#[derive(Component)]
+struct ComboMeter {
+    multiplier: u32,
+    pulse: f32,
+    broken: f32,
+}
+
+#[derive(Component)]
+struct ComboMultiplierText;
+
+#[derive(Component)]
+struct ComboNextText;
+
+#[derive(Component)]
+struct ComboDecayFill;
+
+fn spawn_combo_meter(mut commands: Commands, font: Res<UiFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(64.0),
+                    right: Val::Px(16.0),
+                    width: Val::Px(METER_WIDTH),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            ComboMeter {
+                multiplier: 1,
+                pulse: 0.0,
+                broken: 0.0,
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("x1", font.style(TextSize::Heading, MULTIPLIER_COLOR)),
+                ComboMultiplierText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    font.style(TextSize::Caption, Color::srgb(0.8, 0.9, 1.0)),
+                ),
+                ComboNextText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(6.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.15)),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(MULTIPLIER_COLOR),
+                            ..default()
+                        },
+                        ComboDecayFill,
+                    ));
+                });
+        });
+}
+
+#[allow(clippy::type_complexity)]
+fn update_combo_meter(
+    stats: Res<RunStats>,
+    time: Res<Time<Real>>,
+    mut meters: Query<(&mut ComboMeter, &mut Visibility)>,
+    mut multiplier_text: Query<(&mut Text, &mut Transform), With<ComboMultiplierText>>,
+    mut next_text: Query<&mut Text, (With<ComboNextText>, Without<ComboMultiplierText>)>,
+    mut fill: Query<&mut Style, With<ComboDecayFill>>,
+) {
+    let Ok((mut meter, mut visibility)) = meters.get_single_mut() else {
+        return;
+    };
+    let delta = time.delta_seconds();
+    let multiplier = stats.multiplier();
+    if multiplier > meter.multiplier {
+        meter.pulse = PULSE_SECONDS;
+        meter.broken = 0.0;
+    } else if multiplier < meter.multiplier {
+        meter.broken = BREAK_SECONDS;
+        meter.pulse = 0.0;
+    }
+    meter.multiplier = multiplier;
+    meter.pulse = (meter.pulse - delta).max(0.0);
+    meter.broken = (meter.broken - delta).max(0.0);
+
+    let shown = stats.combo > 0 || meter.broken > 0.0;
+    visibility.set_if_neq(if shown {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    });
+    if !shown {
+        return;
+    }
+
+    if let Ok((mut text, mut transform)) = multiplier_text.get_single_mut() {
+        let section = &mut text.sections[0];
+        let label = format!("x{multiplier}");
+        if section.value != label {
+            section.value = label;
+        }
+        // Pop outwards on a new tier, then shrink away when the chain breaks.
+        let scale = if meter.broken > 0.0 {
+            section.style.color = BROKEN_COLOR.with_alpha(meter.broken / BREAK_SECONDS);
+            0.6 + 0.4 * meter.broken / BREAK_SECONDS
+        } else {
+            section.style.color = MULTIPLIER_COLOR;
+            let t = meter.pulse / PULSE_SECONDS;
+            1.0 + PULSE_SCALE * t * t
+        };
+        transform.scale = Vec3::splat(scale);
+    }
+
+    if let Ok(mut text) = next_text.get_single_mut() {
+        let label = if meter.broken > 0.0 {
+            "COMBO BROKEN".to_string()
+        } else {
+            match stats.kills_to_next_tier() {
+                Some(kills) => format!("{} chain - {kills} to x{}", stats.combo, multiplier + 1),
+                None => format!("{} chain - MAX", stats.combo),
+            }
+        };
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+
+    if let Ok(mut style) = fill.get_single_mut() {
+        let width = Val::Percent(stats.combo_decay() * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+}
+
+fn cleanup_combo_meter(mut commands: Commands, meters: Query<Entity, With<ComboMeter>>) {
+    for entity in &meters {
+        commands.entity(entity).despawn_recursive();
+    }
+}
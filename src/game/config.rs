@@ -1,79 +1,953 @@
+use std::{
+    fmt,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use bevy::{prelude::*, time::Fixed};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    profiles::CurrentProfile,
+    states::InRun,
+    storage::{load_json, local_dir, save_json},
+};
+use crate::util::rng::Rng;
+
+const SETTINGS_FILE: &str = "settings.json";
+const BINDINGS_FILE: &str = "bindings.json";
+const STORYBOARD_PATH: &str = "assets/storyboard.json";
+// Slider drags change the settings every frame, so wait for them to settle before writing.
+const SETTINGS_SAVE_DELAY: f32 = 0.5;
+// Storyboards are laid out for a 16:9 playfield this wide.
+const DESIGN_WIDTH: f32 = 1280.0;
+// The playfield widens with the window between 4:3 and 21:9; beyond that it letterboxes.
+pub const MIN_LOGICAL_WIDTH: f32 = 960.0;
+const MAX_LOGICAL_WIDTH: f32 = 1680.0;
+// Width over height of the upright playfield in tate.
+const TATE_ASPECT: f32 = 3.0 / 4.0;
 
 #[derive(Resource, Debug)]
 pub struct GameConfig {
     pub logical_width: f32,
     pub logical_height: f32,
+    pub storyboard_path: PathBuf,
+    pub start_level: usize,
 }
 
 impl Default for GameConfig {
     fn default() -> Self {
         Self {
-            logical_width: 1280.0,
+            logical_width: DESIGN_WIDTH,
             logical_height: 720.0,
+            storyboard_path: PathBuf::from(STORYBOARD_PATH),
+            start_level: 0,
         }
     }
 }
 
+impl GameConfig {
+    // Spreads storyboard x positions across the current playfield width.
+    pub fn lane_scale(&self) -> f32 {
+        self.logical_width / DESIGN_WIDTH
+    }
+}
+
+// Command-line overrides, inserted by `main` before the game plugins are built.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub windowed: bool,
+    pub resolution: Option<UVec2>,
+    pub storyboard: Option<PathBuf>,
+    pub level: Option<usize>,
+    pub mute: bool,
+    pub data_dir: Option<PathBuf>,
+    pub no_story: bool,
+}
+
+// Why the command line could not be turned into `LaunchOptions`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchError {
+    // `--help` or `-h`: print the usage and stop.
+    Help,
+    Invalid(String),
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::Help => write!(f, "help requested"),
+            LaunchError::Invalid(problem) => write!(f, "{}", problem),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+impl LaunchOptions {
+    // Reads the flags that follow the program name.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, LaunchError> {
+        let mut launch = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| {
+                args.next()
+                    .ok_or_else(|| LaunchError::Invalid(format!("{flag} expects a value")))
+            };
+            match arg.as_str() {
+                "--windowed" => launch.windowed = true,
+                "--mute" => launch.mute = true,
+                "--no-story" => launch.no_story = true,
+                "--resolution" => {
+                    let raw = value("--resolution")?;
+                    let size = raw
+                        .split_once('x')
+                        .and_then(|(width, height)| {
+                            Some(UVec2::new(width.parse().ok()?, height.parse().ok()?))
+                        })
+                        .filter(|size| size.x > 0 && size.y > 0)
+                        .ok_or_else(|| {
+                            LaunchError::Invalid(format!(
+                                "invalid resolution '{raw}', expected e.g. 1920x1080"
+                            ))
+                        })?;
+                    launch.resolution = Some(size);
+                }
+                "--storyboard" => launch.storyboard = Some(PathBuf::from(value("--storyboard")?)),
+                "--data-dir" => launch.data_dir = Some(PathBuf::from(value("--data-dir")?)),
+                "--portable" => launch.data_dir = Some(local_dir()),
+                "--level" => {
+                    let raw = value("--level")?;
+                    let level = raw
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&level| level > 0)
+                        .ok_or_else(|| {
+                            LaunchError::Invalid(format!(
+                                "invalid level '{raw}', expected a number from 1"
+                            ))
+                        })?;
+                    launch.level = Some(level);
+                }
+                "--help" | "-h" => return Err(LaunchError::Help),
+                other => return Err(LaunchError::Invalid(format!("unknown argument '{other}'"))),
+            }
+        }
+        Ok(launch)
+    }
+
+    // Overrides that also hold for settings loaded later from another profile.
+    pub fn apply(&self, settings: &mut GameSettings) {
+        settings.muted |= self.mute;
+        if self.no_story {
+            settings.story_dialogue = false;
+        }
+        if self.windowed {
+            settings.fullscreen = false;
+        }
+    }
+
+    // What gets written back: a value a flag overrode keeps what the file already had,
+    // unless the player has since changed it.
+    pub fn strip(&self, settings: &GameSettings, profile: &CurrentProfile) -> GameSettings {
+        let mut stripped = *settings;
+        if !(self.mute || self.no_story || self.windowed) {
+            return stripped;
+        }
+        let saved = GameSettings::load(profile);
+        if self.mute && settings.muted {
+            stripped.muted = saved.muted;
+        }
+        if self.no_story && !settings.story_dialogue {
+            stripped.story_dialogue = saved.story_dialogue;
+        }
+        if self.windowed && !settings.fullscreen {
+            stripped.fullscreen = saved.fullscreen;
+        }
+        stripped
+    }
+}
+
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GameConfig>()
-            .init_resource::<GameSettings>()
+        let launch = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .cloned()
+            .unwrap_or_default();
+        let mut config = GameConfig::default();
+        if let Some(path) = launch.storyboard.clone() {
+            config.storyboard_path = path;
+        }
+        if let Some(level) = launch.level {
+            config.start_level = level.saturating_sub(1);
+        }
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        let mut settings = GameSettings::load(&profile);
+        launch.apply(&mut settings);
+        let bindings = KeyBindings::load(&profile);
+        let run_difficulty = RunDifficulty::from_settings(&settings);
+
+        app.insert_resource(config)
+            .insert_resource(settings)
+            .insert_resource(run_difficulty)
             .register_type::<GameSettings>()
             .register_type::<Difficulty>()
-            .insert_resource(Time::<Fixed>::from_seconds(1.0 / 120.0));
+            .register_type::<DifficultyKnobs>()
+            .register_type::<ShakeLevel>()
+            .register_type::<LightQuality>()
+            .register_type::<ResultsExport>()
+            .register_type::<WindowSize>()
+            .register_type::<ScreenLayout>()
+            .register_type::<FrameCap>()
+            .register_type::<ColorPalette>()
+            .insert_resource(bindings)
+            .register_type::<KeyBindings>()
+            .init_resource::<GameRng>()
+            .insert_resource(Time::<Fixed>::from_seconds(1.0 / 120.0))
+            .add_systems(Update, follow_difficulty_setting)
+            .add_systems(OnExit(InRun), reset_run_difficulty)
+            .add_systems(Last, save_settings_on_change);
     }
 }
 
-#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub Rng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        Self(Rng::new(seed))
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
 #[reflect(Resource)]
+#[serde(default)]
 pub struct GameSettings {
     pub difficulty: Difficulty,
+    pub custom_difficulty: DifficultyKnobs,
+    pub master_volume: f32,
+    pub muted: bool,
     pub music_volume: f32,
     pub sfx_volume: f32,
+    pub screen_shake: ShakeLevel,
+    pub crt_filter: bool,
+    pub light_quality: LightQuality,
+    pub run_timer: bool,
+    // Adds the level and score to the window title while a run is going.
+    pub title_status: bool,
+    pub results_export: ResultsExport,
+    pub dynamic_rank: bool,
+    // Lets enemies shot down on Hard, or at high rank, fire one aimed shot back.
+    pub revenge_shots: bool,
+    // Flips waves and boss movement left to right, for players who know the storyboard
+    // by heart.
+    pub mirror_mode: bool,
+    // Both take effect from the next ship spawned.
+    pub ship: ShipModel,
+    pub paint: ShipPaint,
+    // Story scenes between waves; when off they are skipped without being shown.
+    pub story_dialogue: bool,
+    pub language: Language,
+    pub fullscreen: bool,
+    pub window_size: WindowSize,
+    pub screen_layout: ScreenLayout,
+    pub vsync: bool,
+    pub frame_cap: FrameCap,
+    pub reduce_flashing: bool,
+    // Holds the camera still: no shake, firing kick, zoom, or drift after the ship.
+    pub reduce_motion: bool,
+    pub high_contrast_bullets: bool,
+    pub palette: ColorPalette,
 }
 
 impl Default for GameSettings {
     fn default() -> Self {
         Self {
             difficulty: Difficulty::Normal,
+            custom_difficulty: DifficultyKnobs::default(),
+            master_volume: 1.0,
+            muted: false,
             music_volume: 0.6,
             sfx_volume: 0.7,
+            screen_shake: ShakeLevel::Full,
+            crt_filter: false,
+            light_quality: LightQuality::High,
+            run_timer: false,
+            title_status: false,
+            results_export: ResultsExport::Off,
+            dynamic_rank: false,
+            revenge_shots: true,
+            mirror_mode: false,
+            ship: ShipModel::Standard,
+            paint: ShipPaint::Standard,
+            story_dialogue: true,
+            language: Language::English,
+            fullscreen: false,
+            window_size: WindowSize::Hd,
+            screen_layout: ScreenLayout::Fill,
+            vsync: true,
+            frame_cap: FrameCap::Off,
+            reduce_flashing: false,
+            reduce_motion: false,
+            high_contrast_bullets: false,
+            palette: ColorPalette::Standard,
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        load_json(&profile.config_path(SETTINGS_FILE))
+    }
+
+    pub fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.config_path(SETTINGS_FILE), self);
+    }
+
+    pub fn difficulty_knobs(&self) -> DifficultyKnobs {
+        self.difficulty.knobs(self.custom_difficulty)
+    }
+
+    pub fn music_level(&self) -> f32 {
+        self.music_volume * self.output_gain()
+    }
+
+    pub fn sfx_level(&self) -> f32 {
+        self.sfx_volume * self.output_gain()
+    }
+
+    fn output_gain(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master_volume }
+    }
+}
+
+// The difficulty the current run plays on. It follows the settings, except that a
+// resumed run keeps the one it was saved with until another is picked mid-run.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RunDifficulty {
+    pub difficulty: Difficulty,
+    pub custom: DifficultyKnobs,
+}
+
+impl RunDifficulty {
+    pub fn from_settings(settings: &GameSettings) -> Self {
+        Self {
+            difficulty: settings.difficulty,
+            custom: settings.custom_difficulty,
+        }
+    }
+
+    pub fn knobs(&self) -> DifficultyKnobs {
+        self.difficulty.knobs(self.custom)
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_up: KeyCode,
+    pub move_down: KeyCode,
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    pub fire: KeyCode,
+    pub bomb: KeyCode,
+    pub pause: KeyCode,
+    pub mute: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_up: KeyCode::KeyW,
+            move_down: KeyCode::KeyS,
+            move_left: KeyCode::KeyA,
+            move_right: KeyCode::KeyD,
+            fire: KeyCode::Space,
+            bomb: KeyCode::KeyX,
+            pause: KeyCode::KeyP,
+            mute: KeyCode::KeyM,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        load_json(&profile.config_path(BINDINGS_FILE))
+    }
+
+    pub fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.config_path(BINDINGS_FILE), self);
+    }
+
+    pub fn key(&self, action: InputAction) -> KeyCode {
+        match action {
+            InputAction::MoveUp => self.move_up,
+            InputAction::MoveDown => self.move_down,
+            InputAction::MoveLeft => self.move_left,
+            InputAction::MoveRight => self.move_right,
+            InputAction::Fire => self.fire,
+            InputAction::Bomb => self.bomb,
+            InputAction::Pause => self.pause,
+            InputAction::Mute => self.mute,
+        }
+    }
+
+    fn slot(&mut self, action: InputAction) -> &mut KeyCode {
+        match action {
+            InputAction::MoveUp => &mut self.move_up,
+            InputAction::MoveDown => &mut self.move_down,
+            InputAction::MoveLeft => &mut self.move_left,
+            InputAction::MoveRight => &mut self.move_right,
+            InputAction::Fire => &mut self.fire,
+            InputAction::Bomb => &mut self.bomb,
+            InputAction::Pause => &mut self.pause,
+            InputAction::Mute => &mut self.mute,
+        }
+    }
+
+    // Binding a key that another action already uses swaps the two.
+    pub fn rebind(&mut self, action: InputAction, key: KeyCode) {
+        let previous = self.key(action);
+        if let Some(other) = InputAction::ALL
+            .into_iter()
+            .find(|&other| other != action && self.key(other) == key)
+        {
+            *self.slot(other) = previous;
+        }
+        *self.slot(action) = key;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Bomb,
+    Pause,
+    Mute,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 8] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Fire,
+        InputAction::Bomb,
+        InputAction::Pause,
+        InputAction::Mute,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move Up",
+            InputAction::MoveDown => "Move Down",
+            InputAction::MoveLeft => "Move Left",
+            InputAction::MoveRight => "Move Right",
+            InputAction::Fire => "Fire",
+            InputAction::Bomb => "Bomb",
+            InputAction::Pause => "Pause",
+            InputAction::Mute => "Mute",
+        }
+    }
+}
+
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ShakeLevel {
+    Off,
+    Subtle,
+    Full,
+}
+
+impl ShakeLevel {
+    pub fn factor(self) -> f32 {
+        match self {
+            ShakeLevel::Off => 0.0,
+            ShakeLevel::Subtle => 0.45,
+            ShakeLevel::Full => 1.0,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ShakeLevel::Off => ShakeLevel::Subtle,
+            ShakeLevel::Subtle => ShakeLevel::Full,
+            ShakeLevel::Full => ShakeLevel::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShakeLevel::Off => "Off",
+            ShakeLevel::Subtle => "Subtle",
+            ShakeLevel::Full => "Full",
+        }
+    }
+}
+
+// Whether each finished run is written out for external tools, and in which format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ResultsExport {
+    Off,
+    Json,
+    Csv,
+}
+
+impl ResultsExport {
+    pub fn next(self) -> Self {
+        match self {
+            ResultsExport::Off => ResultsExport::Json,
+            ResultsExport::Json => ResultsExport::Csv,
+            ResultsExport::Csv => ResultsExport::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResultsExport::Off => "Off",
+            ResultsExport::Json => "JSON",
+            ResultsExport::Csv => "CSV",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ShipModel {
+    Standard,
+    // Quicker, with a thinner hull.
+    Interceptor,
+    // Slower, with a thicker hull.
+    Bulwark,
+}
+
+impl ShipModel {
+    pub const ALL: [ShipModel; 3] = [
+        ShipModel::Standard,
+        ShipModel::Interceptor,
+        ShipModel::Bulwark,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            ShipModel::Standard => ShipModel::Interceptor,
+            ShipModel::Interceptor => ShipModel::Bulwark,
+            ShipModel::Bulwark => ShipModel::Standard,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShipModel::Standard => "Standard",
+            ShipModel::Interceptor => "Interceptor",
+            ShipModel::Bulwark => "Bulwark",
+        }
+    }
+
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            ShipModel::Standard => 1.0,
+            ShipModel::Interceptor => 1.2,
+            ShipModel::Bulwark => 0.85,
+        }
+    }
+
+    pub fn hull(self) -> u8 {
+        match self {
+            ShipModel::Standard => 5,
+            ShipModel::Interceptor => 4,
+            ShipModel::Bulwark => 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ShipPaint {
+    Standard,
+    Ember,
+    Violet,
+}
+
+impl ShipPaint {
+    pub const ALL: [ShipPaint; 3] = [ShipPaint::Standard, ShipPaint::Ember, ShipPaint::Violet];
+
+    pub fn next(self) -> Self {
+        match self {
+            ShipPaint::Standard => ShipPaint::Ember,
+            ShipPaint::Ember => ShipPaint::Violet,
+            ShipPaint::Violet => ShipPaint::Standard,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShipPaint::Standard => "Standard",
+            ShipPaint::Ember => "Ember",
+            ShipPaint::Violet => "Violet",
+        }
+    }
+}
+
+// The language story dialogue is shown in. Menus and the HUD stay in English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Language {
+    pub fn next(self) -> Self {
+        match self {
+            Language::English => Language::French,
+            Language::French => Language::German,
+            Language::German => Language::Spanish,
+            Language::Spanish => Language::English,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+            Language::German => "Deutsch",
+            Language::Spanish => "Español",
+        }
+    }
+
+    // The key dialogue text is stored under in the storyboard.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Spanish => "es",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum LightQuality {
+    Off,
+    Low,
+    High,
+}
+
+impl LightQuality {
+    pub fn max_lights(self) -> usize {
+        match self {
+            LightQuality::Off => 0,
+            LightQuality::Low => 6,
+            LightQuality::High => 24,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            LightQuality::Off => LightQuality::Low,
+            LightQuality::Low => LightQuality::High,
+            LightQuality::High => LightQuality::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LightQuality::Off => "Off",
+            LightQuality::Low => "Low",
+            LightQuality::High => "High",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum WindowSize {
+    Hd,
+    HdPlus,
+    FullHd,
+    Qhd,
+}
+
+impl WindowSize {
+    pub fn size(self) -> Vec2 {
+        match self {
+            WindowSize::Hd => Vec2::new(1280.0, 720.0),
+            WindowSize::HdPlus => Vec2::new(1600.0, 900.0),
+            WindowSize::FullHd => Vec2::new(1920.0, 1080.0),
+            WindowSize::Qhd => Vec2::new(2560.0, 1440.0),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            WindowSize::Hd => WindowSize::HdPlus,
+            WindowSize::HdPlus => WindowSize::FullHd,
+            WindowSize::FullHd => WindowSize::Qhd,
+            WindowSize::Qhd => WindowSize::Hd,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowSize::Hd => "1280x720",
+            WindowSize::HdPlus => "1600x900",
+            WindowSize::FullHd => "1920x1080",
+            WindowSize::Qhd => "2560x1440",
+        }
+    }
+}
+
+// How the playfield is fitted to the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ScreenLayout {
+    // Widens with the window between 4:3 and 21:9.
+    Fill,
+    // Holds the 16:9 design aspect; a wider window shows side panels.
+    Fixed,
+    // A tall 3:4 playfield drawn a quarter turn round, for a monitor stood on its side.
+    Tate,
+}
+
+impl ScreenLayout {
+    pub fn next(self) -> Self {
+        match self {
+            ScreenLayout::Fill => ScreenLayout::Fixed,
+            ScreenLayout::Fixed => ScreenLayout::Tate,
+            ScreenLayout::Tate => ScreenLayout::Fill,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScreenLayout::Fill => "Fill",
+            ScreenLayout::Fixed => "Fixed 16:9",
+            ScreenLayout::Tate => "Tate",
+        }
+    }
+
+    // The upright playfield's logical width, given the aspect of the space it's fitted
+    // to (already turned on its side for tate).
+    pub fn logical_width(self, logical_height: f32, aspect: f32) -> f32 {
+        match self {
+            ScreenLayout::Fill => (logical_height * aspect)
+                .clamp(MIN_LOGICAL_WIDTH, MAX_LOGICAL_WIDTH)
+                .round(),
+            ScreenLayout::Fixed => DESIGN_WIDTH,
+            ScreenLayout::Tate => (logical_height * TATE_ASPECT).round(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum FrameCap {
+    Off,
+    Fps30,
+    Fps60,
+    Fps120,
+    Fps144,
+}
+
+impl FrameCap {
+    pub fn fps(self) -> Option<u32> {
+        match self {
+            FrameCap::Off => None,
+            FrameCap::Fps30 => Some(30),
+            FrameCap::Fps60 => Some(60),
+            FrameCap::Fps120 => Some(120),
+            FrameCap::Fps144 => Some(144),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            FrameCap::Off => FrameCap::Fps30,
+            FrameCap::Fps30 => FrameCap::Fps60,
+            FrameCap::Fps60 => FrameCap::Fps120,
+            FrameCap::Fps120 => FrameCap::Fps144,
+            FrameCap::Fps144 => FrameCap::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FrameCap::Off => "Off",
+            FrameCap::Fps30 => "30 FPS",
+            FrameCap::Fps60 => "60 FPS",
+            FrameCap::Fps120 => "120 FPS",
+            FrameCap::Fps144 => "144 FPS",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ColorPalette {
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl ColorPalette {
+    pub fn next(self) -> Self {
+        match self {
+            ColorPalette::Standard => ColorPalette::Deuteranopia,
+            ColorPalette::Deuteranopia => ColorPalette::Protanopia,
+            ColorPalette::Protanopia => ColorPalette::Tritanopia,
+            ColorPalette::Tritanopia => ColorPalette::Standard,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorPalette::Standard => "Standard",
+            ColorPalette::Deuteranopia => "Deuteranopia",
+            ColorPalette::Protanopia => "Protanopia",
+            ColorPalette::Tritanopia => "Tritanopia",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     Normal,
     Hard,
+    Custom,
 }
 
 impl Difficulty {
-    pub fn spawn_interval_factor(self) -> f32 {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Custom,
+            Difficulty::Custom => Difficulty::Easy,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
         match self {
-            Difficulty::Easy => 1.25,
-            Difficulty::Normal => 1.0,
-            Difficulty::Hard => 0.8,
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom => "Custom",
         }
     }
 
-    pub fn enemy_health_factor(self) -> f32 {
+    pub fn knobs(self, custom: DifficultyKnobs) -> DifficultyKnobs {
+        let normal = DifficultyKnobs::default();
         match self {
-            Difficulty::Easy => 0.9,
-            Difficulty::Normal => 1.0,
-            Difficulty::Hard => 1.15,
+            Difficulty::Easy => DifficultyKnobs {
+                spawn_interval: 1.25,
+                enemy_health: 0.9,
+                bullet_speed: 0.9,
+                ..normal
+            },
+            Difficulty::Normal => normal,
+            Difficulty::Hard => DifficultyKnobs {
+                spawn_interval: 0.8,
+                enemy_health: 1.15,
+                bullet_speed: 1.2,
+                ..normal
+            },
+            Difficulty::Custom => custom,
         }
     }
 
-    pub fn enemy_bullet_factor(self) -> f32 {
+    // The rank from which downed enemies fire a revenge shot: always on Hard, only
+    // once rank runs high otherwise, and never on Easy.
+    pub fn revenge_rank(self) -> Option<f32> {
         match self {
-            Difficulty::Easy => 0.9,
-            Difficulty::Normal => 1.0,
-            Difficulty::Hard => 1.2,
+            Difficulty::Easy => None,
+            Difficulty::Normal | Difficulty::Custom => Some(0.75),
+            Difficulty::Hard => Some(0.0),
+        }
+    }
+}
+
+// The tuning factors behind a difficulty; presets fill these in, Custom exposes them.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyKnobs {
+    pub spawn_interval: f32,
+    pub enemy_health: f32,
+    pub bullet_speed: f32,
+    pub starting_lives: u8,
+    // Below 1 scripted drops may be withheld; above 1 other enemies may carry one.
+    pub drop_rate: f32,
+}
+
+impl Default for DifficultyKnobs {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 1.0,
+            enemy_health: 1.0,
+            bullet_speed: 1.0,
+            starting_lives: 3,
+            drop_rate: 1.0,
+        }
+    }
+}
+
+// Only an actual change of difficulty reaches the run, so a resumed run isn't pulled back
+// to the settings' difficulty when the volume or anything else is touched.
+fn follow_difficulty_setting(
+    settings: Res<GameSettings>,
+    mut run_difficulty: ResMut<RunDifficulty>,
+    mut seen: Local<Option<(Difficulty, DifficultyKnobs)>>,
+) {
+    let current = (settings.difficulty, settings.custom_difficulty);
+    if seen
+        .replace(current)
+        .is_some_and(|previous| previous != current)
+    {
+        *run_difficulty = RunDifficulty::from_settings(&settings);
+    }
+}
+
+fn reset_run_difficulty(settings: Res<GameSettings>, mut run_difficulty: ResMut<RunDifficulty>) {
+    *run_difficulty = RunDifficulty::from_settings(&settings);
+}
+
+fn save_settings_on_change(
+    launch: Option<Res<LaunchOptions>>,
+    settings: Res<GameSettings>,
+    bindings: Res<KeyBindings>,
+    profile: Res<CurrentProfile>,
+    time: Res<Time<Real>>,
+    mut pending: Local<Option<f32>>,
+) {
+    if (settings.is_changed() && !settings.is_added())
+        || (bindings.is_changed() && !bindings.is_added())
+    {
+        *pending = Some(SETTINGS_SAVE_DELAY);
+    }
+    if let Some(remaining) = pending.as_mut() {
+        *remaining -= time.delta_seconds();
+        if *remaining <= 0.0 {
+            launch
+                .map_or(*settings, |launch| launch.strip(&settings, &profile))
+                .save(&profile);
+            bindings.save(&profile);
+            *pending = None;
         }
     }
 }
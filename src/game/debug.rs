@@ -1,51 +1,153 @@
+use std::time::{Duration, Instant};
+
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    ecs::system::SystemParam,
+    math::Vec3Swizzles,
     prelude::*,
 };
 
-use super::spawn::WaveDirector;
+use super::{
+    audio::AudioCue,
+    boss::BossState,
+    collisions::{EnemyDestroyedEvent, sprite_half_extents},
+    config::{GameSettings, RunDifficulty},
+    effects::{ExplosionAnimation, ExplosionEvent},
+    enemies::{Enemy, EnemyKind, SpawnEnemyEvent},
+    player::{Player, PlayerDefense, REFLECT_SHIELD_RADIUS},
+    pool::EntityPool,
+    powerups::PowerUp,
+    rank::{GRAZE_RADIUS, Rank},
+    spawn::WaveDirector,
+    typography::{TextSize, UiFont},
+    weapons::{EnemyFireEvent, EnemyProjectile, PlayerFireEvent, Projectile},
+};
+#[cfg(debug_assertions)]
+use super::{pool::release, states::AppState, ui::ScoreBoard};
+
+const PLAYER_HITBOX_COLOR: Color = Color::srgb(0.3, 1.0, 0.4);
+const ENEMY_HITBOX_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+const PLAYER_SHOT_HITBOX_COLOR: Color = Color::srgb(0.3, 0.9, 1.0);
+const ENEMY_SHOT_HITBOX_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+const POWERUP_HITBOX_COLOR: Color = Color::srgb(1.0, 0.95, 0.3);
+const SHIELD_RADIUS_COLOR: Color = Color::srgba(0.6, 0.85, 1.0, 0.8);
+const GRAZE_RADIUS_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.3);
+#[cfg(debug_assertions)]
+const TIME_SPEEDS: [f32; 7] = [0.1, 0.25, 0.5, 1.0, 1.5, 2.0, 4.0];
+const OVERLAY_SECTIONS: [&str; 4] = ["Performance", "Run", "Entities", "Events"];
+const SECTION_KEYS: [KeyCode; 4] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+];
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct DebugOptions {
     pub show_overlay: bool,
+    pub show_hitboxes: bool,
+    // Scrubbed with F7/F8; slow motion multiplies on top of it.
+    pub time_speed: f32,
+    pub simulation_paused: bool,
+    #[cfg(debug_assertions)]
+    step_requested: bool,
+    // Overlay sections folded down to their heading, in `OVERLAY_SECTIONS` order.
+    collapsed: [bool; 4],
+}
+
+impl Default for DebugOptions {
+    fn default() -> Self {
+        Self {
+            show_overlay: false,
+            show_hitboxes: false,
+            time_speed: 1.0,
+            simulation_paused: false,
+            #[cfg(debug_assertions)]
+            step_requested: false,
+            collapsed: [false; 4],
+        }
+    }
 }
 
 #[derive(Component)]
 struct DebugOverlayText;
 
+#[derive(SystemParam)]
+struct PoolReadouts<'w> {
+    shots: Res<'w, EntityPool<Projectile>>,
+    enemy_shots: Res<'w, EntityPool<EnemyProjectile>>,
+    explosions: Res<'w, EntityPool<ExplosionAnimation>>,
+}
+
+impl PoolReadouts<'_> {
+    fn lines(&self) -> Vec<String> {
+        let line = |label: &str, free: usize, rate: f32| {
+            format!("Pool {}: {} free, {:.0}% reused", label, free, rate * 100.0)
+        };
+        vec![
+            line("shots", self.shots.free_count(), self.shots.reuse_rate()),
+            line(
+                "enemy shots",
+                self.enemy_shots.free_count(),
+                self.enemy_shots.reuse_rate(),
+            ),
+            line(
+                "explosions",
+                self.explosions.free_count(),
+                self.explosions.reuse_rate(),
+            ),
+        ]
+    }
+}
+
+// Counters gathered over one frame and published for the overlay in `Last`.
+#[derive(Resource, Default)]
+struct DebugFrameStats {
+    fixed_started: Option<Instant>,
+    fixed_time: Duration,
+    fixed_ticks: u32,
+    last_fixed_time: Duration,
+    last_fixed_ticks: u32,
+    events: [(&'static str, usize); 6],
+}
+
 pub struct DebugPlugin;
 
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DebugOptions>()
+            .init_resource::<DebugFrameStats>()
             .add_plugins(FrameTimeDiagnosticsPlugin)
             .add_systems(Startup, spawn_debug_overlay)
             .add_systems(
                 Update,
                 (
                     toggle_debug_overlay,
-                    update_debug_overlay_visibility,
-                    refresh_debug_overlay,
+                    update_debug_overlay_visibility.run_if(resource_changed::<DebugOptions>),
+                    refresh_debug_overlay.run_if(|options: Res<DebugOptions>| options.show_overlay),
+                    draw_hitboxes.run_if(|options: Res<DebugOptions>| options.show_hitboxes),
                 ),
+            )
+            .add_systems(FixedFirst, begin_fixed_timing)
+            .add_systems(FixedLast, end_fixed_timing)
+            .add_systems(Last, publish_frame_stats);
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, (debug_time_controls, step_simulation).chain())
+            .add_systems(
+                Update,
+                debug_storyboard_keys.run_if(in_state(AppState::Playing)),
             );
     }
 }
 
-fn spawn_debug_overlay(mut commands: Commands) {
-    let mut bundle = TextBundle::from_section(
-        "",
-        TextStyle {
-            font_size: 16.0,
-            color: Color::WHITE,
+fn spawn_debug_overlay(mut commands: Commands, font: Res<UiFont>) {
+    let mut bundle = TextBundle::from_section("", font.style(TextSize::Caption, Color::WHITE))
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
             ..default()
-        },
-    )
-    .with_style(Style {
-        position_type: PositionType::Absolute,
-        top: Val::Px(8.0),
-        left: Val::Px(8.0),
-        ..default()
-    });
+        });
     bundle.visibility = Visibility::Hidden;
     commands.spawn((bundle, DebugOverlayText));
 }
@@ -54,6 +156,18 @@ fn toggle_debug_overlay(keys: Res<ButtonInput<KeyCode>>, mut options: ResMut<Deb
     if keys.just_pressed(KeyCode::F3) {
         options.show_overlay = !options.show_overlay;
     }
+    if keys.just_pressed(KeyCode::F4) {
+        options.show_hitboxes = !options.show_hitboxes;
+    }
+    // Ctrl+1..4 fold the overlay's sections while it is up.
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if options.show_overlay && ctrl {
+        for (index, key) in SECTION_KEYS.into_iter().enumerate() {
+            if keys.just_pressed(key) {
+                options.collapsed[index] = !options.collapsed[index];
+            }
+        }
+    }
 }
 
 fn update_debug_overlay_visibility(
@@ -61,36 +175,276 @@ fn update_debug_overlay_visibility(
     mut query: Query<&mut Visibility, With<DebugOverlayText>>,
 ) {
     if let Ok(mut visibility) = query.get_single_mut() {
-        *visibility = if options.show_overlay {
+        visibility.set_if_neq(if options.show_overlay {
             Visibility::Visible
         } else {
             Visibility::Hidden
-        };
+        });
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn refresh_debug_overlay(
     options: Res<DebugOptions>,
     diagnostics: Res<DiagnosticsStore>,
+    frame_stats: Res<DebugFrameStats>,
     wave_director: Option<Res<WaveDirector>>,
+    boss: Res<BossState>,
+    run_difficulty: Res<RunDifficulty>,
+    rank: Option<Res<Rank>>,
     entity_query: Query<Entity>,
+    shots: Query<(), With<Projectile>>,
+    enemy_shots: Query<(), With<EnemyProjectile>>,
+    enemies: Query<&Enemy>,
+    pools: PoolReadouts,
     mut query: Query<&mut Text, With<DebugOverlayText>>,
 ) {
-    if !options.show_overlay {
-        return;
-    }
-
     let fps = diagnostics
         .get(&FrameTimeDiagnosticsPlugin::FPS)
         .and_then(|diag| diag.smoothed())
         .unwrap_or(0.0);
-    let entity_count = entity_query.iter().len();
-    let wave = wave_director.map(|w| w.wave_index).unwrap_or_default();
+    let time = if options.simulation_paused {
+        "paused (F6 steps)".to_string()
+    } else {
+        format!("{}x", options.time_speed)
+    };
+    let performance = vec![
+        format!("FPS: {:>5.1}", fps),
+        format!(
+            "Fixed update: {:.2} ms over {} tick(s)",
+            frame_stats.last_fixed_time.as_secs_f64() * 1000.0,
+            frame_stats.last_fixed_ticks
+        ),
+        format!("Entities: {}", entity_query.iter().len()),
+        format!("Time: {}", time),
+    ];
+
+    let (level, wave, difficulty) = wave_director
+        .map(|w| (w.level_index + 1, w.wave_index, w.difficulty))
+        .unwrap_or_default();
+    let boss_line = if boss.active {
+        format!(
+            "Boss: {} phase {} ({:.0}/{:.0})",
+            boss.name,
+            boss.phase.number(),
+            boss.health,
+            boss.max_health
+        )
+    } else {
+        format!("Boss: due at {} points", boss.spawn_score)
+    };
+    let run = vec![
+        format!("Level {} wave {}", level, wave),
+        format!(
+            "Difficulty: {:.2} ({})",
+            difficulty,
+            run_difficulty.difficulty.label()
+        ),
+        format!("Rank: {:.2}", rank.map(|r| r.value).unwrap_or_default()),
+        boss_line,
+    ];
+
+    let mut entities = vec![format!(
+        "Shots: {} player / {} enemy",
+        shots.iter().count(),
+        enemy_shots.iter().count()
+    )];
+    for kind in EnemyKind::ALL {
+        let count = enemies.iter().filter(|enemy| enemy.kind == kind).count();
+        if count > 0 {
+            entities.push(format!("{}: {}", kind.label(), count));
+        }
+    }
+    entities.extend(pools.lines());
+
+    let events = frame_stats
+        .events
+        .iter()
+        .map(|(label, count)| format!("{}: {}/frame", label, count))
+        .collect();
+
+    let mut lines = Vec::new();
+    for (index, body) in [performance, run, entities, events].into_iter().enumerate() {
+        let collapsed = options.collapsed[index];
+        lines.push(format!(
+            "[{}] {} (Ctrl+{})",
+            if collapsed { "+" } else { "-" },
+            OVERLAY_SECTIONS[index],
+            index + 1
+        ));
+        if !collapsed {
+            lines.extend(body.into_iter().map(|line| format!("  {}", line)));
+        }
+    }
 
     if let Ok(mut text) = query.get_single_mut() {
-        text.sections[0].value = format!(
-            "FPS: {:>5.1}\nEntities: {}\nWave: {}",
-            fps, entity_count, wave
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn begin_fixed_timing(mut stats: ResMut<DebugFrameStats>) {
+    stats.fixed_started = Some(Instant::now());
+}
+
+fn end_fixed_timing(mut stats: ResMut<DebugFrameStats>) {
+    if let Some(started) = stats.fixed_started.take() {
+        stats.fixed_time += started.elapsed();
+        stats.fixed_ticks += 1;
+    }
+}
+
+// Each reader keeps its own cursor, so counting here leaves the events for their
+// real consumers.
+fn publish_frame_stats(
+    mut stats: ResMut<DebugFrameStats>,
+    mut player_fire: EventReader<PlayerFireEvent>,
+    mut enemy_fire: EventReader<EnemyFireEvent>,
+    mut spawns: EventReader<SpawnEnemyEvent>,
+    mut kills: EventReader<EnemyDestroyedEvent>,
+    mut explosions: EventReader<ExplosionEvent>,
+    mut audio: EventReader<AudioCue>,
+) {
+    stats.last_fixed_time = std::mem::take(&mut stats.fixed_time);
+    stats.last_fixed_ticks = std::mem::take(&mut stats.fixed_ticks);
+    stats.events = [
+        ("Player fire", player_fire.read().count()),
+        ("Enemy fire", enemy_fire.read().count()),
+        ("Enemy spawns", spawns.read().count()),
+        ("Kills", kills.read().count()),
+        ("Explosions", explosions.read().count()),
+        ("Audio cues", audio.read().count()),
+    ];
+}
+
+// Debug builds only: F5 freezes virtual time (and with it the fixed simulation), F6
+// advances a frozen simulation by a single fixed tick, and F7/F8 step the time scale
+// down and up.
+#[cfg(debug_assertions)]
+fn debug_time_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut options: ResMut<DebugOptions>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if keys.just_pressed(KeyCode::F5) {
+        options.simulation_paused = !options.simulation_paused;
+        if options.simulation_paused {
+            virtual_time.pause();
+        } else {
+            virtual_time.unpause();
+        }
+    }
+    if keys.just_pressed(KeyCode::F6) && options.simulation_paused {
+        options.step_requested = true;
+    }
+
+    let current = TIME_SPEEDS
+        .iter()
+        .position(|&speed| speed >= options.time_speed)
+        .unwrap_or(TIME_SPEEDS.len() - 1);
+    let next = if keys.just_pressed(KeyCode::F7) {
+        current.saturating_sub(1)
+    } else if keys.just_pressed(KeyCode::F8) {
+        (current + 1).min(TIME_SPEEDS.len() - 1)
+    } else {
+        return;
+    };
+    options.time_speed = TIME_SPEEDS[next];
+    virtual_time.set_relative_speed(options.time_speed);
+}
+
+// Runs one pass of the fixed schedule by hand the way Bevy's fixed loop does, with
+// `Time` standing in for `Time<Fixed>`. Virtual time is paused, so the fixed clock
+// itself doesn't advance; its delta is still the one-tick timestep from the last
+// real tick.
+#[cfg(debug_assertions)]
+fn step_simulation(world: &mut World) {
+    let mut options = world.resource_mut::<DebugOptions>();
+    if !std::mem::take(&mut options.step_requested) {
+        return;
+    }
+    *world.resource_mut::<Time>() = world.resource::<Time<Fixed>>().as_generic();
+    world.run_schedule(bevy::app::FixedMain);
+    *world.resource_mut::<Time>() = world.resource::<Time<Virtual>>().as_generic();
+}
+
+// Debug builds only: F9 starts the next wave now, F10 brings the boss in, and F11
+// clears every ordinary enemy and enemy shot off the screen.
+#[cfg(debug_assertions)]
+fn debug_storyboard_keys(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut director: ResMut<WaveDirector>,
+    mut boss: ResMut<BossState>,
+    scoreboard: Res<ScoreBoard>,
+    enemies: Query<(Entity, &Enemy)>,
+    enemy_shots: Query<Entity, With<EnemyProjectile>>,
+) {
+    if keys.just_pressed(KeyCode::F9) && !director.boss_active {
+        // One millisecond short so the wave fires on the next fixed tick.
+        let almost_done = director
+            .timer
+            .duration()
+            .saturating_sub(std::time::Duration::from_millis(1));
+        director.timer.set_elapsed(almost_done);
+        info!("Debug: skipping to the next wave");
+    }
+    if keys.just_pressed(KeyCode::F10) && !boss.active {
+        boss.spawn_score = scoreboard.score;
+        info!("Debug: calling in the boss");
+    }
+    if keys.just_pressed(KeyCode::F11) {
+        for (entity, enemy) in &enemies {
+            if enemy.kind != EnemyKind::Boss {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        for entity in &enemy_shots {
+            release::<EnemyProjectile>(&mut commands, entity);
+        }
+        info!("Debug: cleared the screen");
+    }
+}
+
+// Outlines the boxes the collision systems actually test, plus the reflect shield and
+// graze radii while they apply.
+#[allow(clippy::too_many_arguments)]
+fn draw_hitboxes(
+    settings: Res<GameSettings>,
+    mut gizmos: Gizmos,
+    player: Query<(&Transform, &Sprite, &PlayerDefense), With<Player>>,
+    enemies: Query<(&Transform, &Sprite), With<Enemy>>,
+    shots: Query<(&Transform, &Sprite), With<Projectile>>,
+    enemy_shots: Query<(&Transform, &Sprite), With<EnemyProjectile>>,
+    powerups: Query<(&Transform, &Sprite), With<PowerUp>>,
+) {
+    let mut outline = |transform: &Transform, sprite: &Sprite, color: Color| {
+        gizmos.rect_2d(
+            transform.translation.xy(),
+            0.0,
+            sprite_half_extents(sprite) * 2.0,
+            color,
         );
+    };
+    for (transform, sprite) in &enemies {
+        outline(transform, sprite, ENEMY_HITBOX_COLOR);
+    }
+    for (transform, sprite) in &shots {
+        outline(transform, sprite, PLAYER_SHOT_HITBOX_COLOR);
+    }
+    for (transform, sprite) in &enemy_shots {
+        outline(transform, sprite, ENEMY_SHOT_HITBOX_COLOR);
+    }
+    for (transform, sprite) in &powerups {
+        outline(transform, sprite, POWERUP_HITBOX_COLOR);
+    }
+    if let Ok((transform, sprite, defense)) = player.get_single() {
+        outline(transform, sprite, PLAYER_HITBOX_COLOR);
+        let center = transform.translation.xy();
+        if defense.reflect_shield > 0.0 {
+            gizmos.circle_2d(center, REFLECT_SHIELD_RADIUS, SHIELD_RADIUS_COLOR);
+        }
+        if settings.dynamic_rank {
+            gizmos.circle_2d(center, GRAZE_RADIUS, GRAZE_RADIUS_COLOR);
+        }
     }
 }
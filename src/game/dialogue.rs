@@ -0,0 +1,380 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    boss::{BossEvent, BossState},
+    config::{GameSettings, KeyBindings, Language},
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    palette::ship_paint_color,
+    ship_sprites::{ShipRow, ShipSpriteAssets, ShipSpriteId},
+    spawn::{Storyboard, WaveDirector, WaveStartedEvent},
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+};
+
+// Characters the typewriter reveals per second.
+const TYPE_SPEED: f32 = 45.0;
+const PORTRAIT_SIZE: f32 = 96.0;
+const PANEL_COLOR: Color = Color::srgba(0.03, 0.05, 0.12, 0.92);
+
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StoryProgress>()
+            .add_systems(OnEnter(InRun), reset_story_progress)
+            .add_systems(
+                FixedUpdate,
+                queue_story_scenes.run_if(
+                    in_state(AppState::Playing).and_then(not(resource_exists::<DemoPilot>)),
+                ),
+            )
+            .add_systems(OnEnter(AppState::Dialogue), spawn_dialogue_box)
+            .add_systems(
+                Update,
+                (dialogue_input, update_dialogue_box)
+                    .chain()
+                    .run_if(in_state(AppState::Dialogue)),
+            )
+            .add_systems(OnExit(AppState::Dialogue), cleanup_dialogue_box);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Speaker {
+    Pilot,
+    Command,
+    // Speaks under the name of the boss currently on screen.
+    Boss,
+}
+
+impl Speaker {
+    fn name(self, boss: &BossState) -> &'static str {
+        match self {
+            Speaker::Pilot => "S-FORCE LEAD",
+            Speaker::Command => "AEGIS COMMAND",
+            Speaker::Boss => boss.name,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Speaker::Pilot => Color::srgb(0.55, 0.85, 1.0),
+            Speaker::Command => Color::srgb(0.6, 1.0, 0.65),
+            Speaker::Boss => Color::srgb(1.0, 0.45, 0.4),
+        }
+    }
+}
+
+// A line's text keyed by language code ("en", "fr", ...). Missing translations fall
+// back to English, then to whatever the line has.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct LocalizedText(BTreeMap<String, String>);
+
+impl LocalizedText {
+    pub fn get(&self, language: Language) -> &str {
+        self.0
+            .get(language.code())
+            .or_else(|| self.0.get(Language::English.code()))
+            .or_else(|| self.0.values().next())
+            .map_or("", String::as_str)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DialogueLine {
+    pub speaker: Speaker,
+    pub text: LocalizedText,
+}
+
+// The scene on screen and how far into it the player has read.
+#[derive(Resource, Debug)]
+pub struct DialogueScene {
+    pub lines: Vec<DialogueLine>,
+    pub line: usize,
+    revealed: f32,
+}
+
+impl DialogueScene {
+    fn new(lines: Vec<DialogueLine>) -> Self {
+        Self {
+            lines,
+            line: 0,
+            revealed: 0.0,
+        }
+    }
+}
+
+// Scenes already played this run; each plays once, however often its wave comes round.
+#[derive(Resource, Debug, Default)]
+struct StoryProgress {
+    played: Vec<String>,
+}
+
+#[derive(Component)]
+struct DialogueBox;
+
+#[derive(Component)]
+struct DialoguePortrait;
+
+#[derive(Component)]
+struct DialogueSpeakerText;
+
+#[derive(Component)]
+struct DialogueBodyText;
+
+fn reset_story_progress(mut progress: ResMut<StoryProgress>) {
+    *progress = StoryProgress::default();
+}
+
+// Both cues can land in the same tick when the boss arrives with a wave, so their
+// scenes are run together.
+#[allow(clippy::too_many_arguments)]
+fn queue_story_scenes(
+    mut commands: Commands,
+    mut progress: ResMut<StoryProgress>,
+    mut waves: EventReader<WaveStartedEvent>,
+    mut boss_events: EventReader<BossEvent>,
+    storyboard: Res<Storyboard>,
+    director: Res<WaveDirector>,
+    settings: Res<GameSettings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let mut names: Vec<String> = waves
+        .read()
+        .filter_map(|event| {
+            let level = storyboard.level(event.level.checked_sub(1)?)?;
+            level
+                .waves
+                .get(event.wave.checked_sub(1)? as usize)?
+                .dialogue
+                .clone()
+        })
+        .collect();
+    if boss_events.read().any(|event| *event == BossEvent::Spawned) {
+        names.extend(
+            storyboard
+                .level(director.level_index)
+                .and_then(|level| level.boss_dialogue.clone()),
+        );
+    }
+
+    let mut lines = Vec::new();
+    for name in names {
+        if progress.played.contains(&name) {
+            continue;
+        }
+        match storyboard.dialogue(&name) {
+            Some(scene) => lines.extend_from_slice(scene),
+            None => warn!("Storyboard has no dialogue named '{}'", name),
+        }
+        progress.played.push(name);
+    }
+    if lines.is_empty() || !settings.story_dialogue {
+        return;
+    }
+    commands.insert_resource(DialogueScene::new(lines));
+    next_state.set(AppState::Dialogue);
+}
+
+fn spawn_dialogue_box(
+    mut commands: Commands,
+    font: Res<UiFont>,
+    sprites: Res<ShipSpriteAssets>,
+    settings: Res<GameSettings>,
+) {
+    let portrait = sprites.data(ShipSpriteId::player(settings.ship));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            DialogueBox,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(900.0),
+                        padding: UiRect::all(Val::Px(16.0)),
+                        column_gap: Val::Px(20.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(PANEL_COLOR),
+                    border_color: BorderColor(Color::srgb(0.35, 0.45, 0.7)),
+                    ..default()
+                })
+                .with_children(|panel| {
+                    panel.spawn((
+                        ImageBundle {
+                            style: Style {
+                                width: Val::Px(PORTRAIT_SIZE),
+                                height: Val::Px(PORTRAIT_SIZE),
+                                flex_shrink: 0.0,
+                                ..default()
+                            },
+                            image: UiImage::new(portrait.texture.clone()),
+                            ..default()
+                        },
+                        TextureAtlas {
+                            layout: portrait.layout.clone(),
+                            index: 0,
+                        },
+                        DialoguePortrait,
+                    ));
+                    panel
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                flex_grow: 1.0,
+                                row_gap: Val::Px(6.0),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|column| {
+                            column.spawn((
+                                TextBundle::from_section(
+                                    "",
+                                    font.style(TextSize::Caption, Color::WHITE),
+                                ),
+                                DialogueSpeakerText,
+                            ));
+                            column.spawn((
+                                TextBundle::from_section(
+                                    "",
+                                    font.style(TextSize::Body, Color::WHITE),
+                                ),
+                                DialogueBodyText,
+                            ));
+                            column.spawn((
+                                TextBundle::from_section(
+                                    "",
+                                    font.style(TextSize::Caption, Color::srgb(0.6, 0.7, 0.85)),
+                                ),
+                                DevicePrompt::new(
+                                    "Enter / Fire next  |  Esc skip",
+                                    "A next  |  B / Start skip",
+                                ),
+                            ));
+                        });
+                });
+        });
+}
+
+// Advancing first finishes the line being typed, then moves on; skipping drops the
+// rest of the scene.
+#[allow(clippy::too_many_arguments)]
+fn dialogue_input(
+    mut commands: Commands,
+    mut scene: ResMut<DialogueScene>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    settings: Res<GameSettings>,
+    time: Res<Time<Real>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    let skip = keys.just_pressed(KeyCode::Escape)
+        || keys.just_pressed(bindings.pause)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::Start);
+    let advance = keys.just_pressed(KeyCode::Enter)
+        || keys.just_pressed(KeyCode::Space)
+        || keys.just_pressed(bindings.fire)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::South);
+
+    let length = scene
+        .lines
+        .get(scene.line)
+        .map_or(0, |line| line.text.get(settings.language).chars().count());
+    if skip || advance {
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+    if skip {
+        scene.line = scene.lines.len();
+    } else if advance {
+        if (scene.revealed as usize) < length {
+            scene.revealed = length as f32;
+        } else {
+            scene.line += 1;
+            scene.revealed = 0.0;
+        }
+    } else {
+        scene.revealed = (scene.revealed + TYPE_SPEED * time.delta_seconds()).min(length as f32);
+    }
+
+    if scene.line >= scene.lines.len() {
+        commands.remove_resource::<DialogueScene>();
+        next_state.set(AppState::Playing);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_dialogue_box(
+    scene: Option<Res<DialogueScene>>,
+    settings: Res<GameSettings>,
+    boss: Res<BossState>,
+    sprites: Res<ShipSpriteAssets>,
+    mut portraits: Query<(&mut UiImage, &mut TextureAtlas), With<DialoguePortrait>>,
+    mut speakers: Query<&mut Text, (With<DialogueSpeakerText>, Without<DialogueBodyText>)>,
+    mut bodies: Query<&mut Text, (With<DialogueBodyText>, Without<DialogueSpeakerText>)>,
+) {
+    // Gone once the last line is dismissed, a frame before the box is.
+    let Some(scene) = scene else {
+        return;
+    };
+    let Some(line) = scene.lines.get(scene.line) else {
+        return;
+    };
+    let id = match line.speaker {
+        Speaker::Pilot => ShipSpriteId::player(settings.ship),
+        Speaker::Command => ShipSpriteId::Bulwark,
+        Speaker::Boss => ShipSpriteId::Boss,
+    };
+    let data = sprites.data(id);
+    for (mut image, mut atlas) in &mut portraits {
+        image.texture = data.texture.clone();
+        image.color = match line.speaker {
+            Speaker::Pilot => ship_paint_color(settings.paint),
+            _ => Color::WHITE,
+        };
+        atlas.layout = data.layout.clone();
+        atlas.index = sprites.sequence(id, ShipRow::Idle)[0];
+    }
+    for mut text in &mut speakers {
+        let section = &mut text.sections[0];
+        section.value = line.speaker.name(&boss).to_string();
+        section.style.color = line.speaker.color();
+    }
+    for mut text in &mut bodies {
+        text.sections[0].value = line
+            .text
+            .get(settings.language)
+            .chars()
+            .take(scene.revealed as usize)
+            .collect();
+    }
+}
+
+fn cleanup_dialogue_box(mut commands: Commands, query: Query<Entity, With<DialogueBox>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
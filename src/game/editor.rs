@@ -0,0 +1,760 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use super::{
+    audio::{AudioCue, Sfx},
+    camera::MainCamera,
+    config::{GameConfig, GameSettings},
+    enemies::{Enemy, EnemyKind, SpawnEnemyEvent},
+    powerups::PowerUpKind,
+    spawn::{
+        FixedEnemyConfig, LaneWaveConfig, Level, MovementConfig, SpawnPoint, Storyboard, TOP_Y,
+        WaveDefinition, WavePattern, preview_wave,
+    },
+    states::AppState,
+    typography::{TextSize, UiFont},
+};
+
+// Zoomed out far enough to show the spawn band above the visible playfield.
+const EDITOR_ZOOM: f32 = 1.6;
+const DELAY_STEP: f32 = 0.1;
+const MIN_DELAY: f32 = 0.1;
+const SPEED_STEP: f32 = 10.0;
+const MIN_SPEED: f32 = 10.0;
+// Mouse placement snaps to this grid so exported positions stay readable.
+const PLACEMENT_GRID: f32 = 5.0;
+const MARKER_RADIUS: f32 = 16.0;
+const TIMELINE_PIXELS_PER_SECOND: f32 = 28.0;
+const PLACEABLE_KINDS: [EnemyKind; 5] = [
+    EnemyKind::Grunt,
+    EnemyKind::Sine,
+    EnemyKind::ZigZag,
+    EnemyKind::Tank,
+    EnemyKind::Chaser,
+];
+const PLAYFIELD_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.35);
+const SPAWN_LINE_COLOR: Color = Color::srgba(1.0, 0.6, 0.2, 0.4);
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.95, 0.3);
+const DROP_COLOR: Color = Color::srgb(0.4, 1.0, 0.5);
+const TIMELINE_COLOR: Color = Color::srgba(0.25, 0.35, 0.55, 0.85);
+const TIMELINE_SELECTED_COLOR: Color = Color::srgba(0.85, 0.75, 0.25, 0.95);
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Editor), (open_editor, spawn_editor_ui))
+            .add_systems(
+                Update,
+                (
+                    editor_keyboard_input,
+                    editor_mouse_input,
+                    refresh_editor_ui,
+                    draw_editor_markers,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Editor)),
+            )
+            .add_systems(OnExit(AppState::Editor), close_editor);
+    }
+}
+
+// A working copy of the storyboard; the live one only changes on export.
+#[derive(Resource)]
+struct StoryboardEditor {
+    storyboard: Storyboard,
+    level: usize,
+    wave: usize,
+    point: usize,
+    dirty: bool,
+    // Set by an Esc press with unsaved changes; a second press discards them.
+    confirm_exit: bool,
+    status: String,
+}
+
+impl StoryboardEditor {
+    fn wave_count(&self) -> usize {
+        self.storyboard.wave_count(self.level)
+    }
+
+    fn wave(&self) -> Option<&WaveDefinition> {
+        self.storyboard.level(self.level)?.waves.get(self.wave)
+    }
+
+    fn level_mut(&mut self) -> Option<&mut Level> {
+        self.storyboard.level_mut(self.level)
+    }
+
+    fn wave_mut(&mut self) -> Option<&mut WaveDefinition> {
+        self.storyboard
+            .level_mut(self.level)?
+            .waves
+            .get_mut(self.wave)
+    }
+
+    fn select_level(&mut self, level: usize) {
+        self.level = level;
+        self.wave = 0;
+        self.point = 0;
+    }
+
+    fn select_wave(&mut self, wave: usize) {
+        self.wave = wave;
+        self.point = 0;
+    }
+
+    fn edited(&mut self, status: impl Into<String>) {
+        self.dirty = true;
+        self.status = status.into();
+    }
+}
+
+#[derive(Component)]
+struct EditorScreen;
+
+#[derive(Component)]
+struct EditorDetails;
+
+#[derive(Component)]
+struct EditorTimeline;
+
+fn open_editor(
+    mut commands: Commands,
+    storyboard: Res<Storyboard>,
+    mut cameras: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    commands.insert_resource(StoryboardEditor {
+        storyboard: storyboard.clone(),
+        level: 0,
+        wave: 0,
+        point: 0,
+        dirty: false,
+        confirm_exit: false,
+        status: "Editing a copy of the loaded storyboard".to_string(),
+    });
+    for mut projection in &mut cameras {
+        projection.scale = EDITOR_ZOOM;
+    }
+}
+
+fn close_editor(
+    mut commands: Commands,
+    screens: Query<Entity, With<EditorScreen>>,
+    enemies: Query<Entity, With<Enemy>>,
+    mut cameras: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    for entity in screens.iter().chain(&enemies) {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut projection in &mut cameras {
+        projection.scale = 1.0;
+    }
+    commands.remove_resource::<StoryboardEditor>();
+}
+
+fn spawn_editor_ui(mut commands: Commands, font: Res<UiFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::SpaceBetween,
+                    padding: UiRect::all(Val::Px(16.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            EditorScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", font.style(TextSize::Caption, Color::WHITE)),
+                EditorDetails,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|bottom| {
+                    bottom.spawn((
+                        NodeBundle {
+                            style: Style {
+                                flex_wrap: FlexWrap::Wrap,
+                                column_gap: Val::Px(4.0),
+                                row_gap: Val::Px(4.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        EditorTimeline,
+                    ));
+                    bottom.spawn(TextBundle::from_section(
+                        "Left/Right wave  Up/Down spawn  PgUp/PgDn level  Click select/move  \
+                         Right-click add  Del remove  E enemy  V movement  ,/. speed  \
+                         P drop  -/= delay  T lane/fixed  N new wave  Shift+Del delete wave  \
+                         Space preview  Ctrl+S export  Esc back",
+                        font.style(TextSize::Caption, Color::srgb(0.7, 0.8, 0.9)),
+                    ));
+                });
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn editor_keyboard_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<StoryboardEditor>,
+    settings: Res<GameSettings>,
+    config: Res<GameConfig>,
+    previewed: Query<Entity, With<Enemy>>,
+    mut spawns: EventWriter<SpawnEnemyEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if keys.get_just_pressed().len() == 0 {
+        return;
+    }
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if keys.just_pressed(KeyCode::Escape) {
+        if editor.dirty && !editor.confirm_exit {
+            editor.confirm_exit = true;
+            editor.status = "Unsaved changes - press Esc again to discard them".to_string();
+        } else {
+            audio.send(AudioCue::new(Sfx::UiSelect));
+            next_state.set(AppState::Title);
+        }
+        return;
+    }
+    editor.confirm_exit = false;
+
+    if ctrl {
+        if keys.just_pressed(KeyCode::KeyS) {
+            let path = &config.storyboard_path;
+            match editor.storyboard.export(path) {
+                Ok(()) => {
+                    commands.insert_resource(editor.storyboard.clone());
+                    editor.dirty = false;
+                    editor.status = format!("Exported to {}", path.display());
+                    audio.send(AudioCue::new(Sfx::UiSelect));
+                }
+                Err(err) => {
+                    warn!("Failed to export storyboard to {}: {}", path.display(), err);
+                    editor.status = format!("Export failed: {err}");
+                }
+            }
+        }
+        return;
+    }
+
+    let level_count = editor.storyboard.level_count();
+    if level_count > 1 {
+        if keys.just_pressed(KeyCode::PageDown) {
+            let level = (editor.level + 1) % level_count;
+            editor.select_level(level);
+        } else if keys.just_pressed(KeyCode::PageUp) {
+            let level = (editor.level + level_count - 1) % level_count;
+            editor.select_level(level);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::KeyN) {
+        let copy = editor.wave().cloned().unwrap_or_default();
+        let index = (editor.wave + 1).min(editor.wave_count());
+        if let Some(level) = editor.level_mut() {
+            level.waves.insert(index, copy);
+            editor.select_wave(index);
+            editor.edited(format!("Added wave {}", index + 1));
+        }
+        return;
+    }
+
+    let wave_count = editor.wave_count();
+    if wave_count == 0 {
+        return;
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        let wave = (editor.wave + 1) % wave_count;
+        editor.select_wave(wave);
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        let wave = (editor.wave + wave_count - 1) % wave_count;
+        editor.select_wave(wave);
+    }
+
+    if shift && keys.just_pressed(KeyCode::Delete) {
+        if wave_count > 1 {
+            let removed = editor.wave;
+            if let Some(level) = editor.level_mut() {
+                level.waves.remove(removed);
+            }
+            editor.select_wave(removed.min(wave_count - 2));
+            editor.edited(format!("Deleted wave {}", removed + 1));
+        } else {
+            editor.status = "A level needs at least one wave".to_string();
+        }
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Space) {
+        for entity in &previewed {
+            commands.entity(entity).despawn_recursive();
+        }
+        if let Some(wave) = editor.wave() {
+            preview_wave(wave, editor.wave, &settings, &config, &mut spawns);
+        }
+        editor.status = format!("Previewing wave {}", editor.wave + 1);
+        return;
+    }
+
+    let point = editor.point;
+    let Some(wave) = editor.wave_mut() else {
+        return;
+    };
+    let point_count = spawn_points(wave).len();
+    let point = point.min(point_count.saturating_sub(1));
+    let mut selected = point;
+    let mut status = None;
+
+    if keys.just_pressed(KeyCode::ArrowDown) && point_count > 0 {
+        selected = (point + 1) % point_count;
+    } else if keys.just_pressed(KeyCode::ArrowUp) && point_count > 0 {
+        selected = (point + point_count - 1) % point_count;
+    }
+
+    if keys.just_pressed(KeyCode::Minus) {
+        wave.delay_seconds = (wave.delay_seconds - DELAY_STEP).max(MIN_DELAY);
+        status = Some(format!("Delay {:.1}s", wave.delay_seconds));
+    } else if keys.just_pressed(KeyCode::Equal) {
+        wave.delay_seconds += DELAY_STEP;
+        status = Some(format!("Delay {:.1}s", wave.delay_seconds));
+    } else if keys.just_pressed(KeyCode::KeyT) {
+        toggle_pattern(wave);
+        status = Some(format!("Switched to a {} wave", pattern_label(wave)));
+    } else if keys.just_pressed(KeyCode::KeyP) {
+        let drop = cycle_drop(wave, point);
+        status = Some(match drop {
+            Some(kind) => format!("Spawn {} drops {:?}", point + 1, kind),
+            None => format!("Spawn {} drops nothing", point + 1),
+        });
+    } else if keys.just_pressed(KeyCode::Delete) || keys.just_pressed(KeyCode::Backspace) {
+        if remove_point(wave, point) {
+            selected = point.saturating_sub(1);
+            status = Some(format!("Removed spawn {}", point + 1));
+        } else {
+            status = Some("A wave needs at least one spawn".to_string());
+        }
+    } else if let Some((kind, movement)) = enemy_mut(wave, point) {
+        if keys.just_pressed(KeyCode::KeyE) {
+            let index = PLACEABLE_KINDS.iter().position(|k| k == kind).unwrap_or(0);
+            *kind = PLACEABLE_KINDS[(index + 1) % PLACEABLE_KINDS.len()];
+            status = Some(format!("Enemy {}", kind.label()));
+        } else if keys.just_pressed(KeyCode::KeyV) {
+            *movement = movement.next();
+            status = Some(format!("Movement {}", movement.label()));
+        } else if keys.just_pressed(KeyCode::Comma) {
+            movement.set_speed((movement.speed() - SPEED_STEP).max(MIN_SPEED));
+            status = Some(format!("Speed {:.0}", movement.speed()));
+        } else if keys.just_pressed(KeyCode::Period) {
+            movement.set_speed(movement.speed() + SPEED_STEP);
+            status = Some(format!("Speed {:.0}", movement.speed()));
+        }
+    }
+
+    if selected != editor.point {
+        editor.point = selected;
+    }
+    if let Some(status) = status {
+        editor.edited(status);
+    }
+}
+
+// Left click selects the spawn under the cursor, or moves the selected one there;
+// right click adds a spawn.
+fn editor_mouse_input(
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    config: Res<GameConfig>,
+    mut editor: ResMut<StoryboardEditor>,
+) {
+    let left = buttons.just_pressed(MouseButton::Left);
+    let right = buttons.just_pressed(MouseButton::Right);
+    if !left && !right {
+        return;
+    }
+    let Some(cursor) = windows.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(world) = camera.viewport_to_world_2d(camera_transform, cursor) else {
+        return;
+    };
+    // Back from the stretched playfield to storyboard coordinates.
+    let lane_scale = config.lane_scale();
+    let target =
+        (Vec2::new(world.x / lane_scale, world.y) / PLACEMENT_GRID).round() * PLACEMENT_GRID;
+
+    let point = editor.point;
+    let Some(wave) = editor.wave_mut() else {
+        return;
+    };
+    if right {
+        let added = add_point(wave, point, target);
+        editor.point = added;
+        editor.edited(format!("Added spawn {}", added + 1));
+        return;
+    }
+
+    let points = spawn_points(wave);
+    let under_cursor = points.iter().position(|spawn| {
+        Vec2::new(spawn.x * lane_scale, spawn.y).distance(world) < MARKER_RADIUS * EDITOR_ZOOM
+    });
+    match under_cursor {
+        Some(index) => editor.point = index,
+        None if !points.is_empty() => {
+            move_point(wave, point, target);
+            editor.edited(format!(
+                "Moved spawn {} to ({:.0}, {:.0})",
+                point + 1,
+                target.x,
+                target.y
+            ));
+        }
+        None => {}
+    }
+}
+
+fn refresh_editor_ui(
+    mut commands: Commands,
+    editor: Res<StoryboardEditor>,
+    font: Res<UiFont>,
+    mut details: Query<&mut Text, With<EditorDetails>>,
+    timelines: Query<Entity, With<EditorTimeline>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = details.get_single_mut() {
+        text.sections[0].value = describe(&editor);
+    }
+    let Ok(timeline) = timelines.get_single() else {
+        return;
+    };
+    let Some(level) = editor.storyboard.level(editor.level) else {
+        return;
+    };
+    commands.entity(timeline).despawn_descendants();
+    commands.entity(timeline).with_children(|parent| {
+        let mut start = 0.0;
+        for (index, wave) in level.waves.iter().enumerate() {
+            start += wave.delay_seconds;
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px((wave.delay_seconds * TIMELINE_PIXELS_PER_SECOND).max(48.0)),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(if index == editor.wave {
+                        TIMELINE_SELECTED_COLOR
+                    } else {
+                        TIMELINE_COLOR
+                    }),
+                    ..default()
+                })
+                .with_children(|block| {
+                    block.spawn(TextBundle::from_section(
+                        format!("W{} {:.1}s", index + 1, start),
+                        font.style(TextSize::Caption, Color::WHITE),
+                    ));
+                });
+        }
+    });
+}
+
+fn draw_editor_markers(mut gizmos: Gizmos, editor: Res<StoryboardEditor>, config: Res<GameConfig>) {
+    let half_width = config.logical_width / 2.0;
+    gizmos.rect_2d(
+        Vec2::ZERO,
+        0.0,
+        Vec2::new(config.logical_width, config.logical_height),
+        PLAYFIELD_COLOR,
+    );
+    gizmos.line_2d(
+        Vec2::new(-half_width, TOP_Y),
+        Vec2::new(half_width, TOP_Y),
+        SPAWN_LINE_COLOR,
+    );
+
+    let Some(wave) = editor.wave() else {
+        return;
+    };
+    let lane_scale = config.lane_scale();
+    for (index, spawn) in spawn_points(wave).into_iter().enumerate() {
+        let position = Vec2::new(spawn.x * lane_scale, spawn.y);
+        let kind = enemy_at(wave, index);
+        gizmos.circle_2d(position, MARKER_RADIUS, kind_color(kind));
+        if drop_at(wave, index).is_some() {
+            gizmos.circle_2d(position, MARKER_RADIUS * 0.4, DROP_COLOR);
+        }
+        if index == editor.point {
+            gizmos.circle_2d(position, MARKER_RADIUS + 6.0, SELECTED_COLOR);
+        }
+    }
+}
+
+fn describe(editor: &StoryboardEditor) -> String {
+    let level_name = editor.storyboard.level_name(editor.level).unwrap_or("-");
+    let mut lines = vec![
+        format!(
+            "Storyboard Editor{}",
+            if editor.dirty { " (unsaved)" } else { "" }
+        ),
+        format!(
+            "Level {}/{}: {}",
+            editor.level + 1,
+            editor.storyboard.level_count(),
+            level_name
+        ),
+    ];
+    if let Some(wave) = editor.wave() {
+        let point = editor.point;
+        let movement = enemy_movement(wave, point);
+        lines.push(format!(
+            "Wave {}/{}  {} pattern  delay {:.1}s",
+            editor.wave + 1,
+            editor.wave_count(),
+            pattern_label(wave),
+            wave.delay_seconds
+        ));
+        let points = spawn_points(wave);
+        if let Some(spawn) = points.get(point) {
+            lines.push(format!(
+                "Spawn {}/{} at ({:.0}, {:.0})",
+                point + 1,
+                points.len(),
+                spawn.x,
+                spawn.y
+            ));
+        }
+        lines.push(format!(
+            "Enemy {}  movement {} at {:.0}  drop {}",
+            enemy_at(wave, point).label(),
+            movement.label(),
+            movement.speed(),
+            drop_at(wave, point).map_or("none".to_string(), |kind| format!("{kind:?}"))
+        ));
+    } else {
+        lines.push("No waves - press N to add one".to_string());
+    }
+    lines.push(editor.status.clone());
+    lines.join("\n")
+}
+
+fn pattern_label(wave: &WaveDefinition) -> &'static str {
+    match wave.pattern {
+        WavePattern::Lane(_) => "lane",
+        WavePattern::Fixed { .. } => "fixed",
+    }
+}
+
+fn kind_color(kind: EnemyKind) -> Color {
+    match kind {
+        EnemyKind::Grunt => Color::srgb(1.0, 0.35, 0.35),
+        EnemyKind::Sine => Color::srgb(0.4, 0.75, 1.0),
+        EnemyKind::ZigZag => Color::srgb(0.8, 0.45, 1.0),
+        EnemyKind::Tank => Color::srgb(1.0, 0.65, 0.2),
+        EnemyKind::Chaser => Color::srgb(0.3, 1.0, 0.8),
+        EnemyKind::Boss => Color::WHITE,
+    }
+}
+
+// Lane waves spawn their row at a shared height above the top of the playfield.
+fn spawn_points(wave: &WaveDefinition) -> Vec<Vec2> {
+    match &wave.pattern {
+        WavePattern::Lane(lane) => lane
+            .lanes
+            .iter()
+            .map(|&x| Vec2::new(x, TOP_Y + lane.y_offset))
+            .collect(),
+        WavePattern::Fixed { enemies } => enemies
+            .iter()
+            .map(|enemy| enemy.position.to_vec())
+            .collect(),
+    }
+}
+
+fn enemy_at(wave: &WaveDefinition, point: usize) -> EnemyKind {
+    match &wave.pattern {
+        WavePattern::Lane(lane) => lane.enemy,
+        WavePattern::Fixed { enemies } => enemies
+            .get(point)
+            .map_or(EnemyKind::Grunt, |enemy| enemy.enemy),
+    }
+}
+
+fn enemy_movement(wave: &WaveDefinition, point: usize) -> MovementConfig {
+    match &wave.pattern {
+        WavePattern::Lane(lane) => lane.movement,
+        WavePattern::Fixed { enemies } => enemies
+            .get(point)
+            .map_or(MovementConfig::default(), |enemy| enemy.movement),
+    }
+}
+
+fn drop_at(wave: &WaveDefinition, point: usize) -> Option<PowerUpKind> {
+    match &wave.pattern {
+        WavePattern::Lane(lane) if lane.powerup_lane_index == Some(point) => lane.powerup,
+        WavePattern::Lane(_) => None,
+        WavePattern::Fixed { enemies } => enemies.get(point).and_then(|enemy| enemy.powerup),
+    }
+}
+
+// Lane waves share one enemy kind and movement across the row; fixed waves set them
+// per enemy.
+fn enemy_mut(
+    wave: &mut WaveDefinition,
+    point: usize,
+) -> Option<(&mut EnemyKind, &mut MovementConfig)> {
+    match &mut wave.pattern {
+        WavePattern::Lane(lane) => Some((&mut lane.enemy, &mut lane.movement)),
+        WavePattern::Fixed { enemies } => enemies
+            .get_mut(point)
+            .map(|enemy| (&mut enemy.enemy, &mut enemy.movement)),
+    }
+}
+
+fn move_point(wave: &mut WaveDefinition, point: usize, to: Vec2) {
+    match &mut wave.pattern {
+        WavePattern::Lane(lane) => {
+            if let Some(x) = lane.lanes.get_mut(point) {
+                *x = to.x;
+                lane.y_offset = to.y - TOP_Y;
+            }
+        }
+        WavePattern::Fixed { enemies } => {
+            if let Some(enemy) = enemies.get_mut(point) {
+                enemy.position = SpawnPoint::new(to.x, to.y);
+            }
+        }
+    }
+}
+
+// New fixed spawns copy the selected enemy. Returns the new spawn's index.
+fn add_point(wave: &mut WaveDefinition, selected: usize, at: Vec2) -> usize {
+    match &mut wave.pattern {
+        WavePattern::Lane(lane) => {
+            lane.lanes.push(at.x);
+            lane.lanes.len() - 1
+        }
+        WavePattern::Fixed { enemies } => {
+            let template = enemies.get(selected);
+            enemies.push(FixedEnemyConfig {
+                enemy: template.map_or(EnemyKind::Grunt, |enemy| enemy.enemy),
+                position: SpawnPoint::new(at.x, at.y),
+                movement: template.map_or(MovementConfig::default(), |enemy| enemy.movement),
+                powerup: None,
+                variant: template.and_then(|enemy| enemy.variant.clone()),
+            });
+            enemies.len() - 1
+        }
+    }
+}
+
+fn remove_point(wave: &mut WaveDefinition, point: usize) -> bool {
+    match &mut wave.pattern {
+        WavePattern::Lane(lane) => {
+            if lane.lanes.len() <= 1 || point >= lane.lanes.len() {
+                return false;
+            }
+            lane.lanes.remove(point);
+            lane.powerup_lane_index = match lane.powerup_lane_index {
+                Some(index) if index == point => None,
+                Some(index) if index > point => Some(index - 1),
+                other => other,
+            };
+            if lane.powerup_lane_index.is_none() {
+                lane.powerup = None;
+            }
+        }
+        WavePattern::Fixed { enemies } => {
+            if enemies.len() <= 1 || point >= enemies.len() {
+                return false;
+            }
+            enemies.remove(point);
+        }
+    }
+    true
+}
+
+// Steps the selected spawn's drop through every power-up and back to none. A lane
+// wave carries at most one drop, so giving another lane one moves it there.
+fn cycle_drop(wave: &mut WaveDefinition, point: usize) -> Option<PowerUpKind> {
+    let next = match drop_at(wave, point) {
+        None => Some(PowerUpKind::ALL[0]),
+        Some(kind) => PowerUpKind::ALL
+            .iter()
+            .position(|&other| other == kind)
+            .and_then(|index| PowerUpKind::ALL.get(index + 1))
+            .copied(),
+    };
+    match &mut wave.pattern {
+        WavePattern::Lane(lane) => {
+            lane.powerup = next;
+            lane.powerup_lane_index = next.map(|_| point);
+        }
+        WavePattern::Fixed { enemies } => {
+            if let Some(enemy) = enemies.get_mut(point) {
+                enemy.powerup = next;
+            }
+        }
+    }
+    next
+}
+
+// Converts between a lane row and individually placed enemies, keeping positions.
+// Going back to lanes, the row takes the first enemy's kind, movement, and height.
+fn toggle_pattern(wave: &mut WaveDefinition) {
+    wave.pattern = match &wave.pattern {
+        WavePattern::Lane(lane) => WavePattern::Fixed {
+            enemies: lane
+                .lanes
+                .iter()
+                .enumerate()
+                .map(|(index, &x)| FixedEnemyConfig {
+                    enemy: lane.enemy,
+                    position: SpawnPoint::new(x, TOP_Y + lane.y_offset),
+                    movement: lane.movement,
+                    powerup: lane
+                        .powerup
+                        .filter(|_| lane.powerup_lane_index == Some(index)),
+                    variant: lane.variant.clone(),
+                })
+                .collect(),
+        },
+        WavePattern::Fixed { enemies } => {
+            let Some(first) = enemies.first() else {
+                return;
+            };
+            WavePattern::Lane(LaneWaveConfig {
+                enemy: first.enemy,
+                lanes: enemies.iter().map(|enemy| enemy.position.x).collect(),
+                y_offset: first.position.y - TOP_Y,
+                movement: first.movement,
+                powerup: enemies.iter().find_map(|enemy| enemy.powerup),
+                powerup_lane_index: enemies.iter().position(|enemy| enemy.powerup.is_some()),
+                variant: first.variant.clone(),
+            })
+        }
+    };
+}
@@ -1,20 +1,86 @@
 use bevy::prelude::*;
 use bevy::sprite::{TextureAtlas, TextureAtlasLayout};
+use serde::Deserialize;
 
-use super::states::AppState;
+#[cfg(feature = "hot-reload")]
+use super::sprite_atlas::reload_sprite_atlas;
+use super::{
+    boss::{BossEvent, BossPhase},
+    config::{GameConfig, GameRng, GameSettings},
+    enemies::{EnemyCatalog, EnemyKind},
+    pool::{EntityPool, clear_pool, release},
+    ship_sprites::{AnimationCue, AnimationCueEvent},
+    sprite_atlas::{SpriteAtlas, build_sprite_atlas},
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+};
 
 pub struct EffectsPlugin;
 
 impl Plugin for EffectsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ExplosionEvent>()
+            .add_event::<EnemyDeathEvent>()
+            .add_event::<ParticleBurstEvent>()
+            .add_event::<ScorePopupEvent>()
             .init_resource::<ExplosionAssets>()
-            .add_systems(Startup, load_explosion_assets)
+            .init_resource::<EntityPool<ExplosionAnimation>>()
+            .add_systems(
+                OnExit(AppState::Loading),
+                load_explosion_assets.after(build_sprite_atlas),
+            )
+            .add_systems(
+                Update,
+                (start_death_sequences, play_wrecks, play_chain_pops)
+                    .before(spawn_explosions)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_explosions,
+                    animate_explosions,
+                    spawn_particle_bursts,
+                    emit_particles,
+                    update_particles,
+                    spawn_score_popups,
+                    animate_score_popups,
+                    spawn_warning_flashes,
+                    animate_warning_flashes,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
             .add_systems(
                 Update,
-                (spawn_explosions, animate_explosions).run_if(in_state(AppState::Playing)),
+                pulse_thrusters
+                    .before(emit_particles)
+                    .run_if(in_state(AppState::Playing)),
             )
-            .add_systems(OnExit(AppState::Playing), cleanup_explosions);
+            .add_systems(
+                Update,
+                (cap_explosions, cap_particles)
+                    .after(spawn_explosions)
+                    .after(update_particles)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                OnExit(InRun),
+                (
+                    cleanup_explosions,
+                    clear_pool::<ExplosionAnimation>,
+                    cleanup_particles,
+                    cleanup_score_popups,
+                    cleanup_warning_flashes,
+                    cleanup_death_sequences,
+                ),
+            );
+        #[cfg(feature = "hot-reload")]
+        app.add_systems(
+            Update,
+            load_explosion_assets
+                .after(reload_sprite_atlas)
+                .run_if(resource_exists_and_changed::<SpriteAtlas>),
+        );
     }
 }
 
@@ -33,16 +99,225 @@ pub struct ExplosionEvent {
     pub large: bool,
 }
 
+// A kill, played out with the kind's death sequence.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyDeathEvent {
+    pub kind: EnemyKind,
+    pub position: Vec2,
+    // The atlas frame and drawn size the ship had, for sequences that leave a wreck.
+    pub frame: usize,
+    pub size: Vec2,
+}
+
+impl EnemyDeathEvent {
+    pub fn new(kind: EnemyKind, position: Vec2, sprite: &Sprite, atlas: &TextureAtlas) -> Self {
+        Self {
+            kind,
+            position,
+            frame: atlas.index,
+            size: sprite.custom_size.unwrap_or(kind.body_size()),
+        }
+    }
+}
+
+// How a ship goes down; each enemy kind names its own in `assets/enemies.ron`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DeathSequence {
+    // A single blast.
+    Explode,
+    // A small pop of sparks; light craft barely leave a mark.
+    Fizzle,
+    // The hull is knocked into a smoking tumble and blows a moment later.
+    TumblingWreck,
+    // Small blasts rippling across the hull, building to a large one.
+    ChainPop,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ParticleBurstEvent {
+    pub position: Vec2,
+    pub burst: ParticleBurst,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScorePopupEvent {
+    pub position: Vec2,
+    pub value: u32,
+}
+
 #[derive(Component)]
-struct ExplosionAnimation {
+struct ScorePopup {
+    age: f32,
+    lifetime: f32,
+    rise_speed: f32,
+    color: Color,
+}
+
+#[derive(Component)]
+struct WarningFlash {
+    age: f32,
+    pulses: f32,
+    period: f32,
+    peak_alpha: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleBurst {
+    Debris,
+    LargeDebris,
+    Sparks,
+    Fizzle,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ColorRamp {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl ColorRamp {
+    pub const fn new(start: Color, end: Color) -> Self {
+        Self { start, end }
+    }
+
+    pub fn sample(&self, t: f32) -> Color {
+        self.start.mix(&self.end, t.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct ParticleEmitter {
+    pub rate: f32,
+    pub lifetime: f32,
+    pub direction: Vec2,
+    pub spread: f32,
+    pub speed: f32,
+    pub speed_jitter: f32,
+    pub start_size: f32,
+    pub end_size: f32,
+    pub colors: ColorRamp,
+    pub accumulator: f32,
+}
+
+impl ParticleEmitter {
+    pub fn thruster() -> Self {
+        Self {
+            rate: 90.0,
+            lifetime: 0.22,
+            direction: Vec2::NEG_Y,
+            spread: 0.35,
+            speed: 170.0,
+            speed_jitter: 50.0,
+            start_size: 5.0,
+            end_size: 1.0,
+            colors: ColorRamp::new(
+                Color::srgba(1.0, 0.85, 0.4, 0.95),
+                Color::srgba(1.0, 0.25, 0.1, 0.0),
+            ),
+            accumulator: 0.0,
+        }
+    }
+
+    // Slow grey plumes trailing off a damaged hull.
+    pub fn smoke() -> Self {
+        Self {
+            rate: 24.0,
+            lifetime: 0.8,
+            direction: Vec2::Y,
+            spread: 0.5,
+            speed: 45.0,
+            speed_jitter: 15.0,
+            start_size: 6.0,
+            end_size: 14.0,
+            colors: ColorRamp::new(
+                Color::srgba(0.45, 0.43, 0.42, 0.7),
+                Color::srgba(0.2, 0.2, 0.22, 0.0),
+            ),
+            accumulator: 0.0,
+        }
+    }
+
+    // Sputtering sparks from a hull close to giving out.
+    pub fn damage_sparks() -> Self {
+        Self {
+            rate: 18.0,
+            lifetime: 0.25,
+            direction: Vec2::Y,
+            spread: 1.4,
+            speed: 150.0,
+            speed_jitter: 60.0,
+            start_size: 3.0,
+            end_size: 1.0,
+            colors: ColorRamp::new(
+                Color::srgba(1.0, 0.95, 0.7, 1.0),
+                Color::srgba(1.0, 0.5, 0.1, 0.0),
+            ),
+            accumulator: 0.0,
+        }
+    }
+}
+
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    drag: f32,
+    start_size: f32,
+    end_size: f32,
+    colors: ColorRamp,
+}
+
+struct ParticleSpec {
+    count: usize,
+    lifetime: (f32, f32),
+    speed: (f32, f32),
+    size: (f32, f32),
+    drag: f32,
+    colors: ColorRamp,
+}
+
+// What's left of a ship playing `DeathSequence::TumblingWreck`.
+#[derive(Component)]
+pub struct Wreck {
+    age: f32,
+    velocity: Vec2,
+    spin: f32,
+}
+
+// A `DeathSequence::ChainPop` under way, popping around `center`.
+#[derive(Component)]
+pub struct ChainPop {
+    timer: Timer,
+    pops_left: u32,
+    center: Vec2,
+    half: Vec2,
+}
+
+#[derive(Component)]
+pub struct ExplosionAnimation {
     timer: Timer,
     frame: usize,
     sequence: usize,
 }
 
-const FRAME_SIZE: UVec2 = UVec2::new(16, 16);
-const SHEET_COLUMNS: u32 = 36;
-const SHEET_ROWS: u32 = 13;
+// Hard limits on live effects. Past them the oldest are removed first, so a crowded
+// final boss phase stays bounded instead of piling up thousands of sprites.
+const MAX_EXPLOSIONS: usize = 64;
+const MAX_PARTICLES: usize = 1200;
+// Particles that drift this far past the edge of the playfield are dropped early.
+const PARTICLE_CULL_MARGIN: f32 = 32.0;
+const WRECK_SECONDS: f32 = 0.9;
+const WRECK_FALL_SPEED: f32 = 70.0;
+const WRECK_SPIN: f32 = 5.0;
+const CHAIN_POP_INTERVAL: f32 = 0.09;
+const CHAIN_POPS: u32 = 7;
+// Extra exhaust a thruster puffs out on its ship's pulse frame.
+const THRUSTER_PULSE_PARTICLES: f32 = 6.0;
+
+pub const EFFECTS_SHEET: &str = "images/explosions.png";
+pub const EFFECTS_FRAME_SIZE: UVec2 = UVec2::new(16, 16);
+const SHEET_COLUMNS: usize = 36;
 const EXPLOSION_SETS: [[usize; 6]; 4] = [
     [30, 31, 32, 33, 34, 35],
     [66, 67, 68, 69, 70, 71],
@@ -56,29 +331,27 @@ const POWERUP_ROWS: [usize; 3] = [3, 7, 9];
 const POWERUP_START_COLUMN: usize = 19;
 const POWERUP_FRAME_COUNT: usize = 4;
 
-fn load_explosion_assets(
-    mut commands: Commands,
-    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
-    asset_server: Res<AssetServer>,
-) {
-    let texture = asset_server.load("images/explosions.png");
-    let layout = TextureAtlasLayout::from_grid(FRAME_SIZE, SHEET_COLUMNS, SHEET_ROWS, None, None);
-    let layout_handle = layouts.add(layout);
-    let explosion_sequences = EXPLOSION_SETS.iter().map(|seq| seq.to_vec()).collect();
+fn load_explosion_assets(mut commands: Commands, atlas: Res<SpriteAtlas>) {
+    let rows = &atlas.sheet(EFFECTS_SHEET).rows;
+    let frame = |index: usize| rows[index / SHEET_COLUMNS][index % SHEET_COLUMNS];
+    let explosion_sequences = EXPLOSION_SETS
+        .iter()
+        .map(|seq| seq.iter().map(|&index| frame(index)).collect())
+        .collect();
     let bullet_sequence = (0..BULLET_FRAME_COUNT)
-        .map(|i| BULLET_ROW * SHEET_COLUMNS as usize + BULLET_START_COLUMN + i)
+        .map(|i| rows[BULLET_ROW][BULLET_START_COLUMN + i])
         .collect();
     let powerup_sequences = POWERUP_ROWS
         .iter()
-        .map(|row| {
+        .map(|&row| {
             (0..POWERUP_FRAME_COUNT)
-                .map(|i| row * SHEET_COLUMNS as usize + POWERUP_START_COLUMN + i)
+                .map(|i| rows[row][POWERUP_START_COLUMN + i])
                 .collect()
         })
         .collect();
     commands.insert_resource(ExplosionAssets {
-        texture,
-        layout: layout_handle,
+        texture: atlas.texture.clone(),
+        layout: atlas.layout.clone(),
         explosion_sequences,
         bullet_sequence,
         powerup_sequences,
@@ -89,6 +362,8 @@ fn spawn_explosions(
     mut commands: Commands,
     assets: Res<ExplosionAssets>,
     mut events: EventReader<ExplosionEvent>,
+    mut rng: ResMut<GameRng>,
+    mut pool: ResMut<EntityPool<ExplosionAnimation>>,
 ) {
     if events.is_empty() {
         return;
@@ -102,27 +377,171 @@ fn spawn_explosions(
         };
         let frames = &assets.explosion_sequences[sequence_index];
         let scale = if event.large { 4.5 } else { 2.8 };
-        commands.spawn((
-            SpriteBundle {
-                texture: assets.texture.clone(),
-                transform: Transform::from_translation(event.position.extend(5.0))
-                    .with_scale(Vec3::splat(scale)),
-                sprite: Sprite {
-                    anchor: bevy::sprite::Anchor::Center,
+        pool.spawn(
+            &mut commands,
+            (
+                SpriteBundle {
+                    texture: assets.texture.clone(),
+                    transform: Transform::from_translation(event.position.extend(5.0))
+                        .with_scale(Vec3::splat(scale)),
+                    sprite: Sprite {
+                        anchor: bevy::sprite::Anchor::Center,
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-            TextureAtlas {
-                layout: assets.layout.clone(),
-                index: frames[0],
-            },
-            ExplosionAnimation {
-                timer: Timer::from_seconds(0.04, TimerMode::Repeating),
-                frame: 0,
-                sequence: sequence_index,
-            },
-        ));
+                TextureAtlas {
+                    layout: assets.layout.clone(),
+                    index: frames[0],
+                },
+                ExplosionAnimation {
+                    timer: Timer::from_seconds(0.04, TimerMode::Repeating),
+                    frame: 0,
+                    sequence: sequence_index,
+                },
+            ),
+        );
+        let burst = if event.large {
+            ParticleBurst::LargeDebris
+        } else {
+            ParticleBurst::Debris
+        };
+        spawn_burst(&mut commands, &mut rng, event.position, burst);
+    }
+}
+
+fn start_death_sequences(
+    mut commands: Commands,
+    assets: Res<ExplosionAssets>,
+    catalog: Res<EnemyCatalog>,
+    mut deaths: EventReader<EnemyDeathEvent>,
+    mut explosions: EventWriter<ExplosionEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for death in deaths.read() {
+        match catalog.spec(death.kind).death {
+            DeathSequence::Explode => {
+                explosions.send(ExplosionEvent {
+                    position: death.position,
+                    large: false,
+                });
+            }
+            DeathSequence::Fizzle => {
+                spawn_burst(
+                    &mut commands,
+                    &mut rng,
+                    death.position,
+                    ParticleBurst::Fizzle,
+                );
+                spawn_burst(
+                    &mut commands,
+                    &mut rng,
+                    death.position,
+                    ParticleBurst::Sparks,
+                );
+            }
+            DeathSequence::TumblingWreck => {
+                // The first blast knocks the hull aside; the wreck blows when it stops.
+                explosions.send(ExplosionEvent {
+                    position: death.position,
+                    large: false,
+                });
+                let spin = if rng.signed() < 0.0 { -1.0 } else { 1.0 } * WRECK_SPIN;
+                commands
+                    .spawn((
+                        SpriteBundle {
+                            texture: assets.texture.clone(),
+                            transform: Transform::from_translation(death.position.extend(1.0)),
+                            sprite: Sprite {
+                                color: Color::srgb(0.6, 0.55, 0.5),
+                                custom_size: Some(death.size),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        TextureAtlas {
+                            layout: assets.layout.clone(),
+                            index: death.frame,
+                        },
+                        Wreck {
+                            age: 0.0,
+                            velocity: Vec2::new(rng.signed() * 40.0, -WRECK_FALL_SPEED),
+                            spin,
+                        },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((SpatialBundle::default(), ParticleEmitter::smoke()));
+                    });
+            }
+            DeathSequence::ChainPop => {
+                commands.spawn(ChainPop {
+                    timer: Timer::from_seconds(CHAIN_POP_INTERVAL, TimerMode::Repeating),
+                    pops_left: CHAIN_POPS,
+                    center: death.position,
+                    half: death.size * 0.5,
+                });
+            }
+        }
+    }
+}
+
+fn play_wrecks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut wrecks: Query<(Entity, &mut Wreck, &mut Transform)>,
+    mut explosions: EventWriter<ExplosionEvent>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut wreck, mut transform) in &mut wrecks {
+        wreck.age += delta;
+        if wreck.age >= WRECK_SECONDS {
+            explosions.send(ExplosionEvent {
+                position: transform.translation.truncate(),
+                large: true,
+            });
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += (wreck.velocity * delta).extend(0.0);
+        transform.rotate_z(wreck.spin * delta);
+    }
+}
+
+fn play_chain_pops(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut chains: Query<(Entity, &mut ChainPop)>,
+    mut explosions: EventWriter<ExplosionEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for (entity, mut chain) in &mut chains {
+        if !chain.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        chain.pops_left = chain.pops_left.saturating_sub(1);
+        if chain.pops_left == 0 {
+            explosions.send(ExplosionEvent {
+                position: chain.center,
+                large: true,
+            });
+            commands.entity(entity).despawn();
+        } else {
+            let offset = Vec2::new(rng.signed(), rng.signed()) * chain.half * 0.8;
+            explosions.send(ExplosionEvent {
+                position: chain.center + offset,
+                large: false,
+            });
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_death_sequences(
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<Wreck>, With<ChainPop>)>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
     }
 }
 
@@ -137,7 +556,7 @@ fn animate_explosions(
             anim.frame += 1;
             let frames = &assets.explosion_sequences[anim.sequence];
             if anim.frame >= frames.len() {
-                commands.entity(entity).despawn_recursive();
+                release::<ExplosionAnimation>(&mut commands, entity);
             } else {
                 atlas.index = frames[anim.frame];
             }
@@ -145,12 +564,357 @@ fn animate_explosions(
     }
 }
 
+fn cap_explosions(mut commands: Commands, query: Query<(Entity, &ExplosionAnimation)>) {
+    let excess = query.iter().len().saturating_sub(MAX_EXPLOSIONS);
+    if excess == 0 {
+        return;
+    }
+    let ages = query
+        .iter()
+        .map(|(entity, anim)| (entity, anim.frame as f32 + anim.timer.fraction()));
+    for entity in oldest(ages, excess) {
+        release::<ExplosionAnimation>(&mut commands, entity);
+    }
+}
+
 fn cleanup_explosions(mut commands: Commands, query: Query<Entity, With<ExplosionAnimation>>) {
     for entity in &query {
         commands.entity(entity).despawn_recursive();
     }
 }
 
+fn spawn_particle_bursts(
+    mut commands: Commands,
+    mut events: EventReader<ParticleBurstEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for event in events.read() {
+        spawn_burst(&mut commands, &mut rng, event.position, event.burst);
+    }
+}
+
+fn burst_spec(burst: ParticleBurst) -> ParticleSpec {
+    match burst {
+        ParticleBurst::Debris => ParticleSpec {
+            count: 10,
+            lifetime: (0.35, 0.7),
+            speed: (80.0, 220.0),
+            size: (3.0, 6.0),
+            drag: 2.5,
+            colors: ColorRamp::new(
+                Color::srgb(0.85, 0.8, 0.75),
+                Color::srgba(0.35, 0.3, 0.3, 0.0),
+            ),
+        },
+        ParticleBurst::LargeDebris => ParticleSpec {
+            count: 24,
+            lifetime: (0.5, 1.1),
+            speed: (100.0, 320.0),
+            size: (4.0, 9.0),
+            drag: 2.0,
+            colors: ColorRamp::new(
+                Color::srgb(1.0, 0.8, 0.55),
+                Color::srgba(0.3, 0.25, 0.25, 0.0),
+            ),
+        },
+        ParticleBurst::Sparks => ParticleSpec {
+            count: 5,
+            lifetime: (0.12, 0.25),
+            speed: (160.0, 320.0),
+            size: (2.0, 3.5),
+            drag: 6.0,
+            colors: ColorRamp::new(
+                Color::srgb(1.0, 1.0, 0.75),
+                Color::srgba(1.0, 0.55, 0.2, 0.0),
+            ),
+        },
+        ParticleBurst::Fizzle => ParticleSpec {
+            count: 12,
+            lifetime: (0.2, 0.45),
+            speed: (30.0, 110.0),
+            size: (2.0, 4.0),
+            drag: 4.0,
+            colors: ColorRamp::new(
+                Color::srgb(0.75, 0.9, 1.0),
+                Color::srgba(0.3, 0.45, 0.8, 0.0),
+            ),
+        },
+    }
+}
+
+fn spawn_burst(commands: &mut Commands, rng: &mut GameRng, position: Vec2, burst: ParticleBurst) {
+    let spec = burst_spec(burst);
+    for _ in 0..spec.count {
+        let velocity = rng.unit_vector() * rng.range(spec.speed.0, spec.speed.1);
+        let size = rng.range(spec.size.0, spec.size.1);
+        spawn_particle(
+            commands,
+            position,
+            Particle {
+                velocity,
+                age: 0.0,
+                lifetime: rng.range(spec.lifetime.0, spec.lifetime.1),
+                drag: spec.drag,
+                start_size: size,
+                end_size: size * 0.3,
+                colors: spec.colors,
+            },
+        );
+    }
+}
+
+fn spawn_particle(commands: &mut Commands, position: Vec2, particle: Particle) {
+    commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_translation(position.extend(4.0))
+                .with_scale(Vec3::splat(particle.start_size)),
+            sprite: Sprite {
+                color: particle.colors.start,
+                custom_size: Some(Vec2::ONE),
+                ..default()
+            },
+            ..default()
+        },
+        particle,
+    ));
+}
+
+fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut emitters: Query<(&mut ParticleEmitter, &GlobalTransform)>,
+) {
+    let delta = time.delta_seconds();
+    for (mut emitter, transform) in &mut emitters {
+        emitter.accumulator += emitter.rate * delta;
+        let origin = transform.translation().truncate();
+        while emitter.accumulator >= 1.0 {
+            emitter.accumulator -= 1.0;
+            let angle = rng.signed() * emitter.spread;
+            let direction = Vec2::from_angle(angle).rotate(emitter.direction);
+            let speed = emitter.speed + rng.signed() * emitter.speed_jitter;
+            let mut position = origin;
+            position.x += rng.signed() * 2.0;
+            spawn_particle(
+                &mut commands,
+                position,
+                Particle {
+                    velocity: direction * speed,
+                    age: 0.0,
+                    lifetime: emitter.lifetime,
+                    drag: 0.0,
+                    start_size: emitter.start_size,
+                    end_size: emitter.end_size,
+                    colors: emitter.colors,
+                },
+            );
+        }
+    }
+}
+
+fn pulse_thrusters(
+    mut cues: EventReader<AnimationCueEvent>,
+    mut emitters: Query<(&Parent, &mut ParticleEmitter)>,
+) {
+    for event in cues.read() {
+        if event.cue != AnimationCue::ThrusterPulse {
+            continue;
+        }
+        for (parent, mut emitter) in &mut emitters {
+            if parent.get() == event.entity {
+                emitter.accumulator += THRUSTER_PULSE_PARTICLES;
+            }
+        }
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_seconds();
+    let bounds = Vec2::new(config.logical_width, config.logical_height) * 0.5
+        + Vec2::splat(PARTICLE_CULL_MARGIN);
+    for (entity, mut particle, mut transform, mut sprite) in &mut query {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = particle.age / particle.lifetime;
+        let damping = (1.0 - particle.drag * delta).max(0.0);
+        particle.velocity *= damping;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        // Particles only ever drift further out, so one that has left the screen is gone.
+        if transform.translation.truncate().abs().cmpgt(bounds).any() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.scale =
+            Vec3::splat(particle.start_size + (particle.end_size - particle.start_size) * t);
+        sprite.color = particle.colors.sample(t);
+    }
+}
+
+fn cap_particles(mut commands: Commands, query: Query<(Entity, &Particle)>) {
+    let excess = query.iter().len().saturating_sub(MAX_PARTICLES);
+    if excess == 0 {
+        return;
+    }
+    let ages = query
+        .iter()
+        .map(|(entity, particle)| (entity, particle.age));
+    for entity in oldest(ages, excess) {
+        commands.entity(entity).despawn();
+    }
+}
+
+// The `count` entities with the greatest age, oldest first.
+pub fn oldest(ages: impl Iterator<Item = (Entity, f32)>, count: usize) -> Vec<Entity> {
+    let mut ages: Vec<_> = ages.collect();
+    ages.sort_unstable_by(|a, b| b.1.total_cmp(&a.1));
+    ages.into_iter()
+        .take(count)
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+fn cleanup_particles(mut commands: Commands, query: Query<Entity, With<Particle>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_score_popups(
+    mut commands: Commands,
+    mut events: EventReader<ScorePopupEvent>,
+    font: Res<UiFont>,
+) {
+    for event in events.read() {
+        let big = event.value >= 1000;
+        let color = if big {
+            Color::srgb(1.0, 0.85, 0.3)
+        } else {
+            Color::srgb(0.95, 0.95, 1.0)
+        };
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("+{}", event.value),
+                    font.style(
+                        if big {
+                            TextSize::Body
+                        } else {
+                            TextSize::Caption
+                        },
+                        color,
+                    ),
+                ),
+                transform: Transform::from_translation(event.position.extend(7.0)),
+                ..default()
+            },
+            ScorePopup {
+                age: 0.0,
+                lifetime: if big { 1.4 } else { 0.8 },
+                rise_speed: 60.0,
+                color,
+            },
+        ));
+    }
+}
+
+fn animate_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut popup, mut transform, mut text) in &mut query {
+        popup.age += delta;
+        if popup.age >= popup.lifetime {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let t = popup.age / popup.lifetime;
+        transform.translation.y += popup.rise_speed * (1.0 - t) * delta;
+        let alpha = if t < 0.6 { 1.0 } else { 1.0 - (t - 0.6) / 0.4 };
+        for section in &mut text.sections {
+            section.style.color = popup.color.with_alpha(alpha);
+        }
+    }
+}
+
+fn cleanup_score_popups(mut commands: Commands, query: Query<Entity, With<ScorePopup>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_warning_flashes(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    settings: Res<GameSettings>,
+    mut events: EventReader<BossEvent>,
+) {
+    for event in events.read() {
+        let (pulses, peak_alpha) = match event {
+            BossEvent::PhaseChanged(BossPhase::Second) => (2.0, 0.22),
+            BossEvent::PhaseChanged(BossPhase::Final) => (3.0, 0.32),
+            _ => continue,
+        };
+        // One slow, dim swell rather than a strobe.
+        let (pulses, peak_alpha, period) = if settings.reduce_flashing {
+            (1.0, peak_alpha * 0.4, 1.2)
+        } else {
+            (pulses, peak_alpha, 0.45)
+        };
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(0.0, 0.0, 8.0),
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 0.15, 0.1, 0.0),
+                    custom_size: Some(Vec2::new(config.logical_width, config.logical_height) * 1.2),
+                    ..default()
+                },
+                ..default()
+            },
+            WarningFlash {
+                age: 0.0,
+                pulses,
+                period,
+                peak_alpha,
+            },
+        ));
+    }
+}
+
+fn animate_warning_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut WarningFlash, &mut Sprite)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut flash, mut sprite) in &mut query {
+        flash.age += delta;
+        let cycle = flash.age / flash.period;
+        if cycle >= flash.pulses {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let pulse = (cycle.fract() * std::f32::consts::PI).sin();
+        sprite.color.set_alpha(flash.peak_alpha * pulse);
+    }
+}
+
+fn cleanup_warning_flashes(mut commands: Commands, query: Query<Entity, With<WarningFlash>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
 fn rand_hash(value: Vec2) -> u32 {
     let mut x = value.x.to_bits() ^ value.y.to_bits();
     x ^= x >> 16;
@@ -1,35 +1,69 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+
 use bevy::{prelude::*, sprite::TextureAtlas, time::Fixed};
+use serde::{Deserialize, Serialize};
 
 use super::{
-    config::{GameConfig, GameSettings},
+    collisions::EnemyDestroyedEvent,
+    config::{GameConfig, GameRng, GameSettings, RunDifficulty},
+    effects::{DeathSequence, ParticleBurst, ParticleBurstEvent},
+    interpolation::TransformInterpolation,
     player::Player,
     powerups::{DropsPowerUp, PowerUpKind},
-    ship_sprites::{ShipAnimation, ShipSpriteAssets, ShipSpriteId},
-    states::AppState,
+    rank::Rank,
+    rules::RuleSet,
+    ship_sprites::{
+        AnimationCue, AnimationCueEvent, ShipAnimation, ShipRow, ShipSpriteAssets, ShipSpriteId,
+    },
+    spawn::WaveDirector,
+    states::{AppState, InRun},
+    trails::Trail,
     weapons::EnemyFireEvent,
 };
 
+// Chance per unit of drop rate above 1 that an enemy without a scripted drop gets one.
+const BONUS_DROP_CHANCE: f32 = 0.15;
+const REVENGE_BULLET_SPEED: f32 = 200.0;
+const REVENGE_SPREAD_DEGREES: f32 = 12.0;
+const ENEMY_CATALOG_PATH: &str = "assets/enemies.ron";
+const BUILT_IN_CATALOG: &str = include_str!("../../assets/enemies.ron");
+
 pub struct EnemiesPlugin;
 
 impl Plugin for EnemiesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnEnemyEvent>()
-            .add_systems(OnEnter(AppState::Playing), reset_enemies)
-            .add_systems(OnExit(AppState::Playing), cleanup_enemies)
+        let path = Path::new(ENEMY_CATALOG_PATH);
+        let catalog = EnemyCatalog::from_file(path).unwrap_or_else(|err| {
+            warn!(
+                "Failed to load the enemy catalog from {}: {}. Using built-in defaults.",
+                path.display(),
+                err
+            );
+            EnemyCatalog::default()
+        });
+        app.insert_resource(catalog)
+            .add_event::<SpawnEnemyEvent>()
+            .add_systems(OnEnter(InRun), reset_enemies)
+            .add_systems(OnExit(InRun), cleanup_enemies)
             .add_systems(
                 FixedUpdate,
                 (
-                    spawn_enemies_from_events,
-                    move_enemies,
-                    enemy_fire_system,
-                    cleanup_offscreen_enemies,
-                )
-                    .run_if(in_state(AppState::Playing)),
+                    (enemy_fire_system, revenge_bullets, tick_contact_cooldowns)
+                        .run_if(in_state(AppState::Playing)),
+                    // The storyboard editor previews waves with these alone.
+                    (
+                        spawn_enemies_from_events,
+                        move_enemies,
+                        cleanup_offscreen_enemies,
+                    )
+                        .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Editor))),
+                ),
             );
     }
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EnemyKind {
     Grunt,
     Sine,
@@ -40,6 +74,26 @@ pub enum EnemyKind {
 }
 
 impl EnemyKind {
+    pub const ALL: [EnemyKind; 6] = [
+        EnemyKind::Grunt,
+        EnemyKind::Sine,
+        EnemyKind::ZigZag,
+        EnemyKind::Tank,
+        EnemyKind::Chaser,
+        EnemyKind::Boss,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EnemyKind::Grunt => "Grunt",
+            EnemyKind::Sine => "Sine",
+            EnemyKind::ZigZag => "Zig-zag",
+            EnemyKind::Tank => "Tank",
+            EnemyKind::Chaser => "Chaser",
+            EnemyKind::Boss => "Boss",
+        }
+    }
+
     pub fn health(self) -> i32 {
         match self {
             EnemyKind::Grunt => 1,
@@ -72,14 +126,125 @@ impl EnemyKind {
             EnemyKind::Boss => Vec2::new(220.0, 120.0),
         }
     }
+
+    // Tough enough to take a beating, so its wear is shown as it goes.
+    pub fn shows_damage(self) -> bool {
+        matches!(self, EnemyKind::Tank | EnemyKind::Boss)
+    }
+
+    pub fn contact_cooldown(self) -> Option<f32> {
+        match self {
+            EnemyKind::Tank => Some(0.9),
+            EnemyKind::Boss => Some(1.2),
+            _ => None,
+        }
+    }
+}
+
+// One kind's entry in `assets/enemies.ron`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct EnemySpec {
+    pub death: DeathSequence,
+}
+
+// Per-kind enemy data, keyed by kind, so it can be tuned without a rebuild.
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct EnemyCatalog(HashMap<EnemyKind, EnemySpec>);
+
+impl EnemyCatalog {
+    pub fn from_file(path: &Path) -> Result<Self, EnemyCatalogError> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_ron(&contents)
+    }
+
+    pub fn from_ron(contents: &str) -> Result<Self, EnemyCatalogError> {
+        let catalog: EnemyCatalog = ron::from_str(contents)?;
+        if let Some(kind) = EnemyKind::ALL
+            .into_iter()
+            .find(|kind| !catalog.0.contains_key(kind))
+        {
+            return Err(EnemyCatalogError::Invalid(format!("{kind:?} has no entry")));
+        }
+        Ok(catalog)
+    }
+
+    pub fn spec(&self, kind: EnemyKind) -> &EnemySpec {
+        self.0.get(&kind).expect("enemy catalog was validated")
+    }
+}
+
+// The catalog as it was at build time, for when `enemies.ron` is missing or broken.
+impl Default for EnemyCatalog {
+    fn default() -> Self {
+        Self::from_ron(BUILT_IN_CATALOG).expect("the built-in enemy catalog is valid")
+    }
+}
+
+#[derive(Debug)]
+pub enum EnemyCatalogError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl fmt::Display for EnemyCatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnemyCatalogError::Io(err) => write!(f, "I/O error: {}", err),
+            EnemyCatalogError::Parse(err) => write!(f, "parse error: {}", err),
+            EnemyCatalogError::Invalid(problem) => write!(f, "invalid entry: {}", problem),
+        }
+    }
+}
+
+impl std::error::Error for EnemyCatalogError {}
+
+impl From<std::io::Error> for EnemyCatalogError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for EnemyCatalogError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Parse(value)
+    }
 }
 
 #[derive(Component)]
 pub struct Enemy {
     pub kind: EnemyKind,
     pub health: i32,
+    // What it spawned with, after difficulty scaling.
+    pub max_health: i32,
     pub score: u32,
     pub damage: u8,
+    // The storyboard wave it arrived with, numbered from 1 within its level.
+    pub wave: Option<u32>,
+}
+
+#[derive(Component)]
+pub struct ContactCooldown {
+    pub duration: f32,
+    pub remaining: f32,
+}
+
+impl ContactCooldown {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            remaining: 0.0,
+        }
+    }
+
+    pub fn ready(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    pub fn trigger(&mut self) {
+        self.remaining = self.duration;
+    }
 }
 
 #[derive(Clone)]
@@ -107,6 +272,35 @@ pub enum MovementPattern {
     },
 }
 
+impl MovementPattern {
+    // The same path reflected across the centre line, for mirror mode.
+    pub fn mirrored(self) -> Self {
+        match self {
+            MovementPattern::Sine {
+                speed,
+                amplitude,
+                frequency,
+                base_x,
+            } => MovementPattern::Sine {
+                speed,
+                amplitude: -amplitude,
+                frequency,
+                base_x: -base_x,
+            },
+            MovementPattern::ZigZag {
+                speed,
+                horizontal_speed,
+                direction,
+            } => MovementPattern::ZigZag {
+                speed,
+                horizontal_speed,
+                direction: -direction,
+            },
+            pattern => pattern,
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct EnemyMotion {
     pub pattern: MovementPattern,
@@ -119,6 +313,8 @@ pub struct EnemyWeapon {
     pub bullet_speed: f32,
     pub pattern: FirePattern,
     pub damage: u8,
+    // The timer has come round and the shot is waiting for the ship's muzzle-flash frame.
+    pub primed: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -134,6 +330,11 @@ pub struct SpawnEnemyEvent {
     pub position: Vec2,
     pub movement: MovementPattern,
     pub powerup: Option<PowerUpKind>,
+    // A recolouring from the ship manifest; without one the ship may still take on the
+    // level's colours.
+    pub variant: Option<String>,
+    // None for bonus stage waves, editor previews, and anything sent outside the ladder.
+    pub wave: Option<u32>,
 }
 
 fn reset_enemies(mut commands: Commands, query: Query<Entity, With<Enemy>>) {
@@ -145,14 +346,21 @@ fn reset_enemies(mut commands: Commands, query: Query<Entity, With<Enemy>>) {
 fn spawn_enemies_from_events(
     mut commands: Commands,
     mut reader: EventReader<SpawnEnemyEvent>,
-    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    director: Res<WaveDirector>,
     sprites: Res<ShipSpriteAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let knobs = run_difficulty.knobs();
+    let drop_rate = knobs.drop_rate * director.loop_drop_rate();
     for event in reader.read() {
         let size = event.kind.body_size();
         let (ship_id, row) = enemy_sprite_info(event.kind);
         let sprite_data = sprites.data(ship_id);
-        let sequence = sprites.sequence(ship_id, row);
+        let level = director.level_index as u32 + 1;
+        let variant = sprites.variant_for(ship_id, event.variant.as_deref(), level);
+        let health = ((event.kind.health() as f32) * knobs.enemy_health).ceil() as i32;
+        let sequence = sprites.variant_sequence(ship_id, variant.as_deref(), row);
         let mut entity = commands.spawn((
             SpriteBundle {
                 texture: sprite_data.texture.clone(),
@@ -170,38 +378,66 @@ fn spawn_enemies_from_events(
             },
             Enemy {
                 kind: event.kind,
-                health: ((event.kind.health() as f32) * settings.difficulty.enemy_health_factor())
-                    .ceil() as i32,
+                health,
+                max_health: health,
                 score: event.kind.score_value(),
                 damage: 1,
+                wave: event.wave,
             },
             EnemyMotion {
                 pattern: event.movement.clone(),
                 elapsed: 0.0,
             },
-            ShipAnimation::new(ship_id, row, 0.1),
+            ShipAnimation::new(&sprites, ship_id, row).with_variant(variant),
+            TransformInterpolation::default(),
         ));
 
-        if let Some(powerup) = event.powerup {
+        let powerup = match event.powerup {
+            _ if director.in_bonus_stage() && event.kind != EnemyKind::Boss => {
+                let kinds = PowerUpKind::ALL;
+                Some(
+                    event
+                        .powerup
+                        .unwrap_or(kinds[rng.next_u32() as usize % kinds.len()]),
+                )
+            }
+            Some(kind) => (rng.next_f32() < drop_rate).then_some(kind),
+            None if event.kind != EnemyKind::Boss
+                && rng.next_f32() < (drop_rate - 1.0) * BONUS_DROP_CHANCE =>
+            {
+                let kinds = PowerUpKind::ALL;
+                Some(kinds[rng.next_u32() as usize % kinds.len()])
+            }
+            None => None,
+        };
+        if let Some(powerup) = powerup {
             entity.insert(DropsPowerUp { kind: powerup });
         }
         if let Some(weapon) = default_weapon(event.kind) {
             entity.insert(weapon);
         }
+        if let Some(cooldown) = event.kind.contact_cooldown() {
+            entity.insert(ContactCooldown::new(cooldown));
+        }
+        if event.kind == EnemyKind::Chaser {
+            entity.insert(Trail::chaser());
+        }
     }
 }
 
 fn move_enemies(
-    mut query: Query<(&mut Transform, &mut EnemyMotion), Without<Player>>,
+    mut query: Query<(&mut Transform, &mut EnemyMotion, &mut ShipAnimation), Without<Player>>,
     time: Res<Time<Fixed>>,
     player: Query<&Transform, With<Player>>,
     config: Res<GameConfig>,
 ) {
+    let _span = info_span!("move_enemies", enemies = query.iter().len()).entered();
     let delta = time.delta_seconds();
     let player_x = player.get_single().map(|t| t.translation.x).unwrap_or(0.0);
     let horizontal_bounds = config.logical_width * 0.5 - 40.0;
 
-    for (mut transform, mut motion) in &mut query {
+    for (mut transform, mut motion, mut animation) in &mut query {
+        let start_x = transform.translation.x;
         motion.elapsed += delta;
         let elapsed = motion.elapsed;
         match &mut motion.pattern {
@@ -238,26 +474,63 @@ fn move_enemies(
                 transform.translation.x += dx * delta * 60.0;
             }
         }
+        if delta > 0.0 {
+            animation.bank((transform.translation.x - start_x) / delta);
+        }
     }
 }
 
+// Ships whose art has a muzzle-flash frame hold each shot until it comes round, and
+// fire it with a burst of sparks; the rest fire as soon as their timer does.
+#[allow(clippy::too_many_arguments)]
 fn enemy_fire_system(
-    mut query: Query<(&Transform, &mut EnemyWeapon)>,
+    mut query: Query<(Entity, &Transform, &mut EnemyWeapon, &ShipAnimation)>,
     time: Res<Time<Fixed>>,
+    sprites: Res<ShipSpriteAssets>,
+    mut cues: EventReader<AnimationCueEvent>,
     mut writer: EventWriter<EnemyFireEvent>,
+    mut bursts: EventWriter<ParticleBurstEvent>,
     player: Query<&Transform, With<Player>>,
-    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    rank: Res<Rank>,
+    rules: Res<RuleSet>,
+    director: Res<WaveDirector>,
 ) {
-    let delta = time.delta();
+    let flashes: Vec<Entity> = cues
+        .read()
+        .filter(|event| event.cue == AnimationCue::MuzzleFlash)
+        .map(|event| event.entity)
+        .collect();
+    if director.in_bonus_stage() {
+        return;
+    }
+    // Later New Game+ loops run the fire timers faster.
+    let delta = time.delta().mul_f32(director.loop_fire_rate());
     let player_pos = player
         .get_single()
         .map(|t| t.translation.truncate())
         .unwrap_or(Vec2::ZERO);
 
-    for (transform, mut weapon) in &mut query {
+    for (entity, transform, mut weapon, animation) in &mut query {
         if weapon.timer.tick(delta).just_finished() {
+            weapon.primed = true;
+        }
+        let cued = sprites
+            .data(animation.ship)
+            .has_cue(AnimationCue::MuzzleFlash);
+        if weapon.primed && (!cued || flashes.contains(&entity)) {
+            weapon.primed = false;
             let origin = transform.translation.truncate();
-            let speed = weapon.bullet_speed * settings.difficulty.enemy_bullet_factor();
+            if cued {
+                bursts.send(ParticleBurstEvent {
+                    position: origin,
+                    burst: ParticleBurst::Sparks,
+                });
+            }
+            let speed = weapon.bullet_speed
+                * run_difficulty.knobs().bullet_speed
+                * rank.bullet_speed()
+                * rules.enemy_bullet_speed;
             match weapon.pattern {
                 FirePattern::StraightDown => {
                     writer.send(new_enemy_shot(
@@ -288,14 +561,68 @@ fn enemy_fire_system(
     }
 }
 
+// From the second New Game+ loop on, enemies shot down (not bombed) fire a fan of shots
+// at the ship as they die; on Hard, or once rank runs high, they fire at least one. The
+// boss is spared; its death already ends the level.
+#[allow(clippy::too_many_arguments)]
+fn revenge_bullets(
+    mut kills: EventReader<EnemyDestroyedEvent>,
+    mut writer: EventWriter<EnemyFireEvent>,
+    player: Query<&Transform, With<Player>>,
+    director: Res<WaveDirector>,
+    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    rank: Res<Rank>,
+    rules: Res<RuleSet>,
+) {
+    let hard_mode = settings.revenge_shots
+        && run_difficulty
+            .difficulty
+            .revenge_rank()
+            .is_some_and(|threshold| rank.value >= threshold);
+    let count = director.loop_revenge_bullets().max(u32::from(hard_mode));
+    if count == 0 || director.in_bonus_stage() {
+        kills.clear();
+        return;
+    }
+    let Ok(player) = player.get_single() else {
+        kills.clear();
+        return;
+    };
+    let target = player.translation.truncate();
+    let speed = REVENGE_BULLET_SPEED
+        * run_difficulty.knobs().bullet_speed
+        * rank.bullet_speed()
+        * rules.enemy_bullet_speed;
+    for kill in kills.read() {
+        if kill.by_bomb || kill.kind == EnemyKind::Boss {
+            continue;
+        }
+        let aim = (target - kill.position).normalize_or(Vec2::NEG_Y);
+        let half = (count - 1) as f32 / 2.0;
+        for i in 0..count {
+            let angle = (i as f32 - half) * REVENGE_SPREAD_DEGREES.to_radians();
+            let direction = Vec2::from_angle(angle).rotate(aim);
+            writer.send(new_enemy_shot(kill.position, direction * speed, 1));
+        }
+    }
+}
+
+fn tick_contact_cooldowns(mut query: Query<&mut ContactCooldown>, time: Res<Time<Fixed>>) {
+    for mut cooldown in &mut query {
+        cooldown.remaining = (cooldown.remaining - time.delta_seconds()).max(0.0);
+    }
+}
+
 fn cleanup_offscreen_enemies(
     mut commands: Commands,
     query: Query<(Entity, &Transform), With<Enemy>>,
     config: Res<GameConfig>,
 ) {
     let bottom = -config.logical_height * 0.5 - 120.0;
+    let side = config.logical_width * 0.5 + 120.0;
     for (entity, transform) in &query {
-        if transform.translation.y < bottom {
+        if transform.translation.y < bottom || transform.translation.x.abs() > side {
             commands.entity(entity).despawn_recursive();
         }
     }
@@ -317,18 +644,21 @@ fn default_weapon(kind: EnemyKind) -> Option<EnemyWeapon> {
                 arc_deg: 30.0,
             },
             damage: 1,
+            primed: false,
         }),
         EnemyKind::Chaser => Some(EnemyWeapon {
             timer: Timer::from_seconds(1.5, TimerMode::Repeating),
             bullet_speed: 260.0,
             pattern: FirePattern::TargetPlayer,
             damage: 1,
+            primed: false,
         }),
         EnemyKind::Sine => Some(EnemyWeapon {
             timer: Timer::from_seconds(2.6, TimerMode::Repeating),
             bullet_speed: 200.0,
             pattern: FirePattern::StraightDown,
             damage: 1,
+            primed: false,
         }),
         EnemyKind::Boss => None,
         _ => None,
@@ -346,13 +676,13 @@ pub fn new_enemy_shot(origin: Vec2, velocity: Vec2, damage: u8) -> EnemyFireEven
     }
 }
 
-fn enemy_sprite_info(kind: EnemyKind) -> (ShipSpriteId, usize) {
+fn enemy_sprite_info(kind: EnemyKind) -> (ShipSpriteId, ShipRow) {
     match kind {
-        EnemyKind::Grunt => (ShipSpriteId::Grunt, 0),
-        EnemyKind::Sine => (ShipSpriteId::Sine, 0),
-        EnemyKind::ZigZag => (ShipSpriteId::ZigZag, 0),
-        EnemyKind::Tank => (ShipSpriteId::Tank, 0),
-        EnemyKind::Chaser => (ShipSpriteId::Chaser, 0),
-        EnemyKind::Boss => (ShipSpriteId::Boss, 0),
+        EnemyKind::Grunt => (ShipSpriteId::Grunt, ShipRow::Idle),
+        EnemyKind::Sine => (ShipSpriteId::Sine, ShipRow::Idle),
+        EnemyKind::ZigZag => (ShipSpriteId::ZigZag, ShipRow::Idle),
+        EnemyKind::Tank => (ShipSpriteId::Tank, ShipRow::Idle),
+        EnemyKind::Chaser => (ShipSpriteId::Chaser, ShipRow::Idle),
+        EnemyKind::Boss => (ShipSpriteId::Boss, ShipRow::Idle),
     }
 }
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use super::{
+    collisions::{CollisionSet, EnemyHitEvent},
+    effects::ParticleEmitter,
+    ship_sprites::{ShipAnimation, ShipRow},
+    states::AppState,
+};
+
+const HIT_FLASH_SECONDS: f32 = 0.08;
+const HIT_FLASH_COLOR: Color = Color::srgb(1.0, 0.45, 0.35);
+// Share of health left at which a tough enemy starts to smoke, then to throw sparks.
+const DAMAGED_FRACTION: f32 = 0.5;
+const CRITICAL_FRACTION: f32 = 0.25;
+
+pub struct EnemyDamagePlugin;
+
+impl Plugin for EnemyDamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            react_to_enemy_hits
+                .after(CollisionSet)
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(Update, fade_hit_flashes.run_if(in_state(AppState::Playing)));
+    }
+}
+
+// How worn a Tank or the Boss looks. Only ever gets worse over an enemy's life.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DamageStage {
+    #[default]
+    Intact,
+    Damaged,
+    Critical,
+}
+
+impl DamageStage {
+    pub fn for_health(health: i32, max_health: i32) -> Self {
+        let fraction = health as f32 / max_health.max(1) as f32;
+        if fraction <= CRITICAL_FRACTION {
+            DamageStage::Critical
+        } else if fraction <= DAMAGED_FRACTION {
+            DamageStage::Damaged
+        } else {
+            DamageStage::Intact
+        }
+    }
+}
+
+#[derive(Component)]
+struct HitFlash {
+    remaining: f32,
+}
+
+fn react_to_enemy_hits(
+    mut commands: Commands,
+    mut hits: EventReader<EnemyHitEvent>,
+    mut enemies: Query<(&Sprite, Option<&DamageStage>, &mut ShipAnimation)>,
+) {
+    for hit in hits.read() {
+        // Killed by a later hit in the same tick.
+        let Ok((sprite, shown, mut animation)) = enemies.get_mut(hit.entity) else {
+            continue;
+        };
+        let mut entity = commands.entity(hit.entity);
+        entity.insert(HitFlash {
+            remaining: HIT_FLASH_SECONDS,
+        });
+        if !hit.kind.shows_damage() {
+            continue;
+        }
+        let shown = shown.copied().unwrap_or_default();
+        let stage = DamageStage::for_health(hit.health, hit.max_health);
+        if stage <= shown {
+            continue;
+        }
+
+        // Sheets without a damaged row keep their idle frames and rely on the emitters.
+        animation.set_row(ShipRow::Damaged);
+        let half = sprite.custom_size.unwrap_or(Vec2::splat(32.0)) * 0.5;
+        entity.insert(stage).with_children(|parent| {
+            if shown < DamageStage::Damaged {
+                parent.spawn((
+                    SpatialBundle::from_transform(Transform::from_xyz(
+                        -half.x * 0.3,
+                        half.y * 0.2,
+                        0.5,
+                    )),
+                    ParticleEmitter::smoke(),
+                ));
+            }
+            if stage == DamageStage::Critical {
+                parent.spawn((
+                    SpatialBundle::from_transform(Transform::from_xyz(
+                        half.x * 0.35,
+                        -half.y * 0.1,
+                        0.5,
+                    )),
+                    ParticleEmitter::damage_sparks(),
+                ));
+            }
+        });
+    }
+}
+
+fn fade_hit_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut HitFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in &mut flashes {
+        flash.remaining -= time.delta_seconds();
+        if flash.remaining > 0.0 {
+            sprite.color = HIT_FLASH_COLOR;
+        } else {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode},
+};
+
+use super::config::{GameSettings, LaunchOptions, WindowSize};
+
+pub struct GraphicsPlugin;
+
+impl Plugin for GraphicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_graphics_settings)
+            .add_systems(Last, limit_frame_rate);
+    }
+}
+
+fn apply_graphics_settings(
+    settings: Res<GameSettings>,
+    launch: Option<Res<LaunchOptions>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut applied: Local<Option<(WindowSize, bool)>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let present_mode = if settings.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+
+    // Only a new preset or leaving fullscreen resizes, so a window the player dragged
+    // keeps its size when some other setting changes.
+    let wanted = (settings.window_size, settings.fullscreen);
+    if applied.replace(wanted) == Some(wanted) {
+        return;
+    }
+    // A `--resolution` flag wins over the saved preset for the window it opened.
+    let overridden =
+        settings.is_added() && launch.is_some_and(|launch| launch.resolution.is_some());
+    let size = settings.window_size.size();
+    if !settings.fullscreen
+        && !overridden
+        && (window.resolution.width() != size.x || window.resolution.height() != size.y)
+    {
+        window.resolution.set(size.x, size.y);
+    }
+}
+
+// Sleeps away whatever is left of the frame budget once everything else has run.
+fn limit_frame_rate(settings: Res<GameSettings>, mut last_frame: Local<Option<Instant>>) {
+    if let Some(fps) = settings.frame_cap.fps()
+        && let Some(previous) = *last_frame
+    {
+        let budget = Duration::from_secs_f64(1.0 / f64::from(fps));
+        if let Some(remaining) = budget.checked_sub(previous.elapsed()) {
+            thread::sleep(remaining);
+        }
+    }
+    *last_frame = Some(Instant::now());
+}
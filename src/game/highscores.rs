@@ -0,0 +1,342 @@
+use std::{
+    cmp::Reverse,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AppState,
+    audio::{AudioCue, Sfx},
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    profiles::{CurrentProfile, Profiles, rename_profile},
+    spawn::WaveDirector,
+    storage::{load_json, save_json},
+    typography::{TextSize, UiFont},
+    ui::ScoreBoard,
+};
+
+pub const HIGH_SCORE_FILE: &str = "highscores.json";
+const MAX_ENTRIES: usize = 10;
+const NAME_LENGTH: usize = 3;
+const NAME_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 ";
+
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(HighScores::load(&profile))
+            .add_systems(OnEnter(AppState::NameEntry), spawn_name_entry_screen)
+            .add_systems(
+                Update,
+                (name_entry_input, name_entry_display)
+                    .chain()
+                    .run_if(in_state(AppState::NameEntry)),
+            )
+            .add_systems(OnExit(AppState::NameEntry), cleanup_name_entry);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub level: usize,
+    // New Game+ loops cleared before the run ended.
+    #[serde(default)]
+    pub loop_index: u32,
+    pub date: String,
+}
+
+impl HighScoreEntry {
+    // "L3" on the first pass, "L3+1" a loop later.
+    pub fn level_label(&self) -> String {
+        if self.loop_index == 0 {
+            format!("L{}", self.level)
+        } else {
+            format!("L{}+{}", self.level, self.loop_index)
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        let mut scores: Self = load_json(&profile.data_path(HIGH_SCORE_FILE));
+        scores.entries.sort_by_key(|entry| Reverse(entry.score));
+        scores.entries.truncate(MAX_ENTRIES);
+        scores
+    }
+
+    fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.data_path(HIGH_SCORE_FILE), self);
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_ENTRIES
+                || self.entries.last().is_some_and(|entry| score > entry.score))
+    }
+
+    fn insert(&mut self, entry: HighScoreEntry) {
+        let rank = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// UTC date and time safe to use in a file name, e.g. `2024-05-17_21-04-09`.
+pub fn file_timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let time_of_day = seconds % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}",
+        time_of_day / 3_600,
+        time_of_day / 60 % 60,
+        time_of_day % 60
+    )
+}
+
+// Days since 1970-01-01 to a proleptic Gregorian (year, month, day).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+pub fn spawn_high_score_table(
+    parent: &mut ChildBuilder,
+    scores: &HighScores,
+    rows: usize,
+    style: TextStyle,
+) {
+    let mut sections = vec![TextSection::new(
+        "HIGH SCORES\n",
+        TextStyle {
+            color: Color::srgb(1.0, 0.85, 0.4),
+            ..style.clone()
+        },
+    )];
+    if scores.entries.is_empty() {
+        sections.push(TextSection::new("No scores yet", style));
+    } else {
+        for (index, entry) in scores.entries.iter().take(rows).enumerate() {
+            sections.push(TextSection::new(
+                format!(
+                    "{:>2}. {:<3} {:>7}  {:<5} {}\n",
+                    index + 1,
+                    entry.name,
+                    entry.score,
+                    entry.level_label(),
+                    entry.date
+                ),
+                style.clone(),
+            ));
+        }
+    }
+    parent.spawn(TextBundle::from_sections(sections));
+}
+
+#[derive(Resource, Default)]
+struct NameEntry {
+    letters: [usize; NAME_LENGTH],
+    cursor: usize,
+}
+
+impl NameEntry {
+    // Starts from the initials this profile last used.
+    fn from_name(name: &str) -> Self {
+        let mut entry = Self::default();
+        for (letter, ch) in entry.letters.iter_mut().zip(name.bytes()) {
+            *letter = NAME_CHARSET
+                .iter()
+                .position(|&c| c == ch.to_ascii_uppercase())
+                .unwrap_or(NAME_CHARSET.len() - 1);
+        }
+        entry
+    }
+
+    fn name(&self) -> String {
+        self.letters
+            .iter()
+            .map(|&index| NAME_CHARSET[index] as char)
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    fn cycle(&mut self, step: isize) {
+        let len = NAME_CHARSET.len() as isize;
+        let letter = &mut self.letters[self.cursor];
+        *letter = (*letter as isize + step).rem_euclid(len) as usize;
+    }
+}
+
+#[derive(Component)]
+struct NameEntryScreen;
+
+#[derive(Component)]
+struct NameEntryText;
+
+fn spawn_name_entry_screen(
+    mut commands: Commands,
+    scoreboard: Res<ScoreBoard>,
+    director: Res<WaveDirector>,
+    profile: Res<CurrentProfile>,
+    font: Res<UiFont>,
+) {
+    commands.insert_resource(NameEntry::from_name(&profile.name));
+
+    let title_style = font.style(TextSize::Heading, Color::srgb(1.0, 0.85, 0.4));
+    let info_style = font.style(TextSize::Body, Color::srgb(0.8, 0.85, 1.0));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::NONE),
+                ..default()
+            },
+            NameEntryScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("New High Score!", title_style));
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Score: {}  |  Level: {}",
+                    scoreboard.score,
+                    director.level_index + 1
+                ),
+                info_style.clone(),
+            ));
+            parent.spawn((
+                TextBundle::from_sections(
+                    (0..NAME_LENGTH)
+                        .map(|_| TextSection::new("", font.style(TextSize::Title, Color::WHITE))),
+                ),
+                NameEntryText,
+            ));
+            parent.spawn((
+                TextBundle::from_section("", info_style),
+                DevicePrompt::new(
+                    "Up/Down change letter  |  Left/Right move  |  Enter confirm  |  Esc skip",
+                    "D-pad up/down change letter  |  left/right move  |  A confirm  |  B skip",
+                ),
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn name_entry_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    scoreboard: Res<ScoreBoard>,
+    director: Res<WaveDirector>,
+    mut profiles: ResMut<Profiles>,
+    mut profile: ResMut<CurrentProfile>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keys.just_pressed(key) || gamepad_just_pressed(&gamepads, &buttons, button)
+    };
+
+    if pressed(KeyCode::ArrowUp, GamepadButtonType::DPadUp) {
+        entry.cycle(1);
+    }
+    if pressed(KeyCode::ArrowDown, GamepadButtonType::DPadDown) {
+        entry.cycle(-1);
+    }
+    if pressed(KeyCode::ArrowLeft, GamepadButtonType::DPadLeft) {
+        entry.cursor = entry.cursor.saturating_sub(1);
+    }
+    if pressed(KeyCode::ArrowRight, GamepadButtonType::DPadRight) {
+        entry.cursor = (entry.cursor + 1).min(NAME_LENGTH - 1);
+    }
+
+    if pressed(KeyCode::Escape, GamepadButtonType::East) {
+        next_state.set(AppState::Title);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    } else if pressed(KeyCode::Enter, GamepadButtonType::South) {
+        let name = entry.name();
+        if !name.is_empty() {
+            rename_profile(&mut profiles, &mut profile, &name);
+        }
+        high_scores.insert(HighScoreEntry {
+            name: if name.is_empty() { "???".into() } else { name },
+            score: scoreboard.score,
+            level: director.level_index + 1,
+            loop_index: director.loop_index,
+            date: today(),
+        });
+        high_scores.save(&profile);
+        next_state.set(AppState::Title);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+}
+
+fn name_entry_display(entry: Res<NameEntry>, mut query: Query<&mut Text, With<NameEntryText>>) {
+    if !entry.is_changed() {
+        return;
+    }
+    for mut text in &mut query {
+        for (index, section) in text.sections.iter_mut().enumerate() {
+            let letter = NAME_CHARSET[entry.letters[index]] as char;
+            section.value = format!(" {} ", if letter == ' ' { '_' } else { letter });
+            section.style.color = if index == entry.cursor {
+                Color::srgb(1.0, 0.85, 0.4)
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+fn cleanup_name_entry(mut commands: Commands, query: Query<Entity, With<NameEntryScreen>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<NameEntry>();
+}
@@ -0,0 +1,93 @@
+use bevy::{input::InputSystem, prelude::*};
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub struct InputDevicePlugin;
+
+impl Plugin for InputDevicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastInputDevice>()
+            .add_systems(PreUpdate, detect_input_device.after(InputSystem))
+            .add_systems(PostUpdate, refresh_device_prompts);
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LastInputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+impl LastInputDevice {
+    pub fn pick<'a>(self, keyboard: &'a str, gamepad: &'a str) -> &'a str {
+        match self {
+            LastInputDevice::KeyboardMouse => keyboard,
+            LastInputDevice::Gamepad => gamepad,
+        }
+    }
+}
+
+// Text whose first section follows whichever device was used last.
+#[derive(Component, Debug, Clone)]
+pub struct DevicePrompt {
+    keyboard: String,
+    gamepad: String,
+}
+
+impl DevicePrompt {
+    pub fn new(keyboard: impl Into<String>, gamepad: impl Into<String>) -> Self {
+        Self {
+            keyboard: keyboard.into(),
+            gamepad: gamepad.into(),
+        }
+    }
+}
+
+// Whether `button` was pressed this frame on any connected gamepad.
+pub fn gamepad_just_pressed(
+    gamepads: &Gamepads,
+    buttons: &ButtonInput<GamepadButton>,
+    button: GamepadButtonType,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button)))
+}
+
+fn detect_input_device(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut device: ResMut<LastInputDevice>,
+) {
+    let stick_moved = gamepads.iter().any(|gamepad| {
+        [GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY]
+            .into_iter()
+            .filter_map(|axis| axes.get(GamepadAxis::new(gamepad, axis)))
+            .any(|value| value.abs() > STICK_THRESHOLD)
+    });
+    if buttons.get_just_pressed().next().is_some() || stick_moved {
+        device.set_if_neq(LastInputDevice::Gamepad);
+    } else if keys.get_just_pressed().next().is_some() || mouse.get_just_pressed().next().is_some()
+    {
+        device.set_if_neq(LastInputDevice::KeyboardMouse);
+    }
+}
+
+fn refresh_device_prompts(
+    device: Res<LastInputDevice>,
+    mut prompts: Query<(Ref<DevicePrompt>, &mut Text)>,
+) {
+    for (prompt, mut text) in &mut prompts {
+        if !device.is_changed() && !prompt.is_changed() {
+            continue;
+        }
+        let value = device.pick(&prompt.keyboard, &prompt.gamepad);
+        if let Some(section) = text.sections.first_mut() {
+            section.value = value.to_string();
+        }
+    }
+}
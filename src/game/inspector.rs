@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPlugin, egui};
+
+use super::{
+    boss::BossState,
+    config::GameSettings,
+    enemies::Enemy,
+    player::{MAX_FIRE_RATE_LEVEL, Player, PlayerStats, PlayerWeaponState, WeaponMode},
+    powerups::PowerUp,
+    spawn::{Storyboard, WaveDirector},
+};
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(EguiPlugin)
+            .init_resource::<InspectorState>()
+            .add_systems(Update, (toggle_inspector, inspector_panel).chain());
+    }
+}
+
+#[derive(Resource, Default)]
+struct InspectorState {
+    open: bool,
+    selected: Option<Entity>,
+}
+
+type InspectedEntity<'a> = (
+    Entity,
+    &'a mut Transform,
+    Option<&'a mut Enemy>,
+    Option<&'a PowerUp>,
+    Has<Player>,
+);
+
+// Hands the closure the resource without flagging it as changed, and only marks it
+// afterwards if a widget reported an edit. Otherwise the panel would trigger every
+// change-driven system (and the settings autosave) each frame it is open.
+fn edit_resource<T: Resource>(resource: &mut ResMut<T>, edit: impl FnOnce(&mut T) -> bool) {
+    if edit(resource.bypass_change_detection()) {
+        resource.set_changed();
+    }
+}
+
+fn toggle_inspector(keys: Res<ButtonInput<KeyCode>>, mut state: ResMut<InspectorState>) {
+    if keys.just_pressed(KeyCode::F2) {
+        state.open = !state.open;
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn inspector_panel(
+    mut contexts: EguiContexts,
+    mut state: ResMut<InspectorState>,
+    mut stats: ResMut<PlayerStats>,
+    mut weapon: ResMut<PlayerWeaponState>,
+    mut director: ResMut<WaveDirector>,
+    mut boss: ResMut<BossState>,
+    mut settings: ResMut<GameSettings>,
+    storyboard: Res<Storyboard>,
+    mut entities: Query<InspectedEntity, Or<(With<Enemy>, With<Player>, With<PowerUp>)>>,
+) {
+    if !state.open {
+        return;
+    }
+    let state = state.bypass_change_detection();
+    egui::Window::new("Inspector")
+        .default_width(280.0)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::CollapsingHeader::new("PlayerStats")
+                .default_open(true)
+                .show(ui, |ui| {
+                    edit_resource(&mut stats, |stats| {
+                        let max_health = stats.max_health;
+                        let max_lives = stats.max_lives;
+                        [
+                            ui.add(
+                                egui::Slider::new(&mut stats.health, 0..=max_health).text("health"),
+                            ),
+                            ui.add(
+                                egui::Slider::new(&mut stats.max_health, 1..=10).text("max health"),
+                            ),
+                            ui.add(
+                                egui::Slider::new(&mut stats.lives, 0..=max_lives).text("lives"),
+                            ),
+                            ui.add(
+                                egui::Slider::new(&mut stats.max_lives, 1..=9).text("max lives"),
+                            ),
+                        ]
+                        .iter()
+                        .any(egui::Response::changed)
+                    });
+                });
+
+            egui::CollapsingHeader::new("PlayerWeaponState").show(ui, |ui| {
+                edit_resource(&mut weapon, |weapon| {
+                    let mut changed = false;
+                    egui::ComboBox::from_label("mode")
+                        .selected_text(weapon.mode.label())
+                        .show_ui(ui, |ui| {
+                            for mode in WeaponMode::ALL {
+                                changed |= ui
+                                    .selectable_value(&mut weapon.mode, mode, mode.label())
+                                    .changed();
+                            }
+                        });
+                    changed |= ui
+                        .add(
+                            egui::Slider::new(&mut weapon.fire_rate_level, 0..=MAX_FIRE_RATE_LEVEL)
+                                .text("fire rate"),
+                        )
+                        .changed();
+                    changed |= ui
+                        .add(egui::Slider::new(&mut weapon.bombs, 0..=9).text("bombs"))
+                        .changed();
+                    changed
+                });
+            });
+
+            egui::CollapsingHeader::new("WaveDirector").show(ui, |ui| {
+                edit_resource(&mut director, |director| {
+                    let last_level = storyboard.level_count().saturating_sub(1);
+                    let last_wave = storyboard
+                        .wave_count(director.level_index)
+                        .saturating_sub(1) as u32;
+                    let changed = [
+                        ui.add(
+                            egui::Slider::new(&mut director.level_index, 0..=last_level)
+                                .text("level index"),
+                        ),
+                        ui.add(
+                            egui::Slider::new(&mut director.wave_index, 0..=last_wave)
+                                .text("wave index"),
+                        ),
+                        ui.add(
+                            egui::Slider::new(&mut director.difficulty, 0.1..=5.0)
+                                .text("difficulty"),
+                        ),
+                        ui.checkbox(&mut director.boss_active, "boss active"),
+                    ]
+                    .iter()
+                    .any(egui::Response::changed);
+                    ui.label(format!(
+                        "next wave in {:.1}s",
+                        director.timer.remaining_secs()
+                    ));
+                    changed
+                });
+            });
+
+            egui::CollapsingHeader::new("BossState").show(ui, |ui| {
+                edit_resource(&mut boss, |boss| {
+                    ui.label(format!("{} - phase {}", boss.name, boss.phase.number()));
+                    ui.label(format!(
+                        "active: {}  health: {:.0}/{:.0}",
+                        boss.active, boss.health, boss.max_health
+                    ));
+                    ui.add(
+                        egui::DragValue::new(&mut boss.spawn_score)
+                            .speed(50.0)
+                            .prefix("spawn score: "),
+                    )
+                    .changed()
+                });
+            });
+
+            egui::CollapsingHeader::new("GameSettings").show(ui, |ui| {
+                edit_resource(&mut settings, |settings| {
+                    let mut changed = false;
+                    if ui
+                        .button(format!("Difficulty: {}", settings.difficulty.label()))
+                        .clicked()
+                    {
+                        settings.difficulty = settings.difficulty.next();
+                        changed = true;
+                    }
+                    changed |= [
+                        ui.checkbox(&mut settings.dynamic_rank, "dynamic rank"),
+                        ui.checkbox(&mut settings.muted, "muted"),
+                        ui.add(
+                            egui::Slider::new(&mut settings.master_volume, 0.0..=1.0)
+                                .text("master volume"),
+                        ),
+                        ui.add(
+                            egui::Slider::new(&mut settings.music_volume, 0.0..=1.0)
+                                .text("music volume"),
+                        ),
+                        ui.add(
+                            egui::Slider::new(&mut settings.sfx_volume, 0.0..=1.0)
+                                .text("sfx volume"),
+                        ),
+                        ui.checkbox(&mut settings.run_timer, "run timer"),
+                        ui.checkbox(&mut settings.crt_filter, "CRT filter"),
+                        ui.checkbox(&mut settings.reduce_flashing, "reduce flashing"),
+                        ui.checkbox(&mut settings.reduce_motion, "reduce motion"),
+                        ui.checkbox(&mut settings.high_contrast_bullets, "high-contrast bullets"),
+                    ]
+                    .iter()
+                    .any(egui::Response::changed);
+                    changed
+                });
+            });
+
+            egui::CollapsingHeader::new("Entities").show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for (entity, _, enemy, powerup, is_player) in &entities {
+                            let name = if is_player {
+                                "Player".to_string()
+                            } else if let Some(enemy) = enemy {
+                                enemy.kind.label().to_string()
+                            } else if let Some(powerup) = powerup {
+                                format!("{:?} power-up", powerup.kind)
+                            } else {
+                                continue;
+                            };
+                            if ui
+                                .selectable_label(
+                                    state.selected == Some(entity),
+                                    format!("{name} {entity}"),
+                                )
+                                .clicked()
+                            {
+                                state.selected = Some(entity);
+                            }
+                        }
+                    });
+
+                let Some(Ok((entity, mut transform, enemy, _, _))) =
+                    state.selected.map(|entity| entities.get_mut(entity))
+                else {
+                    return;
+                };
+                ui.separator();
+                ui.label(format!("Selected {entity}"));
+                let translation = &mut transform.bypass_change_detection().translation;
+                let moved = ui
+                    .horizontal(|ui| {
+                        ui.label("position");
+                        ui.add(egui::DragValue::new(&mut translation.x).prefix("x: "))
+                            .changed()
+                            | ui.add(egui::DragValue::new(&mut translation.y).prefix("y: "))
+                                .changed()
+                    })
+                    .inner;
+                if moved {
+                    transform.set_changed();
+                }
+                if let Some(mut enemy) = enemy {
+                    ui.add(egui::Slider::new(&mut enemy.health, 1..=400).text("health"));
+                }
+            });
+        });
+}
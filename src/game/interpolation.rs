@@ -0,0 +1,70 @@
+use bevy::{prelude::*, time::Fixed};
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedFirst, restore_fixed_translations)
+            .add_systems(FixedLast, record_fixed_translations)
+            .add_systems(
+                PostUpdate,
+                interpolate_translations.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+// Smooths an entity that moves in `FixedUpdate`. Gameplay keeps reading and writing
+// `Transform` as usual; between fixed ticks it is drawn part way from the previous
+// tick's translation to the latest one, by how far the clock has run into the next
+// tick. Only the translation is blended.
+#[derive(Component, Debug, Default)]
+pub struct TransformInterpolation {
+    previous: Option<Vec3>,
+    current: Option<Vec3>,
+    // What was last drawn, so a move made outside the fixed schedule can be spotted.
+    rendered: Option<Vec3>,
+}
+
+impl TransformInterpolation {
+    // Jumps straight to the new position instead of sliding there, for respawns.
+    pub fn teleport(&mut self) {
+        self.previous = None;
+    }
+}
+
+// Puts back the simulated translation before the tick runs. A translation that no
+// longer matches what was drawn was moved by something outside the fixed schedule,
+// such as the inspector, and is kept.
+fn restore_fixed_translations(mut query: Query<(&mut Transform, &mut TransformInterpolation)>) {
+    for (mut transform, mut interpolation) in &mut query {
+        if let (Some(current), Some(rendered)) = (interpolation.current, interpolation.rendered)
+            && transform.translation == rendered
+        {
+            transform.translation = current;
+        }
+        interpolation.previous = Some(transform.translation);
+    }
+}
+
+fn record_fixed_translations(mut query: Query<(&Transform, &mut TransformInterpolation)>) {
+    for (transform, mut interpolation) in &mut query {
+        interpolation.current = Some(transform.translation);
+        interpolation.previous.get_or_insert(transform.translation);
+    }
+}
+
+fn interpolate_translations(
+    time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut TransformInterpolation)>,
+) {
+    let blend = time.overstep_fraction();
+    for (mut transform, mut interpolation) in &mut query {
+        let (Some(previous), Some(current)) = (interpolation.previous, interpolation.current)
+        else {
+            interpolation.rendered = None;
+            continue;
+        };
+        transform.translation = previous.lerp(current, blend);
+        interpolation.rendered = Some(transform.translation);
+    }
+}
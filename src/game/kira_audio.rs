@@ -0,0 +1,276 @@
+use std::{collections::HashMap, io::Cursor, time::Duration};
+
+use bevy::{
+    log::{info, warn},
+    prelude::*,
+};
+use bevy_kira_audio::prelude::{
+    AudioApp, AudioChannel, AudioControl, AudioInstance, AudioPlugin as KiraPlugin, AudioSource,
+    AudioTween,
+};
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+
+use super::{
+    audio::{
+        ALARM_VOLUME, AudioMixPlugin, AudioMixSet, LEVEL_TRACKS, LowHealthAlarm, MusicDuck,
+        MusicIntensity, MusicLayer, MusicTrack, PICKUP_KINDS, Sfx, VoiceTrigger, audio_file,
+        pickup_sound_name, synthesize,
+    },
+    boss::BossState,
+    config::{GameConfig, GameSettings},
+    loading::LoadingAssets,
+    powerups::PowerUpKind,
+    spawn::WaveDirector,
+    states::{AppState, InRun},
+};
+
+const MUSIC_CROSSFADE: Duration = Duration::from_secs(2);
+
+pub struct KiraAudioPlugin;
+
+impl Plugin for KiraAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((KiraPlugin, AudioMixPlugin))
+            .add_audio_channel::<MusicChannel>()
+            .add_audio_channel::<SfxChannel>()
+            .init_resource::<KiraAudioAssets>()
+            .init_resource::<KiraMusic>()
+            .init_resource::<KiraVoices>()
+            .add_systems(Startup, (setup_kira_assets, start_music_layers).chain())
+            .add_systems(
+                Update,
+                (
+                    play_voices,
+                    select_music_track.run_if(not(in_state(AppState::Restarting))),
+                    mix_music_layers,
+                    play_low_health_alarm,
+                    apply_channel_volumes,
+                )
+                    .after(AudioMixSet),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct MusicChannel;
+
+#[derive(Resource)]
+struct SfxChannel;
+
+#[derive(Resource, Default)]
+struct KiraAudioAssets {
+    music: Handle<AudioSource>,
+    level_music: Vec<Handle<AudioSource>>,
+    boss_music: Handle<AudioSource>,
+    layers: Vec<(MusicLayer, Handle<AudioSource>)>,
+    shoot: Handle<AudioSource>,
+    hit: Handle<AudioSource>,
+    explosion: Handle<AudioSource>,
+    pickups: HashMap<PowerUpKind, Handle<AudioSource>>,
+    ui: Handle<AudioSource>,
+    warning: Handle<AudioSource>,
+}
+
+#[derive(Resource, Default)]
+struct KiraMusic {
+    current: Option<MusicTrack>,
+    instance: Option<Handle<AudioInstance>>,
+    layers: Vec<(MusicLayer, Handle<AudioInstance>)>,
+    alarm: Option<Handle<AudioInstance>>,
+}
+
+// The instance last started on each voice of each sound, stopped when the voice is
+// re-triggered.
+#[derive(Resource, Default)]
+struct KiraVoices(HashMap<(Sfx, usize), Handle<AudioInstance>>);
+
+fn setup_kira_assets(
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<Assets<AudioSource>>,
+    mut store: ResMut<KiraAudioAssets>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let mut load = |name: &str| match audio_file(&asset_server, name) {
+        Some(path) => {
+            info!("Loading {path}");
+            let handle = asset_server.load(path);
+            loading.track(handle.clone());
+            handle
+        }
+        None => {
+            let cursor = Cursor::new(synthesize(name));
+            match StaticSoundData::from_cursor(cursor, StaticSoundSettings::default()) {
+                Ok(sound) => assets.add(AudioSource { sound }),
+                Err(error) => {
+                    warn!("Failed to decode synthesised {name}: {error}");
+                    Handle::default()
+                }
+            }
+        }
+    };
+    store.music = load("music");
+    store.level_music = LEVEL_TRACKS.iter().map(|name| load(name)).collect();
+    store.boss_music = load("boss");
+    store.layers = MusicLayer::ALL
+        .into_iter()
+        .map(|layer| (layer, load(layer.sound_name())))
+        .collect();
+    store.shoot = load("shoot");
+    store.hit = load("hit");
+    store.explosion = load("explosion");
+    store.pickups = PICKUP_KINDS
+        .into_iter()
+        .map(|kind| (kind, load(pickup_sound_name(kind))))
+        .collect();
+    store.ui = load("ui");
+    store.warning = load("warning");
+}
+
+fn start_music_layers(
+    assets: Res<KiraAudioAssets>,
+    channel: Res<AudioChannel<MusicChannel>>,
+    mut music: ResMut<KiraMusic>,
+) {
+    for (layer, source) in &assets.layers {
+        let instance = channel
+            .play(source.clone())
+            .looped()
+            .with_volume(0.0)
+            .handle();
+        music.layers.push((*layer, instance));
+    }
+}
+
+fn play_voices(
+    mut triggers: EventReader<VoiceTrigger>,
+    assets: Res<KiraAudioAssets>,
+    config: Res<GameConfig>,
+    channel: Res<AudioChannel<SfxChannel>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut voices: ResMut<KiraVoices>,
+) {
+    for trigger in triggers.read() {
+        let handle = match trigger.sfx {
+            Sfx::Shoot => &assets.shoot,
+            Sfx::Hit => &assets.hit,
+            Sfx::Explosion => &assets.explosion,
+            Sfx::Pickup(kind) => match assets.pickups.get(&kind) {
+                Some(handle) => handle,
+                None => continue,
+            },
+            Sfx::UiSelect => &assets.ui,
+        };
+        let voice = (trigger.sfx, trigger.voice);
+        if let Some(previous) = voices.0.remove(&voice)
+            && let Some(instance) = instances.get_mut(&previous)
+        {
+            instance.stop(AudioTween::default());
+        }
+        let panning = trigger.position.map_or(0.5, |position| {
+            (0.5 + position.x / config.logical_width).clamp(0.1, 0.9)
+        });
+        let instance = channel
+            .play(handle.clone())
+            .with_playback_rate(trigger.speed as f64)
+            .with_panning(panning as f64)
+            .handle();
+        voices.0.insert(voice, instance);
+    }
+}
+
+fn select_music_track(
+    assets: Res<KiraAudioAssets>,
+    in_run: Option<Res<State<InRun>>>,
+    boss: Res<BossState>,
+    director: Res<WaveDirector>,
+    channel: Res<AudioChannel<MusicChannel>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut music: ResMut<KiraMusic>,
+) {
+    let track = MusicTrack::wanted(in_run.is_some(), &boss, &director, assets.level_music.len());
+    if music.current == Some(track) {
+        return;
+    }
+
+    if let Some(previous) = music.instance.take()
+        && let Some(instance) = instances.get_mut(&previous)
+    {
+        instance.stop(AudioTween::linear(MUSIC_CROSSFADE));
+    }
+    let source = match track {
+        MusicTrack::Title => assets.music.clone(),
+        MusicTrack::Boss => assets.boss_music.clone(),
+        MusicTrack::Level(index) => assets
+            .level_music
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| assets.music.clone()),
+    };
+    let instance = channel
+        .play(source)
+        .looped()
+        .fade_in(AudioTween::linear(MUSIC_CROSSFADE))
+        .handle();
+    music.instance = Some(instance);
+    music.current = Some(track);
+}
+
+fn mix_music_layers(
+    intensity: Res<MusicIntensity>,
+    music: Res<KiraMusic>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    if !intensity.is_changed() {
+        return;
+    }
+    for (layer, volume) in intensity.layer_volumes() {
+        let Some((_, handle)) = music.layers.iter().find(|(playing, _)| *playing == layer) else {
+            continue;
+        };
+        if let Some(instance) = instances.get_mut(handle) {
+            instance.set_volume(volume as f64, AudioTween::default());
+        }
+    }
+}
+
+fn play_low_health_alarm(
+    alarm: Res<LowHealthAlarm>,
+    assets: Res<KiraAudioAssets>,
+    channel: Res<AudioChannel<SfxChannel>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut music: ResMut<KiraMusic>,
+) {
+    if !alarm.is_changed() {
+        return;
+    }
+    match (alarm.active, music.alarm.take()) {
+        (true, None) => {
+            let instance = channel
+                .play(assets.warning.clone())
+                .looped()
+                .with_volume(ALARM_VOLUME as f64)
+                .handle();
+            music.alarm = Some(instance);
+        }
+        (false, Some(previous)) => {
+            if let Some(instance) = instances.get_mut(&previous) {
+                instance.stop(AudioTween::default());
+            }
+        }
+        (_, playing) => music.alarm = playing,
+    }
+}
+
+// Ducking pulls down the whole music channel, so the layers duck with the track.
+fn apply_channel_volumes(
+    settings: Res<GameSettings>,
+    duck: Res<MusicDuck>,
+    music: Res<AudioChannel<MusicChannel>>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+) {
+    if !settings.is_changed() && !duck.is_changed() {
+        return;
+    }
+    music.set_volume((settings.music_level() * duck.gain) as f64);
+    sfx.set_volume(settings.sfx_level() as f64);
+}
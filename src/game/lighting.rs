@@ -0,0 +1,188 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayoutRef,
+        render_resource::{
+            AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState,
+            RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+        },
+    },
+    sprite::{Material2d, Material2dKey, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use super::{
+    config::{GameSettings, LightQuality},
+    effects::ExplosionEvent,
+    ship_sprites::{AnimationCue, AnimationCueEvent},
+    states::{AppState, InRun},
+    weapons::Projectile,
+};
+
+const LIGHT_SHADER_PATH: &str = "shaders/light.wgsl";
+const LIGHT_INTENSITY_CAP: f32 = 0.85;
+
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(Material2dPlugin::<LightMaterial>::default())
+            .init_resource::<LightAssets>()
+            .add_systems(Startup, load_light_assets)
+            .add_systems(
+                Update,
+                (spawn_light_flashes, fade_light_flashes).run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(InRun), cleanup_light_flashes);
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct LightMaterial {
+    #[uniform(0)]
+    color: LinearRgba,
+}
+
+impl Material2d for LightMaterial {
+    fn fragment_shader() -> ShaderRef {
+        LIGHT_SHADER_PATH.into()
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let additive = BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        };
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            for target in fragment.targets.iter_mut().flatten() {
+                target.blend = Some(BlendState {
+                    color: additive,
+                    alpha: additive,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Resource, Default)]
+struct LightAssets {
+    mesh: Mesh2dHandle,
+}
+
+#[derive(Component)]
+struct LightFlash {
+    age: f32,
+    lifetime: f32,
+    intensity: f32,
+    color: Color,
+    material: Handle<LightMaterial>,
+}
+
+struct LightSpec {
+    position: Vec2,
+    radius: f32,
+    intensity: f32,
+    lifetime: f32,
+    color: Color,
+}
+
+fn load_light_assets(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(LightAssets {
+        mesh: Mesh2dHandle(meshes.add(Rectangle::new(1.0, 1.0))),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_light_flashes(
+    mut commands: Commands,
+    assets: Res<LightAssets>,
+    settings: Res<GameSettings>,
+    mut materials: ResMut<Assets<LightMaterial>>,
+    mut explosions: EventReader<ExplosionEvent>,
+    mut cues: EventReader<AnimationCueEvent>,
+    shots: Query<(), With<Projectile>>,
+    active: Query<(), With<LightFlash>>,
+) {
+    let quality = settings.light_quality;
+    let dimming = if settings.reduce_flashing { 0.35 } else { 1.0 };
+    let mut requests = Vec::new();
+    for event in explosions.read() {
+        requests.push(LightSpec {
+            position: event.position,
+            radius: if event.large { 420.0 } else { 220.0 },
+            intensity: if event.large { 0.8 } else { 0.5 } * dimming,
+            lifetime: if event.large { 0.45 } else { 0.25 },
+            color: Color::srgb(1.0, 0.7, 0.4),
+        });
+    }
+    // Only laser bolts carry a muzzle-flash cue, lit on the frame they flare.
+    let muzzle_flashes = quality == LightQuality::High && !settings.reduce_flashing;
+    for event in cues.read() {
+        if muzzle_flashes && event.cue == AnimationCue::MuzzleFlash && shots.contains(event.entity)
+        {
+            requests.push(LightSpec {
+                position: event.position,
+                radius: 140.0,
+                intensity: 0.35,
+                lifetime: 0.08,
+                color: Color::srgb(0.5, 0.8, 1.0),
+            });
+        }
+    }
+
+    let available = quality.max_lights().saturating_sub(active.iter().count());
+    for spec in requests.into_iter().take(available) {
+        let intensity = spec.intensity.min(LIGHT_INTENSITY_CAP);
+        let material = materials.add(LightMaterial {
+            color: spec.color.with_alpha(intensity).into(),
+        });
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: assets.mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(spec.position.extend(3.0))
+                    .with_scale(Vec3::splat(spec.radius * 2.0)),
+                ..default()
+            },
+            LightFlash {
+                age: 0.0,
+                lifetime: spec.lifetime,
+                intensity,
+                color: spec.color,
+                material,
+            },
+        ));
+    }
+}
+
+fn fade_light_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<LightMaterial>>,
+    mut query: Query<(Entity, &mut LightFlash)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut flash) in &mut query {
+        flash.age += delta;
+        if flash.age >= flash.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = flash.age / flash.lifetime;
+        let strength = flash.intensity * (1.0 - t) * (1.0 - t);
+        if let Some(material) = materials.get_mut(&flash.material) {
+            material.color = flash.color.with_alpha(strength).into();
+        }
+    }
+}
+
+fn cleanup_light_flashes(mut commands: Commands, query: Query<Entity, With<LightFlash>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use super::{
+    states::AppState,
+    typography::{TextSize, UiFont},
+};
+
+const BAR_WIDTH: f32 = 360.0;
+const BAR_COLOR: Color = Color::srgb(0.55, 0.85, 1.0);
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingAssets>()
+            .add_systems(OnEnter(AppState::Loading), spawn_loading_screen)
+            .add_systems(
+                Update,
+                track_loading_progress.run_if(in_state(AppState::Loading)),
+            )
+            .add_systems(OnExit(AppState::Loading), cleanup_loading_screen);
+    }
+}
+
+// Files that have to be in memory before the title screen appears. Plugins add the
+// handles they load at startup, and the game waits in `Loading` until every one has
+// either loaded or failed.
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    handles: Vec<UntypedHandle>,
+}
+
+impl LoadingAssets {
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        self.handles.push(handle.into());
+    }
+
+    fn progress(&self, asset_server: &AssetServer) -> (usize, usize) {
+        let done = self
+            .handles
+            .iter()
+            .filter(|handle| {
+                matches!(
+                    asset_server.load_state(handle.id()),
+                    LoadState::Loaded | LoadState::Failed(_)
+                )
+            })
+            .count();
+        (done, self.handles.len())
+    }
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingBarFill;
+
+fn spawn_loading_screen(mut commands: Commands, font: Res<UiFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "LOADING",
+                font.style(TextSize::Body, BAR_COLOR),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(10.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.15)),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(BAR_COLOR),
+                            ..default()
+                        },
+                        LoadingBarFill,
+                    ));
+                });
+        });
+}
+
+fn track_loading_progress(
+    loading: Res<LoadingAssets>,
+    asset_server: Res<AssetServer>,
+    mut fill: Query<&mut Style, With<LoadingBarFill>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let (done, total) = loading.progress(&asset_server);
+    if let Ok(mut style) = fill.get_single_mut() {
+        let width = Val::Percent(done as f32 / total.max(1) as f32 * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+    if done == total {
+        info!("Loaded {total} assets");
+        next_state.set(AppState::Title);
+    }
+}
+
+fn cleanup_loading_screen(mut commands: Commands, query: Query<Entity, With<LoadingScreen>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<LoadingAssets>();
+}
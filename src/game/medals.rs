@@ -0,0 +1,162 @@
+use bevy::{prelude::*, time::Fixed};
+
+use super::{
+    audio::{AudioCue, Sfx},
+    config::GameConfig,
+    effects::ScorePopupEvent,
+    interpolation::TransformInterpolation,
+    player::{Player, PlayerHitEvent},
+    spawn::WaveStartedEvent,
+    states::{AppState, InRun},
+    ui::ScoreBoard,
+};
+
+// A flawless wave's medal is worth this much per wave in the current no-miss streak.
+const MEDAL_BASE_VALUE: u32 = 500;
+const MAX_MEDAL_STREAK: u32 = 10;
+const MEDAL_FALL_SPEED: f32 = 110.0;
+const MEDAL_SIZE: f32 = 22.0;
+// Streak lengths at which the medal turns silver, then gold.
+const SILVER_STREAK: u32 = 3;
+const GOLD_STREAK: u32 = 5;
+
+pub struct MedalsPlugin;
+
+impl Plugin for MedalsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NoMissStreak>()
+            .add_systems(OnEnter(InRun), reset_no_miss_streak)
+            .add_systems(OnExit(InRun), cleanup_medals)
+            .add_systems(
+                FixedUpdate,
+                (track_flawless_waves, move_medals, collect_medals)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+// Whether the ship has been hit since the current wave started, and how many waves in a
+// row it got through without one. A wave runs until the next one starts, so the last
+// wave of a level takes in its boss fight.
+#[derive(Resource, Debug, Default)]
+pub struct NoMissStreak {
+    pub streak: u32,
+    hit_this_wave: bool,
+    wave_running: bool,
+}
+
+#[derive(Component, Debug)]
+pub struct Medal {
+    pub value: u32,
+}
+
+fn reset_no_miss_streak(mut streak: ResMut<NoMissStreak>) {
+    *streak = NoMissStreak::default();
+}
+
+// A hit in the same tick as a wave start counts against the wave that just ended.
+fn track_flawless_waves(
+    mut commands: Commands,
+    mut streak: ResMut<NoMissStreak>,
+    mut waves: EventReader<WaveStartedEvent>,
+    mut hits: EventReader<PlayerHitEvent>,
+    player: Query<&Transform, With<Player>>,
+    config: Res<GameConfig>,
+) {
+    if hits.read().count() > 0 {
+        streak.hit_this_wave = true;
+    }
+    for _ in waves.read() {
+        if streak.wave_running {
+            if streak.hit_this_wave {
+                streak.streak = 0;
+            } else {
+                streak.streak += 1;
+                let x = player.get_single().map_or(0.0, |t| t.translation.x);
+                spawn_medal(&mut commands, &config, x, streak.streak);
+            }
+        }
+        streak.wave_running = true;
+        streak.hit_this_wave = false;
+    }
+}
+
+// Drops in above the ship so a player who stays put catches it.
+fn spawn_medal(commands: &mut Commands, config: &GameConfig, x: f32, streak: u32) {
+    let (color, scale) = if streak >= GOLD_STREAK {
+        (Color::srgb(1.0, 0.82, 0.25), 1.3)
+    } else if streak >= SILVER_STREAK {
+        (Color::srgb(0.85, 0.9, 1.0), 1.15)
+    } else {
+        (Color::srgb(0.85, 0.55, 0.3), 1.0)
+    };
+    let y = config.logical_height * 0.5 + MEDAL_SIZE;
+    commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_xyz(x, y, 1.0)
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(MEDAL_SIZE * scale)),
+                ..default()
+            },
+            ..default()
+        },
+        Medal {
+            value: MEDAL_BASE_VALUE * streak.min(MAX_MEDAL_STREAK),
+        },
+        TransformInterpolation::default(),
+    ));
+}
+
+fn move_medals(
+    mut commands: Commands,
+    mut medals: Query<(Entity, &mut Transform), With<Medal>>,
+    time: Res<Time<Fixed>>,
+    config: Res<GameConfig>,
+) {
+    let bottom = -config.logical_height * 0.5 - 60.0;
+    for (entity, mut transform) in &mut medals {
+        transform.translation.y -= MEDAL_FALL_SPEED * time.delta_seconds();
+        if transform.translation.y < bottom {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn collect_medals(
+    mut commands: Commands,
+    medals: Query<(Entity, &Transform, &Sprite, &Medal)>,
+    player: Query<(&Transform, &Sprite), With<Player>>,
+    mut scoreboard: ResMut<ScoreBoard>,
+    mut popups: EventWriter<ScorePopupEvent>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    let Ok((player_transform, player_sprite)) = player.get_single() else {
+        return;
+    };
+    let player_half = player_sprite.custom_size.unwrap_or(Vec2::splat(32.0)) * 0.5;
+    let player_center = player_transform.translation.truncate();
+    for (entity, transform, sprite, medal) in &medals {
+        let half = sprite.custom_size.unwrap_or(Vec2::splat(MEDAL_SIZE)) * 0.5;
+        let center = transform.translation.truncate();
+        if (player_center.x - center.x).abs() <= (player_half.x + half.x)
+            && (player_center.y - center.y).abs() <= (player_half.y + half.y)
+        {
+            scoreboard.score += medal.value;
+            popups.send(ScorePopupEvent {
+                position: center,
+                value: medal.value,
+            });
+            audio.send(AudioCue::at(Sfx::UiSelect, center));
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn cleanup_medals(mut commands: Commands, medals: Query<Entity, With<Medal>>) {
+    for entity in &medals {
+        commands.entity(entity).despawn_recursive();
+    }
+}
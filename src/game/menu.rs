@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+
+use super::{
+    audio::{AudioCue, Sfx},
+    input_device::gamepad_just_pressed,
+};
+
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.06);
+const BUTTON_FOCUSED_COLOR: Color = Color::srgba(0.4, 0.7, 1.0, 0.3);
+const BUTTON_PRESSED_COLOR: Color = Color::srgba(0.6, 0.85, 1.0, 0.55);
+const BUTTON_FOCUSED_BORDER: Color = Color::srgba(0.6, 0.85, 1.0, 0.8);
+const PRESS_FLASH_SECONDS: f32 = 0.12;
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuActivated>().add_systems(
+            Update,
+            (menu_navigation, menu_pointer, menu_button_visuals)
+                .chain()
+                .in_set(MenuSet)
+                .run_if(resource_exists::<MenuFocus>),
+        );
+    }
+}
+
+// Screens that react to `MenuActivated` order their input systems after this.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuSet;
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MenuActivated {
+    pub entity: Entity,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct MenuFocus {
+    pub focused: usize,
+    pub count: usize,
+    pub locked: bool,
+}
+
+impl MenuFocus {
+    pub fn new(count: usize) -> Self {
+        Self { count, ..default() }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct MenuButton {
+    pub index: usize,
+    flash: f32,
+}
+
+pub fn menu_button(index: usize, style: Style) -> impl Bundle {
+    (
+        ButtonBundle {
+            style: Style {
+                border: UiRect::all(Val::Px(2.0)),
+                ..style
+            },
+            background_color: BackgroundColor(BUTTON_COLOR),
+            border_color: BorderColor(Color::NONE),
+            ..default()
+        },
+        MenuButton { index, flash: 0.0 },
+    )
+}
+
+pub fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    index: usize,
+    label: &str,
+    width: f32,
+    style: TextStyle,
+    marker: impl Bundle,
+) {
+    parent
+        .spawn((
+            menu_button(
+                index,
+                Style {
+                    width: Val::Px(width),
+                    padding: UiRect::vertical(Val::Px(6.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ),
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(label, style));
+        });
+}
+
+pub fn clear_menu_focus(mut commands: Commands) {
+    commands.remove_resource::<MenuFocus>();
+}
+
+fn menu_navigation(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut focus: ResMut<MenuFocus>,
+    mut items: Query<(Entity, &mut MenuButton)>,
+    mut activated: EventWriter<MenuActivated>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if focus.locked || focus.count == 0 {
+        return;
+    }
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keys.just_pressed(key) || gamepad_just_pressed(&gamepads, &buttons, button)
+    };
+
+    if pressed(KeyCode::ArrowUp, GamepadButtonType::DPadUp) {
+        focus.focused = (focus.focused + focus.count - 1) % focus.count;
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+    if pressed(KeyCode::ArrowDown, GamepadButtonType::DPadDown) {
+        focus.focused = (focus.focused + 1) % focus.count;
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+
+    let confirm = keys.just_pressed(KeyCode::Enter)
+        || keys.just_pressed(KeyCode::Space)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::South);
+    if confirm
+        && let Some((entity, mut item)) = items
+            .iter_mut()
+            .find(|(_, item)| item.index == focus.focused)
+    {
+        item.flash = PRESS_FLASH_SECONDS;
+        activated.send(MenuActivated { entity });
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+}
+
+fn menu_pointer(
+    mut focus: ResMut<MenuFocus>,
+    items: Query<(Entity, &Interaction, &MenuButton), Changed<Interaction>>,
+    mut activated: EventWriter<MenuActivated>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if focus.locked {
+        return;
+    }
+    for (entity, interaction, item) in &items {
+        match interaction {
+            Interaction::Hovered if focus.focused != item.index => {
+                focus.focused = item.index;
+            }
+            Interaction::Pressed => {
+                focus.focused = item.index;
+                activated.send(MenuActivated { entity });
+                audio.send(AudioCue::new(Sfx::UiSelect));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn menu_button_visuals(
+    focus: Res<MenuFocus>,
+    time: Res<Time<Real>>,
+    mut items: Query<(
+        &Interaction,
+        &mut MenuButton,
+        &mut BackgroundColor,
+        &mut BorderColor,
+    )>,
+) {
+    for (interaction, mut item, mut background, mut border) in &mut items {
+        if item.flash > 0.0 {
+            item.flash = (item.flash - time.delta_seconds()).max(0.0);
+        }
+        let focused = item.index == focus.focused;
+        background.set_if_neq(BackgroundColor(
+            if *interaction == Interaction::Pressed || item.flash > 0.0 {
+                BUTTON_PRESSED_COLOR
+            } else if focused {
+                BUTTON_FOCUSED_COLOR
+            } else {
+                BUTTON_COLOR
+            },
+        ));
+        border.set_if_neq(BorderColor(if focused {
+            BUTTON_FOCUSED_BORDER
+        } else {
+            Color::NONE
+        }));
+    }
+}
@@ -1,37 +1,128 @@
+pub mod achievements;
+pub mod attract;
 pub mod audio;
 pub mod background;
+pub mod bonus_stages;
 pub mod boss;
 pub mod camera;
+pub mod career;
+pub mod challenge;
+pub mod cheats;
 pub mod collisions;
+pub mod combo_meter;
 pub mod config;
+pub mod crt;
 pub mod debug;
+pub mod dialogue;
+pub mod editor;
 pub mod effects;
 pub mod enemies;
+pub mod enemy_damage;
+pub mod graphics;
+pub mod highscores;
+pub mod input_device;
+#[cfg(feature = "dev-tools")]
+pub mod inspector;
+pub mod interpolation;
+#[cfg(feature = "kira")]
+pub mod kira_audio;
+pub mod lighting;
+pub mod loading;
+pub mod medals;
+pub mod menu;
+pub mod palette;
+pub mod platform;
 pub mod player;
+pub mod pool;
 pub mod powerups;
+pub mod practice;
+pub mod profiles;
+pub mod rank;
+pub mod results_export;
+#[cfg(not(feature = "kira"))]
+pub mod rodio_audio;
+pub mod rules;
+pub mod run_stats;
+pub mod run_timer;
+pub mod savegame;
+pub mod screenshots;
+pub mod settings_menu;
 pub mod ship_sprites;
 pub mod spawn;
+pub mod sprite_atlas;
 pub mod states;
+#[cfg(feature = "steam")]
+pub mod steam;
+pub mod storage;
+pub mod threat_indicators;
+pub mod time_scale;
+pub mod touch;
+pub mod trails;
+pub mod tutorial;
+pub mod typography;
 pub mod ui;
+pub mod unlocks;
 pub mod weapons;
 
 pub use states::AppState;
 
-use audio::AudioPlugin;
+use achievements::AchievementsPlugin;
+use attract::AttractPlugin;
 use background::BackgroundPlugin;
+use bonus_stages::BonusStagePlugin;
 use boss::BossPlugin;
 use camera::CameraPlugin;
+use career::CareerPlugin;
+use challenge::ChallengePlugin;
+use cheats::CheatsPlugin;
 use collisions::CollisionPlugin;
+use combo_meter::ComboMeterPlugin;
 use config::ConfigPlugin;
+use crt::CrtPlugin;
 use debug::DebugPlugin;
+use dialogue::DialoguePlugin;
+use editor::EditorPlugin;
 use effects::EffectsPlugin;
 use enemies::EnemiesPlugin;
+use enemy_damage::EnemyDamagePlugin;
+use graphics::GraphicsPlugin;
+use highscores::HighScoresPlugin;
+use input_device::InputDevicePlugin;
+#[cfg(feature = "dev-tools")]
+use inspector::InspectorPlugin;
+use interpolation::InterpolationPlugin;
+#[cfg(feature = "kira")]
+use kira_audio::KiraAudioPlugin as AudioPlugin;
+use lighting::LightingPlugin;
+use loading::LoadingPlugin;
+use medals::MedalsPlugin;
+use menu::MenuPlugin;
+use platform::PlatformPlugin;
 use player::PlayerPlugin;
 use powerups::PowerupsPlugin;
+use practice::PracticePlugin;
+use profiles::ProfilesPlugin;
+use rank::RankPlugin;
+use results_export::ResultsExportPlugin;
+#[cfg(not(feature = "kira"))]
+use rodio_audio::RodioAudioPlugin as AudioPlugin;
+use run_stats::RunStatsPlugin;
+use run_timer::RunTimerPlugin;
+use savegame::SaveGamePlugin;
+use screenshots::ScreenshotPlugin;
+use settings_menu::SettingsMenuPlugin;
 use ship_sprites::ShipSpritePlugin;
 use spawn::SpawnPlugin;
+use sprite_atlas::SpriteAtlasPlugin;
 use states::StatePlugin;
+use threat_indicators::ThreatIndicatorPlugin;
+use time_scale::TimeScalePlugin;
+use touch::TouchPlugin;
+use trails::TrailsPlugin;
+use tutorial::TutorialPlugin;
+use typography::TypographyPlugin;
 use ui::UiPlugin;
+use unlocks::UnlocksPlugin;
 use weapons::WeaponsPlugin;
 
 use bevy::prelude::*;
@@ -40,16 +131,25 @@ pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
+        // Platform services come first so cloud saves are in place before anything loads
+        // them, then profiles: the other plugins load their saves from the current one.
+        app.add_plugins(PlatformPlugin);
         app.add_plugins((
+            ProfilesPlugin,
             ConfigPlugin,
             StatePlugin,
+            TypographyPlugin,
             ShipSpritePlugin,
             DebugPlugin,
             CameraPlugin,
+            CrtPlugin,
             BackgroundPlugin,
             UiPlugin,
             PlayerPlugin,
             WeaponsPlugin,
+            AttractPlugin,
+            AchievementsPlugin,
+            TutorialPlugin,
         ))
         .add_plugins((
             EnemiesPlugin,
@@ -59,6 +159,42 @@ impl Plugin for GamePlugin {
             CollisionPlugin,
             BossPlugin,
             AudioPlugin,
+            TimeScalePlugin,
+            TrailsPlugin,
+            LightingPlugin,
+            HighScoresPlugin,
+            SettingsMenuPlugin,
+            RunStatsPlugin,
+            MenuPlugin,
+            ThreatIndicatorPlugin,
+        ))
+        .add_plugins((
+            ComboMeterPlugin,
+            RunTimerPlugin,
+            GraphicsPlugin,
+            InputDevicePlugin,
+            SaveGamePlugin,
+            CareerPlugin,
+            PracticePlugin,
+            RankPlugin,
+            EditorPlugin,
+            CheatsPlugin,
+            ScreenshotPlugin,
+            SpriteAtlasPlugin,
+            InterpolationPlugin,
+            LoadingPlugin,
+            TouchPlugin,
+        ))
+        .add_plugins((
+            ResultsExportPlugin,
+            ChallengePlugin,
+            MedalsPlugin,
+            BonusStagePlugin,
+            UnlocksPlugin,
+            DialoguePlugin,
+            EnemyDamagePlugin,
         ));
+        #[cfg(feature = "dev-tools")]
+        app.add_plugins(InspectorPlugin);
     }
 }
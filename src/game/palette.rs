@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use super::{
+    config::{ColorPalette, ShipPaint},
+    powerups::PowerUpKind,
+};
+
+const STANDARD_HULL: HullColors = HullColors {
+    healthy: Color::srgb(0.3, 0.85, 0.4),
+    damaged: Color::srgb(0.95, 0.75, 0.25),
+    critical: Color::srgb(0.95, 0.3, 0.3),
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct HullColors {
+    pub healthy: Color,
+    pub damaged: Color,
+    pub critical: Color,
+}
+
+// Red-green palettes lean on blue versus yellow and on brightness; the tritanopia
+// palette keeps red versus cyan and avoids blue/yellow pairs. Pickups that share a
+// sprite shape are kept furthest apart.
+
+pub fn enemy_shot_color(palette: ColorPalette, standard: Color) -> Color {
+    match palette {
+        ColorPalette::Standard => standard,
+        ColorPalette::Deuteranopia => Color::srgb(1.0, 0.8, 0.1),
+        ColorPalette::Protanopia => Color::srgb(1.0, 0.95, 0.3),
+        ColorPalette::Tritanopia => Color::srgb(1.0, 0.25, 0.45),
+    }
+}
+
+pub fn powerup_color(palette: ColorPalette, kind: PowerUpKind, standard: Color) -> Color {
+    match palette {
+        ColorPalette::Standard => standard,
+        ColorPalette::Deuteranopia | ColorPalette::Protanopia => match kind {
+            PowerUpKind::Spread => Color::srgb(0.3, 0.5, 1.0),
+            PowerUpKind::Health => Color::srgb(1.0, 0.7, 0.15),
+            PowerUpKind::Rapid => Color::srgb(0.75, 0.85, 1.0),
+            PowerUpKind::Invincibility => Color::srgb(1.0, 0.95, 0.3),
+            PowerUpKind::Shield => Color::srgb(1.0, 1.0, 1.0),
+            PowerUpKind::Reflect => Color::srgb(0.2, 0.4, 0.95),
+        },
+        ColorPalette::Tritanopia => match kind {
+            PowerUpKind::Spread => Color::srgb(0.3, 0.9, 0.8),
+            PowerUpKind::Health => Color::srgb(1.0, 0.35, 0.35),
+            PowerUpKind::Rapid => Color::srgb(1.0, 0.6, 0.8),
+            PowerUpKind::Invincibility => Color::srgb(1.0, 1.0, 1.0),
+            PowerUpKind::Shield => Color::srgb(0.4, 1.0, 0.5),
+            PowerUpKind::Reflect => Color::srgb(0.85, 0.2, 0.5),
+        },
+    }
+}
+
+pub fn hull_colors(palette: ColorPalette) -> HullColors {
+    match palette {
+        ColorPalette::Standard => STANDARD_HULL,
+        ColorPalette::Deuteranopia | ColorPalette::Protanopia => HullColors {
+            healthy: Color::srgb(0.35, 0.6, 1.0),
+            damaged: Color::srgb(1.0, 0.85, 0.3),
+            critical: Color::srgb(0.95, 0.45, 0.05),
+        },
+        ColorPalette::Tritanopia => HullColors {
+            healthy: Color::srgb(0.3, 0.9, 0.85),
+            damaged: Color::srgb(1.0, 0.6, 0.75),
+            critical: Color::srgb(0.95, 0.15, 0.2),
+        },
+    }
+}
+
+// Edge arrows for (ordinary enemies, the boss).
+pub fn threat_colors(palette: ColorPalette) -> (Color, Color) {
+    match palette {
+        ColorPalette::Standard => (
+            Color::srgba(1.0, 0.35, 0.3, 0.85),
+            Color::srgba(1.0, 0.75, 0.2, 0.95),
+        ),
+        ColorPalette::Deuteranopia | ColorPalette::Protanopia => (
+            Color::srgba(1.0, 0.85, 0.2, 0.85),
+            Color::srgba(0.4, 0.6, 1.0, 0.95),
+        ),
+        ColorPalette::Tritanopia => (
+            Color::srgba(1.0, 0.25, 0.45, 0.85),
+            Color::srgba(0.3, 0.95, 0.9, 0.95),
+        ),
+    }
+}
+
+// Multiplied into the player's ship sprite.
+pub fn ship_paint_color(paint: ShipPaint) -> Color {
+    match paint {
+        ShipPaint::Standard => Color::WHITE,
+        ShipPaint::Ember => Color::srgb(1.0, 0.68, 0.45),
+        ShipPaint::Violet => Color::srgb(0.8, 0.62, 1.0),
+    }
+}
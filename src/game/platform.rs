@@ -0,0 +1,122 @@
+use std::{collections::HashMap, fs, path::Path, time::SystemTime};
+
+use bevy::prelude::*;
+
+use super::{
+    achievements::{Achievement, Achievements},
+    config::LaunchOptions,
+    profiles::save_files,
+    states::AppState,
+};
+
+pub struct PlatformPlugin;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<Platform>() {
+            app.insert_resource(Platform::default());
+        }
+        let root = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .and_then(|launch| launch.data_dir.clone());
+        let mut platform = app.world_mut().resource_mut::<Platform>();
+        for (key, path) in save_files(root.as_deref()) {
+            platform.0.download_file(&key, &path);
+        }
+
+        app.init_resource::<CloudUploads>()
+            .add_systems(First, run_platform_callbacks)
+            .add_systems(
+                Update,
+                mirror_achievements.run_if(resource_changed::<Achievements>),
+            )
+            .add_systems(OnEnter(AppState::Title), upload_saves)
+            .add_systems(Last, upload_saves.run_if(on_event::<AppExit>()));
+    }
+}
+
+// A storefront or console SDK the game reports to. Each method has a do-nothing default,
+// so a backend only fills in what its platform offers. Backends live outside the game
+// plugins: a build that ships on a platform inserts `Platform::new(backend)` before
+// adding `GamePlugin`, and nothing else in the game knows which one is running.
+pub trait PlatformServices: Send + Sync + 'static {
+    fn unlock_achievement(&mut self, _achievement: Achievement) {}
+
+    // Replaces `local` with the cloud copy stored under `key` when that copy is newer.
+    fn download_file(&mut self, _key: &str, _local: &Path) {}
+
+    fn upload_file(&mut self, _key: &str, _contents: &[u8]) {}
+
+    // Called once a frame, for SDKs that deliver results through callbacks.
+    fn update(&mut self) {}
+}
+
+// Used when no platform backend is installed: everything stays on local disk.
+struct Offline;
+
+impl PlatformServices for Offline {}
+
+#[derive(Resource)]
+pub struct Platform(Box<dyn PlatformServices>);
+
+impl Platform {
+    pub fn new(services: impl PlatformServices) -> Self {
+        Self(Box::new(services))
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::new(Offline)
+    }
+}
+
+// When each save file was last uploaded, so unchanged files aren't sent again.
+#[derive(Resource, Default)]
+struct CloudUploads(HashMap<String, SystemTime>);
+
+fn run_platform_callbacks(mut platform: ResMut<Platform>) {
+    platform.0.update();
+}
+
+// Platforms ignore repeat unlocks, but each one may cost a round trip, so every
+// achievement is reported once per session. Switching profiles reports whatever the
+// new profile has unlocked.
+fn mirror_achievements(
+    achievements: Res<Achievements>,
+    mut platform: ResMut<Platform>,
+    mut reported: Local<Vec<Achievement>>,
+) {
+    for entry in &achievements.unlocked {
+        if !reported.contains(&entry.achievement) {
+            platform.0.unlock_achievement(entry.achievement);
+            reported.push(entry.achievement);
+        }
+    }
+}
+
+// Runs when a run returns to the title, after its scores and progress have been saved,
+// and again on exit.
+fn upload_saves(
+    launch: Option<Res<LaunchOptions>>,
+    mut platform: ResMut<Platform>,
+    mut uploads: ResMut<CloudUploads>,
+) {
+    let root = launch.and_then(|launch| launch.data_dir.clone());
+    for (key, path) in save_files(root.as_deref()) {
+        let Ok(modified) = fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+            continue;
+        };
+        if uploads.0.get(&key) == Some(&modified) {
+            continue;
+        }
+        match fs::read(&path) {
+            Ok(contents) => {
+                platform.0.upload_file(&key, &contents);
+                uploads.0.insert(key, modified);
+            }
+            Err(err) => warn!("Failed to read {} for upload: {}", path.display(), err),
+        }
+    }
+}
@@ -1,20 +1,53 @@
 use std::f32::consts::FRAC_PI_2;
 
-use bevy::{log::info, prelude::*, time::Fixed};
+use bevy::{
+    log::info,
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    time::Fixed,
+};
+use serde::{Deserialize, Serialize};
 
 use super::{
-    audio::AudioCue,
-    config::GameConfig,
-    ship_sprites::{ShipAnimation, ShipSpriteAssets, ShipSpriteId},
-    states::AppState,
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    cheats::Cheats,
+    config::{GameConfig, GameSettings, KeyBindings, RunDifficulty, ShipModel},
+    effects::ParticleEmitter,
+    interpolation::TransformInterpolation,
+    palette::ship_paint_color,
+    rules::RuleSet,
+    ship_sprites::{ShipAnimation, ShipRow, ShipSpriteAssets, ShipSpriteId},
+    states::{AppState, InRun},
+    touch::TouchControls,
+    trails::Trail,
     weapons::PlayerFireEvent,
 };
 
 #[derive(Event, Debug, Clone, Copy)]
 pub struct PlayerLifeLostEvent;
 
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerHitEvent {
+    pub damage: u8,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BombEvent {
+    pub position: Vec2,
+}
+
 pub const PLAYER_HIT_INVULNERABILITY: f32 = 1.6;
+pub const REFLECT_SHIELD_RADIUS: f32 = 64.0;
+pub const MAX_FIRE_RATE_LEVEL: u8 = 5;
+const STARTING_BOMBS: u8 = 3;
 const PLAYER_INVULNERABILITY_FLICKER_HZ: f32 = 14.0;
+const AURA_RADIUS: f32 = 38.0;
+const AURA_COLOR: Color = Color::srgb(1.0, 0.9, 0.45);
+const AURA_ALPHA: f32 = 0.32;
+// Over its last seconds the aura pulses, quickening as it runs out.
+const AURA_WARNING_SECONDS: f32 = 2.0;
+const AURA_WARNING_HZ: (f32, f32) = (2.0, 6.0);
 
 pub struct PlayerPlugin;
 
@@ -27,12 +60,16 @@ impl Plugin for PlayerPlugin {
             .init_resource::<PlayerWeaponState>()
             .register_type::<PlayerWeaponState>()
             .add_event::<PlayerLifeLostEvent>()
-            .add_systems(OnEnter(AppState::Playing), spawn_player)
-            .add_systems(OnExit(AppState::Playing), despawn_player)
+            .add_event::<PlayerHitEvent>()
+            .add_event::<BombEvent>()
+            .add_systems(Startup, load_aura_assets)
+            .add_systems(OnEnter(InRun), spawn_player)
+            .add_systems(OnExit(InRun), despawn_player)
             .add_systems(
                 FixedUpdate,
                 (
                     handle_player_movement,
+                    apply_max_weapon_cheat,
                     player_fire_input,
                     tick_player_invulnerability,
                     handle_life_loss_respawn,
@@ -41,7 +78,12 @@ impl Plugin for PlayerPlugin {
             )
             .add_systems(
                 Update,
-                update_player_flash.run_if(in_state(AppState::Playing)),
+                (
+                    update_player_flash,
+                    update_player_aura,
+                    player_bomb_input.run_if(not(resource_exists::<DemoPilot>)),
+                )
+                    .run_if(in_state(AppState::Playing)),
             );
     }
 }
@@ -49,7 +91,11 @@ impl Plugin for PlayerPlugin {
 #[derive(Component, Default)]
 pub struct Player;
 
-#[derive(Resource, Debug, Clone, Copy, Reflect)]
+// The model the ship was launched as; changing it in settings waits for the next run.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerShip(pub ShipModel);
+
+#[derive(Resource, Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
 #[reflect(Resource)]
 pub struct PlayerStats {
     pub health: u8,
@@ -70,13 +116,17 @@ impl Default for PlayerStats {
 }
 
 impl PlayerStats {
-    pub fn reset(&mut self) {
-        self.max_health = 5;
+    pub fn reset(&mut self, lives: u8, hull: u8) {
+        self.max_health = hull;
         self.health = self.max_health;
-        self.max_lives = 3;
+        self.max_lives = lives;
         self.lives = self.max_lives;
     }
 
+    pub fn is_critical(&self) -> bool {
+        self.health == 1
+    }
+
     pub fn health_fraction(&self) -> f32 {
         if self.max_health == 0 {
             0.0
@@ -102,14 +152,15 @@ impl Default for PlayerSettings {
     }
 }
 
-#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[derive(Resource, Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
 #[reflect(Resource)]
 pub struct PlayerWeaponState {
     pub mode: WeaponMode,
     pub fire_rate_level: u8,
+    pub bombs: u8,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum WeaponMode {
     Single,
     Double,
@@ -123,6 +174,7 @@ impl Default for PlayerWeaponState {
         Self {
             mode: WeaponMode::Single,
             fire_rate_level: 0,
+            bombs: STARTING_BOMBS,
         }
     }
 }
@@ -131,6 +183,7 @@ impl PlayerWeaponState {
     pub fn reset(&mut self) {
         self.mode = WeaponMode::Single;
         self.fire_rate_level = 0;
+        self.bombs = STARTING_BOMBS;
     }
 
     pub fn current_cooldown(&self, settings: &PlayerSettings) -> f32 {
@@ -152,7 +205,30 @@ impl PlayerWeaponState {
     }
 
     pub fn boost_fire_rate(&mut self) {
-        self.fire_rate_level = self.fire_rate_level.saturating_add(1).min(5);
+        self.fire_rate_level = self
+            .fire_rate_level
+            .saturating_add(1)
+            .min(MAX_FIRE_RATE_LEVEL);
+    }
+}
+
+impl WeaponMode {
+    pub const ALL: [WeaponMode; 5] = [
+        WeaponMode::Single,
+        WeaponMode::Double,
+        WeaponMode::Spread3,
+        WeaponMode::Spread5,
+        WeaponMode::Laser,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WeaponMode::Single => "Single",
+            WeaponMode::Double => "Double",
+            WeaponMode::Spread3 => "Spread x3",
+            WeaponMode::Spread5 => "Spread x5",
+            WeaponMode::Laser => "Laser",
+        }
     }
 }
 
@@ -162,52 +238,120 @@ pub struct Velocity(pub Vec2);
 #[derive(Component)]
 pub struct PlayerDefense {
     pub invulnerability: f32,
+    pub reflect_shield: f32,
+    // The part of `invulnerability` granted by a power-up, shown as an aura around the
+    // ship instead of the post-hit flicker.
+    pub aura: f32,
+}
+
+impl PlayerDefense {
+    pub fn grant_invulnerability(&mut self, seconds: f32) {
+        self.invulnerability = self.invulnerability.max(seconds);
+        self.aura = self.aura.max(seconds);
+    }
+}
+
+#[derive(Component)]
+pub struct PlayerAura;
+
+#[derive(Resource)]
+struct AuraAssets {
+    mesh: Mesh2dHandle,
+    material: Handle<ColorMaterial>,
 }
 
 #[derive(Component)]
 pub struct PlayerAppearance {
     pub normal_color: Color,
     pub hit_color: Color,
+    pub reflect_color: Color,
 }
 
+fn load_aura_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(AuraAssets {
+        mesh: Mesh2dHandle(meshes.add(Circle::new(AURA_RADIUS))),
+        material: materials.add(AURA_COLOR.with_alpha(AURA_ALPHA)),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 fn spawn_player(
     mut commands: Commands,
     mut stats: ResMut<PlayerStats>,
     mut weapon_state: ResMut<PlayerWeaponState>,
     sprites: Res<ShipSpriteAssets>,
+    aura: Res<AuraAssets>,
+    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    rules: Res<RuleSet>,
 ) {
-    stats.reset();
+    stats.reset(
+        rules.lives.unwrap_or(run_difficulty.knobs().starting_lives),
+        settings.ship.hull(),
+    );
     weapon_state.reset();
-    let normal_color = Color::WHITE;
+    if let Some(mode) = rules.weapon {
+        weapon_state.mode = mode;
+    }
+    let normal_color = ship_paint_color(settings.paint);
     let hit_color = Color::srgb(1.0, 0.6, 0.6);
-    let sprite_data = sprites.data(ShipSpriteId::Player);
-    let sequence = sprites.sequence(ShipSpriteId::Player, 0);
-    commands.spawn((
-        SpriteBundle {
-            texture: sprite_data.texture.clone(),
-            transform: Transform::from_xyz(0.0, -260.0, 2.0),
-            sprite: Sprite {
-                color: normal_color,
-                custom_size: Some(sprite_data.frame_size * sprite_data.scale),
+    let reflect_color = Color::srgb(0.6, 1.0, 1.0);
+    let sprite_id = ShipSpriteId::player(settings.ship);
+    let sprite_data = sprites.data(sprite_id);
+    let sequence = sprites.sequence(sprite_id, ShipRow::Idle);
+    commands
+        .spawn((
+            SpriteBundle {
+                texture: sprite_data.texture.clone(),
+                transform: Transform::from_xyz(0.0, -260.0, 2.0),
+                sprite: Sprite {
+                    color: normal_color,
+                    custom_size: Some(sprite_data.frame_size * sprite_data.scale),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-        TextureAtlas {
-            layout: sprite_data.layout.clone(),
-            index: sequence[0],
-        },
-        Player,
-        Velocity::default(),
-        PlayerDefense {
-            invulnerability: 0.0,
-        },
-        PlayerAppearance {
-            normal_color,
-            hit_color,
-        },
-        ShipAnimation::new(ShipSpriteId::Player, 0, 0.08),
-    ));
+            TextureAtlas {
+                layout: sprite_data.layout.clone(),
+                index: sequence[0],
+            },
+            Player,
+            PlayerShip(settings.ship),
+            Velocity::default(),
+            PlayerDefense {
+                invulnerability: 0.0,
+                reflect_shield: 0.0,
+                aura: 0.0,
+            },
+            PlayerAppearance {
+                normal_color,
+                hit_color,
+                reflect_color,
+            },
+            ShipAnimation::new(&sprites, sprite_id, ShipRow::Idle),
+            Trail::player(),
+            TransformInterpolation::default(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpatialBundle::from_transform(Transform::from_xyz(0.0, -26.0, -0.5)),
+                ParticleEmitter::thruster(),
+            ));
+            parent.spawn((
+                MaterialMesh2dBundle {
+                    mesh: aura.mesh.clone(),
+                    material: aura.material.clone(),
+                    transform: Transform::from_xyz(0.0, 0.0, 0.5),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                PlayerAura,
+            ));
+        });
 }
 
 fn despawn_player(mut commands: Commands, query: Query<Entity, With<Player>>) {
@@ -216,45 +360,66 @@ fn despawn_player(mut commands: Commands, query: Query<Entity, With<Player>>) {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_player_movement(
     keys: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&mut Transform, &mut Velocity), With<Player>>,
+    bindings: Res<KeyBindings>,
+    demo: Option<Res<DemoPilot>>,
+    mut touch: ResMut<TouchControls>,
+    mut query: Query<(
+        &mut Transform,
+        &mut Velocity,
+        &PlayerShip,
+        &mut ShipAnimation,
+    )>,
     config: Res<GameConfig>,
     settings: Res<PlayerSettings>,
     time: Res<Time<Fixed>>,
 ) {
-    let Ok((mut transform, mut velocity)) = query.get_single_mut() else {
+    let Ok((mut transform, mut velocity, ship, mut animation)) = query.get_single_mut() else {
         return;
     };
+    let start_x = transform.translation.x;
 
-    let mut direction = Vec2::ZERO;
-    if keys.pressed(KeyCode::KeyW) || keys.pressed(KeyCode::ArrowUp) {
+    let mut direction = demo.map_or(Vec2::ZERO, |pilot| pilot.direction);
+    if keys.pressed(bindings.move_up) || keys.pressed(KeyCode::ArrowUp) {
         direction.y += 1.0;
     }
-    if keys.pressed(KeyCode::KeyS) || keys.pressed(KeyCode::ArrowDown) {
+    if keys.pressed(bindings.move_down) || keys.pressed(KeyCode::ArrowDown) {
         direction.y -= 1.0;
     }
-    if keys.pressed(KeyCode::KeyA) || keys.pressed(KeyCode::ArrowLeft) {
+    if keys.pressed(bindings.move_left) || keys.pressed(KeyCode::ArrowLeft) {
         direction.x -= 1.0;
     }
-    if keys.pressed(KeyCode::KeyD) || keys.pressed(KeyCode::ArrowRight) {
+    if keys.pressed(bindings.move_right) || keys.pressed(KeyCode::ArrowRight) {
         direction.x += 1.0;
     }
 
     direction = direction.normalize_or_zero();
-    velocity.0 = direction * settings.speed;
+    velocity.0 = direction * settings.speed * ship.0.speed_factor();
 
     transform.translation += (velocity.0 * time.delta_seconds()).extend(0.0);
+    // A drag moves the ship as far as the finger went, on top of any key input.
+    transform.translation += std::mem::take(&mut touch.drag).extend(0.0);
 
     let half_w = config.logical_width * 0.5 - 24.0;
     let half_h = config.logical_height * 0.5 - 32.0;
     transform.translation.x = transform.translation.x.clamp(-half_w, half_w);
     transform.translation.y = transform.translation.y.clamp(-half_h, half_h);
+
+    // Banks on how far the ship actually went, so drags count and a wall levels it out.
+    if time.delta_seconds() > 0.0 {
+        animation.bank((transform.translation.x - start_x) / time.delta_seconds());
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn player_fire_input(
     keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
+    demo: Option<Res<DemoPilot>>,
+    touch: Res<TouchControls>,
     query: Query<&Transform, With<Player>>,
     settings: Res<PlayerSettings>,
     weapon_state: Res<PlayerWeaponState>,
@@ -269,9 +434,11 @@ fn player_fire_input(
 
     *time_since_fire += time.delta_seconds();
 
-    let shooting = keys.pressed(KeyCode::Space)
+    let shooting = keys.pressed(bindings.fire)
         || keys.pressed(KeyCode::Enter)
-        || mouse_buttons.pressed(MouseButton::Left);
+        || mouse_buttons.pressed(MouseButton::Left)
+        || touch.firing
+        || demo.is_some_and(|pilot| pilot.firing);
     let cooldown = weapon_state.current_cooldown(&settings);
     if shooting && *time_since_fire >= cooldown {
         *time_since_fire = 0.0;
@@ -280,7 +447,42 @@ fn player_fire_input(
             transform.translation.truncate(),
             &mut writer,
         );
-        audio_writer.send(AudioCue::Shoot);
+        audio_writer.send(AudioCue::at(Sfx::Shoot, transform.translation.truncate()));
+    }
+}
+
+fn player_bomb_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut touch: ResMut<TouchControls>,
+    query: Query<&Transform, With<Player>>,
+    mut weapon_state: ResMut<PlayerWeaponState>,
+    cheats: Res<Cheats>,
+    mut writer: EventWriter<BombEvent>,
+) {
+    let requested = keys.just_pressed(bindings.bomb) || std::mem::take(&mut touch.bomb_requested);
+    if !requested || (weapon_state.bombs == 0 && !cheats.infinite_bombs) {
+        return;
+    }
+    let Ok(transform) = query.get_single() else {
+        return;
+    };
+    if !cheats.infinite_bombs {
+        weapon_state.bombs -= 1;
+    }
+    writer.send(BombEvent {
+        position: transform.translation.truncate(),
+    });
+}
+
+fn apply_max_weapon_cheat(cheats: Res<Cheats>, mut weapon_state: ResMut<PlayerWeaponState>) {
+    if !cheats.max_weapon {
+        return;
+    }
+    if weapon_state.mode != WeaponMode::Laser || weapon_state.fire_rate_level < MAX_FIRE_RATE_LEVEL
+    {
+        weapon_state.mode = WeaponMode::Laser;
+        weapon_state.fire_rate_level = MAX_FIRE_RATE_LEVEL;
     }
 }
 
@@ -394,21 +596,24 @@ fn tick_player_invulnerability(
 ) {
     for mut defense in &mut query {
         defense.invulnerability = (defense.invulnerability - time.delta_seconds()).max(0.0);
+        defense.reflect_shield = (defense.reflect_shield - time.delta_seconds()).max(0.0);
+        defense.aura = (defense.aura - time.delta_seconds()).max(0.0);
     }
 }
 
 fn handle_life_loss_respawn(
     mut events: EventReader<PlayerLifeLostEvent>,
-    mut query: Query<(&mut Transform, &mut Velocity), With<Player>>,
+    mut query: Query<(&mut Transform, &mut Velocity, &mut TransformInterpolation), With<Player>>,
 ) {
     if events.is_empty() {
         return;
     }
     for _ in events.read() {
-        if let Ok((mut transform, mut velocity)) = query.get_single_mut() {
+        if let Ok((mut transform, mut velocity, mut interpolation)) = query.get_single_mut() {
             transform.translation.x = 0.0;
             transform.translation.y = -260.0;
             velocity.0 = Vec2::ZERO;
+            interpolation.teleport();
             info!("Player respawned after life loss");
         }
     }
@@ -425,24 +630,73 @@ pub fn update_player_flash(
         With<Player>,
     >,
     time: Res<Time>,
+    settings: Res<GameSettings>,
 ) {
     let flicker_frequency = PLAYER_INVULNERABILITY_FLICKER_HZ.max(1.0);
     for (defense, appearance, mut sprite, mut visibility) in &mut query {
-        if defense.invulnerability > 0.0 {
+        // A power-up's invulnerability shows as the aura; only a hit's flickers.
+        let flickering = defense.invulnerability > 0.0 && defense.aura <= 0.0;
+        if flickering && settings.reduce_flashing {
+            // A steady translucent tint instead of strobing the ship.
+            sprite.color = appearance.hit_color.with_alpha(0.6);
+            visibility.set_if_neq(Visibility::Inherited);
+        } else if flickering {
             let flicker_on = (time.elapsed_seconds_wrapped() * flicker_frequency).fract() > 0.5;
             sprite.color = if flicker_on {
                 appearance.hit_color
             } else {
                 appearance.normal_color
             };
-            *visibility = if flicker_on {
+            visibility.set_if_neq(if flicker_on {
                 Visibility::Inherited
             } else {
                 Visibility::Hidden
-            };
+            });
         } else {
-            sprite.color = appearance.normal_color;
-            *visibility = Visibility::Inherited;
+            sprite.color = if defense.reflect_shield > 0.0 {
+                appearance.reflect_color
+            } else {
+                appearance.normal_color
+            };
+            visibility.set_if_neq(Visibility::Inherited);
         }
     }
 }
+
+fn update_player_aura(
+    players: Query<&PlayerDefense, With<Player>>,
+    mut auras: Query<(&mut Visibility, &mut Transform), With<PlayerAura>>,
+    aura: Res<AuraAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    settings: Res<GameSettings>,
+) {
+    let remaining = players.get_single().map_or(0.0, |defense| defense.aura);
+    for (mut visibility, mut transform) in &mut auras {
+        visibility.set_if_neq(if remaining > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        let breath = (time.elapsed_seconds_wrapped() * 3.0).sin();
+        transform.scale = Vec3::splat(1.0 + 0.04 * breath);
+    }
+    if remaining <= 0.0 {
+        return;
+    }
+
+    let alpha = if remaining >= AURA_WARNING_SECONDS {
+        AURA_ALPHA
+    } else if settings.reduce_flashing {
+        // Fades out steadily rather than pulsing.
+        AURA_ALPHA * (0.3 + 0.7 * remaining / AURA_WARNING_SECONDS)
+    } else {
+        let t = 1.0 - remaining / AURA_WARNING_SECONDS;
+        let hz = AURA_WARNING_HZ.0 + (AURA_WARNING_HZ.1 - AURA_WARNING_HZ.0) * t;
+        let pulse = 0.5 + 0.5 * (remaining * hz * std::f32::consts::TAU).cos();
+        AURA_ALPHA * (0.25 + 0.75 * pulse)
+    };
+    if let Some(material) = materials.get_mut(&aura.material) {
+        material.color = AURA_COLOR.with_alpha(alpha);
+    }
+}
@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::{system::EntityCommands, world::Command},
+    prelude::*,
+};
+
+// Hidden sprites kept for reuse by whatever spawns entities tagged `T`. Short-lived,
+// high-volume effects such as shots and explosions are handed back with `release`
+// instead of being despawned, and `spawn` fills a free one before creating a new one.
+#[derive(Resource)]
+pub struct EntityPool<T: Component> {
+    free: Vec<Entity>,
+    pub created: u32,
+    pub reused: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T: Component> Default for EntityPool<T> {
+    fn default() -> Self {
+        Self {
+            free: Vec::new(),
+            created: 0,
+            reused: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> EntityPool<T> {
+    // The bundle should carry `T` and a full `SpriteBundle`. A reused entity is stripped
+    // again first, in case something tagged it after it was released.
+    pub fn spawn<'a>(
+        &mut self,
+        commands: &'a mut Commands,
+        bundle: impl Bundle,
+    ) -> EntityCommands<'a> {
+        match self.free.pop() {
+            Some(entity) => {
+                self.reused += 1;
+                commands.add(Reuse { entity, bundle });
+                commands.entity(entity)
+            }
+            None => {
+                self.created += 1;
+                commands.spawn(bundle)
+            }
+        }
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    // Share of spawns served from the pool since it was last cleared.
+    pub fn reuse_rate(&self) -> f32 {
+        let total = self.created + self.reused;
+        if total == 0 {
+            0.0
+        } else {
+            self.reused as f32 / total as f32
+        }
+    }
+}
+
+// Strips `entity` back to a hidden sprite and returns it to its pool. Does nothing if
+// it has already been released or despawned, so overlapping hits are harmless.
+pub fn release<T: Component>(commands: &mut Commands, entity: Entity) {
+    commands.add(Release::<T> {
+        entity,
+        marker: PhantomData,
+    });
+}
+
+// Despawns the pool's free entities, for when a run ends.
+pub fn clear_pool<T: Component>(mut commands: Commands, mut pool: ResMut<EntityPool<T>>) {
+    for entity in pool.free.drain(..) {
+        commands.entity(entity).despawn_recursive();
+    }
+    pool.created = 0;
+    pool.reused = 0;
+}
+
+struct Reuse<B> {
+    entity: Entity,
+    bundle: B,
+}
+
+impl<B: Bundle> Command for Reuse<B> {
+    fn apply(self, world: &mut World) {
+        world
+            .entity_mut(self.entity)
+            .retain::<(SpriteBundle, TextureAtlas)>()
+            .insert(self.bundle);
+    }
+}
+
+struct Release<T> {
+    entity: Entity,
+    marker: PhantomData<T>,
+}
+
+impl<T: Component> Command for Release<T> {
+    fn apply(self, world: &mut World) {
+        let Some(mut entity) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        if !entity.contains::<T>() {
+            return;
+        }
+        entity
+            .despawn_descendants()
+            .retain::<(SpriteBundle, TextureAtlas)>()
+            .insert(Visibility::Hidden);
+        world.resource_mut::<EntityPool<T>>().free.push(self.entity);
+    }
+}
@@ -1,11 +1,16 @@
 use bevy::{prelude::*, sprite::TextureAtlas, time::Fixed};
+use serde::Serialize;
 
 use super::{
-    audio::AudioCue,
-    config::GameConfig,
+    audio::{AudioCue, Sfx},
+    config::{GameConfig, GameSettings},
     effects::ExplosionAssets,
+    interpolation::TransformInterpolation,
+    palette::powerup_color,
     player::{Player, PlayerDefense, PlayerStats, PlayerWeaponState},
-    states::AppState,
+    rules::RuleSet,
+    run_stats::RunStats,
+    states::{AppState, InRun},
 };
 
 pub struct PowerupsPlugin;
@@ -13,7 +18,7 @@ pub struct PowerupsPlugin;
 impl Plugin for PowerupsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SpawnPowerUpEvent>()
-            .add_systems(OnExit(AppState::Playing), cleanup_powerups)
+            .add_systems(OnExit(InRun), cleanup_powerups)
             .add_systems(
                 FixedUpdate,
                 (spawn_powerups_from_events, move_powerups, collect_powerups)
@@ -24,6 +29,7 @@ impl Plugin for PowerupsPlugin {
 }
 
 const INVINCIBLE_POWERUP_DURATION: f32 = 10.0;
+const REFLECT_POWERUP_DURATION: f32 = 6.0;
 
 #[derive(Component)]
 pub struct PowerUp {
@@ -47,13 +53,26 @@ struct PowerUpAnimation {
     timer: Timer,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PowerUpKind {
     Spread,
     Rapid,
     Shield,
     Health,
     Invincibility,
+    Reflect,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 6] = [
+        PowerUpKind::Spread,
+        PowerUpKind::Rapid,
+        PowerUpKind::Shield,
+        PowerUpKind::Health,
+        PowerUpKind::Invincibility,
+        PowerUpKind::Reflect,
+    ];
 }
 
 fn cleanup_powerups(mut commands: Commands, query: Query<Entity, With<PowerUp>>) {
@@ -72,9 +91,17 @@ fn spawn_powerups_from_events(
     mut commands: Commands,
     mut reader: EventReader<SpawnPowerUpEvent>,
     effects: Res<ExplosionAssets>,
+    settings: Res<GameSettings>,
+    rules: Res<RuleSet>,
 ) {
+    // Drops still roll as usual so a seeded run plays the same; they just never appear.
+    if !rules.powerups {
+        reader.clear();
+        return;
+    }
     for event in reader.read() {
         let (color, sequence_index) = powerup_visuals(event.kind);
+        let color = powerup_color(settings.palette, event.kind, color);
         let frames = &effects.powerup_sequences[sequence_index % effects.powerup_sequences.len()];
         commands.spawn((
             SpriteBundle {
@@ -93,6 +120,7 @@ fn spawn_powerups_from_events(
             },
             PowerUp { kind: event.kind },
             PowerUpMotion { speed: 120.0 },
+            TransformInterpolation::default(),
             PowerUpAnimation {
                 sequence_index,
                 frame: 0,
@@ -109,6 +137,7 @@ fn powerup_visuals(kind: PowerUpKind) -> (Color, usize) {
         PowerUpKind::Shield => (Color::srgb(0.5, 1.0, 0.6), 2),
         PowerUpKind::Health => (Color::srgb(1.0, 0.5, 0.5), 0),
         PowerUpKind::Invincibility => (Color::srgb(1.0, 0.9, 0.4), 1),
+        PowerUpKind::Reflect => (Color::srgb(0.4, 1.0, 1.0), 2),
     }
 }
 
@@ -127,12 +156,15 @@ fn move_powerups(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn collect_powerups(
     mut commands: Commands,
     powerups: Query<(Entity, &Transform, &Sprite, &PowerUp)>,
     mut player_query: Query<(&Transform, &Sprite, &mut PlayerDefense), With<Player>>,
     mut weapon_state: ResMut<PlayerWeaponState>,
     mut stats: ResMut<PlayerStats>,
+    mut run_stats: ResMut<RunStats>,
+    rules: Res<RuleSet>,
     mut audio_events: EventWriter<AudioCue>,
 ) {
     let Ok((player_transform, player_sprite, mut defense)) = player_query.get_single_mut() else {
@@ -153,8 +185,10 @@ fn collect_powerups(
                 &mut weapon_state,
                 &mut defense,
                 &mut stats,
+                &rules,
                 &mut audio_events,
             );
+            run_stats.powerups_collected += 1;
             commands.entity(entity).despawn_recursive();
             break;
         }
@@ -181,18 +215,22 @@ fn apply_powerup(
     weapon_state: &mut PlayerWeaponState,
     defense: &mut PlayerDefense,
     stats: &mut PlayerStats,
+    rules: &RuleSet,
     audio_events: &mut EventWriter<AudioCue>,
 ) {
     match kind {
+        // A weapon fixed by the rules stays put.
+        PowerUpKind::Spread if rules.weapon.is_some() => {}
         PowerUpKind::Spread => weapon_state.advance_mode(),
         PowerUpKind::Rapid => weapon_state.boost_fire_rate(),
-        PowerUpKind::Shield => defense.invulnerability = defense.invulnerability.max(3.0),
+        PowerUpKind::Shield => defense.grant_invulnerability(3.0),
         PowerUpKind::Health => {
             stats.health = stats.health.saturating_add(1).min(stats.max_health);
         }
-        PowerUpKind::Invincibility => {
-            defense.invulnerability = defense.invulnerability.max(INVINCIBLE_POWERUP_DURATION);
+        PowerUpKind::Invincibility => defense.grant_invulnerability(INVINCIBLE_POWERUP_DURATION),
+        PowerUpKind::Reflect => {
+            defense.reflect_shield = defense.reflect_shield.max(REFLECT_POWERUP_DURATION);
         }
     }
-    audio_events.send(AudioCue::Pickup);
+    audio_events.send(AudioCue::new(Sfx::Pickup(kind)));
 }
@@ -0,0 +1,369 @@
+use bevy::prelude::*;
+
+use super::{
+    audio::{AudioCue, Sfx},
+    boss::BossState,
+    config::{GameSettings, RunDifficulty},
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    menu::{MenuActivated, MenuFocus, MenuSet, clear_menu_focus, spawn_text_button},
+    player::{PlayerStats, PlayerWeaponState, WeaponMode},
+    spawn::{Storyboard, WaveDirector, jump_to_wave},
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+    ui::ScoreBoard,
+};
+
+const MAX_PRACTICE_LIVES: u8 = 9;
+
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PracticeSetup>()
+            .add_systems(OnEnter(AppState::Practice), spawn_practice_menu)
+            .add_systems(
+                Update,
+                (practice_menu_input, refresh_practice_menu)
+                    .chain()
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::Practice)),
+            )
+            .add_systems(
+                OnExit(AppState::Practice),
+                (cleanup_practice_menu, clear_menu_focus),
+            )
+            .add_systems(
+                OnEnter(InRun),
+                rearm_practice_run.run_if(resource_exists::<PracticeRun>),
+            )
+            .add_systems(
+                Update,
+                apply_practice_run
+                    .run_if(in_state(AppState::Playing).and_then(resource_exists::<PracticeRun>)),
+            )
+            .add_systems(OnEnter(AppState::Title), end_practice);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PracticeStart {
+    Wave(u32),
+    Boss,
+}
+
+// The practice menu's choices, kept between visits.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PracticeSetup {
+    pub level_index: usize,
+    pub start: PracticeStart,
+    pub weapon: WeaponMode,
+    pub lives: u8,
+}
+
+impl Default for PracticeSetup {
+    fn default() -> Self {
+        Self {
+            level_index: 0,
+            start: PracticeStart::Wave(0),
+            weapon: WeaponMode::Single,
+            lives: 3,
+        }
+    }
+}
+
+// Present for the whole of a practice session, restarts and retries included, and
+// cleared on returning to the title. Practice runs skip the high score table, career
+// stats, achievements, and Save & Quit.
+#[derive(Resource, Debug)]
+pub struct PracticeRun {
+    pub setup: PracticeSetup,
+    applied: bool,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum PracticeOption {
+    Level,
+    Start,
+    Weapon,
+    Lives,
+    Begin,
+    Back,
+}
+
+impl PracticeOption {
+    const ALL: [PracticeOption; 6] = [
+        PracticeOption::Level,
+        PracticeOption::Start,
+        PracticeOption::Weapon,
+        PracticeOption::Lives,
+        PracticeOption::Begin,
+        PracticeOption::Back,
+    ];
+
+    fn label(self, setup: &PracticeSetup, storyboard: &Storyboard) -> String {
+        match self {
+            PracticeOption::Level => match storyboard.level_name(setup.level_index) {
+                Some(name) => format!("Level {}: {}", setup.level_index + 1, name),
+                None => format!("Level {}", setup.level_index + 1),
+            },
+            PracticeOption::Start => match setup.start {
+                PracticeStart::Wave(wave) => format!(
+                    "Start: Wave {}/{}",
+                    wave + 1,
+                    storyboard.wave_count(setup.level_index)
+                ),
+                PracticeStart::Boss => "Start: Boss".to_string(),
+            },
+            PracticeOption::Weapon => format!("Weapon: {}", setup.weapon.label()),
+            PracticeOption::Lives => format!("Lives: {}", setup.lives),
+            PracticeOption::Begin => "Start Practice".to_string(),
+            PracticeOption::Back => "Back".to_string(),
+        }
+    }
+}
+
+impl PracticeSetup {
+    // Steps one of the menu's values forwards or backwards, wrapping at either end.
+    fn step(&mut self, option: PracticeOption, forward: bool, storyboard: &Storyboard) {
+        let cycle = |index: usize, len: usize| {
+            if forward {
+                (index + 1) % len
+            } else {
+                (index + len - 1) % len
+            }
+        };
+        match option {
+            PracticeOption::Level => {
+                self.level_index = cycle(self.level_index, storyboard.level_count().max(1));
+                self.start = PracticeStart::Wave(0);
+            }
+            PracticeOption::Start => {
+                // Waves 1..=N followed by the boss.
+                let waves = storyboard.wave_count(self.level_index);
+                let index = match self.start {
+                    PracticeStart::Wave(wave) => wave as usize,
+                    PracticeStart::Boss => waves,
+                };
+                let next = cycle(index, waves + 1);
+                self.start = if next == waves {
+                    PracticeStart::Boss
+                } else {
+                    PracticeStart::Wave(next as u32)
+                };
+            }
+            PracticeOption::Weapon => {
+                let index = WeaponMode::ALL
+                    .iter()
+                    .position(|&mode| mode == self.weapon)
+                    .unwrap_or(0);
+                self.weapon = WeaponMode::ALL[cycle(index, WeaponMode::ALL.len())];
+            }
+            PracticeOption::Lives => {
+                self.lives =
+                    cycle(usize::from(self.lives - 1), usize::from(MAX_PRACTICE_LIVES)) as u8 + 1;
+            }
+            PracticeOption::Begin | PracticeOption::Back => {}
+        }
+    }
+
+    // A different storyboard may have fewer levels or waves than the remembered choice.
+    fn clamp_to(&mut self, storyboard: &Storyboard) {
+        if self.level_index >= storyboard.level_count() {
+            *self = Self::default();
+        }
+        if let PracticeStart::Wave(wave) = self.start
+            && wave as usize >= storyboard.wave_count(self.level_index)
+        {
+            self.start = PracticeStart::Wave(0);
+        }
+    }
+}
+
+#[derive(Component)]
+struct PracticeMenu;
+
+fn spawn_practice_menu(
+    mut commands: Commands,
+    mut setup: ResMut<PracticeSetup>,
+    storyboard: Res<Storyboard>,
+    font: Res<UiFont>,
+) {
+    setup.clamp_to(&storyboard);
+    // Focus opens on Start Practice so a repeat session is a single press away.
+    commands.insert_resource(MenuFocus {
+        focused: 4,
+        ..MenuFocus::new(PracticeOption::ALL.len())
+    });
+    let option_style = font.style(TextSize::Body, Color::WHITE);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ..default()
+            },
+            PracticeMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Practice",
+                font.style(TextSize::Heading, Color::WHITE),
+            ));
+            for (index, option) in PracticeOption::ALL.into_iter().enumerate() {
+                spawn_text_button(
+                    parent,
+                    index,
+                    &option.label(&setup, &storyboard),
+                    420.0,
+                    option_style.clone(),
+                    option,
+                );
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    font.style(TextSize::Caption, Color::srgb(0.7, 0.8, 0.9)),
+                ),
+                DevicePrompt::new(
+                    "Left / Right change  |  Enter start  |  Esc back",
+                    "D-Pad change  |  A start  |  B back",
+                ),
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn practice_menu_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut activated: EventReader<MenuActivated>,
+    options: Query<&PracticeOption>,
+    focus: Res<MenuFocus>,
+    mut setup: ResMut<PracticeSetup>,
+    storyboard: Res<Storyboard>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if keys.just_pressed(KeyCode::Escape)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East)
+    {
+        audio.send(AudioCue::new(Sfx::UiSelect));
+        next_state.set(AppState::Title);
+        return;
+    }
+
+    let chosen = activated
+        .read()
+        .filter_map(|event| options.get(event.entity).ok().copied())
+        .last();
+    match chosen {
+        Some(PracticeOption::Begin) => {
+            commands.insert_resource(PracticeRun {
+                setup: *setup,
+                applied: false,
+            });
+            next_state.set(AppState::Playing);
+        }
+        Some(PracticeOption::Back) => next_state.set(AppState::Title),
+        // Activating a value steps it forward, like the title screen's profile button.
+        Some(option) => setup.step(option, true, &storyboard),
+        None => {
+            let forward = keys.just_pressed(KeyCode::ArrowRight)
+                || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::DPadRight);
+            let backward = keys.just_pressed(KeyCode::ArrowLeft)
+                || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::DPadLeft);
+            if let Some(&option) = PracticeOption::ALL.get(focus.focused)
+                && (forward || backward)
+            {
+                setup.step(option, forward, &storyboard);
+                audio.send(AudioCue::new(Sfx::UiSelect));
+            }
+        }
+    }
+}
+
+fn refresh_practice_menu(
+    setup: Res<PracticeSetup>,
+    storyboard: Res<Storyboard>,
+    options: Query<(&PracticeOption, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !setup.is_changed() {
+        return;
+    }
+    for (option, children) in &options {
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.sections[0].value = option.label(&setup, &storyboard);
+            }
+        }
+    }
+}
+
+fn cleanup_practice_menu(mut commands: Commands, query: Query<Entity, With<PracticeMenu>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn rearm_practice_run(mut practice: ResMut<PracticeRun>) {
+    practice.applied = false;
+}
+
+// Runs on the first playing frame of each practice attempt, after the usual new-run
+// resets, and overwrites them. A boss start drops the boss threshold to the current
+// score so it warps in straight away.
+#[allow(clippy::too_many_arguments)]
+fn apply_practice_run(
+    mut practice: ResMut<PracticeRun>,
+    mut director: ResMut<WaveDirector>,
+    mut boss: ResMut<BossState>,
+    mut stats: ResMut<PlayerStats>,
+    mut weapon: ResMut<PlayerWeaponState>,
+    scoreboard: Res<ScoreBoard>,
+    storyboard: Res<Storyboard>,
+    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+) {
+    if practice.applied {
+        return;
+    }
+    practice.applied = true;
+    let setup = practice.setup;
+    let wave = match setup.start {
+        PracticeStart::Wave(wave) => wave,
+        PracticeStart::Boss => 0,
+    };
+    jump_to_wave(
+        &mut director,
+        &storyboard,
+        &run_difficulty,
+        setup.level_index,
+        wave,
+    );
+    if setup.start == PracticeStart::Boss {
+        boss.spawn_score = scoreboard.score;
+    }
+    stats.reset(setup.lives, settings.ship.hull());
+    weapon.mode = setup.weapon;
+    info!(
+        "Practice started on level {} ({:?})",
+        setup.level_index + 1,
+        setup.start
+    );
+}
+
+fn end_practice(mut commands: Commands) {
+    commands.remove_resource::<PracticeRun>();
+}
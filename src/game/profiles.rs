@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    achievements::{ACHIEVEMENTS_FILE, Achievements},
+    career::{CAREER_FILE, CareerStats},
+    challenge::{CHALLENGE_FILE, ChallengeScores},
+    config::{GameSettings, KeyBindings, LaunchOptions},
+    highscores::{HIGH_SCORE_FILE, HighScores},
+    savegame::{SAVE_FILE, SuspendedRun},
+    storage::{config_path, data_path, load_json, save_json},
+    tutorial::{TUTORIAL_FILE, Tutorial},
+    unlocks::{UNLOCKS_FILE, Unlocks},
+};
+
+const PROFILES_FILE: &str = "profiles.json";
+pub const PROFILE_SLOTS: usize = 3;
+
+pub struct ProfilesPlugin;
+
+impl Plugin for ProfilesPlugin {
+    fn build(&self, app: &mut App) {
+        let data_dir = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .and_then(|launch| launch.data_dir.clone());
+        let profiles = Profiles::load(data_dir);
+        app.insert_resource(profiles.current())
+            .insert_resource(profiles)
+            .add_event::<SwitchProfileEvent>()
+            .add_systems(
+                Update,
+                switch_profile.run_if(on_event::<SwitchProfileEvent>()),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileSlot {
+    // Initials last entered on the high score table; empty until the first entry.
+    pub name: String,
+}
+
+// The slot index file shared by every profile.
+#[derive(Resource, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub active: usize,
+    pub slots: Vec<ProfileSlot>,
+    // Set by `--data-dir`; every profile file then lives under it instead of the
+    // platform directories.
+    #[serde(skip)]
+    root: Option<PathBuf>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            active: 0,
+            slots: vec![ProfileSlot::default(); PROFILE_SLOTS],
+            root: None,
+        }
+    }
+}
+
+impl Profiles {
+    fn load(root: Option<PathBuf>) -> Self {
+        let mut profiles: Self = load_json(&index_path(root.as_deref()));
+        profiles.slots.resize(PROFILE_SLOTS, ProfileSlot::default());
+        profiles.active = profiles.active.min(PROFILE_SLOTS - 1);
+        Self { root, ..profiles }
+    }
+
+    fn save(&self) {
+        save_json(&index_path(self.root.as_deref()), self);
+    }
+
+    fn current(&self) -> CurrentProfile {
+        CurrentProfile {
+            slot: self.active,
+            name: self.slots[self.active].name.clone(),
+            root: self.root.clone(),
+        }
+    }
+}
+
+fn index_path(root: Option<&Path>) -> PathBuf {
+    match root {
+        Some(root) => root.join(PROFILES_FILE),
+        None => data_path(PROFILES_FILE),
+    }
+}
+
+// The slot index and every profile's scores and progress, each paired with its path
+// relative to the data directory, for mirroring to cloud storage.
+pub fn save_files(root: Option<&Path>) -> Vec<(String, PathBuf)> {
+    let mut files = vec![(PROFILES_FILE.to_string(), index_path(root))];
+    for slot in 0..PROFILE_SLOTS {
+        let profile = CurrentProfile {
+            slot,
+            name: String::new(),
+            root: root.map(Path::to_path_buf),
+        };
+        for file in [
+            HIGH_SCORE_FILE,
+            ACHIEVEMENTS_FILE,
+            CAREER_FILE,
+            TUTORIAL_FILE,
+            SAVE_FILE,
+            CHALLENGE_FILE,
+            UNLOCKS_FILE,
+        ] {
+            files.push((profile.relative(file), profile.data_path(file)));
+        }
+    }
+    files
+}
+
+// The profile whose settings, scores, achievements, tutorial progress, career stats, and
+// suspended run are loaded. Every persistence system resolves its files through this.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentProfile {
+    pub slot: usize,
+    pub name: String,
+    root: Option<PathBuf>,
+}
+
+impl CurrentProfile {
+    pub fn label(&self) -> String {
+        if self.name.is_empty() {
+            format!("PILOT {}", self.slot + 1)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    pub fn data_path(&self, file: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(self.relative(file)),
+            None => data_path(&self.relative(file)),
+        }
+    }
+
+    // For files every profile shares, such as screenshots.
+    pub fn shared_data_path(&self, file: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(file),
+            None => data_path(file),
+        }
+    }
+
+    // With a data directory set, settings sit alongside the saves.
+    pub fn config_path(&self, file: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(self.relative(file)),
+            None => config_path(&self.relative(file)),
+        }
+    }
+
+    // The first slot keeps the pre-profile locations so existing saves carry over.
+    fn relative(&self, file: &str) -> String {
+        match self.slot {
+            0 => file.to_string(),
+            slot => format!("profiles/{}/{file}", slot + 1),
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SwitchProfileEvent {
+    pub slot: usize,
+}
+
+// Records the name a profile's high score was entered under.
+pub fn rename_profile(profiles: &mut Profiles, current: &mut CurrentProfile, name: &str) {
+    if current.name == name {
+        return;
+    }
+    current.name = name.to_string();
+    profiles.slots[current.slot].name = current.name.clone();
+    profiles.save();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn switch_profile(
+    launch: Option<Res<LaunchOptions>>,
+    mut requests: EventReader<SwitchProfileEvent>,
+    mut profiles: ResMut<Profiles>,
+    mut current: ResMut<CurrentProfile>,
+    mut settings: ResMut<GameSettings>,
+    mut bindings: ResMut<KeyBindings>,
+    mut high_scores: ResMut<HighScores>,
+    mut achievements: ResMut<Achievements>,
+    mut tutorial: ResMut<Tutorial>,
+    mut suspended: ResMut<SuspendedRun>,
+    mut career: ResMut<CareerStats>,
+    mut challenge_scores: ResMut<ChallengeScores>,
+    mut unlocks: ResMut<Unlocks>,
+) {
+    let Some(slot) = requests.read().last().map(|request| request.slot) else {
+        return;
+    };
+    if slot == current.slot || slot >= PROFILE_SLOTS {
+        return;
+    }
+    // Flush anything the outgoing profile still has pending before the paths change.
+    launch
+        .as_deref()
+        .map_or(*settings, |launch| launch.strip(&settings, &current))
+        .save(&current);
+    bindings.save(&current);
+    achievements.save(&current);
+
+    profiles.active = slot;
+    profiles.save();
+    *current = profiles.current();
+    *settings = GameSettings::load(&current);
+    if let Some(launch) = launch {
+        launch.apply(&mut settings);
+    }
+    *bindings = KeyBindings::load(&current);
+    *high_scores = HighScores::load(&current);
+    *achievements = Achievements::load(&current);
+    *tutorial = Tutorial::load(&current);
+    *suspended = SuspendedRun::load(&current);
+    *career = CareerStats::load(&current);
+    *challenge_scores = ChallengeScores::load(&current);
+    *unlocks = Unlocks::load(&current);
+    info!("Switched to profile {}", current.label());
+}
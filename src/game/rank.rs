@@ -0,0 +1,123 @@
+use bevy::{math::Vec3Swizzles, prelude::*, time::Fixed};
+
+use super::{
+    collisions::EnemyDestroyedEvent,
+    config::GameSettings,
+    player::{Player, PlayerDefense, PlayerHitEvent, PlayerLifeLostEvent},
+    states::{AppState, InRun},
+    weapons::EnemyProjectile,
+};
+
+const RANK_PER_KILL: f32 = 0.004;
+const RANK_PER_GRAZE: f32 = 0.01;
+const RANK_PER_CLEAN_SECOND: f32 = 0.004;
+const RANK_PER_HIT: f32 = 0.05;
+const RANK_PER_DEATH: f32 = 0.25;
+// Rank only creeps up on its own once the ship has gone this long without a hit.
+const CLEAN_STREAK_SECONDS: f32 = 10.0;
+// Enemy shots passing this close to the ship's centre count as grazes.
+pub const GRAZE_RADIUS: f32 = 56.0;
+// At full rank the wave timer runs this much faster and enemy shots fly this much quicker.
+const MAX_SPAWN_RATE_BONUS: f32 = 0.5;
+const MAX_BULLET_SPEED_BONUS: f32 = 0.35;
+
+pub struct RankPlugin;
+
+impl Plugin for RankPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rank>()
+            .add_systems(OnEnter(InRun), reset_rank)
+            .add_systems(
+                FixedUpdate,
+                (detect_grazes, update_rank)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+// Hidden dynamic difficulty from 0 (the storyboard as written) to 1. It stays at 0
+// unless the Dynamic Rank setting is on.
+#[derive(Resource, Debug, Default)]
+pub struct Rank {
+    pub value: f32,
+    clean_time: f32,
+}
+
+impl Rank {
+    // Multiplier on how quickly the wave timer runs.
+    pub fn spawn_rate(&self) -> f32 {
+        1.0 + self.value * MAX_SPAWN_RATE_BONUS
+    }
+
+    pub fn bullet_speed(&self) -> f32 {
+        1.0 + self.value * MAX_BULLET_SPEED_BONUS
+    }
+
+    fn adjust(&mut self, amount: f32) {
+        self.value = (self.value + amount).clamp(0.0, 1.0);
+    }
+}
+
+#[derive(Component)]
+struct Grazed;
+
+fn reset_rank(mut rank: ResMut<Rank>) {
+    *rank = Rank::default();
+}
+
+#[allow(clippy::type_complexity)]
+fn detect_grazes(
+    mut commands: Commands,
+    mut rank: ResMut<Rank>,
+    settings: Res<GameSettings>,
+    player: Query<(&Transform, &PlayerDefense), With<Player>>,
+    projectiles: Query<(Entity, &Transform), (With<EnemyProjectile>, Without<Grazed>)>,
+) {
+    if !settings.dynamic_rank {
+        return;
+    }
+    let Ok((player_transform, defense)) = player.get_single() else {
+        return;
+    };
+    if defense.invulnerability > 0.0 {
+        return;
+    }
+    let player_center = player_transform.translation.xy();
+    for (entity, transform) in &projectiles {
+        if transform.translation.xy().distance(player_center) < GRAZE_RADIUS {
+            // The shot may be despawned by a collision this same tick.
+            commands.entity(entity).try_insert(Grazed);
+            rank.adjust(RANK_PER_GRAZE);
+        }
+    }
+}
+
+fn update_rank(
+    mut rank: ResMut<Rank>,
+    settings: Res<GameSettings>,
+    time: Res<Time<Fixed>>,
+    mut kills: EventReader<EnemyDestroyedEvent>,
+    mut hits: EventReader<PlayerHitEvent>,
+    mut deaths: EventReader<PlayerLifeLostEvent>,
+) {
+    let kills = kills.read().count();
+    let hits = hits.read().count();
+    let deaths = deaths.read().count();
+    if !settings.dynamic_rank {
+        rank.value = 0.0;
+        return;
+    }
+
+    if hits > 0 || deaths > 0 {
+        rank.clean_time = 0.0;
+    } else {
+        rank.clean_time += time.delta_seconds();
+    }
+    let mut change =
+        kills as f32 * RANK_PER_KILL - hits as f32 * RANK_PER_HIT - deaths as f32 * RANK_PER_DEATH;
+    if rank.clean_time > CLEAN_STREAK_SECONDS {
+        change += RANK_PER_CLEAN_SECOND * time.delta_seconds();
+    }
+    rank.adjust(change);
+}
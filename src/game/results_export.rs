@@ -0,0 +1,201 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use super::{
+    attract::DemoPilot,
+    config::{Difficulty, GameRng, GameSettings, ResultsExport, RunDifficulty},
+    enemies::EnemyKind,
+    highscores::file_timestamp,
+    input_device::LastInputDevice,
+    practice::PracticeRun,
+    profiles::CurrentProfile,
+    run_stats::RunStats,
+    spawn::WaveDirector,
+    states::{AppState, InRun},
+    ui::ScoreBoard,
+};
+
+const RESULTS_DIR: &str = "results";
+const CSV_FILE: &str = "results.csv";
+
+pub struct ResultsExportPlugin;
+
+impl Plugin for ResultsExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnExit(InRun),
+            export_run_results.run_if(not(resource_exists::<DemoPilot>).and_then(
+                |settings: Res<GameSettings>| settings.results_export != ResultsExport::Off,
+            )),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RunOutcome {
+    GameOver,
+    Victory,
+    // Quit, restarted, or saved for later.
+    Abandoned,
+}
+
+#[derive(Debug, Serialize)]
+struct KillCount {
+    enemy: EnemyKind,
+    count: u32,
+}
+
+// One finished run, as written for leaderboard scripts and spreadsheets.
+#[derive(Debug, Serialize)]
+struct RunResult {
+    finished: String,
+    profile: String,
+    seed: u64,
+    difficulty: Difficulty,
+    practice: bool,
+    outcome: RunOutcome,
+    score: u32,
+    level: usize,
+    time_seconds: f32,
+    shots_fired: u32,
+    accuracy: f32,
+    max_combo: u32,
+    powerups_collected: u32,
+    kills: Vec<KillCount>,
+    input_device: &'static str,
+}
+
+impl RunResult {
+    fn write_json(&self, dir: &Path) -> io::Result<()> {
+        let path = dir.join(format!("run-{}.json", self.finished));
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    // Appends a row to one file shared by every run, writing the header first if the
+    // file is new.
+    fn write_csv(&self, dir: &Path) -> io::Result<()> {
+        let path = dir.join(CSV_FILE);
+        let new_file = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if new_file {
+            let mut header = vec![
+                "finished",
+                "profile",
+                "seed",
+                "difficulty",
+                "practice",
+                "outcome",
+                "score",
+                "level",
+                "time_seconds",
+                "shots_fired",
+                "accuracy",
+                "max_combo",
+                "powerups_collected",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+            header.extend(
+                self.kills
+                    .iter()
+                    .map(|kill| format!("kills_{}", snake_case(&kill.enemy))),
+            );
+            header.push("input_device".into());
+            writeln!(file, "{}", header.join(","))?;
+        }
+
+        let mut row = vec![
+            self.finished.clone(),
+            self.profile.clone(),
+            self.seed.to_string(),
+            snake_case(&self.difficulty),
+            self.practice.to_string(),
+            snake_case(&self.outcome),
+            self.score.to_string(),
+            self.level.to_string(),
+            format!("{:.3}", self.time_seconds),
+            self.shots_fired.to_string(),
+            format!("{:.4}", self.accuracy),
+            self.max_combo.to_string(),
+            self.powerups_collected.to_string(),
+        ];
+        row.extend(self.kills.iter().map(|kill| kill.count.to_string()));
+        row.push(self.input_device.into());
+        writeln!(file, "{}", row.join(","))
+    }
+}
+
+// The name serde gives a unit variant, so CSV columns match the JSON fields.
+fn snake_case(value: &impl Serialize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_lowercase))
+        .unwrap_or_default()
+}
+
+// Runs however the run ends; the state has already moved on by the time `InRun` exits,
+// which tells a finished run from an abandoned one.
+#[allow(clippy::too_many_arguments)]
+fn export_run_results(
+    state: Res<State<AppState>>,
+    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    profile: Res<CurrentProfile>,
+    rng: Res<GameRng>,
+    practice: Option<Res<PracticeRun>>,
+    run_stats: Res<RunStats>,
+    scoreboard: Res<ScoreBoard>,
+    director: Res<WaveDirector>,
+    device: Res<LastInputDevice>,
+) {
+    let outcome = match state.get() {
+        AppState::GameOver | AppState::NameEntry => RunOutcome::GameOver,
+        AppState::Victory => RunOutcome::Victory,
+        _ => RunOutcome::Abandoned,
+    };
+    let result = RunResult {
+        finished: file_timestamp(),
+        profile: profile.label(),
+        seed: rng.seed(),
+        difficulty: run_difficulty.difficulty,
+        practice: practice.is_some(),
+        outcome,
+        score: scoreboard.score,
+        level: director.level_index + 1,
+        time_seconds: run_stats.time_survived,
+        shots_fired: run_stats.shots_fired,
+        accuracy: run_stats.accuracy(),
+        max_combo: run_stats.max_combo,
+        powerups_collected: run_stats.powerups_collected,
+        kills: EnemyKind::ALL
+            .into_iter()
+            .map(|enemy| KillCount {
+                enemy,
+                count: run_stats.kills.get(&enemy).copied().unwrap_or(0),
+            })
+            .collect(),
+        input_device: match *device {
+            LastInputDevice::KeyboardMouse => "keyboard_mouse",
+            LastInputDevice::Gamepad => "gamepad",
+        },
+    };
+
+    let dir = profile.data_path(RESULTS_DIR);
+    let written = fs::create_dir_all(&dir).and_then(|_| match settings.results_export {
+        ResultsExport::Json => result.write_json(&dir),
+        ResultsExport::Csv => result.write_csv(&dir),
+        ResultsExport::Off => Ok(()),
+    });
+    match written {
+        Ok(()) => info!("Exported run results to {}", dir.display()),
+        Err(err) => warn!("Failed to export run results to {}: {}", dir.display(), err),
+    }
+}
@@ -0,0 +1,341 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bevy::{
+    audio::{
+        AudioBundle, AudioSink, AudioSource, PlaybackSettings, SpatialAudioSink, SpatialScale,
+        Volume,
+    },
+    log::info,
+    prelude::*,
+};
+
+use super::{
+    audio::{
+        ALARM_VOLUME, AudioMixPlugin, AudioMixSet, LEVEL_TRACKS, LowHealthAlarm, MusicDuck,
+        MusicIntensity, MusicLayer, MusicTrack, PICKUP_KINDS, Sfx, VoiceTrigger, audio_file,
+        pickup_sound_name, synthesize,
+    },
+    boss::BossState,
+    config::GameSettings,
+    loading::LoadingAssets,
+    powerups::PowerUpKind,
+    spawn::WaveDirector,
+    states::{AppState, InRun},
+};
+
+const MUSIC_CROSSFADE_SECONDS: f32 = 2.0;
+const SFX_EAR_GAP: f32 = 760.0;
+const SFX_SPATIAL_SCALE: f32 = 1.0 / 420.0;
+
+// Plays the shared mix through Bevy's built-in audio output.
+pub struct RodioAudioPlugin;
+
+impl Plugin for RodioAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AudioMixPlugin)
+            .init_resource::<AudioAssets>()
+            .init_resource::<MusicController>()
+            .init_resource::<LayerSinks>()
+            .init_resource::<AlarmSink>()
+            .init_resource::<VoicePool>()
+            .add_systems(
+                Startup,
+                (
+                    (setup_audio_assets, spawn_music_layers).chain(),
+                    spawn_sfx_listener,
+                    spawn_sfx_voices,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    play_voices,
+                    (
+                        select_music_track.run_if(not(in_state(AppState::Restarting))),
+                        crossfade_music,
+                        mix_music_layers,
+                    )
+                        .chain(),
+                    play_low_health_alarm,
+                )
+                    .after(AudioMixSet),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct AudioAssets {
+    pub music: Handle<AudioSource>,
+    pub level_music: Vec<Handle<AudioSource>>,
+    pub boss_music: Handle<AudioSource>,
+    layers: Vec<(MusicLayer, Handle<AudioSource>)>,
+    pub shoot: Handle<AudioSource>,
+    pub hit: Handle<AudioSource>,
+    pub explosion: Handle<AudioSource>,
+    pub pickups: HashMap<PowerUpKind, Handle<AudioSource>>,
+    pub ui: Handle<AudioSource>,
+    pub warning: Handle<AudioSource>,
+}
+
+#[derive(Resource, Default)]
+struct MusicController {
+    current: Option<MusicTrack>,
+    active: Option<(Entity, f32)>,
+    fading: Vec<(Entity, f32)>,
+}
+
+impl MusicController {
+    fn play(&mut self, commands: &mut Commands, track: MusicTrack, source: Handle<AudioSource>) {
+        if let Some(previous) = self.active.take() {
+            self.fading.push(previous);
+        }
+        let entity = commands
+            .spawn(AudioBundle {
+                source,
+                settings: PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            })
+            .id();
+        self.active = Some((entity, 0.0));
+        self.current = Some(track);
+    }
+}
+
+#[derive(Resource, Default)]
+struct LayerSinks(Vec<(MusicLayer, Entity)>);
+
+#[derive(Resource, Default)]
+struct AlarmSink(Option<Entity>);
+
+// The fixed voice entities each sound is played on, spawned once and re-triggered.
+#[derive(Resource, Default)]
+struct VoicePool(HashMap<Sfx, Vec<Entity>>);
+
+#[derive(Component)]
+struct SfxVoice;
+
+fn setup_audio_assets(
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<Assets<AudioSource>>,
+    mut store: ResMut<AudioAssets>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let mut load = |name: &str| match audio_file(&asset_server, name) {
+        Some(path) => {
+            info!("Loading {path}");
+            let handle = asset_server.load(path);
+            loading.track(handle.clone());
+            handle
+        }
+        None => assets.add(AudioSource {
+            bytes: Arc::from(synthesize(name).into_boxed_slice()),
+        }),
+    };
+    store.music = load("music");
+    store.level_music = LEVEL_TRACKS.iter().map(|name| load(name)).collect();
+    store.boss_music = load("boss");
+    store.layers = MusicLayer::ALL
+        .into_iter()
+        .map(|layer| (layer, load(layer.sound_name())))
+        .collect();
+    store.shoot = load("shoot");
+    store.hit = load("hit");
+    store.explosion = load("explosion");
+    store.pickups = PICKUP_KINDS
+        .into_iter()
+        .map(|kind| (kind, load(pickup_sound_name(kind))))
+        .collect();
+    store.ui = load("ui");
+    store.warning = load("warning");
+}
+
+fn spawn_sfx_listener(mut commands: Commands) {
+    commands.spawn((
+        TransformBundle::default(),
+        SpatialListener::new(SFX_EAR_GAP),
+    ));
+}
+
+fn spawn_sfx_voices(mut commands: Commands, mut pool: ResMut<VoicePool>) {
+    for sfx in Sfx::all() {
+        let voices = (0..sfx.max_voices())
+            .map(|_| commands.spawn((SfxVoice, TransformBundle::default())).id())
+            .collect();
+        pool.0.insert(sfx, voices);
+    }
+}
+
+fn select_music_track(
+    mut commands: Commands,
+    assets: Res<AudioAssets>,
+    in_run: Option<Res<State<InRun>>>,
+    boss: Res<BossState>,
+    director: Res<WaveDirector>,
+    mut controller: ResMut<MusicController>,
+) {
+    let track = MusicTrack::wanted(in_run.is_some(), &boss, &director, assets.level_music.len());
+    if controller.current == Some(track) {
+        return;
+    }
+
+    let source = match track {
+        MusicTrack::Title => assets.music.clone(),
+        MusicTrack::Boss => assets.boss_music.clone(),
+        MusicTrack::Level(index) => assets
+            .level_music
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| assets.music.clone()),
+    };
+    controller.play(&mut commands, track, source);
+}
+
+fn play_voices(
+    mut commands: Commands,
+    mut triggers: EventReader<VoiceTrigger>,
+    assets: Res<AudioAssets>,
+    settings: Res<GameSettings>,
+    pool: Res<VoicePool>,
+) {
+    for trigger in triggers.read() {
+        let handle = match trigger.sfx {
+            Sfx::Shoot => &assets.shoot,
+            Sfx::Hit => &assets.hit,
+            Sfx::Explosion => &assets.explosion,
+            Sfx::Pickup(kind) => match assets.pickups.get(&kind) {
+                Some(handle) => handle,
+                None => continue,
+            },
+            Sfx::UiSelect => &assets.ui,
+        };
+        let Some(&voice) = pool
+            .0
+            .get(&trigger.sfx)
+            .and_then(|voices| voices.get(trigger.voice))
+        else {
+            continue;
+        };
+
+        let playback = PlaybackSettings::REMOVE
+            .with_volume(Volume::new(settings.sfx_level()))
+            .with_speed(trigger.speed);
+        let playback = match trigger.position {
+            Some(_) => playback
+                .with_spatial(true)
+                .with_spatial_scale(SpatialScale::new_2d(SFX_SPATIAL_SCALE)),
+            None => playback,
+        };
+        // Dropping the old sink cuts off whatever the voice was still playing; Bevy then
+        // starts the new sound on it because it has a source and no sink.
+        let mut voice = commands.entity(voice);
+        voice
+            .remove::<(AudioSink, SpatialAudioSink)>()
+            .insert((handle.clone(), playback));
+        if let Some(position) = trigger.position {
+            voice.insert(TransformBundle::from_transform(
+                Transform::from_translation(position.extend(0.0)),
+            ));
+        }
+    }
+}
+
+fn crossfade_music(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    duck: Res<MusicDuck>,
+    time: Res<Time<Real>>,
+    mut controller: ResMut<MusicController>,
+    sinks: Query<&AudioSink>,
+) {
+    let step = time.delta_seconds() / MUSIC_CROSSFADE_SECONDS;
+    let volume = settings.music_level() * duck.gain;
+
+    if let Some((entity, gain)) = controller.active.as_mut() {
+        *gain = (*gain + step).min(1.0);
+        if let Ok(sink) = sinks.get(*entity) {
+            sink.set_volume(volume * *gain);
+        }
+    }
+
+    controller.fading.retain_mut(|(entity, gain)| {
+        *gain -= step;
+        if *gain <= 0.0 {
+            if let Some(entity_cmd) = commands.get_entity(*entity) {
+                entity_cmd.despawn_recursive();
+            }
+            return false;
+        }
+        if let Ok(sink) = sinks.get(*entity) {
+            sink.set_volume(volume * *gain);
+        }
+        true
+    });
+}
+
+fn spawn_music_layers(
+    mut commands: Commands,
+    assets: Res<AudioAssets>,
+    mut layers: ResMut<LayerSinks>,
+) {
+    for (layer, source) in &assets.layers {
+        let entity = commands
+            .spawn(AudioBundle {
+                source: source.clone(),
+                settings: PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            })
+            .id();
+        layers.0.push((*layer, entity));
+    }
+}
+
+fn mix_music_layers(
+    settings: Res<GameSettings>,
+    duck: Res<MusicDuck>,
+    intensity: Res<MusicIntensity>,
+    layers: Res<LayerSinks>,
+    sinks: Query<&AudioSink>,
+) {
+    for (layer, volume) in intensity.layer_volumes() {
+        let Some(&(_, entity)) = layers.0.iter().find(|(sink_layer, _)| *sink_layer == layer)
+        else {
+            continue;
+        };
+        if let Ok(sink) = sinks.get(entity) {
+            sink.set_volume(settings.music_level() * duck.gain * volume);
+        }
+    }
+}
+
+fn play_low_health_alarm(
+    mut commands: Commands,
+    alarm: Res<LowHealthAlarm>,
+    settings: Res<GameSettings>,
+    assets: Res<AudioAssets>,
+    mut playing: ResMut<AlarmSink>,
+    sinks: Query<&AudioSink>,
+) {
+    if settings.is_changed()
+        && let Some(entity) = playing.0
+        && let Ok(sink) = sinks.get(entity)
+    {
+        sink.set_volume(settings.sfx_level() * ALARM_VOLUME);
+    }
+    match (alarm.active, playing.0) {
+        (true, None) => {
+            let entity = commands
+                .spawn(AudioBundle {
+                    source: assets.warning.clone(),
+                    settings: PlaybackSettings::LOOP
+                        .with_volume(Volume::new(settings.sfx_level() * ALARM_VOLUME)),
+                })
+                .id();
+            playing.0 = Some(entity);
+        }
+        (false, Some(entity)) => {
+            if let Some(entity_cmd) = commands.get_entity(entity) {
+                entity_cmd.despawn_recursive();
+            }
+            playing.0 = None;
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+use super::player::WeaponMode;
+
+// Constraints a challenge mission places on a run. The systems that hand out lives,
+// weapons, power-ups, and enemy shots consult it; the default changes nothing, and it
+// is back to the default whenever the title screen is up.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct RuleSet {
+    pub powerups: bool,
+    pub lives: Option<u8>,
+    // Held for the whole run; Spread pickups can't change it.
+    pub weapon: Option<WeaponMode>,
+    pub enemy_bullet_speed: f32,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            powerups: true,
+            lives: None,
+            weapon: None,
+            enemy_bullet_speed: 1.0,
+        }
+    }
+}
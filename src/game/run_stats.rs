@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, time::Fixed};
+
+use super::{
+    enemies::EnemyKind,
+    player::PlayerHitEvent,
+    states::{AppState, InRun},
+};
+
+const COMBO_WINDOW_SECONDS: f32 = 2.0;
+// Combo length needed for each score multiplier, starting at x1.
+const COMBO_TIERS: [u32; 4] = [0, 5, 15, 30];
+
+pub struct RunStatsPlugin;
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(InRun), reset_run_stats)
+            .add_systems(
+                FixedUpdate,
+                tick_run_stats.run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct RunStats {
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    pub kills: HashMap<EnemyKind, u32>,
+    pub powerups_collected: u32,
+    pub combo: u32,
+    pub max_combo: u32,
+    pub time_survived: f32,
+    combo_timer: f32,
+}
+
+impl RunStats {
+    pub fn record_kill(&mut self, kind: EnemyKind) {
+        *self.kills.entry(kind).or_default() += 1;
+        self.combo += 1;
+        self.max_combo = self.max_combo.max(self.combo);
+        self.combo_timer = COMBO_WINDOW_SECONDS;
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        COMBO_TIERS
+            .iter()
+            .filter(|&&tier| self.combo >= tier)
+            .count() as u32
+    }
+
+    pub fn kills_to_next_tier(&self) -> Option<u32> {
+        COMBO_TIERS
+            .iter()
+            .find(|&&tier| tier > self.combo)
+            .map(|tier| tier - self.combo)
+    }
+
+    // Fraction of the combo window left before the chain breaks.
+    pub fn combo_decay(&self) -> f32 {
+        if self.combo == 0 {
+            0.0
+        } else {
+            self.combo_timer / COMBO_WINDOW_SECONDS
+        }
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            (self.shots_hit as f32 / self.shots_fired as f32).min(1.0)
+        }
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    pub fn summary(&self) -> Vec<(String, String)> {
+        let seconds = self.time_survived as u32;
+        let mut rows = vec![
+            (
+                "Time survived".to_string(),
+                format!("{}:{:02}", seconds / 60, seconds % 60),
+            ),
+            ("Shots fired".to_string(), self.shots_fired.to_string()),
+            (
+                "Accuracy".to_string(),
+                format!("{:.0}%", self.accuracy() * 100.0),
+            ),
+            (
+                "Enemies destroyed".to_string(),
+                self.total_kills().to_string(),
+            ),
+        ];
+        for kind in EnemyKind::ALL {
+            if let Some(count) = self.kills.get(&kind) {
+                rows.push((format!("  {}", kind.label()), count.to_string()));
+            }
+        }
+        rows.push((
+            "Power-ups collected".to_string(),
+            self.powerups_collected.to_string(),
+        ));
+        rows.push(("Max combo".to_string(), self.max_combo.to_string()));
+        rows
+    }
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn tick_run_stats(
+    mut stats: ResMut<RunStats>,
+    mut hits: EventReader<PlayerHitEvent>,
+    time: Res<Time<Fixed>>,
+) {
+    let delta = time.delta_seconds();
+    let was_hit = hits.read().count() > 0;
+    stats.time_survived += delta;
+    stats.combo_timer = (stats.combo_timer - delta).max(0.0);
+    if stats.combo_timer <= 0.0 || was_hit {
+        stats.combo = 0;
+    }
+}
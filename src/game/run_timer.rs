@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{
+    config::GameSettings,
+    spawn::WaveStartedEvent,
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+};
+
+pub struct RunTimerPlugin;
+
+impl Plugin for RunTimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunTimer>()
+            .add_systems(OnEnter(InRun), (reset_run_timer, spawn_run_timer).chain())
+            .add_systems(Update, tick_run_timer.run_if(in_state(AppState::Playing)))
+            .add_systems(Update, update_run_timer_display.run_if(in_state(InRun)))
+            .add_systems(OnExit(InRun), cleanup_run_timer);
+    }
+}
+
+// Wall-clock split times; slow motion doesn't stretch them and pausing freezes them.
+#[derive(Resource, Debug, Default)]
+pub struct RunTimer {
+    pub level: Duration,
+    pub total: Duration,
+    level_number: usize,
+}
+
+impl RunTimer {
+    pub fn summary(&self) -> Vec<(String, String)> {
+        vec![
+            ("Level time".to_string(), format_split(self.level)),
+            ("Run time".to_string(), format_split(self.total)),
+        ]
+    }
+}
+
+pub fn format_split(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+#[derive(Component)]
+struct RunTimerDisplay;
+
+fn reset_run_timer(mut timer: ResMut<RunTimer>) {
+    *timer = RunTimer::default();
+}
+
+fn spawn_run_timer(mut commands: Commands, font: Res<UiFont>, settings: Res<GameSettings>) {
+    let style = font.style(TextSize::Caption, Color::srgb(0.85, 0.95, 1.0));
+    let text = TextBundle::from_sections([
+        TextSection::new("", style.clone()),
+        TextSection::new(
+            "",
+            TextStyle {
+                color: Color::srgb(0.6, 0.7, 0.8),
+                ..style
+            },
+        ),
+    ])
+    .with_text_justify(JustifyText::Right)
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        bottom: Val::Px(16.0),
+        right: Val::Px(16.0),
+        ..default()
+    });
+    commands.spawn((
+        TextBundle {
+            visibility: if settings.run_timer {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+            ..text
+        },
+        RunTimerDisplay,
+    ));
+}
+
+fn tick_run_timer(
+    mut timer: ResMut<RunTimer>,
+    mut waves: EventReader<WaveStartedEvent>,
+    time: Res<Time<Real>>,
+) {
+    for wave in waves.read() {
+        if wave.level != timer.level_number {
+            timer.level_number = wave.level;
+            timer.level = Duration::ZERO;
+        }
+    }
+    let delta = time.delta();
+    timer.level += delta;
+    timer.total += delta;
+}
+
+fn update_run_timer_display(
+    timer: Res<RunTimer>,
+    settings: Res<GameSettings>,
+    mut displays: Query<(&mut Text, &mut Visibility), With<RunTimerDisplay>>,
+) {
+    for (mut text, mut visibility) in &mut displays {
+        visibility.set_if_neq(if settings.run_timer {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if settings.run_timer && timer.is_changed() {
+            text.sections[0].value = format!("LEVEL {}\n", format_split(timer.level));
+            text.sections[1].value = format!("TOTAL {}", format_split(timer.total));
+        }
+    }
+}
+
+fn cleanup_run_timer(mut commands: Commands, displays: Query<Entity, With<RunTimerDisplay>>) {
+    for entity in &displays {
+        commands.entity(entity).despawn_recursive();
+    }
+}
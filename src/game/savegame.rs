@@ -0,0 +1,178 @@
+use std::{fs, io};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    boss::BossState,
+    config::{Difficulty, DifficultyKnobs, RunDifficulty},
+    player::{PlayerStats, PlayerWeaponState},
+    profiles::CurrentProfile,
+    spawn::WaveDirector,
+    states::AppState,
+    storage::{load_json, save_json},
+    ui::ScoreBoard,
+};
+
+pub const SAVE_FILE: &str = "savegame.json";
+
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(SuspendedRun::load(&profile))
+            .add_event::<SuspendRunEvent>()
+            .add_event::<ResumeRunEvent>()
+            .add_systems(Update, suspend_run.run_if(in_state(AppState::Paused)))
+            .add_systems(Update, resume_run.run_if(in_state(AppState::Title)))
+            .add_systems(
+                Update,
+                apply_resumed_run
+                    .run_if(in_state(AppState::Playing).and_then(resource_exists::<PendingResume>)),
+            )
+            .add_systems(OnEnter(AppState::GameOver), clear_suspended_run)
+            .add_systems(OnEnter(AppState::Victory), clear_suspended_run);
+    }
+}
+
+// Sent by the pause menu's "Save & Quit".
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SuspendRunEvent;
+
+// Sent by the title screen's "Resume Run".
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ResumeRunEvent;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub score: u32,
+    pub level_index: usize,
+    #[serde(default)]
+    pub loop_index: u32,
+    pub wave_index: u32,
+    pub wave_difficulty: f32,
+    pub boss_spawn_score: u32,
+    pub player: PlayerStats,
+    pub weapon: PlayerWeaponState,
+    pub difficulty: Difficulty,
+    pub custom_difficulty: DifficultyKnobs,
+}
+
+// The current profile's suspended run, if it has one.
+#[derive(Resource, Debug, Default)]
+pub struct SuspendedRun(pub Option<SavedRun>);
+
+impl SuspendedRun {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        Self(load_json(&profile.data_path(SAVE_FILE)))
+    }
+
+    // Clearing the run deletes the file, so a finished run can't be resumed.
+    fn save(&self, profile: &CurrentProfile) {
+        let path = profile.data_path(SAVE_FILE);
+        match &self.0 {
+            Some(run) => save_json(&path, run),
+            None => {
+                if let Err(err) = fs::remove_file(&path)
+                    && err.kind() != io::ErrorKind::NotFound
+                {
+                    warn!(
+                        "Failed to clear the suspended run at {}: {}",
+                        path.display(),
+                        err
+                    );
+                }
+            }
+        }
+    }
+}
+
+// Present from choosing Resume Run until the saved state has been applied.
+#[derive(Resource)]
+pub struct PendingResume(SavedRun);
+
+#[allow(clippy::too_many_arguments)]
+fn suspend_run(
+    mut events: EventReader<SuspendRunEvent>,
+    mut suspended: ResMut<SuspendedRun>,
+    profile: Res<CurrentProfile>,
+    scoreboard: Res<ScoreBoard>,
+    director: Res<WaveDirector>,
+    boss: Res<BossState>,
+    stats: Res<PlayerStats>,
+    weapon: Res<PlayerWeaponState>,
+    run_difficulty: Res<RunDifficulty>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    suspended.0 = Some(SavedRun {
+        score: scoreboard.score,
+        level_index: director.level_index,
+        loop_index: director.loop_index,
+        wave_index: director.wave_index,
+        wave_difficulty: director.difficulty,
+        boss_spawn_score: boss.spawn_score,
+        player: *stats,
+        weapon: *weapon,
+        difficulty: run_difficulty.difficulty,
+        custom_difficulty: run_difficulty.custom,
+    });
+    suspended.save(&profile);
+    next_state.set(AppState::Title);
+}
+
+fn resume_run(
+    mut commands: Commands,
+    mut events: EventReader<ResumeRunEvent>,
+    suspended: Res<SuspendedRun>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    if let Some(run) = &suspended.0 {
+        commands.insert_resource(PendingResume(run.clone()));
+        next_state.set(AppState::Playing);
+    }
+}
+
+// Runs on the first playing frame, after the usual new-run resets, and overwrites them.
+// A boss that was up when the run was saved warps back in straight away.
+#[allow(clippy::too_many_arguments)]
+fn apply_resumed_run(
+    mut commands: Commands,
+    pending: Res<PendingResume>,
+    mut scoreboard: ResMut<ScoreBoard>,
+    mut director: ResMut<WaveDirector>,
+    mut boss: ResMut<BossState>,
+    mut stats: ResMut<PlayerStats>,
+    mut weapon: ResMut<PlayerWeaponState>,
+    mut run_difficulty: ResMut<RunDifficulty>,
+) {
+    let run = &pending.0;
+    // The run plays on as saved without changing the difficulty picked in the settings.
+    *run_difficulty = RunDifficulty {
+        difficulty: run.difficulty,
+        custom: run.custom_difficulty,
+    };
+    scoreboard.score = run.score;
+    director.level_index = run.level_index;
+    director.loop_index = run.loop_index;
+    director.wave_index = run.wave_index;
+    director.difficulty = run.wave_difficulty;
+    director.timer.reset();
+    boss.spawn_score = run.boss_spawn_score;
+    *stats = run.player;
+    *weapon = run.weapon;
+    commands.remove_resource::<PendingResume>();
+    info!("Resumed a suspended run on level {}", run.level_index + 1);
+}
+
+fn clear_suspended_run(mut suspended: ResMut<SuspendedRun>, profile: Res<CurrentProfile>) {
+    if suspended.0.take().is_some() {
+        suspended.save(&profile);
+    }
+}
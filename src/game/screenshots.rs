@@ -0,0 +1,107 @@
+use std::fs;
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+
+use super::{
+    highscores::file_timestamp,
+    profiles::CurrentProfile,
+    typography::{TextSize, UiFont},
+};
+
+const SCREENSHOT_DIR: &str = "screenshots";
+// The capture is of a frame rendered after the request, so the toast waits this long
+// to keep itself out of the picture.
+const TOAST_DELAY_SECONDS: f32 = 0.2;
+const TOAST_HOLD_SECONDS: f32 = 2.0;
+const TOAST_FADE_SECONDS: f32 = 0.5;
+const TOAST_COLOR: Color = Color::srgb(0.8, 0.9, 1.0);
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (capture_screenshot, fade_screenshot_toasts).chain());
+    }
+}
+
+#[derive(Component)]
+struct ScreenshotToast {
+    age: f32,
+}
+
+// F12 saves the next frame as a PNG in the screenshots folder of the data directory.
+fn capture_screenshot(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    screenshots: Option<ResMut<ScreenshotManager>>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    profile: Res<CurrentProfile>,
+    font: Res<UiFont>,
+    toasts: Query<Entity, With<ScreenshotToast>>,
+) {
+    if !keys.just_pressed(KeyCode::F12) {
+        return;
+    }
+    let (Some(mut screenshots), Ok(window)) = (screenshots, window.get_single()) else {
+        return;
+    };
+    let dir = profile.shared_data_path(SCREENSHOT_DIR);
+    if let Err(err) = fs::create_dir_all(&dir) {
+        warn!(
+            "Failed to create screenshot folder {}: {}",
+            dir.display(),
+            err
+        );
+        return;
+    }
+    let path = dir.join(format!("sforce-{}.png", file_timestamp()));
+    if screenshots.save_screenshot_to_disk(window, &path).is_err() {
+        // One is already pending for this frame.
+        return;
+    }
+    info!("Saving screenshot to {}", path.display());
+
+    for entity in &toasts {
+        commands.entity(entity).despawn_recursive();
+    }
+    let text = TextBundle::from_section(
+        format!("Screenshot saved to {}", path.display()),
+        font.style(TextSize::Caption, TOAST_COLOR),
+    )
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        bottom: Val::Px(40.0),
+        left: Val::Px(16.0),
+        ..default()
+    });
+    commands.spawn((
+        TextBundle {
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(60),
+            ..text
+        },
+        ScreenshotToast {
+            age: -TOAST_DELAY_SECONDS,
+        },
+    ));
+}
+
+fn fade_screenshot_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut ScreenshotToast, &mut Text, &mut Visibility)>,
+) {
+    for (entity, mut toast, mut text, mut visibility) in &mut toasts {
+        toast.age += time.delta_seconds();
+        if toast.age < 0.0 {
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+        let fade = (toast.age - TOAST_HOLD_SECONDS) / TOAST_FADE_SECONDS;
+        if fade >= 1.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        text.sections[0].style.color = TOAST_COLOR.with_alpha(1.0 - fade.max(0.0));
+    }
+}
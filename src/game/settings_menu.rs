@@ -0,0 +1,781 @@
+use bevy::{
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition},
+};
+
+use super::{
+    AppState,
+    audio::{AudioCue, Sfx},
+    config::{Difficulty, GameSettings, InputAction, KeyBindings, ShipModel, ShipPaint, key_label},
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    menu::{MenuActivated, MenuFocus, MenuSet, clear_menu_focus, menu_button},
+    profiles::CurrentProfile,
+    states::SettingsOrigin,
+    tutorial::Tutorial,
+    typography::{TextSize, UiFont},
+    unlocks::{Unlock, Unlocks, spawn_lock_icon},
+};
+
+const SLIDER_TRACK_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+const SLIDER_FILL_COLOR: Color = Color::srgb(0.5, 0.85, 1.0);
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Settings), spawn_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    settings_keyboard_input,
+                    settings_activate,
+                    settings_slider_input,
+                    settings_menu_display,
+                )
+                    .chain()
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::Settings)),
+            )
+            .add_systems(
+                OnExit(AppState::Settings),
+                (cleanup_settings_menu, clear_menu_focus),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsSection {
+    General,
+    CustomDifficulty,
+    Graphics,
+    Accessibility,
+    Ship,
+    Story,
+    Controls,
+}
+
+impl SettingsSection {
+    fn label(self) -> &'static str {
+        match self {
+            SettingsSection::General => "General",
+            SettingsSection::CustomDifficulty => "Custom Difficulty",
+            SettingsSection::Graphics => "Graphics",
+            SettingsSection::Accessibility => "Accessibility",
+            SettingsSection::Ship => "Ship",
+            SettingsSection::Story => "Story",
+            SettingsSection::Controls => "Controls",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsRow {
+    Difficulty,
+    DynamicRank,
+    RevengeShots,
+    MirrorMode,
+    Ship,
+    Paint,
+    CustomSpawnInterval,
+    CustomEnemyHealth,
+    CustomBulletSpeed,
+    CustomLives,
+    CustomDropRate,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Mute,
+    ScreenShake,
+    RunTimer,
+    ResultsExport,
+    Tutorial,
+    StoryDialogue,
+    Language,
+    Fullscreen,
+    WindowSize,
+    ScreenLayout,
+    Vsync,
+    FrameCap,
+    CrtFilter,
+    Lighting,
+    TitleStatus,
+    ReduceFlashing,
+    ReduceMotion,
+    HighContrastBullets,
+    Palette,
+    Bind(InputAction),
+    Back,
+}
+
+impl SettingsRow {
+    fn all() -> Vec<SettingsRow> {
+        let mut rows = vec![
+            SettingsRow::Difficulty,
+            SettingsRow::DynamicRank,
+            SettingsRow::RevengeShots,
+            SettingsRow::MirrorMode,
+            SettingsRow::MasterVolume,
+            SettingsRow::MusicVolume,
+            SettingsRow::SfxVolume,
+            SettingsRow::Mute,
+            SettingsRow::RunTimer,
+            SettingsRow::ResultsExport,
+            SettingsRow::Tutorial,
+            SettingsRow::CustomSpawnInterval,
+            SettingsRow::CustomEnemyHealth,
+            SettingsRow::CustomBulletSpeed,
+            SettingsRow::CustomLives,
+            SettingsRow::CustomDropRate,
+            SettingsRow::Fullscreen,
+            SettingsRow::WindowSize,
+            SettingsRow::ScreenLayout,
+            SettingsRow::Vsync,
+            SettingsRow::FrameCap,
+            SettingsRow::CrtFilter,
+            SettingsRow::Lighting,
+            SettingsRow::TitleStatus,
+            SettingsRow::ScreenShake,
+            SettingsRow::ReduceFlashing,
+            SettingsRow::ReduceMotion,
+            SettingsRow::HighContrastBullets,
+            SettingsRow::Palette,
+            SettingsRow::Ship,
+            SettingsRow::Paint,
+            SettingsRow::StoryDialogue,
+            SettingsRow::Language,
+        ];
+        rows.extend(InputAction::ALL.map(SettingsRow::Bind));
+        rows.push(SettingsRow::Back);
+        rows
+    }
+
+    fn section(self) -> SettingsSection {
+        match self {
+            SettingsRow::Fullscreen
+            | SettingsRow::WindowSize
+            | SettingsRow::ScreenLayout
+            | SettingsRow::Vsync
+            | SettingsRow::FrameCap
+            | SettingsRow::CrtFilter
+            | SettingsRow::Lighting
+            | SettingsRow::TitleStatus => SettingsSection::Graphics,
+            SettingsRow::ScreenShake
+            | SettingsRow::ReduceFlashing
+            | SettingsRow::ReduceMotion
+            | SettingsRow::HighContrastBullets
+            | SettingsRow::Palette => SettingsSection::Accessibility,
+            SettingsRow::Ship | SettingsRow::Paint => SettingsSection::Ship,
+            SettingsRow::StoryDialogue | SettingsRow::Language => SettingsSection::Story,
+            SettingsRow::CustomSpawnInterval
+            | SettingsRow::CustomEnemyHealth
+            | SettingsRow::CustomBulletSpeed
+            | SettingsRow::CustomLives
+            | SettingsRow::CustomDropRate => SettingsSection::CustomDifficulty,
+            SettingsRow::Bind(_) | SettingsRow::Back => SettingsSection::Controls,
+            _ => SettingsSection::General,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SettingsRow::Difficulty => "Difficulty",
+            SettingsRow::DynamicRank => "Dynamic Rank",
+            SettingsRow::RevengeShots => "Revenge Shots",
+            SettingsRow::MirrorMode => "Mirror Mode",
+            SettingsRow::Ship => "Model",
+            SettingsRow::Paint => "Paint",
+            SettingsRow::CustomSpawnInterval => "Spawn Interval",
+            SettingsRow::CustomEnemyHealth => "Enemy Health",
+            SettingsRow::CustomBulletSpeed => "Bullet Speed",
+            SettingsRow::CustomLives => "Starting Lives",
+            SettingsRow::CustomDropRate => "Drop Rate",
+            SettingsRow::MasterVolume => "Master Volume",
+            SettingsRow::MusicVolume => "Music Volume",
+            SettingsRow::SfxVolume => "SFX Volume",
+            SettingsRow::Mute => "Mute",
+            SettingsRow::ScreenShake => "Screen Shake",
+            SettingsRow::CrtFilter => "CRT Filter",
+            SettingsRow::Lighting => "Lighting",
+            SettingsRow::TitleStatus => "Score in Title",
+            SettingsRow::Fullscreen => "Fullscreen",
+            SettingsRow::WindowSize => "Window Size",
+            SettingsRow::ScreenLayout => "Screen Layout",
+            SettingsRow::Vsync => "VSync",
+            SettingsRow::FrameCap => "Frame Limit",
+            SettingsRow::ReduceFlashing => "Reduce Flashing",
+            SettingsRow::ReduceMotion => "Reduce Motion",
+            SettingsRow::HighContrastBullets => "High-Contrast Bullets",
+            SettingsRow::Palette => "Color Palette",
+            SettingsRow::RunTimer => "Run Timer",
+            SettingsRow::ResultsExport => "Export Results",
+            SettingsRow::Tutorial => "Tutorial",
+            SettingsRow::StoryDialogue => "Dialogue",
+            SettingsRow::Language => "Language",
+            SettingsRow::Bind(action) => action.label(),
+            SettingsRow::Back => "Back",
+        }
+    }
+
+    // (min, max, step) for rows edited with a slider.
+    fn slider_range(self) -> Option<(f32, f32, f32)> {
+        match self {
+            SettingsRow::MasterVolume | SettingsRow::MusicVolume | SettingsRow::SfxVolume => {
+                Some((0.0, 1.0, 0.05))
+            }
+            SettingsRow::CustomSpawnInterval | SettingsRow::CustomBulletSpeed => {
+                Some((0.5, 2.0, 0.05))
+            }
+            SettingsRow::CustomEnemyHealth => Some((0.5, 3.0, 0.1)),
+            SettingsRow::CustomLives => Some((1.0, 9.0, 1.0)),
+            SettingsRow::CustomDropRate => Some((0.0, 3.0, 0.1)),
+            _ => None,
+        }
+    }
+
+    // Whether the row, or some of the choices it cycles through, still has to be unlocked.
+    fn has_locked_choices(self, unlocks: &Unlocks) -> bool {
+        match self {
+            SettingsRow::MirrorMode => !unlocks.has(Unlock::MirrorMode),
+            SettingsRow::Ship => ShipModel::ALL
+                .into_iter()
+                .any(|model| !unlocks.allows(Unlock::for_ship(model))),
+            SettingsRow::Paint => ShipPaint::ALL
+                .into_iter()
+                .any(|paint| !unlocks.allows(Unlock::for_paint(paint))),
+            _ => false,
+        }
+    }
+
+    fn is_slider(self) -> bool {
+        self.slider_range().is_some()
+    }
+
+    fn slider_value(self, settings: &GameSettings) -> Option<f32> {
+        let custom = &settings.custom_difficulty;
+        match self {
+            SettingsRow::MasterVolume => Some(settings.master_volume),
+            SettingsRow::MusicVolume => Some(settings.music_volume),
+            SettingsRow::SfxVolume => Some(settings.sfx_volume),
+            SettingsRow::CustomSpawnInterval => Some(custom.spawn_interval),
+            SettingsRow::CustomEnemyHealth => Some(custom.enemy_health),
+            SettingsRow::CustomBulletSpeed => Some(custom.bullet_speed),
+            SettingsRow::CustomLives => Some(custom.starting_lives as f32),
+            SettingsRow::CustomDropRate => Some(custom.drop_rate),
+            _ => None,
+        }
+    }
+
+    fn slider_fraction(self, settings: &GameSettings) -> Option<f32> {
+        let (min, max, _) = self.slider_range()?;
+        self.slider_value(settings)
+            .map(|value| (value - min) / (max - min))
+    }
+
+    // Snaps to the row's step; touching a custom knob also selects Custom difficulty.
+    fn set_slider_value(self, settings: &mut GameSettings, value: f32) {
+        let Some((min, max, step)) = self.slider_range() else {
+            return;
+        };
+        let value = (((value - min) / step).round() * step + min).clamp(min, max);
+        let custom = &mut settings.custom_difficulty;
+        match self {
+            SettingsRow::MasterVolume => settings.master_volume = value,
+            SettingsRow::MusicVolume => settings.music_volume = value,
+            SettingsRow::SfxVolume => settings.sfx_volume = value,
+            SettingsRow::CustomSpawnInterval => custom.spawn_interval = value,
+            SettingsRow::CustomEnemyHealth => custom.enemy_health = value,
+            SettingsRow::CustomBulletSpeed => custom.bullet_speed = value,
+            SettingsRow::CustomLives => custom.starting_lives = value as u8,
+            SettingsRow::CustomDropRate => custom.drop_rate = value,
+            _ => return,
+        }
+        if self.section() == SettingsSection::CustomDifficulty {
+            settings.difficulty = Difficulty::Custom;
+        }
+    }
+
+    fn slider_label(self, value: f32) -> String {
+        match self {
+            SettingsRow::MasterVolume | SettingsRow::MusicVolume | SettingsRow::SfxVolume => {
+                format!("{}%", (value * 100.0).round() as i32)
+            }
+            SettingsRow::CustomLives => format!("{}", value as u8),
+            _ => format!("x{value:.2}"),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsMenu {
+    rebinding: Option<InputAction>,
+}
+
+// Run condition for global shortcuts: while a control waits for its new key, that key
+// press belongs to the rebind.
+pub fn capturing_key(menu: Option<Res<SettingsMenu>>) -> bool {
+    menu.is_some_and(|menu| menu.rebinding.is_some())
+}
+
+#[derive(Component)]
+struct SettingsScreen;
+
+#[derive(Component)]
+struct SettingsRowButton(SettingsRow);
+
+#[derive(Component)]
+struct SettingsValueText(SettingsRow);
+
+#[derive(Component)]
+struct SliderTrack(SettingsRow);
+
+#[derive(Component)]
+struct SliderFill(SettingsRow);
+
+fn spawn_settings_menu(mut commands: Commands, unlocks: Res<Unlocks>, font: Res<UiFont>) {
+    commands.insert_resource(SettingsMenu::default());
+    commands.insert_resource(MenuFocus::new(SettingsRow::all().len()));
+
+    let title_style = font.style(TextSize::Heading, Color::WHITE);
+    let row_style = font.style(TextSize::Body, Color::srgb(0.8, 0.9, 1.0));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ..default()
+            },
+            SettingsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section("Settings", title_style).with_style(Style {
+                    margin: UiRect::bottom(Val::Px(12.0)),
+                    ..default()
+                }),
+            );
+            let heading_style = TextStyle {
+                font_size: TextSize::Caption.px(),
+                color: Color::srgb(1.0, 0.85, 0.4),
+                ..row_style.clone()
+            };
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(20.0),
+                        align_items: AlignItems::Start,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|columns| {
+                    let rows = SettingsRow::all();
+                    let column_sections = [
+                        &[SettingsSection::General, SettingsSection::CustomDifficulty][..],
+                        &[
+                            SettingsSection::Graphics,
+                            SettingsSection::Accessibility,
+                            SettingsSection::Ship,
+                        ][..],
+                        &[SettingsSection::Story, SettingsSection::Controls][..],
+                    ];
+                    for sections in column_sections {
+                        columns
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: Val::Px(4.0),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|column| {
+                                for &section in sections {
+                                    column.spawn(
+                                        TextBundle::from_section(
+                                            section.label(),
+                                            heading_style.clone(),
+                                        )
+                                        .with_style(
+                                            Style {
+                                                margin: UiRect::top(Val::Px(8.0)),
+                                                ..default()
+                                            },
+                                        ),
+                                    );
+                                    for (index, &row) in rows.iter().enumerate() {
+                                        if row.section() == section {
+                                            let locked = row.has_locked_choices(&unlocks);
+                                            spawn_settings_row(
+                                                column, index, row, locked, &row_style,
+                                            );
+                                        }
+                                    }
+                                }
+                            });
+                    }
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: TextSize::Caption.px(),
+                        ..row_style
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(12.0)),
+                    ..default()
+                }),
+                DevicePrompt::new(
+                    "Up/Down select  |  Left/Right adjust  |  Enter change  |  Esc back",
+                    "D-pad select  |  Left/Right adjust  |  A change  |  B back",
+                ),
+            ));
+        });
+}
+
+fn spawn_settings_row(
+    parent: &mut ChildBuilder,
+    index: usize,
+    row: SettingsRow,
+    locked: bool,
+    style: &TextStyle,
+) {
+    parent
+        .spawn((
+            menu_button(
+                index,
+                Style {
+                    width: Val::Px(400.0),
+                    height: Val::Px(30.0),
+                    padding: UiRect::horizontal(Val::Px(12.0)),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ),
+            SettingsRowButton(row),
+        ))
+        .with_children(|row_node| {
+            row_node
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|label| {
+                    label.spawn(TextBundle::from_section(row.label(), style.clone()));
+                    if locked {
+                        spawn_lock_icon(label);
+                    }
+                });
+            if row.is_slider() {
+                row_node
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(120.0),
+                                height: Val::Px(12.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(SLIDER_TRACK_COLOR),
+                            focus_policy: FocusPolicy::Block,
+                            ..default()
+                        },
+                        Interaction::default(),
+                        RelativeCursorPosition::default(),
+                        SliderTrack(row),
+                    ))
+                    .with_children(|track| {
+                        track.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: BackgroundColor(SLIDER_FILL_COLOR),
+                                ..default()
+                            },
+                            SliderFill(row),
+                        ));
+                    });
+            }
+            if row != SettingsRow::Back {
+                row_node.spawn((
+                    TextBundle::from_section("", style.clone()).with_style(Style {
+                        min_width: Val::Px(80.0),
+                        justify_content: JustifyContent::End,
+                        ..default()
+                    }),
+                    SettingsValueText(row),
+                ));
+            }
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn settings_keyboard_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut menu: ResMut<SettingsMenu>,
+    mut focus: ResMut<MenuFocus>,
+    mut settings: ResMut<GameSettings>,
+    mut tutorial: ResMut<Tutorial>,
+    unlocks: Res<Unlocks>,
+    profile: Res<CurrentProfile>,
+    mut bindings: ResMut<KeyBindings>,
+    origin: Res<State<SettingsOrigin>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if let Some(action) = menu.rebinding {
+        let cancel = gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East);
+        if let Some(&key) = keys.get_just_pressed().next() {
+            if key != KeyCode::Escape {
+                bindings.rebind(action, key);
+            }
+            menu.rebinding = None;
+            audio.send(AudioCue::new(Sfx::UiSelect));
+        } else if cancel {
+            menu.rebinding = None;
+        }
+        focus.locked = menu.rebinding.is_some();
+        return;
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keys.just_pressed(key) || gamepad_just_pressed(&gamepads, &buttons, button)
+    };
+
+    let row = SettingsRow::all()[focus.focused];
+    let step = if pressed(KeyCode::ArrowLeft, GamepadButtonType::DPadLeft) {
+        -1.0
+    } else if pressed(KeyCode::ArrowRight, GamepadButtonType::DPadRight) {
+        1.0
+    } else {
+        0.0
+    };
+    if step != 0.0 && adjust_row(row, step, &mut settings, &mut tutorial, &unlocks, &profile) {
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+
+    if pressed(KeyCode::Escape, GamepadButtonType::East) {
+        next_state.set(origin.return_state());
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn settings_activate(
+    mut activated: EventReader<MenuActivated>,
+    rows: Query<&SettingsRowButton>,
+    mut menu: ResMut<SettingsMenu>,
+    mut focus: ResMut<MenuFocus>,
+    mut settings: ResMut<GameSettings>,
+    mut tutorial: ResMut<Tutorial>,
+    unlocks: Res<Unlocks>,
+    profile: Res<CurrentProfile>,
+    origin: Res<State<SettingsOrigin>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for event in activated.read() {
+        let Ok(button) = rows.get(event.entity) else {
+            continue;
+        };
+        match button.0 {
+            SettingsRow::Bind(action) => menu.rebinding = Some(action),
+            SettingsRow::Back => next_state.set(origin.return_state()),
+            row if row.is_slider() => {}
+            row => {
+                adjust_row(row, 1.0, &mut settings, &mut tutorial, &unlocks, &profile);
+            }
+        }
+    }
+    focus.locked = menu.rebinding.is_some();
+}
+
+fn settings_slider_input(
+    menu: Res<SettingsMenu>,
+    mut focus: ResMut<MenuFocus>,
+    mut settings: ResMut<GameSettings>,
+    tracks: Query<(&Interaction, &RelativeCursorPosition, &SliderTrack)>,
+) {
+    if menu.rebinding.is_some() {
+        return;
+    }
+
+    for (interaction, cursor, track) in &tracks {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let Some((min, max, step)) = track.0.slider_range() else {
+            continue;
+        };
+        let value = min + position.x.clamp(0.0, 1.0) * (max - min);
+        if track
+            .0
+            .slider_value(&settings)
+            .is_some_and(|current| (current - value).abs() >= step * 0.5)
+        {
+            track.0.set_slider_value(&mut settings, value);
+        }
+        if let Some(index) = SettingsRow::all().iter().position(|&row| row == track.0) {
+            focus.focused = index;
+        }
+    }
+}
+
+fn adjust_row(
+    row: SettingsRow,
+    step: f32,
+    settings: &mut GameSettings,
+    tutorial: &mut Tutorial,
+    unlocks: &Unlocks,
+    profile: &CurrentProfile,
+) -> bool {
+    if let (Some((_, _, increment)), Some(value)) = (row.slider_range(), row.slider_value(settings))
+    {
+        row.set_slider_value(settings, value + step * increment);
+        return true;
+    }
+    match row {
+        SettingsRow::Difficulty => settings.difficulty = settings.difficulty.next(),
+        SettingsRow::DynamicRank => settings.dynamic_rank = !settings.dynamic_rank,
+        SettingsRow::RevengeShots => settings.revenge_shots = !settings.revenge_shots,
+        SettingsRow::MirrorMode if unlocks.has(Unlock::MirrorMode) => {
+            settings.mirror_mode = !settings.mirror_mode;
+        }
+        // Locked choices are skipped; Standard is always there to land on.
+        SettingsRow::Ship => {
+            let mut ship = settings.ship.next();
+            while !unlocks.allows(Unlock::for_ship(ship)) {
+                ship = ship.next();
+            }
+            settings.ship = ship;
+        }
+        SettingsRow::Paint => {
+            let mut paint = settings.paint.next();
+            while !unlocks.allows(Unlock::for_paint(paint)) {
+                paint = paint.next();
+            }
+            settings.paint = paint;
+        }
+        SettingsRow::Mute => settings.muted = !settings.muted,
+        SettingsRow::ScreenShake => settings.screen_shake = settings.screen_shake.next(),
+        SettingsRow::CrtFilter => settings.crt_filter = !settings.crt_filter,
+        SettingsRow::Lighting => settings.light_quality = settings.light_quality.next(),
+        SettingsRow::TitleStatus => settings.title_status = !settings.title_status,
+        SettingsRow::RunTimer => settings.run_timer = !settings.run_timer,
+        SettingsRow::ResultsExport => settings.results_export = settings.results_export.next(),
+        SettingsRow::Tutorial => {
+            tutorial.seen = !tutorial.seen;
+            tutorial.save(profile);
+        }
+        SettingsRow::StoryDialogue => settings.story_dialogue = !settings.story_dialogue,
+        SettingsRow::Language => settings.language = settings.language.next(),
+        SettingsRow::Fullscreen => settings.fullscreen = !settings.fullscreen,
+        SettingsRow::WindowSize => settings.window_size = settings.window_size.next(),
+        SettingsRow::ScreenLayout => settings.screen_layout = settings.screen_layout.next(),
+        SettingsRow::Vsync => settings.vsync = !settings.vsync,
+        SettingsRow::FrameCap => settings.frame_cap = settings.frame_cap.next(),
+        SettingsRow::ReduceFlashing => settings.reduce_flashing = !settings.reduce_flashing,
+        SettingsRow::ReduceMotion => settings.reduce_motion = !settings.reduce_motion,
+        SettingsRow::Palette => settings.palette = settings.palette.next(),
+        SettingsRow::HighContrastBullets => {
+            settings.high_contrast_bullets = !settings.high_contrast_bullets;
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn settings_menu_display(
+    menu: Res<SettingsMenu>,
+    settings: Res<GameSettings>,
+    bindings: Res<KeyBindings>,
+    tutorial: Res<Tutorial>,
+    unlocks: Res<Unlocks>,
+    mut values: Query<(&SettingsValueText, &mut Text)>,
+    mut fills: Query<(&SliderFill, &mut Style)>,
+) {
+    if !menu.is_changed()
+        && !settings.is_changed()
+        && !bindings.is_changed()
+        && !tutorial.is_changed()
+    {
+        return;
+    }
+
+    for (value, mut text) in &mut values {
+        let row = value.0;
+        text.sections[0].value = match row {
+            SettingsRow::Difficulty => settings.difficulty.label().to_string(),
+            SettingsRow::DynamicRank => on_off(settings.dynamic_rank).to_string(),
+            SettingsRow::RevengeShots => on_off(settings.revenge_shots).to_string(),
+            SettingsRow::MirrorMode if !unlocks.has(Unlock::MirrorMode) => "Locked".into(),
+            SettingsRow::MirrorMode => on_off(settings.mirror_mode).to_string(),
+            SettingsRow::Ship => settings.ship.label().to_string(),
+            SettingsRow::Paint => settings.paint.label().to_string(),
+            SettingsRow::Mute => on_off(settings.muted).to_string(),
+            SettingsRow::ScreenShake => settings.screen_shake.label().to_string(),
+            SettingsRow::CrtFilter => on_off(settings.crt_filter).to_string(),
+            SettingsRow::Lighting => settings.light_quality.label().to_string(),
+            SettingsRow::TitleStatus => on_off(settings.title_status).to_string(),
+            SettingsRow::Fullscreen => on_off(settings.fullscreen).to_string(),
+            SettingsRow::WindowSize => settings.window_size.label().to_string(),
+            SettingsRow::ScreenLayout => settings.screen_layout.label().to_string(),
+            SettingsRow::Vsync => on_off(settings.vsync).to_string(),
+            SettingsRow::FrameCap => settings.frame_cap.label().to_string(),
+            SettingsRow::ReduceFlashing => on_off(settings.reduce_flashing).to_string(),
+            SettingsRow::ReduceMotion => on_off(settings.reduce_motion).to_string(),
+            SettingsRow::Palette => settings.palette.label().to_string(),
+            SettingsRow::HighContrastBullets => on_off(settings.high_contrast_bullets).to_string(),
+            SettingsRow::RunTimer => on_off(settings.run_timer).to_string(),
+            SettingsRow::ResultsExport => settings.results_export.label().to_string(),
+            SettingsRow::Tutorial => on_off(!tutorial.seen).to_string(),
+            SettingsRow::StoryDialogue => on_off(settings.story_dialogue).to_string(),
+            SettingsRow::Language => settings.language.label().to_string(),
+            SettingsRow::Bind(action) if menu.rebinding == Some(action) => "Press a key".into(),
+            SettingsRow::Bind(action) => key_label(bindings.key(action)),
+            _ => row
+                .slider_value(&settings)
+                .map(|value| row.slider_label(value))
+                .unwrap_or_default(),
+        };
+    }
+
+    for (fill, mut style) in &mut fills {
+        if let Some(fraction) = fill.0.slider_fraction(&settings) {
+            style.width = Val::Percent(fraction * 100.0);
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "On" } else { "Off" }
+}
+
+fn cleanup_settings_menu(mut commands: Commands, query: Query<Entity, With<SettingsScreen>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<SettingsMenu>();
+}
@@ -1,17 +1,31 @@
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, fmt, fs, path::Path};
 
-use bevy::{
-    math::{URect, UVec2, Vec2},
-    prelude::*,
-    sprite::TextureAtlasLayout,
-};
+use bevy::{math::Vec2, prelude::*, sprite::TextureAtlasLayout};
 use image::RgbaImage;
+use serde::Deserialize;
+
+#[cfg(feature = "hot-reload")]
+use super::sprite_atlas::reload_sprite_atlas;
+use super::{
+    config::ShipModel,
+    sprite_atlas::{SpriteAtlas, build_sprite_atlas},
+    states::AppState,
+};
+
+const SHIP_MANIFEST_PATH: &str = "assets/ships.ron";
+const BUILT_IN_MANIFEST: &str = include_str!("../../assets/ships.ron");
+// Sideways speed, in pixels a second, that tips a ship into a bank, and the lower speed
+// it has to drop under to level out, so a ship on the edge doesn't flicker between rows.
+const BANK_SPEED: f32 = 60.0;
+const LEVEL_SPEED: f32 = 30.0;
 
 pub struct ShipSpritePlugin;
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Deserialize)]
 pub enum ShipSpriteId {
     Player,
+    Interceptor,
+    Bulwark,
     Grunt,
     Sine,
     ZigZag,
@@ -20,6 +34,28 @@ pub enum ShipSpriteId {
     Boss,
 }
 
+impl ShipSpriteId {
+    pub const ALL: [ShipSpriteId; 9] = [
+        ShipSpriteId::Player,
+        ShipSpriteId::Interceptor,
+        ShipSpriteId::Bulwark,
+        ShipSpriteId::Grunt,
+        ShipSpriteId::Sine,
+        ShipSpriteId::ZigZag,
+        ShipSpriteId::Tank,
+        ShipSpriteId::Chaser,
+        ShipSpriteId::Boss,
+    ];
+
+    pub fn player(model: ShipModel) -> Self {
+        match model {
+            ShipModel::Standard => ShipSpriteId::Player,
+            ShipModel::Interceptor => ShipSpriteId::Interceptor,
+            ShipModel::Bulwark => ShipSpriteId::Bulwark,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct ShipSpriteAssets {
     map: HashMap<ShipSpriteId, ShipSpriteData>,
@@ -30,12 +66,53 @@ impl ShipSpriteAssets {
         self.map.get(&id).expect("missing ship sprite data")
     }
 
-    pub fn sequence(&self, id: ShipSpriteId, row: usize) -> &[usize] {
+    // The frames of the sheet row the manifest gives for `row`, or of the idle row for
+    // ships whose art has nothing for it.
+    pub fn sequence(&self, id: ShipSpriteId, row: ShipRow) -> &[usize] {
+        self.variant_sequence(id, None, row)
+    }
+
+    // As `sequence`, from a variant's recoloured copy of the sheet when one is named.
+    pub fn variant_sequence(
+        &self,
+        id: ShipSpriteId,
+        variant: Option<&str>,
+        row: ShipRow,
+    ) -> &[usize] {
         let data = self.data(id);
-        data.sequences
-            .get(row)
+        let sequences = variant
+            .and_then(|name| data.variants.get(name))
+            .map_or(&data.sequences, |variant| &variant.sequences);
+        data.rows
+            .get(&row)
+            .or_else(|| data.rows.get(&ShipRow::Idle))
+            .and_then(|&index| sequences.get(index))
             .expect("invalid row for ship sprite")
     }
+
+    // The variant a ship spawned on `level` (counting from 1) wears: the one asked for if
+    // the ship has it, otherwise the one with the latest `from_level` the level has
+    // reached, if any.
+    pub fn variant_for(
+        &self,
+        id: ShipSpriteId,
+        requested: Option<&str>,
+        level: u32,
+    ) -> Option<String> {
+        let data = self.data(id);
+        if let Some(name) = requested {
+            if data.variants.contains_key(name) {
+                return Some(name.to_string());
+            }
+            warn_once!("{:?} has no variant named '{}'", id, name);
+        }
+        data.variants
+            .iter()
+            .filter_map(|(name, variant)| Some((variant.from_level?, name)))
+            .filter(|&(from_level, _)| from_level <= level)
+            .max()
+            .map(|(_, name)| name.clone())
+    }
 }
 
 #[derive(Clone)]
@@ -45,164 +122,392 @@ pub struct ShipSpriteData {
     pub sequences: Vec<Vec<usize>>,
     pub frame_size: Vec2,
     pub scale: f32,
+    pub frame_rate: f32,
+    rows: HashMap<ShipRow, usize>,
+    variants: HashMap<String, VariantFrames>,
+    cues: HashMap<AnimationCue, Vec<usize>>,
 }
 
-#[derive(Component)]
-pub struct ShipAnimation {
-    pub ship: ShipSpriteId,
-    pub row: usize,
-    pub frame: usize,
-    pub timer: Timer,
+impl ShipSpriteData {
+    pub fn has_cue(&self, cue: AnimationCue) -> bool {
+        self.cues.contains_key(&cue)
+    }
 }
 
-impl ShipAnimation {
-    pub fn new(ship: ShipSpriteId, row: usize, rate: f32) -> Self {
-        Self {
-            ship,
-            row,
-            frame: 0,
-            timer: Timer::from_seconds(rate, TimerMode::Repeating),
+#[derive(Clone)]
+struct VariantFrames {
+    sequences: Vec<Vec<usize>>,
+    from_level: Option<u32>,
+}
+
+// What a row of a ship's sheet is for; the manifest maps each to a row index. Only
+// `Idle` is required.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Deserialize)]
+pub enum ShipRow {
+    Idle,
+    BankLeft,
+    BankRight,
+    Damaged,
+}
+
+// Moments in an animation that effects are timed to, so a flash or a puff of exhaust
+// lands on the frame drawn for it rather than on a timer of its own.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Deserialize)]
+pub enum AnimationCue {
+    MuzzleFlash,
+    ThrusterPulse,
+}
+
+// Sent as an animation reaches a frame with a cue on it: ships per their manifest
+// entry, and shots whose animation was given one.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AnimationCueEvent {
+    pub entity: Entity,
+    pub cue: AnimationCue,
+    pub position: Vec2,
+}
+
+// One ship's entry in `assets/ships.ron`. Frames are found by trimming the sheet; see
+// `sprite_atlas`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShipSpec {
+    pub sheet: String,
+    pub scale: f32,
+    // Seconds per animation frame.
+    pub frame_rate: f32,
+    pub rows: HashMap<ShipRow, usize>,
+    #[serde(default)]
+    pub variants: HashMap<String, ShipVariant>,
+    // Frames, counting from 0 within whichever row is playing, that send each cue.
+    #[serde(default)]
+    pub cues: HashMap<AnimationCue, Vec<usize>>,
+}
+
+// A recolouring of a ship's sheet for elite and later-level versions of the same ship.
+// It's baked into the sprite atlas as a second copy of the sheet, so recoloured ships
+// still batch with everything else.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ShipVariant {
+    // Degrees round the colour wheel.
+    #[serde(default)]
+    pub hue: f32,
+    #[serde(default = "unscaled")]
+    pub saturation: f32,
+    #[serde(default = "unscaled")]
+    pub lightness: f32,
+    // Ships spawned from this level on (counting from 1) wear the variant unless the
+    // storyboard names another.
+    #[serde(default)]
+    pub from_level: Option<u32>,
+}
+
+fn unscaled() -> f32 {
+    1.0
+}
+
+impl ShipVariant {
+    pub fn recolor(&self, pixels: &mut RgbaImage) {
+        for pixel in pixels.pixels_mut() {
+            let [r, g, b, a] = pixel.0;
+            if a == 0 {
+                continue;
+            }
+            let mut color = Hsla::from(Srgba::rgba_u8(r, g, b, a));
+            color.hue = (color.hue + self.hue).rem_euclid(360.0);
+            color.saturation = (color.saturation * self.saturation).clamp(0.0, 1.0);
+            color.lightness = (color.lightness * self.lightness).clamp(0.0, 1.0);
+            pixel.0 = Srgba::from(color).to_u8_array();
         }
     }
 }
 
-impl Plugin for ShipSpritePlugin {
-    fn build(&self, app: &mut App) {
-        app.init_resource::<ShipSpriteAssets>()
-            .add_systems(Startup, load_ship_sprites)
-            .add_systems(Update, animate_ship_sprites);
+// Every ship's sprite sheet, keyed by ship, so art can be swapped without a rebuild.
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct ShipManifest(HashMap<ShipSpriteId, ShipSpec>);
+
+impl ShipManifest {
+    pub fn from_file(path: &Path) -> Result<Self, ShipManifestError> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_ron(&contents)
+    }
+
+    pub fn from_ron(contents: &str) -> Result<Self, ShipManifestError> {
+        let manifest: ShipManifest = ron::from_str(contents)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<(), ShipManifestError> {
+        for id in ShipSpriteId::ALL {
+            let spec = self
+                .0
+                .get(&id)
+                .ok_or_else(|| ShipManifestError::Invalid(format!("{id:?} has no entry")))?;
+            let problem = if !spec.sheet.ends_with(".png") {
+                Some("sheet is not a .png")
+            } else if !(spec.scale.is_finite() && spec.scale > 0.0) {
+                Some("scale must be above zero")
+            } else if !(spec.frame_rate.is_finite() && spec.frame_rate > 0.0) {
+                Some("frame_rate must be above zero")
+            } else if !spec.rows.contains_key(&ShipRow::Idle) {
+                Some("rows has no Idle row")
+            } else if spec.variants.values().any(|variant| {
+                !(variant.hue.is_finite()
+                    && variant.saturation.is_finite()
+                    && variant.saturation >= 0.0
+                    && variant.lightness.is_finite()
+                    && variant.lightness >= 0.0)
+            }) {
+                Some("a variant's hue, saturation, or lightness is out of range")
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                return Err(ShipManifestError::Invalid(format!("{id:?}: {problem}")));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn spec(&self, id: ShipSpriteId) -> &ShipSpec {
+        self.0.get(&id).expect("ship manifest was validated")
+    }
+
+    pub fn sheets(&self) -> impl Iterator<Item = &str> {
+        ShipSpriteId::ALL
+            .into_iter()
+            .map(|id| self.spec(id).sheet.as_str())
+    }
+
+    // Puts back the built-in entry for a ship whose sheet couldn't be loaded.
+    pub fn restore_built_in(&mut self, id: ShipSpriteId) {
+        let built_in = ShipManifest::default().spec(id).clone();
+        self.0.insert(id, built_in);
+    }
+
+    // Every variant as (atlas key, source sheet, recolouring).
+    pub fn variants(&self) -> impl Iterator<Item = (String, &str, &ShipVariant)> {
+        ShipSpriteId::ALL.into_iter().flat_map(move |id| {
+            let spec = self.spec(id);
+            spec.variants.iter().map(move |(name, variant)| {
+                (variant_sheet(spec, id, name), spec.sheet.as_str(), variant)
+            })
+        })
     }
 }
 
-const SHIP_SPECS: &[(ShipSpriteId, &str, f32)] = &[
-    (ShipSpriteId::Player, "images/tinyShip3.png", 3.2),
-    (ShipSpriteId::Grunt, "images/tinyShip1.png", 3.0),
-    (ShipSpriteId::Sine, "images/tinyShip5.png", 3.0),
-    (ShipSpriteId::ZigZag, "images/tinyShip7.png", 2.8),
-    (ShipSpriteId::Tank, "images/tinyShip13.png", 3.8),
-    (ShipSpriteId::Chaser, "images/tinyShip10.png", 3.2),
-    (ShipSpriteId::Boss, "images/tinyShip20.png", 5.5),
-];
+// Where a variant's copy of the sheet sits in the atlas; ships sharing a sheet can
+// recolour it differently under the same name.
+fn variant_sheet(spec: &ShipSpec, id: ShipSpriteId, name: &str) -> String {
+    format!("{}#{:?}.{}", spec.sheet, id, name)
+}
 
-fn load_ship_sprites(
-    mut commands: Commands,
-    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
-    asset_server: Res<AssetServer>,
-) {
-    let mut assets = ShipSpriteAssets::default();
-    for (id, path, scale) in SHIP_SPECS.iter() {
-        let (layout_handle, sequences, frame_size) =
-            build_layout(Path::new("assets").join(path), &mut layouts);
-        let texture = asset_server.load(*path);
-        assets.map.insert(
-            *id,
-            ShipSpriteData {
-                texture,
-                layout: layout_handle,
-                sequences,
-                frame_size,
-                scale: *scale,
-            },
-        );
+// The art the game ships with, for when `ships.ron` is missing or broken: the manifest
+// as it was at build time.
+impl Default for ShipManifest {
+    fn default() -> Self {
+        Self::from_ron(BUILT_IN_MANIFEST).expect("the built-in ship manifest is valid")
     }
-    commands.insert_resource(assets);
 }
 
-fn build_layout(
-    path: impl AsRef<Path>,
-    layouts: &mut Assets<TextureAtlasLayout>,
-) -> (Handle<TextureAtlasLayout>, Vec<Vec<usize>>, Vec2) {
-    let img = image::open(path)
-        .expect("failed to load ship sprite")
-        .to_rgba8();
-    let (width, height) = img.dimensions();
-    let row_ranges = extract_row_ranges(&img);
-
-    let mut layout = TextureAtlasLayout::new_empty(UVec2::new(width, height));
-    let mut sequences = Vec::new();
-    let mut frame_width = width as f32;
-    let mut frame_height = height as f32;
-
-    for row in &row_ranges {
-        let col_ranges = extract_col_ranges(&img, *row);
-        if col_ranges.is_empty() {
-            continue;
-        }
-        if sequences.is_empty() {
-            frame_height = (row.1 - row.0) as f32;
-        }
-        let mut seq = Vec::new();
-        for col in &col_ranges {
-            let rect = URect::new(col.0, row.0, col.1, row.1);
-            let index = layout.add_texture(rect);
-            seq.push(index);
+#[derive(Debug)]
+pub enum ShipManifestError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl fmt::Display for ShipManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShipManifestError::Io(err) => write!(f, "I/O error: {}", err),
+            ShipManifestError::Parse(err) => write!(f, "parse error: {}", err),
+            ShipManifestError::Invalid(problem) => write!(f, "invalid entry: {}", problem),
         }
-        frame_width = (col_ranges[0].1 - col_ranges[0].0) as f32;
-        sequences.push(seq);
     }
+}
+
+impl std::error::Error for ShipManifestError {}
 
-    let handle = layouts.add(layout);
-    (handle, sequences, Vec2::new(frame_width, frame_height))
+impl From<std::io::Error> for ShipManifestError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
 }
 
-fn extract_row_ranges(img: &RgbaImage) -> Vec<(u32, u32)> {
-    extract_ranges_generic(img.height(), |y| row_has_alpha(img, y))
+impl From<ron::error::SpannedError> for ShipManifestError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Parse(value)
+    }
 }
 
-fn extract_col_ranges(img: &RgbaImage, row: (u32, u32)) -> Vec<(u32, u32)> {
-    extract_ranges_generic(img.width(), |x| column_band_has_alpha(img, x, row))
+#[derive(Component)]
+pub struct ShipAnimation {
+    pub ship: ShipSpriteId,
+    pub row: ShipRow,
+    // A recolouring from the ship's manifest entry, if it wears one.
+    pub variant: Option<String>,
+    pub frame: usize,
+    pub timer: Timer,
+    // The row the atlas index was last taken from.
+    shown: ShipRow,
 }
 
-fn extract_ranges_generic(len: u32, mut has_alpha: impl FnMut(u32) -> bool) -> Vec<(u32, u32)> {
-    let mut ranges = Vec::new();
-    let mut start = None;
-    for idx in 0..len {
-        if has_alpha(idx) {
-            if start.is_none() {
-                start = Some(idx);
-            }
-        } else if let Some(s) = start.take() {
-            if idx - s > 2 {
-                ranges.push((s, idx));
-            }
+impl ShipAnimation {
+    pub fn new(assets: &ShipSpriteAssets, ship: ShipSpriteId, row: ShipRow) -> Self {
+        Self {
+            ship,
+            row,
+            variant: None,
+            frame: 0,
+            shown: row,
+            timer: Timer::from_seconds(assets.data(ship).frame_rate, TimerMode::Repeating),
         }
     }
-    if let Some(s) = start {
-        if len - s > 2 {
-            ranges.push((s, len));
+
+    pub fn with_variant(mut self, variant: Option<String>) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    // Switches rows without restarting the cycle: the sprite moves to the same point in
+    // the new row straight away, so engine flicker carries on across the change.
+    pub fn set_row(&mut self, row: ShipRow) {
+        self.row = row;
+    }
+
+    // Banks into the row for a sideways `heading` in pixels a second, or levels out. A
+    // damaged ship stays on its damaged row.
+    pub fn bank(&mut self, heading: f32) {
+        if self.row == ShipRow::Damaged {
+            return;
         }
+        let threshold = if self.row == ShipRow::Idle {
+            BANK_SPEED
+        } else {
+            LEVEL_SPEED
+        };
+        let row = if heading <= -threshold {
+            ShipRow::BankLeft
+        } else if heading >= threshold {
+            ShipRow::BankRight
+        } else {
+            ShipRow::Idle
+        };
+        self.set_row(row);
     }
-    ranges
 }
 
-fn row_has_alpha(img: &RgbaImage, y: u32) -> bool {
-    for x in 0..img.width() {
-        if img.get_pixel(x, y)[3] > 5 {
-            return true;
-        }
+impl Plugin for ShipSpritePlugin {
+    fn build(&self, app: &mut App) {
+        let path = Path::new(SHIP_MANIFEST_PATH);
+        let manifest = ShipManifest::from_file(path).unwrap_or_else(|err| {
+            warn!(
+                "Failed to load ship manifest from {}: {}. Using built-in defaults.",
+                path.display(),
+                err
+            );
+            ShipManifest::default()
+        });
+        app.insert_resource(manifest)
+            .init_resource::<ShipSpriteAssets>()
+            .add_event::<AnimationCueEvent>()
+            .add_systems(
+                OnExit(AppState::Loading),
+                load_ship_sprites.after(build_sprite_atlas),
+            )
+            .add_systems(Update, animate_ship_sprites);
+        #[cfg(feature = "hot-reload")]
+        app.add_systems(
+            Update,
+            load_ship_sprites
+                .after(reload_sprite_atlas)
+                .run_if(resource_exists_and_changed::<SpriteAtlas>),
+        );
     }
-    false
 }
 
-fn column_band_has_alpha(img: &RgbaImage, x: u32, row: (u32, u32)) -> bool {
-    for y in row.0..row.1 {
-        if img.get_pixel(x, y)[3] > 5 {
-            return true;
-        }
+// A row the sheet turns out not to have falls back to the first, so a mistyped index
+// shows the wrong frames rather than stopping the game.
+fn load_ship_sprites(mut commands: Commands, atlas: Res<SpriteAtlas>, manifest: Res<ShipManifest>) {
+    let mut assets = ShipSpriteAssets::default();
+    for id in ShipSpriteId::ALL {
+        let spec = manifest.spec(id);
+        let sheet = atlas.sheet(&spec.sheet);
+        let rows = spec
+            .rows
+            .iter()
+            .map(|(&row, &index)| {
+                if index >= sheet.rows.len() {
+                    warn!(
+                        "{} has no row {} for {:?} {:?}; using row 0",
+                        spec.sheet, index, id, row
+                    );
+                    return (row, 0);
+                }
+                (row, index)
+            })
+            .collect();
+        let variants = spec
+            .variants
+            .iter()
+            .map(|(name, variant)| {
+                let frames = VariantFrames {
+                    sequences: atlas.sheet(&variant_sheet(spec, id, name)).rows.clone(),
+                    from_level: variant.from_level,
+                };
+                (name.clone(), frames)
+            })
+            .collect();
+        assets.map.insert(
+            id,
+            ShipSpriteData {
+                texture: atlas.texture.clone(),
+                layout: atlas.layout.clone(),
+                sequences: sheet.rows.clone(),
+                frame_size: sheet.frame_size,
+                scale: spec.scale,
+                frame_rate: spec.frame_rate,
+                rows,
+                variants,
+                cues: spec.cues.clone(),
+            },
+        );
     }
-    false
+    commands.insert_resource(assets);
 }
 
 fn animate_ship_sprites(
     time: Res<Time>,
     assets: Res<ShipSpriteAssets>,
-    mut query: Query<(&mut ShipAnimation, &mut TextureAtlas)>,
+    mut query: Query<(
+        Entity,
+        &mut ShipAnimation,
+        &mut TextureAtlas,
+        &GlobalTransform,
+    )>,
+    mut cues: EventWriter<AnimationCueEvent>,
 ) {
-    for (mut anim, mut atlas) in &mut query {
-        if anim.timer.tick(time.delta()).just_finished() {
-            let frames = assets.sequence(anim.ship, anim.row);
-            anim.frame = (anim.frame + 1) % frames.len();
+    for (entity, mut anim, mut atlas, transform) in &mut query {
+        let advance = anim.timer.tick(time.delta()).just_finished();
+        if advance || anim.shown != anim.row {
+            let frames = assets.variant_sequence(anim.ship, anim.variant.as_deref(), anim.row);
+            anim.frame = (anim.frame + usize::from(advance)) % frames.len();
+            anim.shown = anim.row;
             atlas.index = frames[anim.frame];
         }
+        if !advance {
+            continue;
+        }
+        for (&cue, frames) in &assets.data(anim.ship).cues {
+            if frames.contains(&anim.frame) {
+                cues.send(AnimationCueEvent {
+                    entity,
+                    cue,
+                    position: transform.translation().truncate(),
+                });
+            }
+        }
     }
 }
@@ -1,19 +1,26 @@
-use std::{fmt, fs, time::Duration};
+use std::{collections::BTreeMap, fmt, fs, path::Path, time::Duration};
 
 use bevy::{log::warn, prelude::*, time::Fixed};
-use serde::Deserialize;
 use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize};
 
 use super::{
-    config::GameSettings,
+    cheats::Cheats,
+    config::{GameConfig, GameSettings, RunDifficulty},
+    dialogue::DialogueLine,
     enemies::{EnemyKind, MovementPattern, SpawnEnemyEvent},
     powerups::PowerUpKind,
-    states::AppState,
+    rank::Rank,
+    states::{AppState, InRun},
 };
 
 const BASE_INTERVAL: f32 = 3.6;
-const TOP_Y: f32 = 420.0;
-const STORYBOARD_PATH: &str = "assets/storyboard.json";
+const WAVE_DIFFICULTY_STEP: f32 = 0.05;
+// New Game+ modifiers, stacking with each loop past the first.
+const LOOP_FIRE_RATE_STEP: f32 = 0.35;
+const LOOP_DROP_RATE_FACTOR: f32 = 0.7;
+const MAX_REVENGE_BULLETS: u32 = 5;
+pub const TOP_Y: f32 = 420.0;
 const CORE_LANES: [f32; 3] = [-360.0, 0.0, 360.0];
 const CHASER_LANES: [f32; 3] = [-180.0, 0.0, 180.0];
 
@@ -21,19 +28,86 @@ pub struct SpawnPlugin;
 
 impl Plugin for SpawnPlugin {
     fn build(&self, app: &mut App) {
-        let storyboard = Storyboard::from_file(STORYBOARD_PATH).unwrap_or_else(|err| {
+        let path = app.world().resource::<GameConfig>().storyboard_path.clone();
+        let storyboard = Storyboard::from_file(&path).unwrap_or_else(|err| {
             warn!(
                 "Failed to load storyboard from {}: {}. Using built-in defaults.",
-                STORYBOARD_PATH, err
+                path.display(),
+                err
             );
             Storyboard::default()
         });
 
         app.insert_resource(storyboard)
             .insert_resource(WaveDirector::default())
-            .add_systems(OnEnter(AppState::Playing), reset_waves)
-            .add_systems(OnExit(AppState::Playing), clear_waves)
+            .add_event::<WaveStartedEvent>()
+            .add_event::<BonusStageEvent>()
+            .add_systems(OnEnter(InRun), reset_waves)
+            .add_systems(OnExit(InRun), clear_waves)
             .add_systems(FixedUpdate, drive_waves.run_if(in_state(AppState::Playing)));
+        #[cfg(feature = "hot-reload")]
+        app.add_systems(
+            Update,
+            reload_storyboard.run_if(not(in_state(AppState::Editor))),
+        );
+    }
+}
+
+// How often dev builds check the storyboard file for changes.
+#[cfg(feature = "hot-reload")]
+const STORYBOARD_POLL_SECONDS: f32 = 0.5;
+
+#[cfg(feature = "hot-reload")]
+struct StoryboardWatch {
+    timer: Timer,
+    modified: Option<std::time::SystemTime>,
+}
+
+#[cfg(feature = "hot-reload")]
+impl Default for StoryboardWatch {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(STORYBOARD_POLL_SECONDS, TimerMode::Repeating),
+            modified: None,
+        }
+    }
+}
+
+// Dev builds swap in each saved version of the storyboard, keeping the one in use if the
+// new one fails to load. Waves already on screen play out and edits show from the next
+// one. The editor is left alone so a reload can't wipe its unsaved changes.
+#[cfg(feature = "hot-reload")]
+fn reload_storyboard(
+    mut watch: Local<StoryboardWatch>,
+    time: Res<Time<Real>>,
+    config: Res<GameConfig>,
+    mut storyboard: ResMut<Storyboard>,
+) {
+    if !watch.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let path = &config.storyboard_path;
+    let Ok(modified) = fs::metadata(path).and_then(|metadata| metadata.modified()) else {
+        return;
+    };
+    // The first check only notes the time the file was loaded at.
+    if watch
+        .modified
+        .replace(modified)
+        .is_none_or(|previous| previous == modified)
+    {
+        return;
+    }
+    match Storyboard::from_file(path) {
+        Ok(reloaded) => {
+            *storyboard = reloaded;
+            info!("Reloaded storyboard from {}", path.display());
+        }
+        Err(err) => warn!(
+            "Failed to reload storyboard from {}: {}. Keeping the current one.",
+            path.display(),
+            err
+        ),
     }
 }
 
@@ -45,6 +119,24 @@ pub struct WaveDirector {
     pub boss_active: bool,
     pub level_index: usize,
     pub pending_level: Option<usize>,
+    // How many times the storyboard has been cleared this run; 0 on the first pass.
+    pub loop_index: u32,
+    pub bonus: BonusProgress,
+}
+
+// Where the current level's bonus stage stands. A level plays its bonus stage at most
+// once per visit; waves looping while the boss is awaited don't bring it back.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BonusProgress {
+    #[default]
+    Hidden,
+    // The secret is found; the stage takes the place of the next wave.
+    Found,
+    // `next_wave` indexes the bonus stage's own waves.
+    Playing {
+        next_wave: u32,
+    },
+    Done,
 }
 
 impl Default for WaveDirector {
@@ -56,37 +148,137 @@ impl Default for WaveDirector {
             boss_active: false,
             level_index: 0,
             pending_level: None,
+            loop_index: 0,
+            bonus: BonusProgress::Hidden,
         }
     }
 }
 
-#[derive(Resource)]
+impl WaveDirector {
+    // Multiplier on how often enemies fire.
+    pub fn loop_fire_rate(&self) -> f32 {
+        1.0 + LOOP_FIRE_RATE_STEP * self.loop_index as f32
+    }
+
+    // Shots an enemy fires back at the ship as it dies.
+    pub fn loop_revenge_bullets(&self) -> u32 {
+        self.loop_index.min(MAX_REVENGE_BULLETS)
+    }
+
+    // Multiplier on power-up drop chances.
+    pub fn loop_drop_rate(&self) -> f32 {
+        LOOP_DROP_RATE_FACTOR.powi(self.loop_index as i32)
+    }
+
+    // "LOOP 2 - " once the run is past its first pass, for prefixing level labels.
+    pub fn loop_label(&self) -> String {
+        if self.loop_index == 0 {
+            String::new()
+        } else {
+            format!("LOOP {} - ", self.loop_index + 1)
+        }
+    }
+
+    // Enemies hold their fire and all carry power-ups while this is true.
+    pub fn in_bonus_stage(&self) -> bool {
+        matches!(self.bonus, BonusProgress::Playing { .. })
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WaveStartedEvent {
+    pub level: usize,
+    pub wave: u32,
+    pub wave_count: u32,
+}
+
+#[derive(Event, Debug, Clone)]
+pub enum BonusStageEvent {
+    SecretFound,
+    Entered { name: String },
+}
+
+#[derive(Resource, Clone)]
 pub struct Storyboard {
     levels: Vec<Level>,
+    // Story scenes by name, played by event waves and boss arrivals.
+    dialogue: BTreeMap<String, Vec<DialogueLine>>,
 }
 
 impl Storyboard {
-    fn from_file(path: &str) -> Result<Self, StoryboardLoadError> {
+    fn from_file(path: &Path) -> Result<Self, StoryboardLoadError> {
         let contents = fs::read_to_string(path)?;
-        let parsed: StoryboardFile = serde_json::from_str(&contents)?;
+        Self::from_json(&contents)
+    }
+
+    fn from_json(contents: &str) -> Result<Self, StoryboardLoadError> {
+        let parsed: StoryboardFile = serde_json::from_str(contents)?;
         Ok(Self {
             levels: parsed.levels.into_iter().map(Level::from).collect(),
+            dialogue: parsed.dialogue,
         })
     }
 
-    fn level(&self, index: usize) -> Option<&Level> {
+    // Writes the storyboard in the same format it is loaded from, reading the output
+    // back first so a file that would not load is never written.
+    pub fn export(&self, path: &Path) -> Result<(), StoryboardLoadError> {
+        let file = StoryboardFile {
+            levels: self
+                .levels
+                .iter()
+                .map(|level| LevelFile {
+                    name: level.name.clone(),
+                    waves: level.waves.clone(),
+                    bonus_stage: level.bonus_stage.clone(),
+                    boss_dialogue: level.boss_dialogue.clone(),
+                    background: level.background.clone(),
+                })
+                .collect(),
+            dialogue: self.dialogue.clone(),
+        };
+        let contents = serde_json::to_string_pretty(&file)?;
+        Self::from_json(&contents)?;
+        fs::write(path, contents + "\n")?;
+        Ok(())
+    }
+
+    pub fn level(&self, index: usize) -> Option<&Level> {
         self.levels.get(index)
     }
 
+    pub fn level_mut(&mut self, index: usize) -> Option<&mut Level> {
+        self.levels.get_mut(index)
+    }
+
+    pub fn dialogue(&self, name: &str) -> Option<&[DialogueLine]> {
+        self.dialogue.get(name).map(Vec::as_slice)
+    }
+
     fn first_delay(&self, index: usize) -> Option<f32> {
         self.level(index)
             .and_then(|level| level.waves.first())
             .map(|wave| wave.delay_seconds)
     }
 
-    fn level_count(&self) -> usize {
+    // Every background image the storyboard's levels use, for loading up front.
+    pub fn background_images(&self) -> impl Iterator<Item = &str> {
+        self.levels
+            .iter()
+            .filter_map(|level| level.background.as_ref())
+            .map(|background| background.image.as_str())
+    }
+
+    pub fn level_count(&self) -> usize {
         self.levels.len()
     }
+
+    pub fn level_name(&self, index: usize) -> Option<&str> {
+        self.level(index).map(|level| level.name.as_str())
+    }
+
+    pub fn wave_count(&self, index: usize) -> usize {
+        self.level(index).map_or(0, |level| level.waves.len())
+    }
 }
 
 impl Default for Storyboard {
@@ -144,6 +336,7 @@ impl Default for Storyboard {
                             difficulty_factor: Some(0.1),
                         },
                         powerup: Some(PowerUpKind::Health),
+                        variant: None,
                     },
                     FixedEnemyConfig {
                         enemy: EnemyKind::Tank,
@@ -154,6 +347,7 @@ impl Default for Storyboard {
                             difficulty_factor: Some(0.1),
                         },
                         powerup: None,
+                        variant: None,
                     },
                 ],
             ),
@@ -167,7 +361,7 @@ impl Default for Storyboard {
                     turn_rate: Some(120.0),
                     turn_rate_scale: Some(20.0),
                 },
-                None,
+                Some(PowerUpKind::Reflect),
                 Some(1),
             ),
             lane_wave(
@@ -188,13 +382,17 @@ impl Default for Storyboard {
             levels: vec![Level {
                 name: "Default".to_string(),
                 waves: default_waves,
+                bonus_stage: None,
+                boss_dialogue: None,
+                background: None,
             }],
+            dialogue: BTreeMap::new(),
         }
     }
 }
 
 #[derive(Debug)]
-enum StoryboardLoadError {
+pub enum StoryboardLoadError {
     Io(std::io::Error),
     Parse(serde_json::Error),
 }
@@ -222,21 +420,33 @@ impl From<serde_json::Error> for StoryboardLoadError {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct StoryboardFile {
     levels: Vec<LevelFile>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    dialogue: BTreeMap<String, Vec<DialogueLine>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct LevelFile {
     name: String,
     waves: Vec<WaveDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bonus_stage: Option<BonusStage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    boss_dialogue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    background: Option<LevelBackground>,
 }
 
-struct Level {
-    #[allow(dead_code)]
-    name: String,
-    waves: Vec<WaveDefinition>,
+#[derive(Clone)]
+pub struct Level {
+    pub name: String,
+    pub waves: Vec<WaveDefinition>,
+    pub bonus_stage: Option<BonusStage>,
+    // The scene played as the level's boss warps in.
+    pub boss_dialogue: Option<String>,
+    pub background: Option<LevelBackground>,
 }
 
 impl From<LevelFile> for Level {
@@ -244,127 +454,283 @@ impl From<LevelFile> for Level {
         Self {
             name: value.name,
             waves: value.waves,
+            bonus_stage: value.bonus_stage,
+            boss_dialogue: value.boss_dialogue,
+            background: value.background,
         }
     }
 }
 
-#[derive(Deserialize, Clone)]
-struct WaveDefinition {
+// A hidden stage inside a level, played in place of the next wave once its secret is
+// found. Its enemies never fire and each one carries a power-up; when its last wave has
+// come and gone the level carries on from the wave it would have played.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BonusStage {
+    pub name: String,
+    pub secret: SecretCondition,
+    pub waves: Vec<WaveDefinition>,
+}
+
+// A texture tiled across the playfield beneath the starfield, scrolling down at `speed`
+// pixels a second. `image` is a path inside the assets folder.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LevelBackground {
+    pub image: String,
+    #[serde(default = "default_background_speed")]
+    pub speed: f32,
+}
+
+fn default_background_speed() -> f32 {
+    20.0
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SecretCondition {
+    // Shoot down every enemy of the level's nth wave (counting from 1).
+    Formation { wave: u32 },
+    // Reach this score without losing a life since the level's first wave.
+    Score { score: u32 },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WaveDefinition {
     #[serde(default = "default_wave_delay")]
-    delay_seconds: f32,
+    pub delay_seconds: f32,
     #[serde(flatten)]
-    pattern: WavePattern,
+    pub pattern: WavePattern,
+    // Makes this an event wave: the named scene plays as it arrives, the first time
+    // through the level in a run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialogue: Option<String>,
+}
+
+impl WaveDefinition {
+    pub fn enemy_count(&self) -> usize {
+        match &self.pattern {
+            WavePattern::Lane(config) => config.lanes.len(),
+            WavePattern::Fixed { enemies } => enemies.len(),
+        }
+    }
+}
+
+impl Default for WaveDefinition {
+    fn default() -> Self {
+        lane_wave(
+            BASE_INTERVAL,
+            EnemyKind::Grunt,
+            &CORE_LANES,
+            0.0,
+            MovementConfig::default(),
+            None,
+            None,
+        )
+    }
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "pattern", rename_all = "snake_case")]
-enum WavePattern {
+pub enum WavePattern {
     Lane(LaneWaveConfig),
     Fixed { enemies: Vec<FixedEnemyConfig> },
 }
 
-#[derive(Deserialize, Clone)]
-struct LaneWaveConfig {
-    enemy: EnemyKind,
-    lanes: Vec<f32>,
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LaneWaveConfig {
+    pub enemy: EnemyKind,
+    pub lanes: Vec<f32>,
     #[serde(default)]
-    y_offset: f32,
-    movement: MovementConfig,
-    powerup: Option<PowerUpKind>,
-    powerup_lane_index: Option<usize>,
+    pub y_offset: f32,
+    pub movement: MovementConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub powerup: Option<PowerUpKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub powerup_lane_index: Option<usize>,
+    // A recolouring named in the ship's entry in `ships.ron`, such as "Elite".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
 
-#[derive(Deserialize, Clone)]
-struct FixedEnemyConfig {
-    enemy: EnemyKind,
-    position: SpawnPoint,
-    movement: MovementConfig,
-    powerup: Option<PowerUpKind>,
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FixedEnemyConfig {
+    pub enemy: EnemyKind,
+    pub position: SpawnPoint,
+    pub movement: MovementConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub powerup: Option<PowerUpKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
 
-#[derive(Clone, Copy, Deserialize)]
-struct SpawnPoint {
-    x: f32,
-    y: f32,
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub x: f32,
+    pub y: f32,
 }
 
 impl SpawnPoint {
-    const fn new(x: f32, y: f32) -> Self {
+    pub const fn new(x: f32, y: f32) -> Self {
         Self { x, y }
     }
 
-    fn to_vec(self) -> Vec2 {
+    pub fn to_vec(self) -> Vec2 {
         Vec2::new(self.x, self.y)
     }
-
-    fn x(self) -> f32 {
-        self.x
-    }
 }
 
-#[derive(Clone, Copy, Deserialize)]
+// Unset fields fall back to the defaults in `to_pattern` and are left out on export.
+#[derive(Clone, Copy, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
-enum MovementConfig {
+pub enum MovementConfig {
     Straight {
+        #[serde(skip_serializing_if = "Option::is_none")]
         speed: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         scale_with_difficulty: Option<bool>,
     },
     Sine {
+        #[serde(skip_serializing_if = "Option::is_none")]
         speed: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         amplitude: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         frequency: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         frequency_gain: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         base_x_offset: Option<f32>,
     },
     ZigZag {
+        #[serde(skip_serializing_if = "Option::is_none")]
         speed: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         horizontal_speed: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         direction: Option<f32>,
     },
     Tank {
+        #[serde(skip_serializing_if = "Option::is_none")]
         speed: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         base_factor: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         difficulty_factor: Option<f32>,
     },
     Chaser {
+        #[serde(skip_serializing_if = "Option::is_none")]
         speed: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         turn_rate: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         turn_rate_scale: Option<f32>,
     },
 }
 
+impl Default for MovementConfig {
+    fn default() -> Self {
+        MovementConfig::Straight {
+            speed: None,
+            scale_with_difficulty: None,
+        }
+    }
+}
+
 impl MovementConfig {
+    pub fn label(self) -> &'static str {
+        match self {
+            MovementConfig::Straight { .. } => "Straight",
+            MovementConfig::Sine { .. } => "Sine",
+            MovementConfig::ZigZag { .. } => "Zig-zag",
+            MovementConfig::Tank { .. } => "Tank",
+            MovementConfig::Chaser { .. } => "Chaser",
+        }
+    }
+
+    // The following movement type, with every field left at its default.
+    pub fn next(self) -> Self {
+        match self {
+            MovementConfig::Straight { .. } => MovementConfig::Sine {
+                speed: None,
+                amplitude: None,
+                frequency: None,
+                frequency_gain: None,
+                base_x_offset: None,
+            },
+            MovementConfig::Sine { .. } => MovementConfig::ZigZag {
+                speed: None,
+                horizontal_speed: None,
+                direction: None,
+            },
+            MovementConfig::ZigZag { .. } => MovementConfig::Tank {
+                speed: None,
+                base_factor: None,
+                difficulty_factor: None,
+            },
+            MovementConfig::Tank { .. } => MovementConfig::Chaser {
+                speed: None,
+                turn_rate: None,
+                turn_rate_scale: None,
+            },
+            MovementConfig::Chaser { .. } => MovementConfig::Straight {
+                speed: None,
+                scale_with_difficulty: None,
+            },
+        }
+    }
+
+    // Base speed before any difficulty scaling.
+    pub fn speed(self) -> f32 {
+        match self {
+            MovementConfig::Straight { speed, .. } => speed.unwrap_or(160.0),
+            MovementConfig::Sine { speed, .. } => speed.unwrap_or(130.0),
+            MovementConfig::ZigZag { speed, .. } => speed.unwrap_or(150.0),
+            MovementConfig::Tank { speed, .. } => speed.unwrap_or(90.0),
+            MovementConfig::Chaser { speed, .. } => speed.unwrap_or(180.0),
+        }
+    }
+
+    pub fn set_speed(&mut self, value: f32) {
+        match self {
+            MovementConfig::Straight { speed, .. }
+            | MovementConfig::Sine { speed, .. }
+            | MovementConfig::ZigZag { speed, .. }
+            | MovementConfig::Tank { speed, .. }
+            | MovementConfig::Chaser { speed, .. } => *speed = Some(value),
+        }
+    }
+
     fn to_pattern(self, difficulty_scale: f32, lane_x: Option<f32>) -> MovementPattern {
+        let speed = self.speed();
         match self {
             MovementConfig::Straight {
-                speed,
                 scale_with_difficulty,
+                ..
             } => {
-                let mut final_speed = speed.unwrap_or(160.0);
+                let mut final_speed = speed;
                 if scale_with_difficulty.unwrap_or(true) {
                     final_speed *= difficulty_scale;
                 }
                 MovementPattern::Straight { speed: final_speed }
             }
             MovementConfig::Sine {
-                speed,
                 amplitude,
                 frequency,
                 frequency_gain,
                 base_x_offset,
+                ..
             } => MovementPattern::Sine {
-                speed: speed.unwrap_or(130.0),
+                speed,
                 amplitude: amplitude.unwrap_or(140.0),
                 frequency: frequency.unwrap_or(1.4)
                     + difficulty_scale * frequency_gain.unwrap_or(0.15),
                 base_x: lane_x.unwrap_or(0.0) + base_x_offset.unwrap_or(0.0),
             },
             MovementConfig::ZigZag {
-                speed,
                 horizontal_speed,
                 direction,
+                ..
             } => MovementPattern::ZigZag {
-                speed: speed.unwrap_or(150.0),
+                speed,
                 horizontal_speed: horizontal_speed.unwrap_or(180.0),
                 direction: direction.unwrap_or_else(|| {
                     if lane_x.unwrap_or(0.0) >= 0.0 {
@@ -375,22 +741,22 @@ impl MovementConfig {
                 }),
             },
             MovementConfig::Tank {
-                speed,
                 base_factor,
                 difficulty_factor,
+                ..
             } => {
                 let base = base_factor.unwrap_or(0.8);
                 let factor = difficulty_factor.unwrap_or(0.1);
                 MovementPattern::Tank {
-                    speed: speed.unwrap_or(90.0) * (base + difficulty_scale * factor),
+                    speed: speed * (base + difficulty_scale * factor),
                 }
             }
             MovementConfig::Chaser {
-                speed,
                 turn_rate,
                 turn_rate_scale,
+                ..
             } => MovementPattern::Chaser {
-                speed: speed.unwrap_or(180.0),
+                speed,
                 turn_rate: turn_rate.unwrap_or(120.0)
                     + difficulty_scale * turn_rate_scale.unwrap_or(20.0),
             },
@@ -420,7 +786,9 @@ fn lane_wave(
             movement,
             powerup,
             powerup_lane_index,
+            variant: None,
         }),
+        dialogue: None,
     }
 }
 
@@ -428,13 +796,14 @@ fn fixed_wave(delay_seconds: f32, enemies: Vec<FixedEnemyConfig>) -> WaveDefinit
     WaveDefinition {
         delay_seconds,
         pattern: WavePattern::Fixed { enemies },
+        dialogue: None,
     }
 }
 
 fn set_timer_for_next_wave(
     director: &mut WaveDirector,
     storyboard: &Storyboard,
-    settings: &GameSettings,
+    difficulty: &RunDifficulty,
 ) {
     let delay = storyboard
         .level(director.level_index)
@@ -442,7 +811,11 @@ fn set_timer_for_next_wave(
         .map(|wave| wave.delay_seconds)
         .or_else(|| storyboard.first_delay(director.level_index))
         .unwrap_or(BASE_INTERVAL);
-    let scaled = delay * settings.difficulty.spawn_interval_factor();
+    set_wave_timer(director, delay, difficulty);
+}
+
+fn set_wave_timer(director: &mut WaveDirector, delay: f32, difficulty: &RunDifficulty) {
+    let scaled = delay * difficulty.knobs().spawn_interval;
     director.timer.set_duration(Duration::from_secs_f32(scaled));
     director.timer.reset();
 }
@@ -450,7 +823,7 @@ fn set_timer_for_next_wave(
 pub fn advance_level(
     director: &mut WaveDirector,
     storyboard: &Storyboard,
-    settings: &GameSettings,
+    difficulty: &RunDifficulty,
 ) {
     let level_count = storyboard.level_count();
     if level_count == 0 {
@@ -461,37 +834,78 @@ pub fn advance_level(
         .unwrap_or((director.level_index + 1) % level_count);
     director.level_index = next_index;
     director.wave_index = 0;
-    director.difficulty = settings.difficulty.enemy_health_factor();
+    director.difficulty = difficulty.knobs().enemy_health;
+    director.pending_level = None;
+    director.bonus = BonusProgress::Hidden;
+    set_timer_for_next_wave(director, storyboard, difficulty);
+}
+
+// Sends a run that has cleared the final level back to the first, one loop harder.
+pub fn start_next_loop(
+    director: &mut WaveDirector,
+    storyboard: &Storyboard,
+    difficulty: &RunDifficulty,
+) {
+    director.loop_index += 1;
+    director.pending_level = Some(0);
+    advance_level(director, storyboard, difficulty);
+}
+
+// Puts the director at the start of the given wave as though the earlier ones had
+// already played, including their difficulty ramp.
+pub fn jump_to_wave(
+    director: &mut WaveDirector,
+    storyboard: &Storyboard,
+    difficulty: &RunDifficulty,
+    level_index: usize,
+    wave_index: u32,
+) {
+    director.level_index = level_index % storyboard.level_count().max(1);
+    director.wave_index = wave_index;
+    director.difficulty =
+        difficulty.knobs().enemy_health + WAVE_DIFFICULTY_STEP * wave_index as f32;
+    director.boss_active = false;
     director.pending_level = None;
-    set_timer_for_next_wave(director, storyboard, settings);
+    director.bonus = BonusProgress::Hidden;
+    set_timer_for_next_wave(director, storyboard, difficulty);
 }
 
 fn reset_waves(
     mut director: ResMut<WaveDirector>,
-    settings: Res<GameSettings>,
+    config: Res<GameConfig>,
+    run_difficulty: Res<RunDifficulty>,
     storyboard: Res<Storyboard>,
 ) {
     director.timer.reset();
     director.wave_index = 0;
-    director.difficulty = settings.difficulty.enemy_health_factor();
+    director.difficulty = run_difficulty.knobs().enemy_health;
     director.boss_active = false;
-    director.level_index = 0;
+    director.level_index = config.start_level % storyboard.level_count().max(1);
     director.pending_level = None;
-    set_timer_for_next_wave(&mut director, &storyboard, &settings);
+    director.loop_index = 0;
+    director.bonus = BonusProgress::Hidden;
+    set_timer_for_next_wave(&mut director, &storyboard, &run_difficulty);
 }
 
 fn clear_waves(mut director: ResMut<WaveDirector>) {
     director.timer.reset();
 }
 
+#[allow(clippy::too_many_arguments, clippy::collapsible_if)]
 fn drive_waves(
     mut director: ResMut<WaveDirector>,
     time: Res<Time<Fixed>>,
     mut writer: EventWriter<SpawnEnemyEvent>,
+    mut wave_events: EventWriter<WaveStartedEvent>,
+    mut bonus_events: EventWriter<BonusStageEvent>,
     settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
     storyboard: Res<Storyboard>,
+    config: Res<GameConfig>,
+    rank: Res<Rank>,
+    cheats: Res<Cheats>,
 ) {
-    if director.boss_active {
+    if director.boss_active || cheats.freeze_spawns {
         return;
     }
 
@@ -502,7 +916,12 @@ fn drive_waves(
         return;
     }
 
-    if !director.timer.tick(time.delta()).just_finished() {
+    // Rank speeds up the wave timer rather than rewriting the storyboard's delays.
+    if !director
+        .timer
+        .tick(time.delta().mul_f32(rank.spawn_rate()))
+        .just_finished()
+    {
         return;
     }
 
@@ -510,14 +929,72 @@ fn drive_waves(
     if wave_count == 0 {
         return;
     }
+    let difficulty_scale = director.difficulty * run_difficulty.knobs().enemy_health;
+
+    // The bonus stage runs beside the ladder rather than through it: `wave_index` is
+    // left alone, so the level resumes with the wave that was due when it started. The
+    // wave that was due is held back while the stage is announced.
+    if director.bonus == BonusProgress::Found
+        && let Some(stage) = &level.bonus_stage
+    {
+        director.bonus = BonusProgress::Playing { next_wave: 0 };
+        bonus_events.send(BonusStageEvent::Entered {
+            name: stage.name.clone(),
+        });
+        let delay = stage.waves.first().map_or(0.0, |wave| wave.delay_seconds);
+        set_wave_timer(&mut director, delay, &run_difficulty);
+        return;
+    }
+    if let BonusProgress::Playing { next_wave } = director.bonus {
+        let stage_waves = level
+            .bonus_stage
+            .as_ref()
+            .map_or(&[][..], |stage| &stage.waves[..]);
+        if let Some(wave) = stage_waves.get(next_wave as usize) {
+            spawn_wave_from_definition(
+                wave,
+                difficulty_scale,
+                config.lane_scale(),
+                settings.mirror_mode,
+                None,
+                &mut writer,
+            );
+            director.bonus = BonusProgress::Playing {
+                next_wave: next_wave + 1,
+            };
+            match stage_waves.get(next_wave as usize + 1) {
+                Some(next) => set_wave_timer(&mut director, next.delay_seconds, &run_difficulty),
+                None => set_timer_for_next_wave(&mut director, &storyboard, &run_difficulty),
+            }
+            return;
+        }
+        director.bonus = BonusProgress::Done;
+    }
 
     let current_index = director.wave_index as usize % wave_count;
 
-    let difficulty_scale = director.difficulty * settings.difficulty.enemy_health_factor();
-    spawn_wave_from_definition(&level.waves[current_index], difficulty_scale, &mut writer);
+    let _span = info_span!(
+        "spawn_wave",
+        level = director.level_index + 1,
+        wave = current_index + 1
+    )
+    .entered();
+    spawn_wave_from_definition(
+        &level.waves[current_index],
+        difficulty_scale,
+        config.lane_scale(),
+        settings.mirror_mode,
+        Some(current_index as u32 + 1),
+        &mut writer,
+    );
+    wave_events.send(WaveStartedEvent {
+        level: director.level_index + 1,
+        wave: current_index as u32 + 1,
+        wave_count: wave_count as u32,
+    });
 
     director.wave_index = (director.wave_index + 1) % wave_count as u32;
-    director.difficulty += 0.05;
+    director.difficulty += WAVE_DIFFICULTY_STEP;
 
     if director.wave_index == 0 {
         if director.pending_level.is_none() {
@@ -528,20 +1005,61 @@ fn drive_waves(
         }
     }
 
-    set_timer_for_next_wave(&mut director, &storyboard, &settings);
+    set_timer_for_next_wave(&mut director, &storyboard, &run_difficulty);
+}
+
+// Spawns a single wave as it would arrive in a run, ramp included, for the editor. The
+// preview always matches the storyboard as drawn, so mirror mode is ignored.
+pub fn preview_wave(
+    wave: &WaveDefinition,
+    wave_index: usize,
+    settings: &GameSettings,
+    config: &GameConfig,
+    writer: &mut EventWriter<SpawnEnemyEvent>,
+) {
+    let knobs = settings.difficulty_knobs();
+    let difficulty = knobs.enemy_health + WAVE_DIFFICULTY_STEP * wave_index as f32;
+    spawn_wave_from_definition(
+        wave,
+        difficulty * knobs.enemy_health,
+        config.lane_scale(),
+        false,
+        None,
+        writer,
+    );
 }
 
+// `lane_scale` stretches the storyboard's x positions to the current playfield width,
+// `mirrored` flips the whole wave left to right, and `number` is the wave's place in its
+// level, tagged on each enemy.
 fn spawn_wave_from_definition(
     wave: &WaveDefinition,
     difficulty_scale: f32,
+    lane_scale: f32,
+    mirrored: bool,
+    number: Option<u32>,
     writer: &mut EventWriter<SpawnEnemyEvent>,
 ) {
     match &wave.pattern {
         WavePattern::Lane(config) => {
-            spawn_lane_wave(config, difficulty_scale, writer);
+            spawn_lane_wave(
+                config,
+                difficulty_scale,
+                lane_scale,
+                mirrored,
+                number,
+                writer,
+            );
         }
         WavePattern::Fixed { enemies } => {
-            spawn_fixed_wave(enemies, difficulty_scale, writer);
+            spawn_fixed_wave(
+                enemies,
+                difficulty_scale,
+                lane_scale,
+                mirrored,
+                number,
+                writer,
+            );
         }
     }
 }
@@ -549,49 +1067,80 @@ fn spawn_wave_from_definition(
 fn spawn_lane_wave(
     config: &LaneWaveConfig,
     difficulty_scale: f32,
+    lane_scale: f32,
+    mirrored: bool,
+    number: Option<u32>,
     writer: &mut EventWriter<SpawnEnemyEvent>,
 ) {
     for (index, lane_x) in config.lanes.iter().enumerate() {
-        let position = Vec2::new(*lane_x, TOP_Y + config.y_offset);
-        let movement = config.movement.to_pattern(difficulty_scale, Some(*lane_x));
+        let lane_x = lane_x * lane_scale;
+        let position = Vec2::new(lane_x, TOP_Y + config.y_offset);
+        let movement = config.movement.to_pattern(difficulty_scale, Some(lane_x));
         let drop = if config.powerup_lane_index == Some(index) {
             config.powerup
         } else {
             None
         };
-        writer.send(spawn_enemy(config.enemy, position, movement, drop));
+        writer.send(spawn_enemy(
+            config.enemy,
+            position,
+            movement,
+            drop,
+            config.variant.clone(),
+            mirrored,
+            number,
+        ));
     }
 }
 
 fn spawn_fixed_wave(
     enemies: &[FixedEnemyConfig],
     difficulty_scale: f32,
+    lane_scale: f32,
+    mirrored: bool,
+    number: Option<u32>,
     writer: &mut EventWriter<SpawnEnemyEvent>,
 ) {
     for enemy in enemies {
+        let position = enemy.position.to_vec() * Vec2::new(lane_scale, 1.0);
         let movement = enemy
             .movement
-            .to_pattern(difficulty_scale, Some(enemy.position.x()));
+            .to_pattern(difficulty_scale, Some(position.x));
         writer.send(spawn_enemy(
             enemy.enemy,
-            enemy.position.to_vec(),
+            position,
             movement,
             enemy.powerup,
+            enemy.variant.clone(),
+            mirrored,
+            number,
         ));
     }
 }
 
+// Movement is built from the storyboard's own x, then reflected along with the position,
+// so a zig-zag's default heading (away from its side of the screen) still holds.
 fn spawn_enemy(
     kind: EnemyKind,
     position: Vec2,
     movement: MovementPattern,
     powerup: Option<PowerUpKind>,
+    variant: Option<String>,
+    mirrored: bool,
+    wave: Option<u32>,
 ) -> SpawnEnemyEvent {
+    let (position, movement) = if mirrored {
+        (position * Vec2::new(-1.0, 1.0), movement.mirrored())
+    } else {
+        (position, movement)
+    };
     SpawnEnemyEvent {
         kind,
         position,
         movement,
         powerup,
+        variant,
+        wave,
     }
 }
 
@@ -630,9 +1179,17 @@ impl<'de> Deserialize<'de> for PowerUpKind {
             "shield" => Ok(PowerUpKind::Shield),
             "health" => Ok(PowerUpKind::Health),
             "invincibility" | "invincible" => Ok(PowerUpKind::Invincibility),
+            "reflect" | "reflector" => Ok(PowerUpKind::Reflect),
             _ => Err(de::Error::unknown_variant(
                 &value,
-                &["spread", "rapid", "shield", "health", "invincibility"],
+                &[
+                    "spread",
+                    "rapid",
+                    "shield",
+                    "health",
+                    "invincibility",
+                    "reflect",
+                ],
             )),
         }
     }
@@ -0,0 +1,422 @@
+use std::{borrow::Cow, collections::HashMap, fmt, iter};
+
+use bevy::{
+    asset::{AssetLoader, AsyncReadExt, LoadContext, io::Reader},
+    math::{URect, UVec2, Vec2},
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    sprite::TextureAtlasLayout,
+};
+use image::{ImageError, RgbaImage, imageops};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    effects::{EFFECTS_FRAME_SIZE, EFFECTS_SHEET},
+    loading::LoadingAssets,
+    ship_sprites::{ShipManifest, ShipSpriteId},
+    states::AppState,
+};
+
+// Transparent gap left between sheets so filtering at a frame's edge never picks up
+// a neighbouring sheet.
+const SHEET_PADDING: u32 = 2;
+
+pub struct SpriteAtlasPlugin;
+
+impl Plugin for SpriteAtlasPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SlicedSheet>()
+            .register_asset_loader(SpriteSheetLoader)
+            .add_systems(Startup, load_sprite_sheets)
+            .add_systems(OnExit(AppState::Loading), build_sprite_atlas);
+        #[cfg(feature = "hot-reload")]
+        app.add_systems(
+            Update,
+            reload_sprite_atlas.run_if(resource_exists::<SpriteAtlas>),
+        );
+    }
+}
+
+// Every sprite sheet packed into one texture once loading finishes, so ships, shots,
+// power-ups, and explosions can all be drawn from the same image and batch together.
+#[derive(Resource)]
+pub struct SpriteAtlas {
+    pub texture: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    sheets: HashMap<String, AtlasSheet>,
+}
+
+impl SpriteAtlas {
+    pub fn sheet(&self, path: &str) -> &AtlasSheet {
+        self.sheets
+            .get(path)
+            .unwrap_or_else(|| panic!("{path} is not in the sprite atlas"))
+    }
+}
+
+// A sheet's frames as atlas indices, one list per row of the source image.
+pub struct AtlasSheet {
+    pub rows: Vec<Vec<usize>>,
+    pub frame_size: Vec2,
+}
+
+// The source sheets while they load; dropped once they are packed, unless hot reloading
+// needs them again.
+#[derive(Resource)]
+pub struct SpriteSheets(Vec<(String, Handle<SlicedSheet>)>);
+
+// A sheet decoded and cut into frames by `SpriteSheetLoader`. The asset server runs
+// the loader on its own threads, reading through whichever asset source the
+// platform uses, so none of the decoding or pixel scanning happens on the main thread.
+#[derive(Asset, TypePath, Clone)]
+pub struct SlicedSheet {
+    pixels: RgbaImage,
+    rows: Vec<Vec<URect>>,
+    frame_size: Vec2,
+}
+
+// How a sheet is cut up, passed as the loader's settings.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+enum Slicing {
+    // Fixed-size cells covering the whole sheet.
+    Grid {
+        width: u32,
+        height: u32,
+    },
+    // Frames found by scanning for runs of opaque pixels, row by row.
+    #[default]
+    Trimmed,
+}
+
+#[derive(Debug)]
+enum SpriteSheetError {
+    Io(std::io::Error),
+    Decode(ImageError),
+}
+
+impl fmt::Display for SpriteSheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpriteSheetError::Io(err) => write!(f, "I/O error: {}", err),
+            SpriteSheetError::Decode(err) => write!(f, "decode error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SpriteSheetError {}
+
+impl From<std::io::Error> for SpriteSheetError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ImageError> for SpriteSheetError {
+    fn from(value: ImageError) -> Self {
+        Self::Decode(value)
+    }
+}
+
+struct SpriteSheetLoader;
+
+impl AssetLoader for SpriteSheetLoader {
+    type Asset = SlicedSheet;
+    type Settings = Slicing;
+    type Error = SpriteSheetError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        slicing: &'a Slicing,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<SlicedSheet, SpriteSheetError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let pixels = image::load_from_memory(&bytes)?.to_rgba8();
+        let (rows, frame_size) = match *slicing {
+            Slicing::Grid { width, height } => grid_frames(&pixels, UVec2::new(width, height)),
+            Slicing::Trimmed => trimmed_frames(&pixels),
+        };
+        Ok(SlicedSheet {
+            pixels,
+            rows,
+            frame_size,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["png"]
+    }
+}
+
+fn load_sprite_sheets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+    manifest: Res<ShipManifest>,
+) {
+    let grid = Slicing::Grid {
+        width: EFFECTS_FRAME_SIZE.x,
+        height: EFFECTS_FRAME_SIZE.y,
+    };
+    let mut sheets: Vec<(String, Handle<SlicedSheet>)> = Vec::new();
+    // Ships may share a sheet; it's loaded and packed once. The built-in sheets come
+    // along so a ship whose sheet fails to load has its original art to fall back on.
+    let built_in = ShipManifest::default();
+    for (path, slicing) in iter::once((EFFECTS_SHEET, grid)).chain(
+        manifest
+            .sheets()
+            .chain(built_in.sheets())
+            .map(|path| (path, Slicing::Trimmed)),
+    ) {
+        if sheets.iter().any(|(loaded, _)| loaded == path) {
+            continue;
+        }
+        let handle = asset_server
+            .load_with_settings(path.to_string(), move |settings: &mut Slicing| {
+                *settings = slicing
+            });
+        loading.track(handle.clone());
+        sheets.push((path.to_string(), handle));
+    }
+    commands.insert_resource(SpriteSheets(sheets));
+}
+
+// Runs as loading ends; the ship and effect plugins read the atlas right after.
+pub fn build_sprite_atlas(
+    mut commands: Commands,
+    mut sources: ResMut<SpriteSheets>,
+    mut manifest: ResMut<ShipManifest>,
+    sliced: Res<Assets<SlicedSheet>>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    // A sheet that failed to load is left out, and the ships drawn from it go back to
+    // their built-in entries.
+    sources.0.retain(|(path, handle)| {
+        let loaded = sliced.contains(handle);
+        if !loaded {
+            warn!("Failed to load sprite sheet {}; leaving it out", path);
+        }
+        loaded
+    });
+    for id in ShipSpriteId::ALL {
+        let sheet = &manifest.spec(id).sheet;
+        if !sources.0.iter().any(|(path, _)| path == sheet) {
+            warn!("{:?} falls back to its built-in sheet", id);
+            manifest.restore_built_in(id);
+        }
+    }
+    let (image, layout, sheets) = pack_sheets(&sources.0, &sliced, &manifest);
+    commands.insert_resource(SpriteAtlas {
+        texture: images.add(image),
+        layout: layouts.add(layout),
+        sheets,
+    });
+    // Hot reloading keeps the sources so the atlas can be packed again.
+    #[cfg(not(feature = "hot-reload"))]
+    commands.remove_resource::<SpriteSheets>();
+}
+
+// Repacks the atlas when a sheet changes on disk and the loader has sliced it again.
+// The texture and layout are replaced under the same handles, so sprites already on
+// screen pick up the new art, and the ship and effect frames are read again after.
+#[cfg(feature = "hot-reload")]
+pub fn reload_sprite_atlas(
+    mut events: EventReader<AssetEvent<SlicedSheet>>,
+    sources: Res<SpriteSheets>,
+    manifest: Res<ShipManifest>,
+    sliced: Res<Assets<SlicedSheet>>,
+    mut atlas: ResMut<SpriteAtlas>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let modified = events
+        .read()
+        .filter(|event| matches!(event, AssetEvent::Modified { .. }))
+        .count();
+    if modified == 0 {
+        return;
+    }
+    let (image, layout, sheets) = pack_sheets(&sources.0, &sliced, &manifest);
+    images.insert(&atlas.texture, image);
+    layouts.insert(&atlas.layout, layout);
+    atlas.sheets = sheets;
+    info!("Reloaded {} sprite sheet(s)", modified);
+}
+
+// Every sheet stacked into one image, with each sheet's frames as atlas indices.
+fn pack_sheets(
+    sources: &[(String, Handle<SlicedSheet>)],
+    sliced: &Assets<SlicedSheet>,
+    manifest: &ShipManifest,
+) -> (Image, TextureAtlasLayout, HashMap<String, AtlasSheet>) {
+    let loaded = |path: &str| {
+        sources
+            .iter()
+            .find(|(loaded, _)| loaded == path)
+            .and_then(|(_, handle)| sliced.get(handle))
+            .unwrap_or_else(|| panic!("failed to load sprite sheet {path}"))
+    };
+    let mut sheets: Vec<_> = sources
+        .iter()
+        .map(|(path, _)| (path.clone(), Cow::Borrowed(loaded(path))))
+        .collect();
+    // Ship variants are recoloured copies of their sheet, frames and all.
+    for (key, path, variant) in manifest.variants() {
+        let source = loaded(path);
+        let mut pixels = source.pixels.clone();
+        variant.recolor(&mut pixels);
+        let sheet = SlicedSheet {
+            pixels,
+            rows: source.rows.clone(),
+            frame_size: source.frame_size,
+        };
+        sheets.push((key, Cow::Owned(sheet)));
+    }
+
+    // Sheets are stacked top to bottom; none is wide enough to need anything cleverer.
+    let width = sheets
+        .iter()
+        .map(|(_, sheet)| sheet.pixels.width())
+        .max()
+        .unwrap_or(1);
+    let height = sheets
+        .iter()
+        .map(|(_, sheet)| sheet.pixels.height() + SHEET_PADDING)
+        .sum::<u32>();
+    let mut atlas_image = RgbaImage::new(width, height);
+    let mut layout = TextureAtlasLayout::new_empty(UVec2::new(width, height));
+    let mut placed = HashMap::new();
+    let mut top = 0;
+    for (path, sheet) in &sheets {
+        imageops::replace(&mut atlas_image, &sheet.pixels, 0, i64::from(top));
+        let offset = UVec2::new(0, top);
+        let rows = sheet
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|rect| {
+                        layout
+                            .add_texture(URect::from_corners(rect.min + offset, rect.max + offset))
+                    })
+                    .collect()
+            })
+            .collect();
+        placed.insert(
+            path.to_string(),
+            AtlasSheet {
+                rows,
+                frame_size: sheet.frame_size,
+            },
+        );
+        top += sheet.pixels.height() + SHEET_PADDING;
+    }
+
+    let image = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        atlas_image.into_raw(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    (image, layout, placed)
+}
+
+fn grid_frames(image: &RgbaImage, cell: UVec2) -> (Vec<Vec<URect>>, Vec2) {
+    let rows = (0..image.height() / cell.y)
+        .map(|row| {
+            (0..image.width() / cell.x)
+                .map(|column| {
+                    let min = UVec2::new(column, row) * cell;
+                    URect::from_corners(min, min + cell)
+                })
+                .collect()
+        })
+        .collect();
+    (rows, cell.as_vec2())
+}
+
+fn trimmed_frames(img: &RgbaImage) -> (Vec<Vec<URect>>, Vec2) {
+    let (width, height) = img.dimensions();
+    let row_ranges = extract_row_ranges(img);
+
+    let mut sequences = Vec::new();
+    let mut frame_width = width as f32;
+    let mut frame_height = height as f32;
+
+    for row in &row_ranges {
+        let col_ranges = extract_col_ranges(img, *row);
+        if col_ranges.is_empty() {
+            continue;
+        }
+        if sequences.is_empty() {
+            frame_height = (row.1 - row.0) as f32;
+        }
+        let seq = col_ranges
+            .iter()
+            .map(|col| URect::new(col.0, row.0, col.1, row.1))
+            .collect();
+        frame_width = (col_ranges[0].1 - col_ranges[0].0) as f32;
+        sequences.push(seq);
+    }
+
+    (sequences, Vec2::new(frame_width, frame_height))
+}
+
+fn extract_row_ranges(img: &RgbaImage) -> Vec<(u32, u32)> {
+    extract_ranges_generic(img.height(), |y| row_has_alpha(img, y))
+}
+
+fn extract_col_ranges(img: &RgbaImage, row: (u32, u32)) -> Vec<(u32, u32)> {
+    extract_ranges_generic(img.width(), |x| column_band_has_alpha(img, x, row))
+}
+
+#[allow(clippy::collapsible_if)]
+fn extract_ranges_generic(len: u32, mut has_alpha: impl FnMut(u32) -> bool) -> Vec<(u32, u32)> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for idx in 0..len {
+        if has_alpha(idx) {
+            if start.is_none() {
+                start = Some(idx);
+            }
+        } else if let Some(s) = start.take() {
+            if idx - s > 2 {
+                ranges.push((s, idx));
+            }
+        }
+    }
+    if let Some(s) = start {
+        if len - s > 2 {
+            ranges.push((s, len));
+        }
+    }
+    ranges
+}
+
+fn row_has_alpha(img: &RgbaImage, y: u32) -> bool {
+    for x in 0..img.width() {
+        if img.get_pixel(x, y)[3] > 5 {
+            return true;
+        }
+    }
+    false
+}
+
+fn column_band_has_alpha(img: &RgbaImage, x: u32, row: (u32, u32)) -> bool {
+    for y in row.0..row.1 {
+        if img.get_pixel(x, y)[3] > 5 {
+            return true;
+        }
+    }
+    false
+}
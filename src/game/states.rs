@@ -2,17 +2,75 @@ use bevy::prelude::*;
 
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub enum AppState {
+    // Waits for textures, fonts, and audio files before showing the title screen.
     #[default]
+    Loading,
     Title,
     Playing,
+    // A story scene between waves; the run is held until it ends or is skipped.
+    Dialogue,
     Paused,
     GameOver,
+    NameEntry,
+    Settings,
+    CareerStats,
+    Practice,
+    Challenges,
+    // Shown in place of the title screen while the profile has unlocks it hasn't seen.
+    NewUnlock,
+    LevelComplete,
+    Victory,
+    Editor,
+    // Passed through for a single frame so a restart runs the normal run teardown and setup.
+    Restarting,
+}
+
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum SettingsOrigin {
+    #[default]
+    Title,
+    Paused,
+}
+
+impl SettingsOrigin {
+    pub fn return_state(self) -> AppState {
+        match self {
+            SettingsOrigin::Title => AppState::Title,
+            SettingsOrigin::Paused => AppState::Paused,
+        }
+    }
+}
+
+// Present while a run is alive, including while paused or in the settings
+// opened from the pause menu, so run setup/teardown hooks onto this instead.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = (AppState, SettingsOrigin);
+
+    fn compute((app, origin): (AppState, SettingsOrigin)) -> Option<Self> {
+        match app {
+            AppState::Playing | AppState::Dialogue | AppState::Paused | AppState::LevelComplete => {
+                Some(InRun)
+            }
+            AppState::Settings if origin == SettingsOrigin::Paused => Some(InRun),
+            _ => None,
+        }
+    }
 }
 
 pub struct StatePlugin;
 
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<AppState>();
+        app.init_state::<AppState>()
+            .init_state::<SettingsOrigin>()
+            .add_computed_state::<InRun>()
+            .add_systems(OnEnter(AppState::Restarting), finish_restart);
     }
 }
+
+fn finish_restart(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Playing);
+}
@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use bevy::log::{info, warn};
+use steamworks::Client;
+
+use super::{
+    achievements::Achievement,
+    platform::{Platform, PlatformServices},
+};
+
+// Mirrors unlocked achievements to Steam under their `Achievement::id` API names and
+// keeps the save files in Steam Cloud under their profile-relative keys.
+pub struct Steam {
+    client: Client,
+}
+
+impl Steam {
+    // Connects to the running Steam client. Outside Steam this needs a `steam_appid.txt`
+    // in the working directory, as Steam only supplies the app ID when it launches the game.
+    pub fn init() -> Result<Self, steamworks::SteamAPIInitError> {
+        Client::init().map(|client| Self { client })
+    }
+
+    fn cloud_enabled(&self) -> bool {
+        let storage = self.client.remote_storage();
+        storage.is_cloud_enabled_for_account() && storage.is_cloud_enabled_for_app()
+    }
+}
+
+// The Steam backend when the client is reachable, otherwise the offline one so the game
+// still runs from a plain build directory.
+pub fn platform() -> Platform {
+    match Steam::init() {
+        Ok(steam) => {
+            info!("Connected to Steam");
+            Platform::new(steam)
+        }
+        Err(err) => {
+            warn!("Steam is unavailable, playing offline: {err}");
+            Platform::default()
+        }
+    }
+}
+
+impl PlatformServices for Steam {
+    fn unlock_achievement(&mut self, achievement: Achievement) {
+        let stats = self.client.user_stats();
+        if stats.achievement(achievement.id()).set().is_err() {
+            warn!("Steam rejected achievement {}", achievement.id());
+            return;
+        }
+        if stats.store_stats().is_err() {
+            warn!("Failed to store Steam stats for {}", achievement.id());
+        }
+    }
+
+    fn download_file(&mut self, key: &str, local: &Path) {
+        if !self.cloud_enabled() {
+            return;
+        }
+        let file = self.client.remote_storage().file(key);
+        if !file.exists() {
+            return;
+        }
+        let local_seconds = fs::metadata(local)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|age| age.as_secs() as i64);
+        if local_seconds.is_some_and(|seconds| seconds >= file.timestamp()) {
+            return;
+        }
+
+        let mut contents = Vec::new();
+        if let Err(err) = file.read().read_to_end(&mut contents) {
+            warn!("Failed to read {key} from Steam Cloud: {err}");
+            return;
+        }
+        let written = match local.parent() {
+            Some(dir) => fs::create_dir_all(dir).and_then(|_| fs::write(local, &contents)),
+            None => fs::write(local, &contents),
+        };
+        match written {
+            Ok(()) => info!("Restored {key} from Steam Cloud"),
+            Err(err) => warn!("Failed to write {}: {}", local.display(), err),
+        }
+    }
+
+    fn upload_file(&mut self, key: &str, contents: &[u8]) {
+        if !self.cloud_enabled() {
+            return;
+        }
+        let mut writer = self.client.remote_storage().file(key).write();
+        if let Err(err) = writer.write_all(contents) {
+            warn!("Failed to upload {key} to Steam Cloud: {err}");
+        }
+    }
+
+    fn update(&mut self) {
+        self.client.run_callbacks();
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use bevy::log::warn;
+use directories::ProjectDirs;
+use serde::{Serialize, de::DeserializeOwned};
+
+// Folder beside the executable used by portable installs, and whenever the platform
+// directories can't be worked out (no home directory, for instance).
+const LOCAL_DIR: &str = "userdata";
+
+// Where settings live: `~/.config/sforce` on Linux, `Application Support` on macOS,
+// and the roaming `AppData` folder on Windows.
+pub fn config_path(file: &str) -> PathBuf {
+    match project_dirs() {
+        Some(dirs) => dirs.config_dir().join(file),
+        None => local_dir().join(file),
+    }
+}
+
+// Where scores, saves, profiles, and screenshots live: `~/.local/share/sforce` on
+// Linux, `Application Support` on macOS, and the roaming `AppData` folder on Windows.
+pub fn data_path(file: &str) -> PathBuf {
+    match project_dirs() {
+        Some(dirs) => dirs.data_dir().join(file),
+        None => local_dir().join(file),
+    }
+}
+
+// The folder `--portable` keeps everything in, so the game can run from a USB stick
+// without touching the machine's own directories.
+pub fn local_dir() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(LOCAL_DIR)))
+        .unwrap_or_else(|| PathBuf::from(LOCAL_DIR))
+}
+
+// Reads a JSON store, falling back to its default when the file is missing or can't be
+// read. Anything other than a missing file is logged.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let result = fs::read_to_string(path)
+        .and_then(|contents| serde_json::from_str(&contents).map_err(io::Error::from));
+    match result {
+        Ok(value) => value,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => T::default(),
+        Err(err) => {
+            warn!(
+                "Failed to load {}: {}. Starting fresh.",
+                path.display(),
+                err
+            );
+            T::default()
+        }
+    }
+}
+
+// Writes a JSON store, creating its folder first. Failures are logged rather than
+// returned, as there is nothing the game can do about them mid-run.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| serde_json::to_string_pretty(value).map_err(io::Error::from))
+        .and_then(|contents| fs::write(path, contents));
+    if let Err(err) = result {
+        warn!("Failed to save {}: {}", path.display(), err);
+    }
+}
+
+fn project_dirs() -> Option<&'static ProjectDirs> {
+    static DIRS: OnceLock<Option<ProjectDirs>> = OnceLock::new();
+    DIRS.get_or_init(|| {
+        let dirs = ProjectDirs::from("com", "tuttlem", "sforce");
+        if dirs.is_none() {
+            warn!(
+                "No platform config or data directory available; saving to {}",
+                local_dir().display()
+            );
+        }
+        dirs
+    })
+    .as_ref()
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use super::{
+    camera::MainCamera,
+    config::GameSettings,
+    enemies::{Enemy, EnemyKind},
+    palette::threat_colors,
+    states::{AppState, InRun},
+};
+
+const LOOKAHEAD: f32 = 260.0;
+const EDGE_INSET: f32 = 18.0;
+const INDICATOR_Z: f32 = 20.0;
+const MIN_SCALE: f32 = 0.6;
+
+pub struct ThreatIndicatorPlugin;
+
+impl Plugin for ThreatIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_indicator_assets)
+            .add_systems(
+                Update,
+                update_threat_indicators.run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, recolor_threat_indicators)
+            .add_systems(OnExit(InRun), cleanup_threat_indicators);
+    }
+}
+
+#[derive(Resource)]
+struct IndicatorAssets {
+    arrow: Mesh2dHandle,
+    enemy: Handle<ColorMaterial>,
+    boss: Handle<ColorMaterial>,
+}
+
+#[derive(Component)]
+struct ThreatIndicator {
+    target: Entity,
+}
+
+fn load_indicator_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<GameSettings>,
+) {
+    let (enemy, boss) = threat_colors(settings.palette);
+    commands.insert_resource(IndicatorAssets {
+        arrow: Mesh2dHandle(meshes.add(Triangle2d::new(
+            Vec2::new(0.0, 10.0),
+            Vec2::new(-9.0, -8.0),
+            Vec2::new(9.0, -8.0),
+        ))),
+        enemy: materials.add(enemy),
+        boss: materials.add(boss),
+    });
+}
+
+fn recolor_threat_indicators(
+    settings: Res<GameSettings>,
+    assets: Option<Res<IndicatorAssets>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if !settings.is_changed() {
+        return;
+    }
+    let (enemy, boss) = threat_colors(settings.palette);
+    for (handle, color) in [(&assets.enemy, enemy), (&assets.boss, boss)] {
+        if let Some(material) = materials.get_mut(handle)
+            && material.color != color
+        {
+            material.color = color;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_threat_indicators(
+    mut commands: Commands,
+    assets: Res<IndicatorAssets>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    enemies: Query<(Entity, &Transform, &Enemy)>,
+    mut indicators: Query<
+        (Entity, &ThreatIndicator, &mut Transform),
+        (Without<Enemy>, Without<MainCamera>),
+    >,
+) {
+    let Ok((camera, projection)) = cameras.get_single() else {
+        return;
+    };
+    let center = camera.translation.truncate();
+    let view = Rect::from_center_size(center, projection.area.size());
+    let watch = view.inflate(LOOKAHEAD);
+    let inner = view.inflate(-EDGE_INSET);
+
+    let mut existing: HashMap<Entity, Entity> = indicators
+        .iter()
+        .map(|(entity, indicator, _)| (indicator.target, entity))
+        .collect();
+
+    for (target, transform, enemy) in &enemies {
+        let position = transform.translation.truncate();
+        // Anything below the view has already flown past the player.
+        if view.contains(position) || !watch.contains(position) || position.y < view.min.y {
+            continue;
+        }
+
+        let anchor = position.clamp(inner.min, inner.max);
+        let heading = (position - anchor).normalize_or(Vec2::Y);
+        let distance = (position - anchor).length();
+        let scale = MIN_SCALE + (1.0 - MIN_SCALE) * (1.0 - distance / LOOKAHEAD).clamp(0.0, 1.0);
+        let scale = if enemy.kind == EnemyKind::Boss {
+            scale * 1.6
+        } else {
+            scale
+        };
+        let placed = Transform::from_translation(anchor.extend(INDICATOR_Z))
+            .with_rotation(Quat::from_rotation_arc_2d(Vec2::Y, heading))
+            .with_scale(Vec3::splat(scale));
+
+        match existing
+            .remove(&target)
+            .and_then(|entity| indicators.get_mut(entity).ok())
+        {
+            Some((_, _, mut transform)) => *transform = placed,
+            None => {
+                let material = if enemy.kind == EnemyKind::Boss {
+                    assets.boss.clone()
+                } else {
+                    assets.enemy.clone()
+                };
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: assets.arrow.clone(),
+                        material,
+                        transform: placed,
+                        ..default()
+                    },
+                    ThreatIndicator { target },
+                ));
+            }
+        }
+    }
+
+    for entity in existing.into_values() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn cleanup_threat_indicators(
+    mut commands: Commands,
+    indicators: Query<Entity, With<ThreatIndicator>>,
+) {
+    for entity in &indicators {
+        commands.entity(entity).despawn_recursive();
+    }
+}
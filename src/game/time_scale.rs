@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use super::{
+    boss::BossEvent,
+    debug::DebugOptions,
+    player::PlayerLifeLostEvent,
+    states::{AppState, InRun},
+};
+
+const BOSS_KILL_SLOW_MOTION: (f32, f32) = (0.3, 0.6);
+const LIFE_LOST_SLOW_MOTION: (f32, f32) = (0.3, 0.4);
+const SLOW_MOTION_RECOVERY: f32 = 0.15;
+
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SlowMotion>()
+            .add_systems(
+                Update,
+                (trigger_slow_motion, apply_slow_motion)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(InRun), reset_slow_motion);
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct SlowMotion {
+    scale: f32,
+    remaining: f32,
+}
+
+impl Default for SlowMotion {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            remaining: 0.0,
+        }
+    }
+}
+
+impl SlowMotion {
+    pub fn trigger(&mut self, scale: f32, duration: f32) {
+        if self.remaining <= 0.0 || scale < self.scale {
+            self.scale = scale;
+        }
+        self.remaining = self.remaining.max(duration);
+    }
+
+    pub fn active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    fn current_speed(&self) -> f32 {
+        if self.remaining <= 0.0 {
+            return 1.0;
+        }
+        let recovery = (1.0 - self.remaining / SLOW_MOTION_RECOVERY).clamp(0.0, 1.0);
+        self.scale + (1.0 - self.scale) * recovery
+    }
+}
+
+fn trigger_slow_motion(
+    mut slow_motion: ResMut<SlowMotion>,
+    mut boss_events: EventReader<BossEvent>,
+    mut life_events: EventReader<PlayerLifeLostEvent>,
+) {
+    for event in boss_events.read() {
+        if *event == BossEvent::Defeated {
+            let (scale, duration) = BOSS_KILL_SLOW_MOTION;
+            slow_motion.trigger(scale, duration);
+        }
+    }
+    for _ in life_events.read() {
+        let (scale, duration) = LIFE_LOST_SLOW_MOTION;
+        slow_motion.trigger(scale, duration);
+    }
+}
+
+// The debug time scale multiplies on top of slow motion, and a debug pause holds
+// slow motion where it is.
+fn apply_slow_motion(
+    mut slow_motion: ResMut<SlowMotion>,
+    debug: Res<DebugOptions>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if !slow_motion.active() {
+        if virtual_time.relative_speed() != debug.time_speed {
+            virtual_time.set_relative_speed(debug.time_speed);
+        }
+        return;
+    }
+
+    if !virtual_time.is_paused() {
+        slow_motion.remaining = (slow_motion.remaining - real_time.delta_seconds()).max(0.0);
+    }
+    virtual_time.set_relative_speed(slow_motion.current_speed() * debug.time_speed);
+}
+
+fn reset_slow_motion(
+    mut slow_motion: ResMut<SlowMotion>,
+    debug: Res<DebugOptions>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    slow_motion.remaining = 0.0;
+    slow_motion.scale = 1.0;
+    virtual_time.set_relative_speed(debug.time_speed);
+}
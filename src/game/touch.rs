@@ -0,0 +1,196 @@
+use bevy::{input::touch::Touches, prelude::*};
+
+use super::{
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    camera::MainCamera,
+    states::{AppState, InRun},
+    typography::{TextSize, UiFont},
+};
+
+const BUTTON_SIZE: f32 = 88.0;
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.12);
+const BUTTON_PRESSED_COLOR: Color = Color::srgba(0.6, 0.85, 1.0, 0.4);
+const BUTTON_BORDER: Color = Color::srgba(0.6, 0.85, 1.0, 0.6);
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            .add_systems(OnEnter(InRun), spawn_touch_buttons)
+            .add_systems(OnExit(InRun), (cleanup_touch_buttons, reset_touch_controls))
+            .add_systems(
+                Update,
+                (touch_buttons, track_touch_steering, show_touch_buttons)
+                    .chain()
+                    .run_if(
+                        in_state(AppState::Playing).and_then(not(resource_exists::<DemoPilot>)),
+                    ),
+            );
+    }
+}
+
+// The touch screen's share of the ship's controls, read by the player systems the
+// same way as the demo pilot. A finger held anywhere outside the on-screen buttons
+// steers: the ship moves by however far the finger moves, wherever it started, and
+// fires for as long as it stays down.
+#[derive(Resource, Debug, Default)]
+pub struct TouchControls {
+    // Playfield distance dragged since the last fixed tick moved the ship.
+    pub drag: Vec2,
+    pub firing: bool,
+    pub bomb_requested: bool,
+    steering: Option<u64>,
+    // The buttons stay hidden until the first touch, so mouse and keyboard players
+    // never see them.
+    seen: bool,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum TouchButton {
+    Bomb,
+    Pause,
+}
+
+fn spawn_touch_buttons(mut commands: Commands, font: Res<UiFont>) {
+    let style = font.style(TextSize::Caption, Color::WHITE);
+    for (button, label, position) in [
+        (
+            TouchButton::Bomb,
+            "BOMB",
+            Style {
+                right: Val::Px(24.0),
+                bottom: Val::Px(96.0),
+                ..default()
+            },
+        ),
+        (
+            TouchButton::Pause,
+            "II",
+            Style {
+                left: Val::Percent(50.0),
+                top: Val::Px(12.0),
+                margin: UiRect::left(Val::Px(-BUTTON_SIZE * 0.35)),
+                ..default()
+            },
+        ),
+    ] {
+        let size = if button == TouchButton::Bomb {
+            BUTTON_SIZE
+        } else {
+            BUTTON_SIZE * 0.7
+        };
+        commands
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        border: UiRect::all(Val::Px(2.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..position
+                    },
+                    background_color: BackgroundColor(BUTTON_COLOR),
+                    border_color: BorderColor(BUTTON_BORDER),
+                    border_radius: BorderRadius::all(Val::Percent(50.0)),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                button,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(label, style.clone()));
+            });
+    }
+}
+
+fn touch_buttons(
+    mut controls: ResMut<TouchControls>,
+    mut buttons: Query<(&TouchButton, &Interaction, &mut BackgroundColor), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    for (button, interaction, mut background) in &mut buttons {
+        let pressed = *interaction == Interaction::Pressed;
+        background.0 = if pressed {
+            BUTTON_PRESSED_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+        if !pressed {
+            continue;
+        }
+        match button {
+            TouchButton::Bomb => controls.bomb_requested = true,
+            TouchButton::Pause => {
+                next_state.set(AppState::Paused);
+                audio.send(AudioCue::new(Sfx::UiSelect));
+            }
+        }
+    }
+}
+
+fn track_touch_steering(
+    touches: Res<Touches>,
+    mut controls: ResMut<TouchControls>,
+    buttons: Query<&Interaction, With<TouchButton>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    if touches.any_just_pressed() {
+        controls.seen = true;
+    }
+    // A touch that lands on a button belongs to that button, not the ship.
+    let on_button = buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    if let Some(id) = controls.steering
+        && touches.get_pressed(id).is_none()
+    {
+        controls.steering = None;
+    }
+    if controls.steering.is_none()
+        && !on_button
+        && let Some(touch) = touches.iter_just_pressed().next()
+    {
+        controls.steering = Some(touch.id());
+    }
+
+    let steering = controls.steering.and_then(|id| touches.get_pressed(id));
+    controls.firing = steering.is_some();
+    let (Some(touch), Ok((camera, transform))) = (steering, camera.get_single()) else {
+        return;
+    };
+    // Only the difference matters, so the viewport's offset inside the window cancels.
+    let world = |position| camera.viewport_to_world_2d(transform, position);
+    if let (Some(from), Some(to)) = (world(touch.previous_position()), world(touch.position())) {
+        controls.drag += to - from;
+    }
+}
+
+fn show_touch_buttons(
+    controls: Res<TouchControls>,
+    mut buttons: Query<&mut Visibility, With<TouchButton>>,
+) {
+    if !controls.is_changed() || !controls.seen {
+        return;
+    }
+    for mut visibility in &mut buttons {
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+fn cleanup_touch_buttons(mut commands: Commands, buttons: Query<Entity, With<TouchButton>>) {
+    for entity in &buttons {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn reset_touch_controls(mut controls: ResMut<TouchControls>) {
+    *controls = TouchControls {
+        seen: controls.seen,
+        ..default()
+    };
+}
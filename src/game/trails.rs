@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+
+use super::{
+    config::GameConfig,
+    effects::{ColorRamp, oldest},
+    states::{AppState, InRun},
+};
+
+pub const FAST_PROJECTILE_SPEED: f32 = 400.0;
+// Past this many live segments the oldest are dropped first.
+const MAX_TRAIL_SEGMENTS: usize = 800;
+
+pub struct TrailsPlugin;
+
+impl Plugin for TrailsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            emit_trails
+                .after(TransformSystem::TransformPropagate)
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (fade_trails, cap_trail_segments)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(OnExit(InRun), cleanup_trails);
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Trail {
+    pub interval: f32,
+    pub lifetime: f32,
+    pub width: f32,
+    pub min_speed: f32,
+    pub colors: ColorRamp,
+    last_position: Option<Vec2>,
+    accumulator: f32,
+}
+
+impl Trail {
+    pub fn new(interval: f32, lifetime: f32, width: f32, colors: ColorRamp) -> Self {
+        Self {
+            interval,
+            lifetime,
+            width,
+            min_speed: 0.0,
+            colors,
+            last_position: None,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn with_min_speed(mut self, min_speed: f32) -> Self {
+        self.min_speed = min_speed;
+        self
+    }
+
+    pub fn projectile() -> Self {
+        Self::new(
+            0.016,
+            0.12,
+            5.0,
+            ColorRamp::new(
+                Color::srgba(1.0, 0.95, 0.6, 0.7),
+                Color::srgba(1.0, 0.5, 0.2, 0.0),
+            ),
+        )
+    }
+
+    pub fn chaser() -> Self {
+        Self::new(
+            0.03,
+            0.3,
+            10.0,
+            ColorRamp::new(
+                Color::srgba(1.0, 0.35, 0.35, 0.5),
+                Color::srgba(0.6, 0.1, 0.3, 0.0),
+            ),
+        )
+    }
+
+    pub fn player() -> Self {
+        Self::new(
+            0.025,
+            0.18,
+            14.0,
+            ColorRamp::new(
+                Color::srgba(0.55, 0.85, 1.0, 0.45),
+                Color::srgba(0.2, 0.4, 1.0, 0.0),
+            ),
+        )
+        .with_min_speed(300.0)
+    }
+}
+
+#[derive(Component)]
+struct TrailSegment {
+    age: f32,
+    lifetime: f32,
+    width: f32,
+    colors: ColorRamp,
+}
+
+fn emit_trails(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(&mut Trail, &GlobalTransform)>,
+) {
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+    let bounds = Vec2::new(config.logical_width, config.logical_height) * 0.5;
+
+    for (mut trail, transform) in &mut query {
+        let position = transform.translation().truncate();
+        let Some(last) = trail.last_position else {
+            trail.last_position = Some(position);
+            continue;
+        };
+
+        trail.accumulator += delta;
+        if trail.accumulator < trail.interval {
+            continue;
+        }
+        let elapsed = trail.accumulator;
+        trail.accumulator = 0.0;
+        trail.last_position = Some(position);
+
+        let offset = position - last;
+        let length = offset.length();
+        if length <= f32::EPSILON || length / elapsed < trail.min_speed {
+            continue;
+        }
+
+        let midpoint = (position + last) * 0.5;
+        // Shots keep flying a little way past the edge before they are culled; their
+        // trail there would never be seen.
+        if midpoint.abs().cmpgt(bounds + trail.width).any() {
+            continue;
+        }
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(midpoint.extend(0.5))
+                    .with_rotation(Quat::from_rotation_z(offset.to_angle()))
+                    .with_scale(Vec3::new(length + trail.width * 0.5, trail.width, 1.0)),
+                sprite: Sprite {
+                    color: trail.colors.start,
+                    custom_size: Some(Vec2::ONE),
+                    ..default()
+                },
+                ..default()
+            },
+            TrailSegment {
+                age: 0.0,
+                lifetime: trail.lifetime,
+                width: trail.width,
+                colors: trail.colors,
+            },
+        ));
+    }
+}
+
+fn fade_trails(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut TrailSegment, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut segment, mut transform, mut sprite) in &mut query {
+        segment.age += delta;
+        if segment.age >= segment.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = segment.age / segment.lifetime;
+        transform.scale.y = segment.width * (1.0 - t * 0.7);
+        sprite.color = segment.colors.sample(t);
+    }
+}
+
+fn cap_trail_segments(mut commands: Commands, query: Query<(Entity, &TrailSegment)>) {
+    let excess = query.iter().len().saturating_sub(MAX_TRAIL_SEGMENTS);
+    if excess == 0 {
+        return;
+    }
+    let ages = query.iter().map(|(entity, segment)| (entity, segment.age));
+    for entity in oldest(ages, excess) {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn cleanup_trails(mut commands: Commands, query: Query<Entity, With<TrailSegment>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,244 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    config::{InputAction, KeyBindings, key_label},
+    input_device::DevicePrompt,
+    player::{Player, Velocity},
+    profiles::CurrentProfile,
+    run_stats::RunStats,
+    states::{AppState, InRun},
+    storage::{load_json, save_json},
+    typography::{TextSize, UiFont},
+    weapons::PlayerFireEvent,
+};
+
+pub const TUTORIAL_FILE: &str = "tutorial.json";
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(Tutorial::load(&profile))
+            .add_systems(
+                OnEnter(InRun),
+                spawn_tutorial_prompt.run_if(not(resource_exists::<DemoPilot>)),
+            )
+            .add_systems(
+                Update,
+                advance_tutorial.run_if(
+                    in_state(AppState::Playing).and_then(resource_exists::<TutorialProgress>),
+                ),
+            )
+            .add_systems(
+                OnEnter(AppState::Paused),
+                complete_pause_step.run_if(resource_exists::<TutorialProgress>),
+            )
+            .add_systems(OnExit(InRun), cleanup_tutorial);
+    }
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct Tutorial {
+    pub seen: bool,
+}
+
+impl Tutorial {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        load_json(&profile.data_path(TUTORIAL_FILE))
+    }
+
+    pub fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.data_path(TUTORIAL_FILE), self);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TutorialStep {
+    Move,
+    Fire,
+    CollectPowerUp,
+    Pause,
+}
+
+impl TutorialStep {
+    fn next(self) -> Option<TutorialStep> {
+        match self {
+            TutorialStep::Move => Some(TutorialStep::Fire),
+            TutorialStep::Fire => Some(TutorialStep::CollectPowerUp),
+            TutorialStep::CollectPowerUp => Some(TutorialStep::Pause),
+            TutorialStep::Pause => None,
+        }
+    }
+
+    fn prompt(self, bindings: &KeyBindings) -> DevicePrompt {
+        match self {
+            TutorialStep::Move => {
+                let keys: String = [
+                    InputAction::MoveUp,
+                    InputAction::MoveLeft,
+                    InputAction::MoveDown,
+                    InputAction::MoveRight,
+                ]
+                .into_iter()
+                .map(|action| key_label(bindings.key(action)))
+                .collect();
+                let text = format!("Fly with {keys} or the arrow keys");
+                DevicePrompt::new(text.clone(), text)
+            }
+            TutorialStep::Fire => {
+                let text = format!("Hold {} to fire", key_label(bindings.fire));
+                DevicePrompt::new(text.clone(), text)
+            }
+            TutorialStep::CollectPowerUp => {
+                let text = "Fly into a glowing power-up to collect it";
+                DevicePrompt::new(text, text)
+            }
+            TutorialStep::Pause => DevicePrompt::new(
+                format!("Press {} to pause", key_label(bindings.pause)),
+                "Press Start to pause",
+            ),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct TutorialProgress {
+    step: TutorialStep,
+}
+
+#[derive(Component)]
+struct TutorialPrompt;
+
+#[derive(Component)]
+struct TutorialPromptText;
+
+fn spawn_tutorial_prompt(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    bindings: Res<KeyBindings>,
+    font: Res<UiFont>,
+) {
+    if tutorial.seen {
+        return;
+    }
+    let step = TutorialStep::Move;
+    commands.insert_resource(TutorialProgress { step });
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(140.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            TutorialPrompt,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(18.0), Val::Px(8.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.55)),
+                    ..default()
+                })
+                .with_children(|panel| {
+                    panel.spawn((
+                        TextBundle::from_section(
+                            "",
+                            font.style(TextSize::Body, Color::srgb(1.0, 0.85, 0.4)),
+                        ),
+                        step.prompt(&bindings),
+                        TutorialPromptText,
+                    ));
+                    panel.spawn(TextBundle::from_section(
+                        "The tutorial can be turned off in Settings",
+                        font.style(TextSize::Caption, Color::srgb(0.7, 0.8, 0.9)),
+                    ));
+                });
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn advance_tutorial(
+    mut commands: Commands,
+    mut progress: ResMut<TutorialProgress>,
+    mut tutorial: ResMut<Tutorial>,
+    profile: Res<CurrentProfile>,
+    bindings: Res<KeyBindings>,
+    player: Query<&Velocity, With<Player>>,
+    mut shots: EventReader<PlayerFireEvent>,
+    run_stats: Res<RunStats>,
+    mut text: Query<&mut DevicePrompt, With<TutorialPromptText>>,
+    prompts: Query<Entity, With<TutorialPrompt>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    let fired = shots.read().count() > 0;
+    if tutorial.seen {
+        finish_tutorial(&mut commands, &prompts);
+        return;
+    }
+    let done = match progress.step {
+        TutorialStep::Move => player
+            .get_single()
+            .is_ok_and(|velocity| velocity.0 != Vec2::ZERO),
+        TutorialStep::Fire => fired,
+        TutorialStep::CollectPowerUp => run_stats.powerups_collected > 0,
+        TutorialStep::Pause => false,
+    };
+    if !done {
+        return;
+    }
+
+    audio.send(AudioCue::new(Sfx::UiSelect));
+    match progress.step.next() {
+        Some(step) => {
+            progress.step = step;
+            for mut prompt in &mut text {
+                *prompt = step.prompt(&bindings);
+            }
+        }
+        None => {
+            tutorial.seen = true;
+            tutorial.save(&profile);
+            finish_tutorial(&mut commands, &prompts);
+        }
+    }
+}
+
+fn complete_pause_step(
+    mut commands: Commands,
+    progress: Res<TutorialProgress>,
+    mut tutorial: ResMut<Tutorial>,
+    profile: Res<CurrentProfile>,
+    prompts: Query<Entity, With<TutorialPrompt>>,
+) {
+    if progress.step != TutorialStep::Pause {
+        return;
+    }
+    tutorial.seen = true;
+    tutorial.save(&profile);
+    finish_tutorial(&mut commands, &prompts);
+}
+
+fn finish_tutorial(commands: &mut Commands, prompts: &Query<Entity, With<TutorialPrompt>>) {
+    for entity in prompts {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<TutorialProgress>();
+}
+
+fn cleanup_tutorial(mut commands: Commands, prompts: Query<Entity, With<TutorialPrompt>>) {
+    finish_tutorial(&mut commands, &prompts);
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use super::loading::LoadingAssets;
+
+const UI_FONT_PATH: &str = "fonts/monogram.ttf";
+
+pub struct TypographyPlugin;
+
+impl Plugin for TypographyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiFont>()
+            .add_systems(Startup, track_ui_font);
+    }
+}
+
+// monogram is drawn on a 16px grid, so the presets stay on multiples of 8 to
+// keep the pixels crisp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextSize {
+    Title,
+    Heading,
+    Body,
+    Caption,
+}
+
+impl TextSize {
+    pub fn px(self) -> f32 {
+        match self {
+            TextSize::Title => 96.0,
+            TextSize::Heading => 64.0,
+            TextSize::Body => 32.0,
+            TextSize::Caption => 24.0,
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct UiFont(Handle<Font>);
+
+impl FromWorld for UiFont {
+    fn from_world(world: &mut World) -> Self {
+        UiFont(world.resource::<AssetServer>().load(UI_FONT_PATH))
+    }
+}
+
+fn track_ui_font(font: Res<UiFont>, mut loading: ResMut<LoadingAssets>) {
+    loading.track(font.0.clone());
+}
+
+impl UiFont {
+    pub fn style(&self, size: TextSize, color: Color) -> TextStyle {
+        TextStyle {
+            font: self.0.clone(),
+            font_size: size.px(),
+            color,
+        }
+    }
+}
@@ -2,45 +2,141 @@ use bevy::prelude::*;
 
 use super::{
     AppState,
-    audio::AudioCue,
-    boss::BossState,
-    config::{Difficulty, GameSettings},
-    player::PlayerStats,
+    attract::DemoPilot,
+    audio::{AudioCue, Sfx},
+    boss::{BOSS_PHASE_THRESHOLDS, BossState},
+    camera::{LetterboxCamera, PlayfieldViewport},
+    challenge::{ChallengeRun, ChallengeScores, spawn_challenge_table},
+    config::{GameSettings, InputAction, KeyBindings, RunDifficulty, ScreenLayout, key_label},
+    highscores::{HighScores, spawn_high_score_table},
+    input_device::{DevicePrompt, LastInputDevice, gamepad_just_pressed},
+    menu::{MenuActivated, MenuFocus, MenuSet, clear_menu_focus, spawn_text_button},
+    palette::{hull_colors, ship_paint_color},
+    player::{MAX_FIRE_RATE_LEVEL, Player, PlayerDefense, PlayerStats, PlayerWeaponState},
+    practice::PracticeRun,
+    profiles::{CurrentProfile, PROFILE_SLOTS, SwitchProfileEvent},
+    rules::RuleSet,
+    run_stats::RunStats,
+    run_timer::RunTimer,
+    savegame::{ResumeRunEvent, SuspendRunEvent, SuspendedRun},
+    settings_menu::capturing_key,
+    ship_sprites::{ShipRow, ShipSpriteAssets, ShipSpriteId},
+    spawn::{BonusStageEvent, Storyboard, WaveDirector, WaveStartedEvent, start_next_loop},
+    states::{InRun, SettingsOrigin},
+    typography::{TextSize, UiFont},
 };
 
+const HUD_LIFE_ICON_SIZE: f32 = 28.0;
+const HULL_FILL_RATE: f32 = 6.0;
+const HULL_FLASH_SECONDS: f32 = 0.35;
+const WAVE_BANNER_SECONDS: f32 = 2.0;
+// A second R inside this window confirms a quick restart.
+const RESTART_CONFIRM_SECONDS: f32 = 2.0;
+const BOSS_BAR_WIDTH: f32 = 420.0;
+const BOSS_CHIP_DELAY: f32 = 0.6;
+const BOSS_CHIP_DRAIN_RATE: f32 = 0.5;
+const BOSS_BIG_HIT_RATIO: f32 = 0.03;
+const BOSS_SHAKE_SECONDS: f32 = 0.3;
+const BOSS_SHAKE_PIXELS: f32 = 6.0;
+const FIRE_RATE_PIP_EMPTY: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+const FIRE_RATE_PIP_FULL: Color = Color::srgb(1.0, 0.8, 0.3);
+// Bars narrower than this stay plain black rather than squeezing in a side panel.
+const SIDE_PANEL_MIN_WIDTH: f32 = 160.0;
+
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ScoreBoard>()
             .register_type::<ScoreBoard>()
+            .add_systems(OnEnter(AppState::Title), spawn_title_screen)
+            .add_systems(
+                Update,
+                (title_input.after(MenuSet), refresh_title_profile)
+                    .run_if(in_state(AppState::Title)),
+            )
+            .add_systems(
+                OnExit(AppState::Title),
+                (cleanup_ui::<TitleScreen>, clear_menu_focus),
+            )
+            .add_systems(
+                OnEnter(InRun),
+                ((reset_scoreboard, spawn_hud).chain(), spawn_side_panels),
+            )
             .add_systems(
-                OnEnter(AppState::Title),
-                (reset_scoreboard, spawn_title_screen),
+                OnExit(InRun),
+                (cleanup_ui::<HudRoot>, cleanup_ui::<SidePanel>),
             )
+            .add_systems(Update, update_side_panels.run_if(in_state(InRun)))
             .add_systems(
                 Update,
-                (title_input, title_settings_input, title_settings_display)
-                    .run_if(in_state(AppState::Title)),
+                (
+                    hud_update,
+                    hud_hull_bar,
+                    hud_loadout_update,
+                    wave_progress_update,
+                    bonus_stage_banner.after(wave_progress_update),
+                    fade_wave_banner,
+                    low_health_vignette,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, boss_health_bar_update.run_if(in_state(InRun)))
+            .add_systems(
+                Update,
+                mute_input.before(MenuSet).run_if(not(capturing_key)),
+            )
+            .add_systems(
+                Update,
+                (pause_input, quick_restart_input).run_if(
+                    in_state(AppState::Playing).and_then(not(resource_exists::<DemoPilot>)),
+                ),
             )
-            .add_systems(OnExit(AppState::Title), cleanup_ui::<TitleScreen>)
-            .add_systems(OnEnter(AppState::Playing), spawn_hud)
-            .add_systems(OnExit(AppState::Playing), cleanup_ui::<HudRoot>)
-            .add_systems(Update, hud_update.run_if(in_state(AppState::Playing)))
-            .add_systems(Update, boss_health_bar_update)
-            .add_systems(Update, pause_input.run_if(in_state(AppState::Playing)))
             .add_systems(OnEnter(AppState::Paused), spawn_pause_overlay)
-            .add_systems(OnExit(AppState::Paused), cleanup_ui::<PauseOverlay>)
-            .add_systems(Update, resume_input.run_if(in_state(AppState::Paused)))
             .add_systems(
-                OnEnter(AppState::GameOver),
+                OnExit(AppState::Paused),
+                (cleanup_ui::<PauseOverlay>, clear_menu_focus),
+            )
+            .add_systems(
+                Update,
+                (pause_menu_input, pause_audio_display)
+                    .chain()
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::Paused)),
+            )
+            .add_systems(
+                OnEnter(AppState::LevelComplete),
+                spawn_level_complete_screen,
+            )
+            .add_systems(
+                OnExit(AppState::LevelComplete),
+                (cleanup_ui::<LevelCompleteScreen>, clear_menu_focus),
+            )
+            .add_systems(
+                Update,
+                level_complete_input
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::LevelComplete)),
+            );
+        // The victory screen is the game-over screen with a different headline.
+        for state in [AppState::GameOver, AppState::Victory] {
+            app.add_systems(
+                OnEnter(state),
                 (spawn_game_over_screen, init_game_over_cooldown),
             )
             .add_systems(
-                OnExit(AppState::GameOver),
-                (cleanup_ui::<GameOverScreen>, clear_game_over_cooldown),
+                OnExit(state),
+                (
+                    cleanup_ui::<GameOverScreen>,
+                    clear_game_over_cooldown,
+                    clear_menu_focus,
+                ),
             )
-            .add_systems(Update, game_over_input.run_if(in_state(AppState::GameOver)));
+            .add_systems(
+                Update,
+                game_over_input.after(MenuSet).run_if(in_state(state)),
+            );
+        }
     }
 }
 
@@ -54,13 +150,73 @@ pub struct ScoreBoard {
 struct TitleScreen;
 
 #[derive(Component)]
-struct TitleDifficultyText;
+struct TitleHighScores;
 
-#[derive(Component)]
-struct TitleMusicText;
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum TitleOption {
+    Start,
+    Resume,
+    Practice,
+    Challenges,
+    Editor,
+    Settings,
+    Career,
+    Profile,
+    Quit,
+}
 
-#[derive(Component)]
-struct TitleSfxText;
+impl TitleOption {
+    const ALL: [TitleOption; 9] = [
+        TitleOption::Start,
+        TitleOption::Resume,
+        TitleOption::Practice,
+        TitleOption::Challenges,
+        TitleOption::Editor,
+        TitleOption::Settings,
+        TitleOption::Career,
+        TitleOption::Profile,
+        TitleOption::Quit,
+    ];
+
+    fn label(self, profile: &CurrentProfile, suspended: &SuspendedRun) -> String {
+        match self {
+            TitleOption::Start => "Start Game".to_string(),
+            TitleOption::Resume => match &suspended.0 {
+                Some(run) if run.loop_index > 0 => format!(
+                    "Resume Run - Loop {} Level {}",
+                    run.loop_index + 1,
+                    run.level_index + 1
+                ),
+                Some(run) => format!("Resume Run - Level {}", run.level_index + 1),
+                None => "Resume Run".to_string(),
+            },
+            TitleOption::Practice => "Practice".to_string(),
+            TitleOption::Challenges => "Challenges".to_string(),
+            TitleOption::Editor => "Storyboard Editor".to_string(),
+            TitleOption::Settings => "Settings".to_string(),
+            TitleOption::Career => "Career Stats".to_string(),
+            TitleOption::Profile => format!("Profile: {}", profile.label()),
+            TitleOption::Quit => "Quit".to_string(),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum GameOverOption {
+    SignName,
+    PlayAgain,
+    Title,
+}
+
+impl GameOverOption {
+    fn label(self) -> &'static str {
+        match self {
+            GameOverOption::SignName => "Sign the High Score Table",
+            GameOverOption::PlayAgain => "Play Again",
+            GameOverOption::Title => "Back to Title",
+        }
+    }
+}
 
 #[derive(Component)]
 struct HudRoot;
@@ -68,14 +224,74 @@ struct HudRoot;
 #[derive(Component)]
 struct HudScoreText;
 
+// Fills a bar beside a playfield held to its aspect ratio: ship art on the left, the
+// score on the right. Drawn by the letterbox camera, since the main one only covers
+// the playfield.
+#[derive(Component)]
+pub struct SidePanel;
+
+#[derive(Component)]
+struct SidePanelStats;
+
+#[derive(Component)]
+struct HudLifeIcon(u8);
+
+#[derive(Component)]
+struct HudHullBar {
+    displayed: f32,
+    flash: f32,
+    last_health: u8,
+}
+
+#[derive(Component)]
+struct HudHullSegment(u8);
+
+#[derive(Component)]
+struct HudWeaponText;
+
+#[derive(Component)]
+struct HudFireRatePip(u8);
+
+#[derive(Component)]
+struct HudDefenseText;
+
+#[derive(Component)]
+struct HudBombText;
+
+#[derive(Component)]
+struct HudWaveText;
+
+#[derive(Component)]
+struct HudWaveFill;
+
+#[derive(Component)]
+struct LevelCompleteScreen;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum LevelCompleteOption {
+    Continue,
+    // Offered after the final level instead of Continue.
+    NextLoop,
+    EndRun,
+}
+
+#[derive(Component)]
+struct WaveBanner {
+    timer: Timer,
+}
+
 #[derive(Component)]
-struct HudLivesText;
+struct RestartPrompt {
+    timer: Timer,
+}
 
 #[derive(Component)]
-struct HudHealthFill;
+struct LowHealthOverlay;
 
 #[derive(Component)]
-struct HudHealthBar;
+struct LowHealthVignette {
+    base_alpha: f32,
+}
 
 #[derive(Component)]
 struct GameOverScreen;
@@ -84,11 +300,54 @@ struct GameOverScreen;
 struct PauseOverlay;
 
 #[derive(Component)]
-struct BossHealthBar;
+struct PauseAudioText;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseOption {
+    Resume,
+    Restart,
+    Settings,
+    SaveAndQuit,
+    Quit,
+}
+
+impl PauseOption {
+    const ALL: [PauseOption; 5] = [
+        PauseOption::Resume,
+        PauseOption::Restart,
+        PauseOption::Settings,
+        PauseOption::SaveAndQuit,
+        PauseOption::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PauseOption::Resume => "Resume",
+            PauseOption::Restart => "Restart",
+            PauseOption::Settings => "Settings",
+            PauseOption::SaveAndQuit => "Save & Quit",
+            PauseOption::Quit => "Quit to Title",
+        }
+    }
+}
+
+#[derive(Component)]
+struct BossHealthBar {
+    chip: f32,
+    chip_delay: f32,
+    shake: f32,
+    last_health: f32,
+}
 
 #[derive(Component)]
 struct BossHealthFill;
 
+#[derive(Component)]
+struct BossChipFill;
+
+#[derive(Component)]
+struct BossNameText;
+
 #[derive(Resource)]
 struct GameOverCooldown(Timer);
 
@@ -96,19 +355,30 @@ fn reset_scoreboard(mut scoreboard: ResMut<ScoreBoard>) {
     scoreboard.score = 0;
 }
 
-fn spawn_title_screen(mut commands: Commands) {
-    let title_style = TextStyle {
-        font_size: 56.0,
-        color: Color::WHITE,
-        ..default()
-    };
+fn spawn_title_screen(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    profile: Res<CurrentProfile>,
+    suspended: Res<SuspendedRun>,
+    bindings: Res<KeyBindings>,
+    font: Res<UiFont>,
+) {
+    let title_style = font.style(TextSize::Title, Color::WHITE);
 
-    let instructions_style = TextStyle {
-        font_size: 24.0,
-        color: Color::srgb(0.7, 0.9, 1.0),
-        ..default()
-    };
+    let instructions_style = font.style(TextSize::Body, Color::srgb(0.7, 0.9, 1.0));
+    let option_style = font.style(TextSize::Body, Color::WHITE);
+
+    let movement_keys: String = [
+        InputAction::MoveUp,
+        InputAction::MoveLeft,
+        InputAction::MoveDown,
+        InputAction::MoveRight,
+    ]
+    .into_iter()
+    .map(|action| key_label(bindings.key(action)))
+    .collect();
 
+    commands.insert_resource(MenuFocus::new(TitleOption::ALL.len()));
     commands
         .spawn((
             NodeBundle {
@@ -128,42 +398,78 @@ fn spawn_title_screen(mut commands: Commands) {
         ))
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section("S-FORCE", title_style));
+            for (index, option) in TitleOption::ALL.into_iter().enumerate() {
+                spawn_text_button(
+                    parent,
+                    index,
+                    &option.label(&profile, &suspended),
+                    280.0,
+                    option_style.clone(),
+                    option,
+                );
+            }
             parent.spawn(TextBundle::from_sections([
                 TextSection::new(
-                    "Press Space or Enter to Start\n",
-                    instructions_style.clone(),
-                ),
-                TextSection::new("WASD / Arrow Keys to move\n", instructions_style.clone()),
-                TextSection::new(
-                    "Hold Space or Left Click to fire\n",
+                    format!("{} / Arrow Keys to move\n", movement_keys),
                     instructions_style.clone(),
                 ),
                 TextSection::new(
-                    "Tab=Difficulty  |  -/+ Music  |  [/] SFX",
+                    format!("Hold {} or Left Click to fire", key_label(bindings.fire)),
                     instructions_style.clone(),
                 ),
             ]));
             parent.spawn((
-                TextBundle::from_section("Difficulty: ", instructions_style.clone()),
-                TitleDifficultyText,
-            ));
-            parent.spawn((
-                TextBundle::from_section("Music Volume: ", instructions_style.clone()),
-                TitleMusicText,
-            ));
-            parent.spawn((
-                TextBundle::from_section("SFX Volume: ", instructions_style),
-                TitleSfxText,
+                TextBundle::from_section("", instructions_style),
+                DevicePrompt::new(
+                    "Tab = Settings  |  M = Mute",
+                    "Start = Play  |  Select = Settings",
+                ),
             ));
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(32.0),
+                            right: Val::Px(32.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    TitleHighScores,
+                ))
+                .with_children(|table| {
+                    spawn_high_score_table(
+                        table,
+                        &high_scores,
+                        5,
+                        font.style(TextSize::Caption, Color::srgb(0.7, 0.9, 1.0)),
+                    );
+                });
         });
 }
 
-fn spawn_hud(mut commands: Commands, stats: Res<PlayerStats>, scoreboard: Res<ScoreBoard>) {
-    let label_style = TextStyle {
-        font_size: 24.0,
-        color: Color::WHITE,
-        ..default()
-    };
+#[allow(clippy::too_many_arguments)]
+fn spawn_hud(
+    mut commands: Commands,
+    stats: Res<PlayerStats>,
+    settings: Res<GameSettings>,
+    run_difficulty: Res<RunDifficulty>,
+    scoreboard: Res<ScoreBoard>,
+    sprites: Res<ShipSpriteAssets>,
+    font: Res<UiFont>,
+    practice: Option<Res<PracticeRun>>,
+    rules: Res<RuleSet>,
+) {
+    let label_style = font.style(TextSize::Body, Color::WHITE);
+    let starting_lives = practice
+        .map(|practice| practice.setup.lives)
+        .or(rules.lives)
+        .unwrap_or(run_difficulty.knobs().starting_lives);
+    let ship_sprite = ShipSpriteId::player(settings.ship);
+    let ship = sprites.data(ship_sprite);
+    let ship_frame = sprites.sequence(ship_sprite, ShipRow::Idle)[0];
+    let icon_width = HUD_LIFE_ICON_SIZE * ship.frame_size.x / ship.frame_size.y;
 
     commands
         .spawn((
@@ -189,14 +495,36 @@ fn spawn_hud(mut commands: Commands, stats: Res<PlayerStats>, scoreboard: Res<Sc
                 ),
                 HudScoreText,
             ));
-            parent.spawn((
-                TextBundle::from_section(format!("Lives: {}", stats.lives), label_style.clone()),
-                HudLivesText,
-            ));
-            parent.spawn(TextBundle::from_section(
-                "Hull Integrity",
-                label_style.clone(),
-            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    // Player stats may not be reset yet, so size the row from the difficulty,
+                    // the practice setup, or the mission's rules.
+                    for index in 0..starting_lives {
+                        row.spawn((
+                            ImageBundle {
+                                style: Style {
+                                    width: Val::Px(icon_width),
+                                    height: Val::Px(HUD_LIFE_ICON_SIZE),
+                                    ..default()
+                                },
+                                image: UiImage::new(ship.texture.clone()),
+                                ..default()
+                            },
+                            TextureAtlas {
+                                layout: ship.layout.clone(),
+                                index: ship_frame,
+                            },
+                            HudLifeIcon(index),
+                        ));
+                    }
+                });
             parent
                 .spawn((
                     NodeBundle {
@@ -204,309 +532,1377 @@ fn spawn_hud(mut commands: Commands, stats: Res<PlayerStats>, scoreboard: Res<Sc
                             width: Val::Px(220.0),
                             height: Val::Px(18.0),
                             border: UiRect::all(Val::Px(2.0)),
+                            column_gap: Val::Px(3.0),
                             ..default()
                         },
                         background_color: BackgroundColor(Color::srgba(0.15, 0.25, 0.3, 0.8)),
                         ..default()
                     },
-                    HudHealthFill,
+                    HudHullBar {
+                        displayed: stats.health as f32,
+                        flash: 0.0,
+                        last_health: stats.health,
+                    },
                 ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        NodeBundle {
+                .with_children(|bar| {
+                    for index in 0..stats.max_health {
+                        bar.spawn(NodeBundle {
                             style: Style {
-                                width: Val::Percent(stats.health_fraction() * 100.0),
+                                flex_grow: 1.0,
                                 height: Val::Percent(100.0),
                                 ..default()
                             },
-                            background_color: BackgroundColor(Color::srgb(0.3, 0.85, 0.4)),
+                            background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.35)),
                             ..default()
-                        },
-                        HudHealthBar,
-                    ));
+                        })
+                        .with_children(|segment| {
+                            segment.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
+                                        ..default()
+                                    },
+                                    background_color: BackgroundColor(
+                                        hull_colors(settings.palette).healthy,
+                                    ),
+                                    ..default()
+                                },
+                                HudHullSegment(index),
+                            ));
+                        });
+                    }
                 });
         });
 
+    spawn_loadout_cluster(&mut commands, &font, &label_style);
+    spawn_wave_progress(&mut commands, &font);
+
     let mut boss_bar = NodeBundle {
         style: Style {
             position_type: PositionType::Absolute,
             top: Val::Px(16.0),
             left: Val::Percent(35.0),
-            width: Val::Px(420.0),
-            height: Val::Px(18.0),
-            border: UiRect::all(Val::Px(2.0)),
+            width: Val::Px(BOSS_BAR_WIDTH),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
             ..default()
         },
-        background_color: BackgroundColor(Color::srgba(0.2, 0.2, 0.3, 0.6)),
         ..default()
     };
     boss_bar.visibility = Visibility::Hidden;
 
+    let fill_style = Style {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        ..default()
+    };
     commands
-        .spawn((boss_bar, BossHealthBar))
+        .spawn((
+            boss_bar,
+            BossHealthBar {
+                chip: 1.0,
+                chip_delay: 0.0,
+                shake: 0.0,
+                last_health: 0.0,
+            },
+        ))
         .with_children(|parent| {
             parent.spawn((
-                NodeBundle {
+                TextBundle::from_section(
+                    "",
+                    font.style(TextSize::Caption, Color::srgb(1.0, 0.75, 0.7)),
+                ),
+                BossNameText,
+            ));
+            parent
+                .spawn(NodeBundle {
                     style: Style {
                         width: Val::Percent(100.0),
-                        height: Val::Percent(100.0),
+                        height: Val::Px(18.0),
+                        border: UiRect::all(Val::Px(2.0)),
                         ..default()
                     },
-                    background_color: BackgroundColor(Color::srgb(0.95, 0.32, 0.36)),
+                    background_color: BackgroundColor(Color::srgba(0.2, 0.2, 0.3, 0.6)),
                     ..default()
-                },
-                BossHealthFill,
-            ));
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: fill_style.clone(),
+                            background_color: BackgroundColor(Color::srgb(1.0, 0.9, 0.8)),
+                            ..default()
+                        },
+                        BossChipFill,
+                    ));
+                    bar.spawn((
+                        NodeBundle {
+                            style: fill_style.clone(),
+                            background_color: BackgroundColor(Color::srgb(0.95, 0.32, 0.36)),
+                            ..default()
+                        },
+                        BossHealthFill,
+                    ));
+                    for threshold in BOSS_PHASE_THRESHOLDS {
+                        bar.spawn(NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(threshold * 100.0),
+                                width: Val::Px(2.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.8)),
+                            ..default()
+                        });
+                    }
+                });
         });
-}
 
-fn hud_update(
-    scoreboard: Res<ScoreBoard>,
-    stats: Res<PlayerStats>,
-    mut queries: ParamSet<(
-        Query<&mut Text, With<HudScoreText>>,
-        Query<&mut Style, With<HudHealthBar>>,
-        Query<&mut Text, With<HudLivesText>>,
-    )>,
-) {
-    if scoreboard.is_changed() {
-        if let Ok(mut text) = queries.p0().get_single_mut() {
-            text.sections[0].value = format!("Score: {}", scoreboard.score);
-        }
-    }
-    if stats.is_changed() {
-        if let Ok(mut style) = queries.p1().get_single_mut() {
-            style.width = Val::Percent((stats.health_fraction() * 100.0).max(0.0));
-        }
-        if let Ok(mut text) = queries.p2().get_single_mut() {
-            text.sections[0].value = format!("Lives: {}", stats.lives);
-        }
-    }
+    spawn_low_health_vignette(&mut commands);
 }
 
-fn spawn_game_over_screen(mut commands: Commands, scoreboard: Res<ScoreBoard>) {
-    let title_style = TextStyle {
-        font_size: 48.0,
-        color: Color::WHITE,
-        ..default()
-    };
-    let info_style = TextStyle {
-        font_size: 24.0,
-        color: Color::srgb(0.8, 0.85, 1.0),
-        ..default()
-    };
-
+fn spawn_loadout_cluster(commands: &mut Commands, font: &UiFont, label_style: &TextStyle) {
+    let small_style = font.style(TextSize::Caption, Color::srgb(0.8, 0.9, 1.0));
     commands
         .spawn((
             NodeBundle {
                 style: Style {
                     position_type: PositionType::Absolute,
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
+                    bottom: Val::Px(16.0),
+                    left: Val::Px(16.0),
                     flex_direction: FlexDirection::Column,
-                    row_gap: Val::Px(16.0),
+                    row_gap: Val::Px(6.0),
                     ..default()
                 },
-                background_color: BackgroundColor(Color::NONE),
                 ..default()
             },
-            GameOverScreen,
+            HudRoot,
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section("Game Over", title_style.clone()));
-            parent.spawn(TextBundle::from_section(
-                format!("Final Score: {}", scoreboard.score),
-                info_style.clone(),
+            parent.spawn((
+                TextBundle::from_section("", label_style.clone()),
+                HudWeaponText,
             ));
-            parent.spawn(TextBundle::from_section(
-                "Press Enter to return to Title",
-                info_style,
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(TextBundle::from_section("Rate ", small_style.clone()));
+                    for index in 0..MAX_FIRE_RATE_LEVEL {
+                        row.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Px(12.0),
+                                    height: Val::Px(12.0),
+                                    ..default()
+                                },
+                                background_color: BackgroundColor(FIRE_RATE_PIP_EMPTY),
+                                ..default()
+                            },
+                            HudFireRatePip(index),
+                        ));
+                    }
+                });
+            parent.spawn((
+                TextBundle::from_section("", small_style.clone()),
+                HudBombText,
             ));
+            parent.spawn((TextBundle::from_section("", small_style), HudDefenseText));
         });
 }
 
-fn init_game_over_cooldown(mut commands: Commands) {
-    commands.insert_resource(GameOverCooldown(Timer::from_seconds(0.6, TimerMode::Once)));
-}
-
-fn clear_game_over_cooldown(mut commands: Commands) {
-    commands.remove_resource::<GameOverCooldown>();
-}
-
-fn spawn_pause_overlay(mut commands: Commands) {
-    let style = TextStyle {
-        font_size: 40.0,
-        color: Color::WHITE,
-        ..default()
-    };
+fn spawn_wave_progress(commands: &mut Commands, font: &UiFont) {
     commands
         .spawn((
             NodeBundle {
                 style: Style {
                     position_type: PositionType::Absolute,
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
+                    top: Val::Px(16.0),
+                    right: Val::Px(16.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    row_gap: Val::Px(4.0),
                     ..default()
                 },
-                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.35)),
                 ..default()
             },
-            PauseOverlay,
+            HudRoot,
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                "Paused\nPress P or Esc to Resume",
-                style,
+            parent.spawn((
+                TextBundle::from_section(
+                    "Level 1",
+                    font.style(TextSize::Caption, Color::srgb(0.8, 0.9, 1.0)),
+                ),
+                HudWaveText,
             ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(160.0),
+                        height: Val::Px(8.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.15)),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(Color::srgb(0.5, 0.85, 1.0)),
+                            ..default()
+                        },
+                        HudWaveFill,
+                    ));
+                });
         });
 }
 
-fn title_input(
-    mut next_state: ResMut<NextState<AppState>>,
-    keys: Res<ButtonInput<KeyCode>>,
-    mut audio: EventWriter<AudioCue>,
-) {
-    if keys.just_pressed(KeyCode::Space) || keys.just_pressed(KeyCode::Enter) {
-        next_state.set(AppState::Playing);
-        audio.send(AudioCue::UiSelect);
-    }
-}
-
-fn pause_input(
-    keys: Res<ButtonInput<KeyCode>>,
+fn spawn_low_health_vignette(commands: &mut Commands) {
+    let layers = [0.45, 0.28, 0.14];
+    let mut parent = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            HudRoot,
+            LowHealthOverlay,
+        ))
+        .id();
+    for base_alpha in layers {
+        let layer = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        border: UiRect::all(Val::Px(16.0)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::srgba(0.9, 0.05, 0.05, base_alpha)),
+                    ..default()
+                },
+                LowHealthVignette { base_alpha },
+            ))
+            .id();
+        commands.entity(parent).add_child(layer);
+        parent = layer;
+    }
+}
+
+fn spawn_side_panels(
+    mut commands: Commands,
+    letterbox: Query<Entity, With<LetterboxCamera>>,
+    settings: Res<GameSettings>,
+    sprites: Res<ShipSpriteAssets>,
+    font: Res<UiFont>,
+) {
+    let Ok(camera) = letterbox.get_single() else {
+        return;
+    };
+    let ship_sprite = ShipSpriteId::player(settings.ship);
+    let ship = sprites.data(ship_sprite);
+    let ship_frame = sprites.sequence(ship_sprite, ShipRow::Idle)[0];
+    for left in [true, false] {
+        let edge = Val::Px(0.0);
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: if left { edge } else { Val::Auto },
+                        right: if left { Val::Auto } else { edge },
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(24.0),
+                        display: Display::None,
+                        ..default()
+                    },
+                    ..default()
+                },
+                SidePanel,
+                TargetCamera(camera),
+            ))
+            .with_children(|panel| {
+                if left {
+                    panel.spawn(TextBundle::from_section(
+                        "S-FORCE",
+                        font.style(TextSize::Heading, Color::srgb(0.55, 0.85, 1.0)),
+                    ));
+                    panel.spawn((
+                        ImageBundle {
+                            style: Style {
+                                width: Val::Px(ship.frame_size.x * ship.scale * 1.5),
+                                height: Val::Px(ship.frame_size.y * ship.scale * 1.5),
+                                ..default()
+                            },
+                            image: UiImage::new(ship.texture.clone())
+                                .with_color(ship_paint_color(settings.paint)),
+                            ..default()
+                        },
+                        TextureAtlas {
+                            layout: ship.layout.clone(),
+                            index: ship_frame,
+                        },
+                    ));
+                } else {
+                    panel.spawn((
+                        TextBundle::from_section("", font.style(TextSize::Body, Color::WHITE))
+                            .with_text_justify(JustifyText::Center),
+                        SidePanelStats,
+                    ));
+                }
+            });
+    }
+}
+
+// The panels show once the bars are wide enough, and the HUD's own score line steps
+// aside while they do.
+#[allow(clippy::too_many_arguments)]
+fn update_side_panels(
+    playfield: Res<PlayfieldViewport>,
+    settings: Res<GameSettings>,
+    ui_scale: Res<UiScale>,
+    scoreboard: Res<ScoreBoard>,
+    high_scores: Res<HighScores>,
+    director: Res<WaveDirector>,
+    mut panels: Query<&mut Style, With<SidePanel>>,
+    mut stats: Query<&mut Text, With<SidePanelStats>>,
+    mut hud_score: Query<&mut Visibility, With<HudScoreText>>,
+) {
+    let width =
+        playfield.position.x as f32 / (playfield.scale_factor * ui_scale.0).max(f32::EPSILON);
+    let shown = settings.screen_layout != ScreenLayout::Tate && width >= SIDE_PANEL_MIN_WIDTH;
+    let display = if shown { Display::Flex } else { Display::None };
+    for mut style in &mut panels {
+        if style.display != display || style.width != Val::Px(width) {
+            style.display = display;
+            style.width = Val::Px(width);
+        }
+    }
+    let visibility = if shown {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut score in &mut hud_score {
+        if *score != visibility {
+            *score = visibility;
+        }
+    }
+    if !shown {
+        return;
+    }
+    let best = high_scores
+        .entries
+        .first()
+        .map_or(0, |entry| entry.score)
+        .max(scoreboard.score);
+    let value = format!(
+        "SCORE\n{}\n\nHIGH SCORE\n{}\n\nLEVEL {}-{}",
+        scoreboard.score,
+        best,
+        director.level_index + 1,
+        director.wave_index.max(1)
+    );
+    for mut text in &mut stats {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+#[allow(clippy::collapsible_if)]
+fn hud_update(
+    scoreboard: Res<ScoreBoard>,
+    stats: Res<PlayerStats>,
+    mut score_text: Query<&mut Text, With<HudScoreText>>,
+    mut life_icons: Query<(&HudLifeIcon, &mut UiImage)>,
+) {
+    if scoreboard.is_changed() {
+        if let Ok(mut text) = score_text.get_single_mut() {
+            text.sections[0].value = format!("Score: {}", scoreboard.score);
+        }
+    }
+    if stats.is_changed() {
+        for (icon, mut image) in &mut life_icons {
+            let alpha = if icon.0 < stats.lives { 1.0 } else { 0.2 };
+            image.color = Color::WHITE.with_alpha(alpha);
+        }
+    }
+}
+
+fn hud_hull_bar(
+    stats: Res<PlayerStats>,
+    settings: Res<GameSettings>,
+    time: Res<Time<Real>>,
+    mut bars: Query<&mut HudHullBar>,
+    mut segments: Query<(&HudHullSegment, &mut Style, &mut BackgroundColor)>,
+) {
+    let Ok(mut bar) = bars.get_single_mut() else {
+        return;
+    };
+    if stats.health < bar.last_health {
+        bar.flash = HULL_FLASH_SECONDS;
+    }
+    bar.last_health = stats.health;
+
+    let delta = time.delta_seconds();
+    let target = stats.health as f32;
+    let step = HULL_FILL_RATE * delta;
+    bar.displayed += (target - bar.displayed).clamp(-step, step);
+    bar.flash = (bar.flash - delta).max(0.0);
+
+    let colors = hull_colors(settings.palette);
+    let base = if stats.is_critical() {
+        colors.critical
+    } else if stats.health_fraction() <= 0.5 {
+        colors.damaged
+    } else {
+        colors.healthy
+    };
+    let flash = bar.flash / HULL_FLASH_SECONDS;
+    let color = base.mix(&Color::WHITE, flash);
+    for (segment, mut style, mut background) in &mut segments {
+        let fill = (bar.displayed - segment.0 as f32).clamp(0.0, 1.0);
+        let width = Val::Percent(fill * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+        background.set_if_neq(BackgroundColor(color));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn hud_loadout_update(
+    weapon_state: Res<PlayerWeaponState>,
+    defense: Query<Ref<PlayerDefense>, With<Player>>,
+    mut weapon_text: Query<&mut Text, (With<HudWeaponText>, Without<HudDefenseText>)>,
+    mut bomb_text: Query<
+        &mut Text,
+        (
+            With<HudBombText>,
+            Without<HudWeaponText>,
+            Without<HudDefenseText>,
+        ),
+    >,
+    mut defense_text: Query<&mut Text, With<HudDefenseText>>,
+    mut pips: Query<(&HudFireRatePip, &mut BackgroundColor)>,
+) {
+    if weapon_state.is_changed() {
+        if let Ok(mut text) = weapon_text.get_single_mut() {
+            text.sections[0].value = format!("Weapon: {}", weapon_state.mode.label());
+        }
+        if let Ok(mut text) = bomb_text.get_single_mut() {
+            text.sections[0].value = format!("Bombs: {}", weapon_state.bombs);
+        }
+        for (pip, mut background) in &mut pips {
+            background.0 = if pip.0 < weapon_state.fire_rate_level {
+                FIRE_RATE_PIP_FULL
+            } else {
+                FIRE_RATE_PIP_EMPTY
+            };
+        }
+    }
+
+    if let Ok(defense) = defense.get_single()
+        && defense.is_changed()
+        && let Ok(mut text) = defense_text.get_single_mut()
+    {
+        let mut parts = Vec::new();
+        if defense.invulnerability > 0.0 {
+            parts.push(format!("Shield {:.1}s", defense.invulnerability));
+        }
+        if defense.reflect_shield > 0.0 {
+            parts.push(format!("Reflect {:.1}s", defense.reflect_shield));
+        }
+        text.sections[0].value = parts.join("  |  ");
+    }
+}
+
+fn wave_progress_update(
+    mut commands: Commands,
+    mut events: EventReader<WaveStartedEvent>,
+    mut text: Query<&mut Text, With<HudWaveText>>,
+    mut fill: Query<&mut Style, With<HudWaveFill>>,
+    banners: Query<Entity, With<WaveBanner>>,
+    director: Res<WaveDirector>,
+    font: Res<UiFont>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+    let label = format!(
+        "{}LEVEL {} - WAVE {}/{}",
+        director.loop_label(),
+        event.level,
+        event.wave,
+        event.wave_count
+    );
+    if let Ok(mut text) = text.get_single_mut() {
+        let loop_prefix = if director.loop_index > 0 {
+            format!("Loop {}  ", director.loop_index + 1)
+        } else {
+            String::new()
+        };
+        text.sections[0].value = format!(
+            "{}Level {}  {}/{}",
+            loop_prefix, event.level, event.wave, event.wave_count
+        );
+    }
+    if let Ok(mut style) = fill.get_single_mut() {
+        style.width = Val::Percent(event.wave as f32 / event.wave_count.max(1) as f32 * 100.0);
+    }
+    spawn_wave_banner(&mut commands, &banners, &font, label);
+}
+
+fn bonus_stage_banner(
+    mut commands: Commands,
+    mut events: EventReader<BonusStageEvent>,
+    banners: Query<Entity, With<WaveBanner>>,
+    font: Res<UiFont>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+    let label = match event {
+        BonusStageEvent::SecretFound => "SECRET FOUND".to_string(),
+        BonusStageEvent::Entered { name } => format!("BONUS STAGE - {}", name.to_uppercase()),
+    };
+    spawn_wave_banner(&mut commands, &banners, &font, label);
+}
+
+// Replaces whatever banner is still fading.
+fn spawn_wave_banner(
+    commands: &mut Commands,
+    banners: &Query<Entity, With<WaveBanner>>,
+    font: &UiFont,
+    label: String,
+) {
+    for entity in banners {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Percent(22.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            WaveBanner {
+                timer: Timer::from_seconds(WAVE_BANNER_SECONDS, TimerMode::Once),
+            },
+            HudRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                font.style(TextSize::Heading, Color::WHITE),
+            ));
+        });
+}
+
+fn fade_wave_banner(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut banners: Query<(Entity, &mut WaveBanner, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (entity, mut banner, children) in &mut banners {
+        if banner.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let remaining = 1.0 - banner.timer.fraction();
+        let alpha = (remaining * WAVE_BANNER_SECONDS / 0.5).min(1.0);
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.sections[0].style.color.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+fn low_health_vignette(
+    stats: Res<PlayerStats>,
+    settings: Res<GameSettings>,
+    time: Res<Time<Real>>,
+    mut overlay: Query<&mut Visibility, With<LowHealthOverlay>>,
+    mut layers: Query<(&LowHealthVignette, &mut BorderColor)>,
+) {
+    let critical = stats.is_critical();
+    if stats.is_changed()
+        && let Ok(mut visibility) = overlay.get_single_mut()
+    {
+        visibility.set_if_neq(if critical {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !critical {
+        return;
+    }
+
+    let pulse = if settings.reduce_flashing {
+        0.8
+    } else {
+        0.65 + 0.35 * (time.elapsed_seconds() * 4.0).sin()
+    };
+    for (layer, mut border) in &mut layers {
+        border.0.set_alpha(layer.base_alpha * pulse);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    state: Res<State<AppState>>,
+    storyboard: Res<Storyboard>,
+    director: Res<WaveDirector>,
+    scoreboard: Res<ScoreBoard>,
+    high_scores: Res<HighScores>,
+    run_stats: Res<RunStats>,
+    run_timer: Res<RunTimer>,
+    settings: Res<GameSettings>,
+    font: Res<UiFont>,
+    practice: Option<Res<PracticeRun>>,
+    challenge: Option<Res<ChallengeRun>>,
+    challenge_scores: Res<ChallengeScores>,
+) {
+    let title_style = font.style(TextSize::Heading, Color::WHITE);
+    let info_style = font.style(TextSize::Body, Color::srgb(0.8, 0.85, 1.0));
+    // Practice and challenge runs don't go on the high score table.
+    let qualifies =
+        practice.is_none() && challenge.is_none() && high_scores.qualifies(scoreboard.score);
+    let options: &[GameOverOption] = if qualifies {
+        &[GameOverOption::SignName]
+    } else {
+        &[GameOverOption::PlayAgain, GameOverOption::Title]
+    };
+
+    // Locked until the cooldown runs out so a held fire key can't skip the screen.
+    commands.insert_resource(MenuFocus {
+        locked: true,
+        ..MenuFocus::new(options.len())
+    });
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::NONE),
+                ..default()
+            },
+            GameOverScreen,
+        ))
+        .with_children(|parent| {
+            if let Some(challenge) = &challenge {
+                parent.spawn(TextBundle::from_section(
+                    challenge.headline(),
+                    title_style.clone(),
+                ));
+                parent.spawn(TextBundle::from_section(
+                    challenge.result_line(),
+                    info_style.clone(),
+                ));
+            } else if *state.get() == AppState::Victory {
+                parent.spawn(TextBundle::from_section(
+                    "Victory!",
+                    TextStyle {
+                        color: Color::srgb(1.0, 0.85, 0.4),
+                        ..title_style.clone()
+                    },
+                ));
+                parent.spawn(TextBundle::from_section(
+                    if director.loop_index > 0 {
+                        format!(
+                            "All {} levels cleared on loop {} - the sector is safe",
+                            storyboard.level_count(),
+                            director.loop_index + 1
+                        )
+                    } else {
+                        format!(
+                            "All {} levels cleared - the sector is safe",
+                            storyboard.level_count()
+                        )
+                    },
+                    info_style.clone(),
+                ));
+            } else {
+                parent.spawn(TextBundle::from_section("Game Over", title_style.clone()));
+            }
+            parent.spawn(TextBundle::from_section(
+                format!("Final Score: {}", scoreboard.score),
+                info_style.clone(),
+            ));
+            let table_style = TextStyle {
+                font_size: TextSize::Caption.px(),
+                ..info_style.clone()
+            };
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(64.0),
+                        align_items: AlignItems::Start,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|columns| {
+                    spawn_results_panel(
+                        columns,
+                        "RESULTS",
+                        &run_stats,
+                        settings.run_timer.then_some(&*run_timer),
+                        table_style.clone(),
+                    );
+                    match &challenge {
+                        Some(challenge) => spawn_challenge_table(
+                            columns,
+                            &challenge_scores,
+                            challenge.mode,
+                            10,
+                            table_style,
+                        ),
+                        None => spawn_high_score_table(columns, &high_scores, 10, table_style),
+                    }
+                });
+            if qualifies {
+                parent.spawn(TextBundle::from_section(
+                    "New high score!",
+                    info_style.clone(),
+                ));
+            }
+            for (index, &option) in options.iter().enumerate() {
+                spawn_text_button(
+                    parent,
+                    index,
+                    option.label(),
+                    420.0,
+                    info_style.clone(),
+                    option,
+                );
+            }
+        });
+}
+
+fn spawn_results_panel(
+    parent: &mut ChildBuilder,
+    heading: &str,
+    stats: &RunStats,
+    timer: Option<&RunTimer>,
+    style: TextStyle,
+) {
+    let mut sections = vec![TextSection::new(
+        format!("{heading}\n"),
+        TextStyle {
+            color: Color::srgb(1.0, 0.85, 0.4),
+            ..style.clone()
+        },
+    )];
+    let splits = timer.map(RunTimer::summary).unwrap_or_default();
+    for (label, value) in splits.into_iter().chain(stats.summary()) {
+        sections.push(TextSection::new(
+            format!("{:<20}{:>8}\n", label, value),
+            style.clone(),
+        ));
+    }
+    parent.spawn(TextBundle::from_sections(sections));
+}
+
+fn spawn_level_complete_screen(
+    mut commands: Commands,
+    director: Res<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    run_stats: Res<RunStats>,
+    run_timer: Res<RunTimer>,
+    settings: Res<GameSettings>,
+    font: Res<UiFont>,
+) {
+    // The final boss leaves the director on the last level until the player chooses.
+    let options: &[LevelCompleteOption] = if director.level_index + 1 >= storyboard.level_count() {
+        &[LevelCompleteOption::NextLoop, LevelCompleteOption::EndRun]
+    } else {
+        &[LevelCompleteOption::Continue]
+    };
+    commands.insert_resource(MenuFocus::new(options.len()));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            LevelCompleteScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(16.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.55)),
+                    ..default()
+                })
+                .with_children(|panel| {
+                    spawn_results_panel(
+                        panel,
+                        "LEVEL CLEAR",
+                        &run_stats,
+                        settings.run_timer.then_some(&*run_timer),
+                        font.style(TextSize::Caption, Color::srgb(0.8, 0.85, 1.0)),
+                    );
+                    for (index, &option) in options.iter().enumerate() {
+                        let label = match option {
+                            LevelCompleteOption::Continue => {
+                                format!("Continue to Level {}", director.level_index + 1)
+                            }
+                            LevelCompleteOption::NextLoop => {
+                                format!("New Game+ - Loop {}", director.loop_index + 2)
+                            }
+                            LevelCompleteOption::EndRun => "End Run".to_string(),
+                        };
+                        spawn_text_button(
+                            panel,
+                            index,
+                            &label,
+                            420.0,
+                            font.style(TextSize::Body, Color::WHITE),
+                            option,
+                        );
+                    }
+                });
+        });
+}
+
+fn level_complete_input(
+    mut activated: EventReader<MenuActivated>,
+    options: Query<&LevelCompleteOption>,
+    mut director: ResMut<WaveDirector>,
+    storyboard: Res<Storyboard>,
+    run_difficulty: Res<RunDifficulty>,
     mut next_state: ResMut<NextState<AppState>>,
+) {
+    let chosen = activated
+        .read()
+        .filter_map(|event| options.get(event.entity).ok().copied())
+        .last();
+    match chosen {
+        Some(LevelCompleteOption::Continue) => next_state.set(AppState::Playing),
+        Some(LevelCompleteOption::NextLoop) => {
+            start_next_loop(&mut director, &storyboard, &run_difficulty);
+            info!("Starting New Game+ loop {}", director.loop_index + 1);
+            next_state.set(AppState::Playing);
+        }
+        Some(LevelCompleteOption::EndRun) => next_state.set(AppState::Victory),
+        None => {}
+    }
+}
+
+fn init_game_over_cooldown(mut commands: Commands) {
+    commands.insert_resource(GameOverCooldown(Timer::from_seconds(0.6, TimerMode::Once)));
+}
+
+fn clear_game_over_cooldown(mut commands: Commands) {
+    commands.remove_resource::<GameOverCooldown>();
+}
+
+fn spawn_pause_overlay(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    bindings: Res<KeyBindings>,
+    device: Res<LastInputDevice>,
+    font: Res<UiFont>,
+    practice: Option<Res<PracticeRun>>,
+    challenge: Option<Res<ChallengeRun>>,
+) {
+    // Practice and challenge runs can't be suspended.
+    let suspendable = practice.is_none() && challenge.is_none();
+    let options: Vec<PauseOption> = PauseOption::ALL
+        .into_iter()
+        .filter(|&option| suspendable || option != PauseOption::SaveAndQuit)
+        .collect();
+    commands.insert_resource(MenuFocus::new(options.len()));
+
+    let style = font.style(TextSize::Heading, Color::WHITE);
+    let option_style = TextStyle {
+        font_size: TextSize::Body.px(),
+        ..style.clone()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.35)),
+                ..default()
+            },
+            PauseOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Paused", style.clone()));
+            for (index, option) in options.into_iter().enumerate() {
+                spawn_text_button(
+                    parent,
+                    index,
+                    option.label(),
+                    280.0,
+                    option_style.clone(),
+                    option,
+                );
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    pause_audio_label(&settings, &bindings, *device),
+                    TextStyle {
+                        font_size: TextSize::Body.px(),
+                        ..style
+                    },
+                ),
+                PauseAudioText,
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn title_input(
+    mut next_state: ResMut<NextState<AppState>>,
+    mut settings_origin: ResMut<NextState<SettingsOrigin>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut activated: EventReader<MenuActivated>,
+    options: Query<&TitleOption>,
+    focus: Option<Res<MenuFocus>>,
+    profile: Res<CurrentProfile>,
+    mut switch_profile: EventWriter<SwitchProfileEvent>,
+    mut resume: EventWriter<ResumeRunEvent>,
+    mut exit: EventWriter<AppExit>,
     mut audio: EventWriter<AudioCue>,
 ) {
-    if keys.just_pressed(KeyCode::Escape) || keys.just_pressed(KeyCode::KeyP) {
+    let mut chosen = activated
+        .read()
+        .filter_map(|event| options.get(event.entity).ok().copied())
+        .last();
+    if gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::Start) {
+        chosen = Some(TitleOption::Start);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    } else if keys.just_pressed(KeyCode::Tab)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::Select)
+    {
+        chosen = Some(TitleOption::Settings);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+
+    match chosen {
+        Some(TitleOption::Start) => next_state.set(AppState::Playing),
+        Some(TitleOption::Resume) => {
+            resume.send(ResumeRunEvent);
+        }
+        Some(TitleOption::Practice) => next_state.set(AppState::Practice),
+        Some(TitleOption::Challenges) => next_state.set(AppState::Challenges),
+        Some(TitleOption::Editor) => next_state.set(AppState::Editor),
+        Some(TitleOption::Career) => next_state.set(AppState::CareerStats),
+        Some(TitleOption::Settings) => {
+            settings_origin.set(SettingsOrigin::Title);
+            next_state.set(AppState::Settings);
+        }
+        Some(TitleOption::Profile) => {
+            switch_profile.send(SwitchProfileEvent {
+                slot: (profile.slot + 1) % PROFILE_SLOTS,
+            });
+        }
+        Some(TitleOption::Quit) => {
+            exit.send(AppExit::Success);
+        }
+        None => {
+            // Left/Right also flip through profiles while the selector is focused.
+            let on_profile = focus.is_some_and(|focus| {
+                TitleOption::ALL.get(focus.focused) == Some(&TitleOption::Profile)
+            });
+            let step = if keys.just_pressed(KeyCode::ArrowRight)
+                || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::DPadRight)
+            {
+                1
+            } else if keys.just_pressed(KeyCode::ArrowLeft)
+                || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::DPadLeft)
+            {
+                PROFILE_SLOTS - 1
+            } else {
+                0
+            };
+            if on_profile && step != 0 {
+                switch_profile.send(SwitchProfileEvent {
+                    slot: (profile.slot + step) % PROFILE_SLOTS,
+                });
+                audio.send(AudioCue::new(Sfx::UiSelect));
+            }
+        }
+    }
+}
+
+// Keeps the profile and resume buttons and the score table in step with the
+// selected profile. Resume is dimmed while there is nothing to resume.
+#[allow(clippy::too_many_arguments)]
+fn refresh_title_profile(
+    mut commands: Commands,
+    profile: Res<CurrentProfile>,
+    suspended: Res<SuspendedRun>,
+    high_scores: Res<HighScores>,
+    font: Res<UiFont>,
+    options: Query<(&TitleOption, &Children)>,
+    mut texts: Query<&mut Text>,
+    tables: Query<Entity, With<TitleHighScores>>,
+) {
+    if profile.is_changed() || suspended.is_changed() {
+        for (option, children) in &options {
+            for &child in children {
+                if let Ok(mut text) = texts.get_mut(child) {
+                    let section = &mut text.sections[0];
+                    section.value = option.label(&profile, &suspended);
+                    if *option == TitleOption::Resume {
+                        section.style.color = if suspended.0.is_some() {
+                            Color::WHITE
+                        } else {
+                            Color::srgba(1.0, 1.0, 1.0, 0.35)
+                        };
+                    }
+                }
+            }
+        }
+    }
+    if high_scores.is_changed() {
+        for table in &tables {
+            commands
+                .entity(table)
+                .despawn_descendants()
+                .with_children(|table| {
+                    spawn_high_score_table(
+                        table,
+                        &high_scores,
+                        5,
+                        font.style(TextSize::Caption, Color::srgb(0.7, 0.9, 1.0)),
+                    );
+                });
+        }
+    }
+}
+
+fn pause_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    if keys.just_pressed(KeyCode::Escape)
+        || keys.just_pressed(bindings.pause)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::Start)
+    {
         next_state.set(AppState::Paused);
-        audio.send(AudioCue::UiSelect);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+}
+
+fn quick_restart_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut prompts: Query<(Entity, &mut RestartPrompt)>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+    font: Res<UiFont>,
+) {
+    let pressed = keys.just_pressed(KeyCode::KeyR);
+    if let Ok((entity, mut prompt)) = prompts.get_single_mut() {
+        if pressed {
+            next_state.set(AppState::Restarting);
+            audio.send(AudioCue::new(Sfx::UiSelect));
+        } else if prompt.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    if !pressed {
+        return;
     }
+    audio.send(AudioCue::new(Sfx::UiSelect));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Percent(32.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            RestartPrompt {
+                timer: Timer::from_seconds(RESTART_CONFIRM_SECONDS, TimerMode::Once),
+            },
+            HudRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Press R again to restart the run",
+                font.style(TextSize::Body, Color::srgb(1.0, 0.85, 0.4)),
+            ));
+        });
 }
 
-fn resume_input(
+#[allow(clippy::too_many_arguments)]
+fn pause_menu_input(
     keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut activated: EventReader<MenuActivated>,
+    options: Query<&PauseOption>,
     mut next_state: ResMut<NextState<AppState>>,
+    mut settings_origin: ResMut<NextState<SettingsOrigin>>,
+    mut suspend: EventWriter<SuspendRunEvent>,
     mut audio: EventWriter<AudioCue>,
 ) {
+    let chosen = activated
+        .read()
+        .filter_map(|event| options.get(event.entity).ok().copied())
+        .last();
     if keys.just_pressed(KeyCode::Escape)
-        || keys.just_pressed(KeyCode::KeyP)
-        || keys.just_pressed(KeyCode::Space)
+        || keys.just_pressed(bindings.pause)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::Start)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East)
     {
         next_state.set(AppState::Playing);
-        audio.send(AudioCue::UiSelect);
+        audio.send(AudioCue::new(Sfx::UiSelect));
+        return;
+    }
+
+    let Some(option) = chosen else {
+        return;
+    };
+    match option {
+        PauseOption::Resume => next_state.set(AppState::Playing),
+        PauseOption::Restart => next_state.set(AppState::Restarting),
+        PauseOption::Settings => {
+            settings_origin.set(SettingsOrigin::Paused);
+            next_state.set(AppState::Settings);
+        }
+        PauseOption::SaveAndQuit => {
+            suspend.send(SuspendRunEvent);
+        }
+        PauseOption::Quit => next_state.set(AppState::Title),
     }
 }
 
+#[allow(clippy::collapsible_if)]
 fn game_over_input(
     mut next_state: ResMut<NextState<AppState>>,
-    keys: Res<ButtonInput<KeyCode>>,
+    mut activated: EventReader<MenuActivated>,
+    options: Query<&GameOverOption>,
+    mut focus: ResMut<MenuFocus>,
     time: Res<Time>,
     mut cooldown: Option<ResMut<GameOverCooldown>>,
-    mut audio: EventWriter<AudioCue>,
 ) {
     if let Some(cooldown) = cooldown.as_deref_mut() {
         if !cooldown.0.tick(time.delta()).finished() {
             return;
         }
     }
-    if keys.just_pressed(KeyCode::Enter) {
-        next_state.set(AppState::Title);
-        audio.send(AudioCue::UiSelect);
+    focus.locked = false;
+
+    let chosen = activated
+        .read()
+        .filter_map(|event| options.get(event.entity).ok().copied())
+        .last();
+    match chosen {
+        Some(GameOverOption::SignName) => next_state.set(AppState::NameEntry),
+        Some(GameOverOption::PlayAgain) => next_state.set(AppState::Playing),
+        Some(GameOverOption::Title) => next_state.set(AppState::Title),
+        None => {}
     }
 }
 
-fn title_settings_input(
+// Runs ahead of the menus, so the key press that ends a rebind is seen here while the
+// capture is still active.
+fn mute_input(
     keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
     mut settings: ResMut<GameSettings>,
     mut audio: EventWriter<AudioCue>,
 ) {
-    let mut changed = false;
-
-    if keys.just_pressed(KeyCode::Tab) {
-        settings.difficulty = match settings.difficulty {
-            Difficulty::Easy => Difficulty::Normal,
-            Difficulty::Normal => Difficulty::Hard,
-            Difficulty::Hard => Difficulty::Easy,
-        };
-        changed = true;
-    }
-    if keys.just_pressed(KeyCode::Minus) {
-        settings.music_volume = (settings.music_volume - 0.05).clamp(0.0, 1.0);
-        changed = true;
-    }
-    if keys.just_pressed(KeyCode::Equal) {
-        settings.music_volume = (settings.music_volume + 0.05).clamp(0.0, 1.0);
-        changed = true;
-    }
-    if keys.just_pressed(KeyCode::BracketLeft) {
-        settings.sfx_volume = (settings.sfx_volume - 0.05).clamp(0.0, 1.0);
-        changed = true;
-    }
-    if keys.just_pressed(KeyCode::BracketRight) {
-        settings.sfx_volume = (settings.sfx_volume + 0.05).clamp(0.0, 1.0);
-        changed = true;
-    }
-
-    if changed {
-        audio.send(AudioCue::UiSelect);
+    if keys.just_pressed(bindings.mute) {
+        settings.muted = !settings.muted;
+        audio.send(AudioCue::new(Sfx::UiSelect));
     }
 }
 
-fn title_settings_display(
+fn pause_audio_display(
     settings: Res<GameSettings>,
-    mut queries: ParamSet<(
-        Query<&mut Text, With<TitleDifficultyText>>,
-        Query<&mut Text, With<TitleMusicText>>,
-        Query<&mut Text, With<TitleSfxText>>,
-    )>,
+    bindings: Res<KeyBindings>,
+    device: Res<LastInputDevice>,
+    mut query: Query<&mut Text, With<PauseAudioText>>,
 ) {
-    if let Ok(mut text) = queries.p0().get_single_mut() {
-        text.sections[0].value = format!("Difficulty: {}", difficulty_label(settings.difficulty));
-    }
-    if let Ok(mut text) = queries.p1().get_single_mut() {
-        text.sections[0].value =
-            format!("Music Volume: {}%", (settings.music_volume * 100.0) as i32);
+    if !settings.is_changed() && !device.is_changed() {
+        return;
     }
-    if let Ok(mut text) = queries.p2().get_single_mut() {
-        text.sections[0].value = format!("SFX Volume: {}%", (settings.sfx_volume * 100.0) as i32);
+    for mut text in &mut query {
+        text.sections[0].value = pause_audio_label(&settings, &bindings, *device);
     }
 }
 
-fn difficulty_label(difficulty: Difficulty) -> &'static str {
-    match difficulty {
-        Difficulty::Easy => "Easy",
-        Difficulty::Normal => "Normal",
-        Difficulty::Hard => "Hard",
-    }
+fn pause_audio_label(
+    settings: &GameSettings,
+    bindings: &KeyBindings,
+    device: LastInputDevice,
+) -> String {
+    let status = if settings.muted { "Muted" } else { "On" };
+    let keyboard = format!("Esc resume  |  {} mute", key_label(bindings.mute));
+    let resume = device.pick(&keyboard, "B resume");
+    format!(
+        "Sound: {} ({}%)  |  {}",
+        status,
+        (settings.master_volume * 100.0) as i32,
+        resume
+    )
 }
 
+#[allow(clippy::type_complexity)]
 fn boss_health_bar_update(
     boss_state: Res<BossState>,
     app_state: Res<State<AppState>>,
-    mut visibility_query: Query<&mut Visibility, With<BossHealthBar>>,
-    mut fill_query: Query<&mut Style, With<BossHealthFill>>,
+    time: Res<Time<Real>>,
+    mut bars: Query<(&mut BossHealthBar, &mut Visibility, &mut Style)>,
+    mut fills: Query<
+        (&mut Style, Has<BossChipFill>),
+        (
+            Or<(With<BossHealthFill>, With<BossChipFill>)>,
+            Without<BossHealthBar>,
+        ),
+    >,
+    mut names: Query<&mut Text, With<BossNameText>>,
 ) {
+    let Ok((mut bar, mut visibility, mut bar_style)) = bars.get_single_mut() else {
+        return;
+    };
     let playing = matches!(app_state.get(), AppState::Playing);
     let active = playing && boss_state.active && boss_state.max_health > 0.0;
-    if let Ok(mut visibility) = visibility_query.get_single_mut() {
-        *visibility = if active {
-            Visibility::Visible
-        } else {
-            Visibility::Hidden
-        };
-    }
+    visibility.set_if_neq(if active {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    });
 
     if !active {
+        bar.chip = 1.0;
+        bar.chip_delay = 0.0;
+        bar.shake = 0.0;
+        bar.last_health = boss_state.max_health;
         return;
     }
 
-    let percent = (boss_state.health / boss_state.max_health).clamp(0.0, 1.0) * 100.0;
-    if let Ok(mut style) = fill_query.get_single_mut() {
-        style.width = Val::Percent(percent);
+    let delta = time.delta_seconds();
+    let ratio = (boss_state.health / boss_state.max_health).clamp(0.0, 1.0);
+    let damage = (bar.last_health - boss_state.health).max(0.0) / boss_state.max_health;
+    bar.last_health = boss_state.health;
+    if damage > 0.0 {
+        bar.chip_delay = BOSS_CHIP_DELAY;
+    }
+    if damage >= BOSS_BIG_HIT_RATIO {
+        bar.shake = BOSS_SHAKE_SECONDS;
+    }
+
+    bar.chip_delay = (bar.chip_delay - delta).max(0.0);
+    if bar.chip_delay <= 0.0 {
+        bar.chip = (bar.chip - BOSS_CHIP_DRAIN_RATE * delta).max(ratio);
+    }
+    bar.chip = bar.chip.max(ratio);
+
+    bar.shake = (bar.shake - delta).max(0.0);
+    let strength = bar.shake / BOSS_SHAKE_SECONDS * BOSS_SHAKE_PIXELS;
+    let wobble = time.elapsed_seconds() * 60.0;
+    // Only touch styles that actually moved, so a steady bar doesn't force a UI relayout.
+    let margin = UiRect {
+        left: Val::Px(wobble.sin() * strength),
+        top: Val::Px((wobble * 1.3).cos() * strength),
+        ..default()
+    };
+    if bar_style.margin != margin {
+        bar_style.margin = margin;
+    }
+
+    for (mut style, chip) in &mut fills {
+        let value = if chip { bar.chip } else { ratio };
+        let width = Val::Percent(value * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+    for mut text in &mut names {
+        let label = format!(
+            "{}  -  PHASE {}",
+            boss_state.name,
+            boss_state.phase.number()
+        );
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
     }
 }
 
@@ -0,0 +1,335 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    audio::{AudioCue, Sfx},
+    career::CareerStats,
+    config::{ShipModel, ShipPaint},
+    input_device::{DevicePrompt, gamepad_just_pressed},
+    menu::{MenuActivated, MenuFocus, MenuSet, clear_menu_focus, spawn_text_button},
+    profiles::CurrentProfile,
+    states::AppState,
+    storage::{load_json, save_json},
+    typography::{TextSize, UiFont},
+};
+
+pub const UNLOCKS_FILE: &str = "unlocks.json";
+pub const LOCK_COLOR: Color = Color::srgb(0.6, 0.65, 0.75);
+const NEW_UNLOCK_COLOR: Color = Color::srgb(1.0, 0.85, 0.4);
+
+pub struct UnlocksPlugin;
+
+impl Plugin for UnlocksPlugin {
+    fn build(&self, app: &mut App) {
+        let profile = app.world().resource::<CurrentProfile>().clone();
+        app.insert_resource(Unlocks::load(&profile))
+            .add_systems(
+                Update,
+                grant_unlocks.run_if(resource_changed::<CareerStats>),
+            )
+            .add_systems(
+                OnEnter(AppState::Title),
+                announce_unlocks.run_if(|unlocks: Res<Unlocks>| !unlocks.unseen.is_empty()),
+            )
+            .add_systems(OnEnter(AppState::NewUnlock), spawn_new_unlock_screen)
+            .add_systems(
+                Update,
+                new_unlock_input
+                    .after(MenuSet)
+                    .run_if(in_state(AppState::NewUnlock)),
+            )
+            .add_systems(
+                OnExit(AppState::NewUnlock),
+                (cleanup_new_unlock_screen, clear_menu_focus),
+            );
+    }
+}
+
+// Extras earned through career milestones. Each is checked against the profile's career
+// totals whenever they change, so a milestone reached mid-run unlocks at once and is
+// announced the next time the title screen comes up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unlock {
+    MirrorMode,
+    Interceptor,
+    BossRush,
+    EmberPaint,
+    Bulwark,
+    VioletPaint,
+}
+
+impl Unlock {
+    pub const ALL: [Unlock; 6] = [
+        Unlock::MirrorMode,
+        Unlock::Interceptor,
+        Unlock::BossRush,
+        Unlock::EmberPaint,
+        Unlock::Bulwark,
+        Unlock::VioletPaint,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Unlock::MirrorMode => "Mirror Mode",
+            Unlock::Interceptor => "Interceptor ship",
+            Unlock::BossRush => "Boss Rush",
+            Unlock::EmberPaint => "Ember paint",
+            Unlock::Bulwark => "Bulwark ship",
+            Unlock::VioletPaint => "Violet paint",
+        }
+    }
+
+    pub fn requirement(self) -> &'static str {
+        match self {
+            Unlock::MirrorMode => "Play 5 games",
+            Unlock::Interceptor => "Destroy 500 enemies",
+            Unlock::BossRush => "Defeat 3 bosses",
+            Unlock::EmberPaint => "Reach a 30-kill combo",
+            Unlock::Bulwark => "Defeat 10 bosses",
+            Unlock::VioletPaint => "Play for 2 hours",
+        }
+    }
+
+    // Where to find it once it's unlocked.
+    fn location(self) -> &'static str {
+        match self {
+            Unlock::MirrorMode => "Settings > Mirror Mode",
+            Unlock::Interceptor | Unlock::Bulwark => "Settings > Ship",
+            Unlock::EmberPaint | Unlock::VioletPaint => "Settings > Paint",
+            Unlock::BossRush => "Challenges",
+        }
+    }
+
+    fn earned(self, career: &CareerStats) -> bool {
+        match self {
+            Unlock::MirrorMode => career.games_played >= 5,
+            Unlock::Interceptor => career.total_kills() >= 500,
+            Unlock::BossRush => career.bosses_defeated >= 3,
+            Unlock::EmberPaint => career.best_combo >= 30,
+            Unlock::Bulwark => career.bosses_defeated >= 10,
+            Unlock::VioletPaint => career.play_time >= 2.0 * 3600.0,
+        }
+    }
+
+    pub fn for_ship(model: ShipModel) -> Option<Unlock> {
+        match model {
+            ShipModel::Standard => None,
+            ShipModel::Interceptor => Some(Unlock::Interceptor),
+            ShipModel::Bulwark => Some(Unlock::Bulwark),
+        }
+    }
+
+    pub fn for_paint(paint: ShipPaint) -> Option<Unlock> {
+        match paint {
+            ShipPaint::Standard => None,
+            ShipPaint::Ember => Some(Unlock::EmberPaint),
+            ShipPaint::Violet => Some(Unlock::VioletPaint),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Unlocks {
+    pub unlocked: Vec<Unlock>,
+    // Unlocked but not yet shown on the "New Unlock!" screen.
+    pub unseen: Vec<Unlock>,
+}
+
+impl Unlocks {
+    pub fn load(profile: &CurrentProfile) -> Self {
+        load_json(&profile.data_path(UNLOCKS_FILE))
+    }
+
+    fn save(&self, profile: &CurrentProfile) {
+        save_json(&profile.data_path(UNLOCKS_FILE), self);
+    }
+
+    pub fn has(&self, unlock: Unlock) -> bool {
+        self.unlocked.contains(&unlock)
+    }
+
+    // Whether an option that may sit behind an unlock can be picked.
+    pub fn allows(&self, unlock: Option<Unlock>) -> bool {
+        unlock.is_none_or(|unlock| self.has(unlock))
+    }
+}
+
+// A small padlock drawn from UI nodes, shown beside menu entries that are still locked.
+pub fn spawn_lock_icon(parent: &mut ChildBuilder) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|lock| {
+            lock.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(8.0),
+                    height: Val::Px(6.0),
+                    border: UiRect {
+                        left: Val::Px(2.0),
+                        right: Val::Px(2.0),
+                        top: Val::Px(2.0),
+                        bottom: Val::Px(0.0),
+                    },
+                    ..default()
+                },
+                border_color: BorderColor(LOCK_COLOR),
+                ..default()
+            });
+            lock.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(14.0),
+                    height: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(LOCK_COLOR),
+                ..default()
+            });
+        });
+}
+
+// Every unlock, with a padlock and its requirement for those still to be earned.
+pub fn spawn_unlock_list(parent: &mut ChildBuilder, unlocks: &Unlocks, style: TextStyle) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|list| {
+            for unlock in Unlock::ALL {
+                list.spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    if unlocks.has(unlock) {
+                        row.spawn(TextBundle::from_section(unlock.label(), style.clone()));
+                    } else {
+                        spawn_lock_icon(row);
+                        row.spawn(TextBundle::from_section(
+                            format!("{} - {}", unlock.label(), unlock.requirement()),
+                            TextStyle {
+                                color: LOCK_COLOR,
+                                ..style.clone()
+                            },
+                        ));
+                    }
+                });
+            }
+        });
+}
+
+#[derive(Component)]
+struct NewUnlockScreen;
+
+fn grant_unlocks(
+    career: Res<CareerStats>,
+    mut unlocks: ResMut<Unlocks>,
+    profile: Res<CurrentProfile>,
+) {
+    let earned: Vec<_> = Unlock::ALL
+        .into_iter()
+        .filter(|&unlock| !unlocks.has(unlock) && unlock.earned(&career))
+        .collect();
+    if earned.is_empty() {
+        return;
+    }
+    for &unlock in &earned {
+        info!("Unlocked {}", unlock.label());
+    }
+    unlocks.unlocked.extend(&earned);
+    unlocks.unseen.extend(earned);
+    unlocks.save(&profile);
+}
+
+fn announce_unlocks(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::NewUnlock);
+}
+
+fn spawn_new_unlock_screen(mut commands: Commands, unlocks: Res<Unlocks>, font: Res<UiFont>) {
+    commands.insert_resource(MenuFocus::new(1));
+    let label_style = font.style(TextSize::Body, Color::WHITE);
+    let hint_style = font.style(TextSize::Caption, Color::srgb(0.7, 0.8, 0.9));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+                ..default()
+            },
+            NewUnlockScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "New Unlock!",
+                font.style(TextSize::Heading, NEW_UNLOCK_COLOR),
+            ));
+            for &unlock in &unlocks.unseen {
+                parent.spawn(TextBundle::from_sections([
+                    TextSection::new(format!("{}\n", unlock.label()), label_style.clone()),
+                    TextSection::new(
+                        format!("{}  |  {}", unlock.requirement(), unlock.location()),
+                        hint_style.clone(),
+                    ),
+                ]));
+            }
+            spawn_text_button(parent, 0, "Continue", 280.0, label_style.clone(), ());
+            parent.spawn((
+                TextBundle::from_section("", hint_style.clone()),
+                DevicePrompt::new("Enter / Esc continue", "A / B continue"),
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn new_unlock_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut activated: EventReader<MenuActivated>,
+    mut unlocks: ResMut<Unlocks>,
+    profile: Res<CurrentProfile>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut audio: EventWriter<AudioCue>,
+) {
+    let back = keys.just_pressed(KeyCode::Escape)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East);
+    if back {
+        audio.send(AudioCue::new(Sfx::UiSelect));
+    }
+    if back || activated.read().count() > 0 {
+        unlocks.unseen.clear();
+        unlocks.save(&profile);
+        next_state.set(AppState::Title);
+    }
+}
+
+fn cleanup_new_unlock_screen(mut commands: Commands, query: Query<Entity, With<NewUnlockScreen>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,6 +1,17 @@
 use bevy::{prelude::*, sprite::TextureAtlas, time::Fixed};
 
-use super::{config::GameConfig, effects::ExplosionAssets, states::AppState};
+use super::{
+    config::{GameConfig, GameSettings},
+    effects::ExplosionAssets,
+    interpolation::TransformInterpolation,
+    palette::enemy_shot_color,
+    player::{PlayerWeaponState, WeaponMode},
+    pool::{EntityPool, clear_pool, release},
+    run_stats::RunStats,
+    ship_sprites::{AnimationCue, AnimationCueEvent},
+    states::{AppState, InRun},
+    trails::{FAST_PROJECTILE_SPEED, Trail},
+};
 
 pub struct WeaponsPlugin;
 
@@ -8,7 +19,16 @@ impl Plugin for WeaponsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PlayerFireEvent>()
             .add_event::<EnemyFireEvent>()
-            .add_systems(OnExit(AppState::Playing), cleanup_projectiles)
+            .init_resource::<EntityPool<Projectile>>()
+            .init_resource::<EntityPool<EnemyProjectile>>()
+            .add_systems(
+                OnExit(InRun),
+                (
+                    cleanup_projectiles,
+                    clear_pool::<Projectile>,
+                    clear_pool::<EnemyProjectile>,
+                ),
+            )
             .add_systems(
                 FixedUpdate,
                 (
@@ -65,11 +85,16 @@ pub struct EnemyProjectile {
     pub damage: u8,
 }
 
+// A laser bolt flares on this frame of its animation, and the muzzle light with it.
+const LASER_FLASH_FRAME: usize = 1;
+
 #[derive(Component)]
 struct ProjectileAnimation {
     frames: Vec<usize>,
     frame: usize,
     timer: Timer,
+    // Sent once, the first time the animation reaches the frame.
+    cue: Option<(usize, AnimationCue)>,
 }
 
 impl ProjectileAnimation {
@@ -78,37 +103,58 @@ impl ProjectileAnimation {
             frames: frames.to_vec(),
             frame: 0,
             timer: Timer::from_seconds(frame_time, TimerMode::Repeating),
+            cue: None,
         }
     }
+
+    fn with_cue(mut self, frame: usize, cue: AnimationCue) -> Self {
+        self.cue = Some((frame, cue));
+        self
+    }
 }
 
 fn spawn_player_projectiles(
     mut commands: Commands,
     mut reader: EventReader<PlayerFireEvent>,
     assets: Res<ExplosionAssets>,
+    weapon_state: Res<PlayerWeaponState>,
+    mut stats: ResMut<RunStats>,
+    mut pool: ResMut<EntityPool<Projectile>>,
 ) {
     for event in reader.read() {
-        commands.spawn((
-            SpriteBundle {
-                texture: assets.texture.clone(),
-                transform: Transform::from_xyz(event.origin.x, event.origin.y, 1.0),
-                sprite: Sprite {
-                    color: Color::WHITE,
-                    custom_size: Some(event.size * 1.6),
+        stats.shots_fired += 1;
+        let mut animation = ProjectileAnimation::new(&assets.bullet_sequence, 0.04);
+        if weapon_state.mode == WeaponMode::Laser {
+            animation = animation.with_cue(LASER_FLASH_FRAME, AnimationCue::MuzzleFlash);
+        }
+        let mut entity = pool.spawn(
+            &mut commands,
+            (
+                SpriteBundle {
+                    texture: assets.texture.clone(),
+                    transform: Transform::from_xyz(event.origin.x, event.origin.y, 1.0),
+                    sprite: Sprite {
+                        color: Color::WHITE,
+                        custom_size: Some(event.size * 1.6),
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-            TextureAtlas {
-                layout: assets.layout.clone(),
-                index: assets.bullet_sequence[0],
-            },
-            Projectile {
-                velocity: event.velocity,
-                lifetime: event.lifetime,
-            },
-            ProjectileAnimation::new(&assets.bullet_sequence, 0.04),
-        ));
+                TextureAtlas {
+                    layout: assets.layout.clone(),
+                    index: assets.bullet_sequence[0],
+                },
+                Projectile {
+                    velocity: event.velocity,
+                    lifetime: event.lifetime,
+                },
+                animation,
+                TransformInterpolation::default(),
+            ),
+        );
+        if event.velocity.length() >= FAST_PROJECTILE_SPEED {
+            entity.insert(Trail::projectile());
+        }
     }
 }
 
@@ -118,11 +164,13 @@ fn advance_player_projectiles(
     time: Res<Time<Fixed>>,
     config: Res<GameConfig>,
 ) {
+    let _span = info_span!("advance_player_projectiles", shots = query.iter().len()).entered();
     let top = config.logical_height * 0.5 + 100.0;
+    let side = config.logical_width * 0.5 + 100.0;
     for (entity, mut transform, projectile) in &mut query {
         transform.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
-        if transform.translation.y > top {
-            commands.entity(entity).despawn_recursive();
+        if transform.translation.y > top || transform.translation.x.abs() > side {
+            release::<Projectile>(&mut commands, entity);
         }
     }
 }
@@ -135,7 +183,7 @@ fn expire_player_projectiles(
     for (entity, mut projectile) in &mut query {
         projectile.lifetime -= time.delta_seconds();
         if projectile.lifetime <= 0.0 {
-            commands.entity(entity).despawn_recursive();
+            release::<Projectile>(&mut commands, entity);
         }
     }
 }
@@ -144,30 +192,66 @@ fn spawn_enemy_projectiles(
     mut commands: Commands,
     mut reader: EventReader<EnemyFireEvent>,
     assets: Res<ExplosionAssets>,
+    settings: Res<GameSettings>,
+    mut pool: ResMut<EntityPool<EnemyProjectile>>,
 ) {
     for event in reader.read() {
-        commands.spawn((
-            SpriteBundle {
-                texture: assets.texture.clone(),
-                transform: Transform::from_xyz(event.origin.x, event.origin.y, 1.0),
-                sprite: Sprite {
-                    color: event.color,
-                    custom_size: Some(event.size * 1.6),
+        let color = enemy_shot_color(settings.palette, event.color);
+        let color = if settings.high_contrast_bullets {
+            color.mix(&Color::WHITE, 0.45)
+        } else {
+            color
+        };
+        let mut entity = pool.spawn(
+            &mut commands,
+            (
+                SpriteBundle {
+                    texture: assets.texture.clone(),
+                    transform: Transform::from_xyz(event.origin.x, event.origin.y, 1.0),
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(event.size * 1.6),
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-            TextureAtlas {
-                layout: assets.layout.clone(),
-                index: assets.bullet_sequence[0],
-            },
-            EnemyProjectile {
-                velocity: event.velocity,
-                lifetime: event.lifetime,
-                damage: event.damage,
-            },
-            ProjectileAnimation::new(&assets.bullet_sequence, 0.05),
-        ));
+                TextureAtlas {
+                    layout: assets.layout.clone(),
+                    index: assets.bullet_sequence[0],
+                },
+                EnemyProjectile {
+                    velocity: event.velocity,
+                    lifetime: event.lifetime,
+                    damage: event.damage,
+                },
+                ProjectileAnimation::new(&assets.bullet_sequence, 0.05),
+                TransformInterpolation::default(),
+            ),
+        );
+        if event.velocity.length() >= FAST_PROJECTILE_SPEED {
+            entity.insert(Trail::projectile());
+        }
+        if settings.high_contrast_bullets {
+            // Dark halo behind the shot; the collision box still comes from the parent sprite.
+            entity.with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        texture: assets.texture.clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, -0.1),
+                        sprite: Sprite {
+                            color: Color::srgba(0.0, 0.0, 0.0, 0.85),
+                            custom_size: Some(event.size * 2.4),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    TextureAtlas {
+                        layout: assets.layout.clone(),
+                        index: assets.bullet_sequence[0],
+                    },
+                ));
+            });
+        }
     }
 }
 
@@ -177,11 +261,13 @@ fn advance_enemy_projectiles(
     time: Res<Time<Fixed>>,
     config: Res<GameConfig>,
 ) {
+    let _span = info_span!("advance_enemy_projectiles", shots = query.iter().len()).entered();
     let bottom = -config.logical_height * 0.5 - 120.0;
+    let side = config.logical_width * 0.5 + 120.0;
     for (entity, mut transform, projectile) in &mut query {
         transform.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
-        if transform.translation.y < bottom {
-            commands.entity(entity).despawn_recursive();
+        if transform.translation.y < bottom || transform.translation.x.abs() > side {
+            release::<EnemyProjectile>(&mut commands, entity);
         }
     }
 }
@@ -194,7 +280,7 @@ fn expire_enemy_projectiles(
     for (entity, mut projectile) in &mut query {
         projectile.lifetime -= time.delta_seconds();
         if projectile.lifetime <= 0.0 {
-            commands.entity(entity).despawn_recursive();
+            release::<EnemyProjectile>(&mut commands, entity);
         }
     }
 }
@@ -214,12 +300,28 @@ fn cleanup_projectiles(
 
 fn animate_projectile_sprites(
     time: Res<Time>,
-    mut query: Query<(&mut ProjectileAnimation, &mut TextureAtlas)>,
+    mut query: Query<(
+        Entity,
+        &mut ProjectileAnimation,
+        &mut TextureAtlas,
+        &GlobalTransform,
+    )>,
+    mut cues: EventWriter<AnimationCueEvent>,
 ) {
-    for (mut anim, mut atlas) in &mut query {
+    for (entity, mut anim, mut atlas, transform) in &mut query {
         if anim.timer.tick(time.delta()).just_finished() {
             anim.frame = (anim.frame + 1) % anim.frames.len();
             atlas.index = anim.frames[anim.frame];
+            if let Some((frame, cue)) = anim.cue
+                && frame == anim.frame
+            {
+                anim.cue = None;
+                cues.send(AnimationCueEvent {
+                    entity,
+                    cue,
+                    position: transform.translation().truncate(),
+                });
+            }
         }
     }
 }
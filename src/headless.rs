@@ -0,0 +1,149 @@
+//! Builds the game without a window, GPU, or audio device so integration tests can
+//! drive it one fixed tick at a time.
+
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    audio::{AudioPlugin, AudioSource},
+    ecs::schedule::ExecutorKind,
+    gilrs::GilrsPlugin,
+    log::LogPlugin,
+    prelude::*,
+    render::{RenderPlugin, settings::WgpuSettings},
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+
+use crate::{
+    game::{
+        AppState, GamePlugin,
+        config::{GameRng, LaunchOptions},
+    },
+    util::rng::Rng,
+};
+
+pub const DEFAULT_SEED: u64 = 0x5f0c_e000;
+const LOADING_TIMEOUT: Duration = Duration::from_secs(30);
+
+static NEXT_DATA_DIR: AtomicUsize = AtomicUsize::new(0);
+
+// The assets were still loading when `LOADING_TIMEOUT` ran out.
+#[derive(Debug)]
+pub struct LoadingTimedOut;
+
+impl fmt::Display for LoadingTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "assets did not finish loading within {}s",
+            LOADING_TIMEOUT.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for LoadingTimedOut {}
+
+// A save directory made for one app, deleted again when the app is dropped.
+#[derive(Resource)]
+struct ScratchDataDir(PathBuf);
+
+impl Drop for ScratchDataDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+// Every `App::update` advances game time by exactly one fixed timestep, so with the
+// simulation running at normal speed each update runs one `FixedUpdate` tick.
+pub fn headless_app(seed: u64, mut launch: LaunchOptions) -> Result<App, LoadingTimedOut> {
+    // Unless told otherwise each app gets an empty save directory of its own, so tests
+    // neither see the player's saves nor each other's.
+    let scratch = match launch.data_dir {
+        Some(_) => None,
+        None => {
+            let dir = scratch_data_dir();
+            launch.data_dir = Some(dir.clone());
+            Some(ScratchDataDir(dir))
+        }
+    };
+    // Nobody is there to read story scenes; a test that wants one turns
+    // `GameSettings::story_dialogue` back on.
+    launch.no_story = true;
+
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .disable::<AudioPlugin>()
+            .disable::<GilrsPlugin>()
+            .disable::<LogPlugin>(),
+    )
+    // The game still creates its sounds as assets; they are just never played.
+    .init_asset::<AudioSource>()
+    .insert_resource(GameRng(Rng::new(seed)))
+    .insert_resource(launch)
+    .add_plugins(GamePlugin);
+    if let Some(scratch) = scratch {
+        app.insert_resource(scratch);
+    }
+
+    let timestep = app.world().resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    app.finish();
+    app.cleanup();
+    // The multi-threaded executor may order unrelated systems differently from one
+    // update to the next; a single thread keeps every run of a seed identical.
+    for (_, schedule) in app.world_mut().resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+    // Assets load on background threads, so keep updating with game time held still
+    // until the title screen is up and leave the caller there with every `Startup`
+    // system done. Each run of a seed then starts from the same state however long the
+    // loads took.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    let started = Instant::now();
+    while *app.world().resource::<State<AppState>>().get() == AppState::Loading {
+        if started.elapsed() >= LOADING_TIMEOUT {
+            return Err(LoadingTimedOut);
+        }
+        app.update();
+        thread::sleep(Duration::from_millis(1));
+    }
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    Ok(app)
+}
+
+pub fn run_fixed_updates(app: &mut App, ticks: u32) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn scratch_data_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "sforce-headless-{}-{}",
+        process::id(),
+        NEXT_DATA_DIR.fetch_add(1, Ordering::Relaxed)
+    ))
+}
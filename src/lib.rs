@@ -0,0 +1,3 @@
+pub mod game;
+pub mod headless;
+mod util;
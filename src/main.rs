@@ -1,44 +1,149 @@
-mod game;
-mod util;
+use std::{env, process};
 
 use bevy::{
+    app::PluginGroupBuilder,
     prelude::*,
-    window::{PresentMode, PrimaryWindow, Window, WindowMode, WindowPlugin, WindowResolution},
+    window::{
+        PresentMode, PrimaryWindow, Window, WindowMode, WindowPlugin, WindowResizeConstraints,
+        WindowResolution,
+    },
+    winit::WinitWindows,
 };
-use game::GamePlugin;
+use sforce::game::{
+    AppState, GamePlugin,
+    config::{GameSettings, LaunchError, LaunchOptions},
+    spawn::WaveDirector,
+    states::InRun,
+    ui::ScoreBoard,
+};
+use winit::window::Icon;
+
+const TITLE: &str = "S-Force";
+// The Wayland app ID and X11 WM_CLASS, which desktop files and taskbars group by.
+const APP_ID: &str = "com.tuttlem.sforce";
+const ICON: &[u8] = include_bytes!("../assets/icon.png");
+
+const USAGE: &str = "Usage: sforce [--windowed] [--resolution WIDTHxHEIGHT] [--storyboard PATH] [--level N] [--mute] [--no-story] [--data-dir PATH] [--portable]";
 
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(Color::srgb(0.05, 0.05, 0.14)))
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "S-Force".into(),
-                resolution: WindowResolution::new(1280.0, 720.0),
-                present_mode: PresentMode::AutoVsync,
-                resizable: false,
+    let launch = match LaunchOptions::parse(env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(LaunchError::Help) => {
+            println!("{USAGE}");
+            process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let mut app = App::new();
+    app.insert_resource(ClearColor(Color::srgb(0.05, 0.05, 0.14)))
+        .add_plugins(default_plugins(&launch))
+        .insert_resource(launch)
+        .add_systems(
+            Update,
+            (
+                toggle_fullscreen_shortcut,
+                set_window_icon,
+                update_window_title,
+            ),
+        );
+    // After the default plugins, so a failed Steam connection is logged.
+    #[cfg(feature = "steam")]
+    app.insert_resource(sforce::game::steam::platform());
+    app.add_plugins(GamePlugin).run();
+}
+
+fn default_plugins(launch: &LaunchOptions) -> PluginGroupBuilder {
+    let size = launch.resolution.unwrap_or(UVec2::new(1280, 720)).as_vec2();
+    let plugins = DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: TITLE.into(),
+            name: Some(APP_ID.into()),
+            resolution: WindowResolution::new(size.x, size.y),
+            mode: WindowMode::Windowed,
+            present_mode: PresentMode::AutoVsync,
+            resizable: true,
+            resize_constraints: WindowResizeConstraints {
+                min_width: 640.0,
+                min_height: 360.0,
                 ..default()
-            }),
+            },
             ..default()
-        }))
-        .add_systems(Update, toggle_fullscreen_shortcut)
-        .add_plugins(GamePlugin)
-        .run();
+        }),
+        ..default()
+    });
+    #[cfg(feature = "kira")]
+    let plugins = plugins.disable::<bevy::audio::AudioPlugin>();
+    plugins
 }
 
-fn toggle_fullscreen_shortcut(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut windows: Query<&mut Window, With<PrimaryWindow>>,
-) {
+fn toggle_fullscreen_shortcut(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<GameSettings>) {
     let ctrl_pressed = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
-    if !ctrl_pressed || !keys.just_pressed(KeyCode::Enter) {
+    if ctrl_pressed && keys.just_pressed(KeyCode::Enter) {
+        settings.fullscreen = !settings.fullscreen;
+    }
+}
+
+// Winit only exposes the icon on its own window, so this reaches past Bevy's `Window`
+// and keeps trying each frame until winit has created it.
+fn set_window_icon(
+    mut done: Local<bool>,
+    windows: NonSend<WinitWindows>,
+    primary: Query<Entity, With<PrimaryWindow>>,
+) {
+    if *done {
+        return;
+    }
+    let Some(window) = primary
+        .get_single()
+        .ok()
+        .and_then(|entity| windows.get_window(entity))
+    else {
         return;
+    };
+    let icon = image::load_from_memory(ICON)
+        .map_err(|err| err.to_string())
+        .and_then(|image| {
+            let image = image.into_rgba8();
+            let (width, height) = image.dimensions();
+            Icon::from_rgba(image.into_raw(), width, height).map_err(|err| err.to_string())
+        });
+    *done = true;
+    match icon {
+        Ok(icon) => window.set_window_icon(Some(icon)),
+        Err(err) => warn!("Failed to set the window icon: {err}"),
     }
+}
 
-    if let Ok(mut window) = windows.get_single_mut() {
-        window.mode = if window.mode == WindowMode::Windowed {
-            WindowMode::BorderlessFullscreen
+fn update_window_title(
+    settings: Res<GameSettings>,
+    in_run: Option<Res<State<InRun>>>,
+    state: Res<State<AppState>>,
+    director: Res<WaveDirector>,
+    score: Res<ScoreBoard>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let title = if settings.title_status && in_run.is_some() {
+        let paused = if *state.get() == AppState::Paused {
+            " (paused)"
         } else {
-            WindowMode::Windowed
+            ""
         };
+        format!(
+            "{TITLE} - Level {} - {} pts{paused}",
+            director.level_index + 1,
+            score.score
+        )
+    } else {
+        TITLE.to_string()
+    };
+    if window.title != title {
+        window.title = title;
     }
 }
@@ -1 +1,3 @@
 //! Utility helpers will live here in later phases (math helpers, timers, rng wrappers).
+
+pub mod rng;
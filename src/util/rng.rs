@@ -0,0 +1,55 @@
+use std::f32::consts::TAU;
+
+use bevy::math::Vec2;
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+
+    // The seed the generator started from, for reproducing a session.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    pub fn signed(&mut self) -> f32 {
+        self.range(-1.0, 1.0)
+    }
+
+    pub fn unit_vector(&mut self) -> Vec2 {
+        Vec2::from_angle(self.next_f32() * TAU)
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(0x5f0c_e000)
+    }
+}
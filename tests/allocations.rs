@@ -0,0 +1,106 @@
+mod common;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use bevy::{ecs::schedule::ExecutorKind, prelude::*};
+use common::{enemies, spawn_enemy, start_default_run};
+use sforce::{
+    game::{
+        collisions::projectile_enemy_collisions,
+        enemies::EnemyKind,
+        weapons::{PlayerFireEvent, Projectile},
+    },
+    headless::run_fixed_updates,
+};
+
+// Tallies allocations made on a thread while it has counting switched on, so the
+// asset and render threads don't add noise.
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn note_allocation() {
+    if COUNTING.try_with(Cell::get).unwrap_or(false) {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        note_allocation();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        note_allocation();
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        note_allocation();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations(run: impl FnOnce()) -> usize {
+    ALLOCATIONS.with(|count| count.set(0));
+    COUNTING.with(|counting| counting.set(true));
+    run();
+    COUNTING.with(|counting| counting.set(false));
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn collision_pass_allocates_nothing_once_its_buffers_are_sized() {
+    let mut app = start_default_run();
+    for column in 0..8 {
+        spawn_enemy(
+            &mut app,
+            EnemyKind::Grunt,
+            Vec2::new(-280.0 + 80.0 * column as f32, 250.0),
+        );
+    }
+    // A screenful of shots well clear of the enemies, so every tick tests each pair.
+    for column in 0..32 {
+        app.world_mut().send_event(PlayerFireEvent {
+            origin: Vec2::new(-310.0 + 20.0 * column as f32, -150.0),
+            velocity: Vec2::ZERO,
+            size: Vec2::splat(8.0),
+            lifetime: 10.0,
+        });
+    }
+    run_fixed_updates(&mut app, 3);
+    assert_eq!(enemies(&mut app).len(), 8);
+    let shots = app
+        .world_mut()
+        .query_filtered::<(), With<Projectile>>()
+        .iter(app.world())
+        .count();
+    assert_eq!(shots, 32);
+
+    // On its own schedule, so only the collision pass is measured.
+    let mut collisions = Schedule::default();
+    collisions
+        .set_executor_kind(ExecutorKind::SingleThreaded)
+        .add_systems(projectile_enemy_collisions);
+    collisions.run(app.world_mut());
+    let allocations = count_allocations(|| {
+        for _ in 0..10 {
+            collisions.run(app.world_mut());
+        }
+    });
+    assert_eq!(allocations, 0);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use bevy::prelude::*;
+use common::start_default_run;
+use sforce::{
+    game::{
+        audio::{AudioCue, LowHealthAlarm, Sfx, VoiceTrigger},
+        player::PlayerStats,
+    },
+    headless::run_fixed_updates,
+};
+
+fn triggers_of(app: &App, sfx: Sfx) -> Vec<VoiceTrigger> {
+    app.world()
+        .resource::<Events<VoiceTrigger>>()
+        .iter_current_update_events()
+        .filter(|trigger| trigger.sfx == sfx)
+        .copied()
+        .collect()
+}
+
+fn alarm_active(app: &App) -> bool {
+    app.world().resource::<LowHealthAlarm>().active
+}
+
+#[test]
+fn limiter_plays_one_voice_for_a_burst_of_cues() {
+    let mut app = start_default_run();
+    for _ in 0..5 {
+        app.world_mut().send_event(AudioCue::new(Sfx::UiSelect));
+    }
+    run_fixed_updates(&mut app, 1);
+
+    let triggers = triggers_of(&app, Sfx::UiSelect);
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].voice, 0);
+}
+
+#[test]
+fn alarm_follows_critical_health() {
+    let mut app = start_default_run();
+    assert!(!alarm_active(&app));
+
+    app.world_mut().resource_mut::<PlayerStats>().health = 1;
+    run_fixed_updates(&mut app, 1);
+    assert!(alarm_active(&app));
+
+    app.world_mut().resource_mut::<PlayerStats>().health = 3;
+    run_fixed_updates(&mut app, 1);
+    assert!(!alarm_active(&app));
+}
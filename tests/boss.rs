@@ -0,0 +1,116 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{run_until, start_default_run, state};
+use sforce::{
+    game::{
+        AppState,
+        boss::{BOSS_PHASE_THRESHOLDS, BossPhase, BossState},
+        config::RunDifficulty,
+        enemies::{Enemy, EnemyKind},
+        spawn::{Storyboard, WaveDirector, jump_to_wave},
+        ui::ScoreBoard,
+        weapons::PlayerFireEvent,
+    },
+    headless::run_fixed_updates,
+};
+
+fn summon_boss(app: &mut App) -> Entity {
+    let threshold = app.world().resource::<BossState>().spawn_score;
+    app.world_mut().resource_mut::<ScoreBoard>().score = threshold;
+    run_fixed_updates(app, 2);
+    app.world()
+        .resource::<BossState>()
+        .entity
+        .expect("boss should have spawned")
+}
+
+fn shoot_down_boss(app: &mut App, boss: Entity) {
+    app.world_mut().get_mut::<Enemy>(boss).unwrap().health = 1;
+    let position = app.world().get::<Transform>(boss).unwrap().translation;
+    app.world_mut().send_event(PlayerFireEvent {
+        origin: position.truncate(),
+        velocity: Vec2::ZERO,
+        size: Vec2::splat(8.0),
+        lifetime: 1.0,
+    });
+    run_fixed_updates(app, 2);
+}
+
+fn set_boss_health(app: &mut App, boss: Entity, fraction: f32) {
+    let max_health = app.world().resource::<BossState>().max_health;
+    app.world_mut().get_mut::<Enemy>(boss).unwrap().health = (max_health * fraction) as i32;
+}
+
+#[test]
+fn boss_arrives_once_score_reaches_threshold() {
+    let mut app = start_default_run();
+    let threshold = app.world().resource::<BossState>().spawn_score;
+    app.world_mut().resource_mut::<ScoreBoard>().score = threshold - 1;
+    run_fixed_updates(&mut app, 2);
+    assert!(!app.world().resource::<BossState>().active);
+
+    let boss = summon_boss(&mut app);
+    assert!(app.world().resource::<BossState>().active);
+    assert!(app.world().resource::<WaveDirector>().boss_active);
+    assert_eq!(
+        app.world().get::<Enemy>(boss).map(|enemy| enemy.kind),
+        Some(EnemyKind::Boss)
+    );
+}
+
+#[test]
+fn boss_phases_follow_its_health() {
+    let mut app = start_default_run();
+    let boss = summon_boss(&mut app);
+    assert_eq!(app.world().resource::<BossState>().phase, BossPhase::Entry);
+
+    set_boss_health(&mut app, boss, BOSS_PHASE_THRESHOLDS[0] - 0.05);
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(app.world().resource::<BossState>().phase, BossPhase::Second);
+
+    set_boss_health(&mut app, boss, BOSS_PHASE_THRESHOLDS[1] - 0.05);
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(app.world().resource::<BossState>().phase, BossPhase::Final);
+}
+
+#[test]
+fn defeating_the_boss_completes_the_level() {
+    let mut app = start_default_run();
+    let boss = summon_boss(&mut app);
+    let score = app.world().resource::<ScoreBoard>().score;
+    shoot_down_boss(&mut app, boss);
+    assert!(!app.world().resource::<BossState>().active);
+    assert_eq!(
+        app.world().resource::<ScoreBoard>().score,
+        score + EnemyKind::Boss.score_value()
+    );
+
+    let completed = run_until(&mut app, 1200, |app| state(app) == AppState::LevelComplete);
+    assert!(completed, "the level never completed");
+    assert_eq!(app.world().resource::<WaveDirector>().level_index, 1);
+}
+
+#[test]
+fn defeating_the_final_boss_holds_for_a_new_game_plus_choice() {
+    let mut app = start_default_run();
+    let last_level = app.world().resource::<Storyboard>().level_count() - 1;
+    app.world_mut()
+        .resource_scope(|world, mut director: Mut<WaveDirector>| {
+            jump_to_wave(
+                &mut director,
+                world.resource::<Storyboard>(),
+                world.resource::<RunDifficulty>(),
+                last_level,
+                0,
+            );
+        });
+    let boss = summon_boss(&mut app);
+    shoot_down_boss(&mut app, boss);
+
+    let completed = run_until(&mut app, 1200, |app| state(app) == AppState::LevelComplete);
+    assert!(completed, "the final level never completed");
+    let director = app.world().resource::<WaveDirector>();
+    assert_eq!(director.level_index, last_level);
+    assert_eq!(director.loop_index, 0);
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{enemies, enemy_event, player_position, spawn_enemy, start_default_run, state};
+use sforce::{
+    game::{
+        AppState,
+        cheats::Cheats,
+        config::{Difficulty, GameSettings},
+        effects::ParticleEmitter,
+        enemies::{EnemyKind, SpawnEnemyEvent},
+        enemy_damage::DamageStage,
+        player::{Player, PlayerAura, PlayerDefense, PlayerStats},
+        powerups::{PowerUp, PowerUpKind, SpawnPowerUpEvent},
+        rules::RuleSet,
+        ui::ScoreBoard,
+        weapons::{EnemyFireEvent, EnemyProjectile, PlayerFireEvent},
+    },
+    headless::run_fixed_updates,
+};
+
+fn player_shot_at(position: Vec2) -> PlayerFireEvent {
+    PlayerFireEvent {
+        origin: position,
+        velocity: Vec2::ZERO,
+        size: Vec2::splat(8.0),
+        lifetime: 1.0,
+    }
+}
+
+fn enemy_shot_at(position: Vec2) -> EnemyFireEvent {
+    EnemyFireEvent {
+        origin: position,
+        velocity: Vec2::ZERO,
+        size: Vec2::splat(8.0),
+        color: Color::WHITE,
+        lifetime: 1.0,
+        damage: 1,
+    }
+}
+
+#[test]
+fn player_shot_destroys_enemy_and_scores() {
+    let mut app = start_default_run();
+    spawn_enemy(&mut app, EnemyKind::Grunt, Vec2::new(0.0, 200.0));
+    run_fixed_updates(&mut app, 2);
+    let spawned = enemies(&mut app);
+    assert_eq!(spawned.len(), 1);
+
+    let (_, _, position) = spawned[0];
+    app.world_mut().send_event(player_shot_at(position));
+    run_fixed_updates(&mut app, 2);
+    assert!(enemies(&mut app).is_empty());
+    assert_eq!(
+        app.world().resource::<ScoreBoard>().score,
+        EnemyKind::Grunt.score_value()
+    );
+}
+
+#[test]
+fn tanks_smoke_then_spark_as_their_health_runs_down() {
+    let mut app = start_default_run();
+    spawn_enemy(&mut app, EnemyKind::Tank, Vec2::new(0.0, 200.0));
+    run_fixed_updates(&mut app, 2);
+    let (tank, _, position) = enemies(&mut app)[0];
+    let shoot = |app: &mut App, shots: u32| {
+        for _ in 0..shots {
+            app.world_mut().send_event(player_shot_at(position));
+            run_fixed_updates(app, 2);
+        }
+    };
+    let wear = |app: &mut App| {
+        let stage = app.world().get::<DamageStage>(tank).copied();
+        let emitters = app
+            .world_mut()
+            .query::<(&Parent, &ParticleEmitter)>()
+            .iter(app.world())
+            .filter(|(parent, _)| parent.get() == tank)
+            .count();
+        (stage, emitters)
+    };
+
+    // Six hits of health: half gone starts the smoke, one left adds sparks.
+    shoot(&mut app, 2);
+    assert_eq!(wear(&mut app), (None, 0));
+    shoot(&mut app, 1);
+    assert_eq!(wear(&mut app), (Some(DamageStage::Damaged), 1));
+    shoot(&mut app, 2);
+    assert_eq!(wear(&mut app), (Some(DamageStage::Critical), 2));
+}
+
+#[test]
+fn enemy_shot_costs_hull_then_grants_invulnerability() {
+    let mut app = start_default_run();
+    let full = app.world().resource::<PlayerStats>().health;
+    let position = player_position(&mut app);
+
+    app.world_mut().send_event(enemy_shot_at(position));
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(app.world().resource::<PlayerStats>().health, full - 1);
+    let defense = app
+        .world_mut()
+        .query_filtered::<&PlayerDefense, With<Player>>()
+        .single(app.world());
+    assert!(defense.invulnerability > 0.0);
+
+    let position = player_position(&mut app);
+    app.world_mut().send_event(enemy_shot_at(position));
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(app.world().resource::<PlayerStats>().health, full - 1);
+}
+
+#[test]
+fn invincibility_pickup_wears_an_aura_instead_of_flickering() {
+    let mut app = start_default_run();
+    let looks = |app: &mut App| {
+        let ship = *app
+            .world_mut()
+            .query_filtered::<&Visibility, With<Player>>()
+            .single(app.world());
+        let aura = *app
+            .world_mut()
+            .query_filtered::<&Visibility, With<PlayerAura>>()
+            .single(app.world());
+        (ship, aura)
+    };
+    let position = player_position(&mut app);
+    app.world_mut().send_event(enemy_shot_at(position));
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(looks(&mut app).1, Visibility::Hidden);
+    run_fixed_updates(&mut app, 240);
+
+    let position = player_position(&mut app);
+    app.world_mut().send_event(SpawnPowerUpEvent {
+        position,
+        kind: PowerUpKind::Invincibility,
+    });
+    run_fixed_updates(&mut app, 3);
+    for _ in 0..20 {
+        assert_eq!(
+            looks(&mut app),
+            (Visibility::Inherited, Visibility::Inherited)
+        );
+        run_fixed_updates(&mut app, 1);
+    }
+}
+
+#[test]
+fn ramming_kill_scores_like_a_shot_down_enemy() {
+    let mut app = start_default_run();
+    let position = player_position(&mut app);
+    spawn_enemy(&mut app, EnemyKind::Grunt, position);
+    run_fixed_updates(&mut app, 3);
+    assert!(enemies(&mut app).is_empty());
+    assert_eq!(
+        app.world().resource::<ScoreBoard>().score,
+        EnemyKind::Grunt.score_value()
+    );
+}
+
+#[test]
+fn god_mode_ignores_enemy_shots() {
+    let mut app = start_default_run();
+    app.world_mut().resource_mut::<Cheats>().god_mode = true;
+    let full = app.world().resource::<PlayerStats>().health;
+    let position = player_position(&mut app);
+
+    app.world_mut().send_event(enemy_shot_at(position));
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(app.world().resource::<PlayerStats>().health, full);
+}
+
+#[test]
+fn losing_the_last_life_ends_the_run() {
+    let mut app = start_default_run();
+    {
+        let mut stats = app.world_mut().resource_mut::<PlayerStats>();
+        stats.lives = 1;
+        stats.health = 1;
+    }
+    let position = player_position(&mut app);
+    app.world_mut().send_event(enemy_shot_at(position));
+    run_fixed_updates(&mut app, 3);
+    assert_eq!(state(&app), AppState::GameOver);
+}
+
+#[test]
+fn no_powerups_rule_keeps_drops_off_the_field() {
+    let drops_after_kill = |rules: RuleSet| {
+        let mut app = start_default_run();
+        app.insert_resource(rules);
+        app.world_mut().send_event(SpawnEnemyEvent {
+            powerup: Some(PowerUpKind::Health),
+            ..enemy_event(EnemyKind::Grunt, Vec2::new(0.0, 200.0))
+        });
+        run_fixed_updates(&mut app, 2);
+        let (_, _, position) = enemies(&mut app)[0];
+        app.world_mut().send_event(player_shot_at(position));
+        run_fixed_updates(&mut app, 3);
+        app.world_mut()
+            .query_filtered::<(), With<PowerUp>>()
+            .iter(app.world())
+            .count()
+    };
+    assert_eq!(drops_after_kill(RuleSet::default()), 1);
+    assert_eq!(
+        drops_after_kill(RuleSet {
+            powerups: false,
+            ..default()
+        }),
+        0
+    );
+}
+
+#[test]
+fn hard_mode_kills_fire_one_aimed_revenge_shot() {
+    let revenge_shots = |difficulty| {
+        let mut app = start_default_run();
+        spawn_enemy(&mut app, EnemyKind::Grunt, Vec2::new(120.0, 200.0));
+        run_fixed_updates(&mut app, 2);
+        // Switched after the spawn so Hard's tougher hulls don't outlast one shot.
+        app.world_mut().resource_mut::<GameSettings>().difficulty = difficulty;
+        let (_, _, position) = enemies(&mut app)[0];
+        app.world_mut().send_event(player_shot_at(position));
+        // The kill, the revenge shot, and its projectile each take a tick to land.
+        run_fixed_updates(&mut app, 4);
+        assert!(enemies(&mut app).is_empty());
+
+        let target = player_position(&mut app);
+        app.world_mut()
+            .query::<&EnemyProjectile>()
+            .iter(app.world())
+            .map(|shot| {
+                shot.velocity
+                    .normalize()
+                    .dot((target - position).normalize())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert!(revenge_shots(Difficulty::Normal).is_empty());
+    let shots = revenge_shots(Difficulty::Hard);
+    assert_eq!(shots.len(), 1);
+    assert!(shots[0] > 0.99, "the revenge shot isn't aimed at the ship");
+}
@@ -0,0 +1,80 @@
+//! Helpers shared by the headless integration tests. Each test binary only uses some
+//! of them.
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use sforce::{
+    game::{
+        AppState,
+        config::LaunchOptions,
+        enemies::{Enemy, EnemyKind, MovementPattern, SpawnEnemyEvent},
+        player::Player,
+    },
+    headless::{DEFAULT_SEED, headless_app, run_fixed_updates},
+};
+
+// A headless game already through the title screen and one tick into a fresh run.
+pub fn start_run(seed: u64) -> App {
+    let mut app = headless_app(seed, LaunchOptions::default()).expect("assets load");
+    set_state(&mut app, AppState::Playing);
+    run_fixed_updates(&mut app, 1);
+    assert_eq!(state(&app), AppState::Playing);
+    app
+}
+
+pub fn start_default_run() -> App {
+    start_run(DEFAULT_SEED)
+}
+
+pub fn state(app: &App) -> AppState {
+    *app.world().resource::<State<AppState>>().get()
+}
+
+pub fn set_state(app: &mut App, state: AppState) {
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(state);
+}
+
+// Ticks until `done` holds, giving up after `max_ticks`. Returns whether it held.
+pub fn run_until(app: &mut App, max_ticks: u32, mut done: impl FnMut(&mut App) -> bool) -> bool {
+    for _ in 0..max_ticks {
+        if done(app) {
+            return true;
+        }
+        run_fixed_updates(app, 1);
+    }
+    done(app)
+}
+
+// A stationary enemy of `kind` at `position`, carrying nothing.
+pub fn enemy_event(kind: EnemyKind, position: Vec2) -> SpawnEnemyEvent {
+    SpawnEnemyEvent {
+        kind,
+        position,
+        movement: MovementPattern::Straight { speed: 0.0 },
+        powerup: None,
+        variant: None,
+        wave: None,
+    }
+}
+
+pub fn spawn_enemy(app: &mut App, kind: EnemyKind, position: Vec2) {
+    app.world_mut().send_event(enemy_event(kind, position));
+}
+
+pub fn enemies(app: &mut App) -> Vec<(Entity, EnemyKind, Vec2)> {
+    app.world_mut()
+        .query::<(Entity, &Enemy, &Transform)>()
+        .iter(app.world())
+        .map(|(entity, enemy, transform)| (entity, enemy.kind, transform.translation.truncate()))
+        .collect()
+}
+
+pub fn player_position(app: &mut App) -> Vec2 {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .translation
+        .truncate()
+}
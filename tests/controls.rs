@@ -0,0 +1,50 @@
+mod common;
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput, NativeKey},
+    },
+    prelude::*,
+};
+use common::start_default_run;
+use sforce::{
+    game::config::{GameSettings, InputAction, KeyBindings},
+    headless::run_fixed_updates,
+};
+
+// Presses and releases `key` through the input events, so it shows up as just pressed.
+fn tap(app: &mut App, key: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        app.world_mut().send_event(KeyboardInput {
+            key_code: key,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+        run_fixed_updates(app, 1);
+    }
+}
+
+fn muted(app: &App) -> bool {
+    app.world().resource::<GameSettings>().muted
+}
+
+#[test]
+fn mute_follows_its_binding() {
+    let mut app = start_default_run();
+    tap(&mut app, KeyCode::KeyM);
+    assert!(muted(&app));
+    tap(&mut app, KeyCode::KeyM);
+    assert!(!muted(&app));
+
+    // Taking M for fire swaps mute onto fire's old key.
+    app.world_mut()
+        .resource_mut::<KeyBindings>()
+        .rebind(InputAction::Fire, KeyCode::KeyM);
+    assert_eq!(app.world().resource::<KeyBindings>().mute, KeyCode::Space);
+    tap(&mut app, KeyCode::KeyM);
+    assert!(!muted(&app));
+    tap(&mut app, KeyCode::Space);
+    assert!(muted(&app));
+}
@@ -0,0 +1,294 @@
+mod common;
+
+use bevy::{
+    prelude::*,
+    render::camera::RenderTarget,
+    window::{PrimaryWindow, WindowResolution},
+};
+use common::{spawn_enemy, start_default_run};
+use sforce::{
+    game::{
+        background::{AmbientEvents, AmbientPiece, LevelTile, ScrollSpeed},
+        boss::BossEvent,
+        camera::{CameraMotion, CameraZoom, MainCamera, PlayfieldViewport},
+        config::{GameConfig, GameSettings, ScreenLayout},
+        effects::{ExplosionAnimation, ExplosionEvent, Wreck},
+        enemies::{EnemyCatalog, EnemyCatalogError, EnemyKind},
+        player::{BombEvent, Player},
+        spawn::{LevelBackground, Storyboard, WaveDirector},
+        states::AppState,
+        ui::SidePanel,
+    },
+    headless::run_fixed_updates,
+};
+
+#[test]
+fn explosion_flood_is_capped() {
+    let mut app = start_default_run();
+    for index in 0..200 {
+        app.world_mut().send_event(ExplosionEvent {
+            position: Vec2::new(index as f32 - 100.0, 0.0),
+            large: index % 10 == 0,
+        });
+    }
+    run_fixed_updates(&mut app, 1);
+    let live = app
+        .world_mut()
+        .query::<&ExplosionAnimation>()
+        .iter(app.world())
+        .count();
+    assert!(live > 0);
+    assert!(live <= 64, "{live} explosions still live");
+}
+
+#[test]
+fn bombed_tank_tumbles_as_a_wreck_before_it_blows() {
+    let mut app = start_default_run();
+    spawn_enemy(&mut app, EnemyKind::Tank, Vec2::new(0.0, 200.0));
+    run_fixed_updates(&mut app, 2);
+    app.world_mut().send_event(BombEvent {
+        position: Vec2::ZERO,
+    });
+    run_fixed_updates(&mut app, 2);
+    let wreck = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<Wreck>>()
+            .iter(app.world())
+            .map(|transform| transform.translation.truncate())
+            .next()
+    };
+
+    let start = wreck(&mut app).expect("tank left no wreck");
+    assert!(start.distance(Vec2::new(0.0, 200.0)) < 5.0);
+    run_fixed_updates(&mut app, 60);
+    let falling = wreck(&mut app).expect("wreck blew too soon");
+    assert!(falling.y < start.y);
+    run_fixed_updates(&mut app, 60);
+    assert_eq!(wreck(&mut app), None);
+}
+
+#[test]
+fn enemy_catalog_decides_how_each_kind_goes_down() {
+    let contents = std::fs::read_to_string("assets/enemies.ron").unwrap();
+    let missing = contents.replacen("\"tank\": (death: TumblingWreck),", "", 1);
+    assert!(matches!(
+        EnemyCatalog::from_ron(&missing),
+        Err(EnemyCatalogError::Invalid(problem)) if problem.starts_with("Tank")
+    ));
+
+    let wrecked_grunts = contents.replacen(
+        "\"grunt\": (death: Fizzle)",
+        "\"grunt\": (death: TumblingWreck)",
+        1,
+    );
+    let mut app = start_default_run();
+    app.world_mut()
+        .insert_resource(EnemyCatalog::from_ron(&wrecked_grunts).expect("valid catalog"));
+    spawn_enemy(&mut app, EnemyKind::Grunt, Vec2::new(0.0, 200.0));
+    run_fixed_updates(&mut app, 2);
+    app.world_mut().send_event(BombEvent {
+        position: Vec2::ZERO,
+    });
+    run_fixed_updates(&mut app, 2);
+    let wrecks = app
+        .world_mut()
+        .query_filtered::<(), With<Wreck>>()
+        .iter(app.world())
+        .count();
+    assert_eq!(wrecks, 1);
+}
+
+#[test]
+fn level_background_crossfades_on_level_change() {
+    let mut app = start_default_run();
+    let tiles = |app: &mut App| {
+        app.world_mut()
+            .query::<&LevelTile>()
+            .iter(app.world())
+            .map(|tile| (tile.level, tile.alpha, tile.leaving))
+            .collect::<Vec<_>>()
+    };
+    {
+        let mut storyboard = app.world_mut().resource_mut::<Storyboard>();
+        for index in 0..2 {
+            storyboard.level_mut(index).unwrap().background = Some(LevelBackground {
+                image: "images/backgrounds/hull_plating.png".to_string(),
+                speed: 30.0,
+            });
+        }
+    }
+    // A fade takes 1.5 seconds of 120 Hz ticks.
+    run_fixed_updates(&mut app, 200);
+    assert_eq!(tiles(&mut app), vec![(0, 1.0, false)]);
+
+    app.world_mut().resource_mut::<WaveDirector>().level_index = 1;
+    run_fixed_updates(&mut app, 60);
+    let fading = tiles(&mut app);
+    assert_eq!(fading.len(), 2, "both backgrounds should show mid-fade");
+    assert!(
+        fading
+            .iter()
+            .any(|&(level, _, leaving)| level == 0 && leaving)
+    );
+    assert!(
+        fading
+            .iter()
+            .any(|&(level, _, leaving)| level == 1 && !leaving)
+    );
+
+    run_fixed_updates(&mut app, 200);
+    assert_eq!(tiles(&mut app), vec![(1, 1.0, false)]);
+}
+
+#[test]
+fn ambient_set_pieces_drift_through_and_clear() {
+    let mut app = start_default_run();
+    let pieces = |app: &mut App| {
+        app.world_mut()
+            .query::<&AmbientPiece>()
+            .iter(app.world())
+            .count()
+    };
+    assert_eq!(pieces(&mut app), 0);
+
+    app.world_mut().resource_mut::<AmbientEvents>().countdown = 0.0;
+    run_fixed_updates(&mut app, 2);
+    assert!(pieces(&mut app) > 0, "a set piece should have spawned");
+    assert!(app.world().resource::<AmbientEvents>().countdown > 0.0);
+
+    // Carry every piece well past the edge it's heading for.
+    for (piece, mut transform) in app
+        .world_mut()
+        .query::<(&AmbientPiece, &mut Transform)>()
+        .iter_mut(app.world_mut())
+    {
+        transform.translation = (piece.velocity.normalize() * 5000.0).extend(-15.0);
+    }
+    run_fixed_updates(&mut app, 1);
+    assert_eq!(pieces(&mut app), 0);
+}
+
+#[test]
+fn scroll_speed_follows_difficulty_and_eases_off_between_levels() {
+    let mut app = start_default_run();
+    run_fixed_updates(&mut app, 2);
+    let speed = |app: &App| app.world().resource::<ScrollSpeed>().current;
+    let cruising = speed(&app);
+
+    app.world_mut().resource_mut::<WaveDirector>().difficulty += 1.0;
+    run_fixed_updates(&mut app, 240);
+    let hard = speed(&app);
+    assert!(
+        hard > cruising + 0.4,
+        "{hard} should be well above {cruising}"
+    );
+
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::LevelComplete);
+    run_fixed_updates(&mut app, 480);
+    assert!(speed(&app) < 0.5, "stars should drift between levels");
+}
+
+#[test]
+fn bomb_kicks_the_camera_unless_motion_is_reduced() {
+    let mut app = start_default_run();
+    let camera = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<MainCamera>>()
+            .single(app.world())
+            .translation
+    };
+    let bomb = |app: &mut App| {
+        let position = app
+            .world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .single(app.world())
+            .translation
+            .truncate();
+        app.world_mut().send_event(BombEvent { position });
+        run_fixed_updates(app, 1);
+    };
+
+    bomb(&mut app);
+    assert!(app.world().resource::<CameraMotion>().kick.y < 0.0);
+    assert_ne!(camera(&mut app), Vec3::ZERO);
+
+    app.world_mut().resource_mut::<GameSettings>().reduce_motion = true;
+    bomb(&mut app);
+    let held = camera(&mut app);
+    assert_eq!((held.x, held.y), (0.0, 0.0));
+}
+
+#[test]
+fn boss_entrance_zooms_out_and_settles_back() {
+    let mut app = start_default_run();
+    let scale = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&OrthographicProjection, With<MainCamera>>()
+            .single(app.world())
+            .scale
+    };
+    app.world_mut().send_event(BossEvent::Spawned);
+    run_fixed_updates(&mut app, 60);
+    assert!(scale(&mut app) > 1.05, "the view should pull back");
+
+    run_fixed_updates(&mut app, 600);
+    assert_eq!(scale(&mut app), 1.0);
+    assert_eq!(app.world().resource::<CameraZoom>().scale, 1.0);
+}
+
+#[test]
+fn screen_layouts_fit_an_ultrawide_window() {
+    let mut app = start_default_run();
+    // Fullscreen, so the window size preset leaves the resolution alone.
+    app.world_mut().resource_mut::<GameSettings>().fullscreen = true;
+    app.world_mut().spawn((
+        Window {
+            resolution: WindowResolution::new(2520.0, 1080.0),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+    let layout = |app: &mut App, layout| {
+        app.world_mut().resource_mut::<GameSettings>().screen_layout = layout;
+        run_fixed_updates(app, 2);
+        let width = app.world().resource::<GameConfig>().logical_width;
+        let viewport = *app.world().resource::<PlayfieldViewport>();
+        let drawn_to_image = matches!(
+            app.world_mut()
+                .query_filtered::<&Camera, With<MainCamera>>()
+                .single(app.world())
+                .target,
+            RenderTarget::Image(_)
+        );
+        let panels = app
+            .world_mut()
+            .query_filtered::<&Style, With<SidePanel>>()
+            .iter(app.world())
+            .filter(|style| style.display != Display::None)
+            .count();
+        (
+            width,
+            viewport.position,
+            viewport.size,
+            drawn_to_image,
+            panels,
+        )
+    };
+
+    // 21:9 is as wide as the playfield stretches.
+    assert_eq!(
+        layout(&mut app, ScreenLayout::Fill),
+        (1680.0, UVec2::ZERO, UVec2::new(2520, 1080), false, 0)
+    );
+    assert_eq!(
+        layout(&mut app, ScreenLayout::Fixed),
+        (1280.0, UVec2::new(300, 0), UVec2::new(1920, 1080), false, 2)
+    );
+    // On its side the 3:4 playfield is 1440 across and 1080 down.
+    assert_eq!(
+        layout(&mut app, ScreenLayout::Tate),
+        (540.0, UVec2::new(540, 0), UVec2::new(1440, 1080), true, 0)
+    );
+}
@@ -0,0 +1,87 @@
+use std::{env, fs, process};
+
+use bevy::prelude::*;
+use sforce::{
+    game::config::{GameSettings, LaunchError, LaunchOptions},
+    headless::{DEFAULT_SEED, headless_app, run_fixed_updates},
+};
+
+fn parse(args: &[&str]) -> Result<LaunchOptions, LaunchError> {
+    LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn resolution_needs_two_positive_sizes() {
+    let launch = parse(&["--resolution", "1920x1080"]).expect("valid resolution");
+    assert_eq!(launch.resolution, Some(UVec2::new(1920, 1080)));
+
+    for raw in [
+        "1920",
+        "1920x",
+        "x1080",
+        "0x720",
+        "1280x0",
+        "widextall",
+        "1920x1080x2",
+    ] {
+        assert!(
+            matches!(parse(&["--resolution", raw]), Err(LaunchError::Invalid(_))),
+            "accepted resolution '{raw}'"
+        );
+    }
+}
+
+#[test]
+fn flags_that_take_a_value_reject_a_missing_one() {
+    for flag in ["--resolution", "--storyboard", "--level", "--data-dir"] {
+        assert_eq!(
+            parse(&["--mute", flag]).unwrap_err(),
+            LaunchError::Invalid(format!("{flag} expects a value"))
+        );
+    }
+    assert!(matches!(
+        parse(&["--level", "0"]),
+        Err(LaunchError::Invalid(_))
+    ));
+    assert!(matches!(parse(&["--fast"]), Err(LaunchError::Invalid(_))));
+    assert_eq!(parse(&["--help"]).unwrap_err(), LaunchError::Help);
+}
+
+#[test]
+fn launch_flags_stay_out_of_the_saved_settings() {
+    let dir = env::temp_dir().join(format!("sforce-launch-{}", process::id()));
+    let path = dir.join("settings.json");
+    fs::create_dir_all(&dir).expect("create the data directory");
+    let saved = GameSettings {
+        muted: false,
+        fullscreen: true,
+        story_dialogue: true,
+        ..default()
+    };
+    fs::write(&path, serde_json::to_string(&saved).unwrap()).expect("write settings.json");
+
+    let launch = LaunchOptions {
+        mute: true,
+        windowed: true,
+        data_dir: Some(dir.clone()),
+        ..default()
+    };
+    let mut app = headless_app(DEFAULT_SEED, launch).expect("assets load");
+    let settings = *app.world().resource::<GameSettings>();
+    assert!(settings.muted);
+    assert!(!settings.fullscreen);
+    assert!(!settings.story_dialogue);
+
+    // Any change saves the settings once they settle, which writes the flags back out.
+    app.world_mut().resource_mut::<GameSettings>().music_volume = 0.25;
+    run_fixed_updates(&mut app, 120);
+    let written: GameSettings =
+        serde_json::from_str(&fs::read_to_string(&path).expect("read settings.json")).unwrap();
+    assert_eq!(written.music_volume, 0.25);
+    assert!(!written.muted);
+    assert!(written.fullscreen);
+    assert!(written.story_dialogue);
+
+    drop(app);
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bevy::prelude::*;
+use common::start_default_run;
+use sforce::{
+    game::{
+        cheats::Cheats,
+        effects::ExplosionAnimation,
+        pool::EntityPool,
+        weapons::{PlayerFireEvent, Projectile},
+    },
+    headless::run_fixed_updates,
+};
+
+fn short_shot() -> PlayerFireEvent {
+    PlayerFireEvent {
+        origin: Vec2::new(0.0, -100.0),
+        velocity: Vec2::ZERO,
+        size: Vec2::splat(8.0),
+        lifetime: 0.05,
+    }
+}
+
+fn live_shots(app: &mut App) -> Vec<Entity> {
+    app.world_mut()
+        .query_filtered::<Entity, With<Projectile>>()
+        .iter(app.world())
+        .collect()
+}
+
+#[test]
+fn expired_shots_are_reused() {
+    let mut app = start_default_run();
+    app.world_mut().send_event(short_shot());
+    run_fixed_updates(&mut app, 2);
+    let fired = live_shots(&mut app);
+    assert_eq!(fired.len(), 1);
+
+    run_fixed_updates(&mut app, 10);
+    assert!(live_shots(&mut app).is_empty());
+    assert_eq!(
+        app.world()
+            .resource::<EntityPool<Projectile>>()
+            .free_count(),
+        1
+    );
+    let hidden = app.world().get::<Visibility>(fired[0]);
+    assert_eq!(hidden, Some(&Visibility::Hidden));
+
+    app.world_mut().send_event(short_shot());
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(live_shots(&mut app), fired);
+    let pool = app.world().resource::<EntityPool<Projectile>>();
+    assert_eq!((pool.created, pool.reused), (1, 1));
+    assert_eq!(
+        app.world().get::<Visibility>(fired[0]),
+        Some(&Visibility::Inherited)
+    );
+}
+
+// Shot and explosion spawns over the first twenty seconds of the default seed with fire
+// held down: (shots created, shots reused, explosions created, explosions reused).
+fn measure_held_fire() -> (u32, u32, u32, u32) {
+    let mut app = start_default_run();
+    let mut cheats = app.world_mut().resource_mut::<Cheats>();
+    cheats.god_mode = true;
+    cheats.max_weapon = true;
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::Space);
+    run_fixed_updates(&mut app, 2400);
+    let shots = app.world().resource::<EntityPool<Projectile>>();
+    let explosions = app.world().resource::<EntityPool<ExplosionAnimation>>();
+    (
+        shots.created,
+        shots.reused,
+        explosions.created,
+        explosions.reused,
+    )
+}
+
+#[test]
+fn held_fire_on_a_fixed_seed_mostly_reuses_pooled_entities() {
+    let counts = measure_held_fire();
+    // The same seed gives the same counts, so a change to pooling shows up as a change
+    // in these numbers rather than as noise.
+    assert_eq!(measure_held_fire(), counts);
+
+    let (shots_created, shots_reused, explosions_created, explosions_reused) = counts;
+    // Respawning would have made a fresh entity for every one of these shots.
+    assert!(shots_created + shots_reused > 500, "{counts:?}");
+    assert!(shots_reused >= shots_created * 10, "{counts:?}");
+    assert!(
+        explosions_created > 0 && explosions_reused > 0,
+        "{counts:?}"
+    );
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{run_until, set_state, start_default_run, state};
+use sforce::{
+    game::{
+        AppState,
+        config::{Difficulty, GameSettings, LaunchOptions, RunDifficulty},
+        player::{PlayerStats, PlayerWeaponState, WeaponMode},
+        savegame::{ResumeRunEvent, SAVE_FILE, SuspendRunEvent, SuspendedRun},
+        spawn::WaveDirector,
+        ui::ScoreBoard,
+    },
+    headless::{DEFAULT_SEED, headless_app, run_fixed_updates},
+};
+
+#[test]
+fn save_and_quit_resumes_where_the_run_left_off() {
+    let mut app = start_default_run();
+    app.world_mut().resource_mut::<GameSettings>().difficulty = Difficulty::Hard;
+    let started = run_until(&mut app, 1200, |app| {
+        app.world().resource::<WaveDirector>().wave_index == 1
+    });
+    assert!(started, "the first wave never started");
+    app.world_mut().resource_mut::<ScoreBoard>().score = 4321;
+    *app.world_mut().resource_mut::<PlayerStats>() = PlayerStats {
+        health: 2,
+        lives: 1,
+        ..default()
+    };
+    *app.world_mut().resource_mut::<PlayerWeaponState>() = PlayerWeaponState {
+        mode: WeaponMode::Spread3,
+        fire_rate_level: 2,
+        bombs: 1,
+    };
+    let (level, wave) = {
+        let director = app.world().resource::<WaveDirector>();
+        (director.level_index, director.wave_index)
+    };
+
+    set_state(&mut app, AppState::Paused);
+    run_fixed_updates(&mut app, 1);
+    app.world_mut().send_event(SuspendRunEvent);
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(state(&app), AppState::Title);
+
+    // A fresh game on the same save directory, as after quitting and relaunching.
+    let data_dir = app.world().resource::<LaunchOptions>().data_dir.clone();
+    let save_path = data_dir.clone().unwrap().join(SAVE_FILE);
+    assert!(save_path.exists());
+    let launch = LaunchOptions {
+        data_dir,
+        ..default()
+    };
+    let mut resumed = headless_app(DEFAULT_SEED, launch).expect("assets load");
+    assert_eq!(
+        resumed.world().resource::<GameSettings>().difficulty,
+        Difficulty::Hard
+    );
+    resumed
+        .world_mut()
+        .resource_mut::<GameSettings>()
+        .difficulty = Difficulty::Easy;
+    resumed.world_mut().send_event(ResumeRunEvent);
+    run_fixed_updates(&mut resumed, 3);
+    assert_eq!(state(&resumed), AppState::Playing);
+
+    let world = resumed.world();
+    assert_eq!(world.resource::<ScoreBoard>().score, 4321);
+    let director = world.resource::<WaveDirector>();
+    assert_eq!((director.level_index, director.wave_index), (level, wave));
+    let stats = world.resource::<PlayerStats>();
+    assert_eq!((stats.health, stats.lives), (2, 1));
+    let weapon = world.resource::<PlayerWeaponState>();
+    assert_eq!(weapon.mode, WeaponMode::Spread3);
+    assert_eq!((weapon.fire_rate_level, weapon.bombs), (2, 1));
+    assert_eq!(
+        world.resource::<RunDifficulty>().difficulty,
+        Difficulty::Hard
+    );
+
+    // Losing the resumed run throws the save away.
+    set_state(&mut resumed, AppState::GameOver);
+    run_fixed_updates(&mut resumed, 2);
+    assert!(resumed.world().resource::<SuspendedRun>().0.is_none());
+    assert!(!save_path.exists());
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{enemy_event, spawn_enemy, start_default_run};
+use sforce::{
+    game::{
+        enemies::{Enemy, EnemyKind, SpawnEnemyEvent},
+        player::Player,
+        ship_sprites::{ShipAnimation, ShipManifest, ShipManifestError, ShipRow, ShipSpriteId},
+        spawn::WaveDirector,
+        weapons::EnemyProjectile,
+    },
+    headless::run_fixed_updates,
+};
+
+#[test]
+fn shipped_manifest_matches_the_built_in_art() {
+    let manifest = ShipManifest::from_file("assets/ships.ron".as_ref()).expect("valid manifest");
+    let defaults = ShipManifest::default();
+    for id in ShipSpriteId::ALL {
+        let (shipped, built_in) = (manifest.spec(id), defaults.spec(id));
+        assert_eq!(shipped.sheet, built_in.sheet, "{id:?}");
+        assert_eq!(shipped.scale, built_in.scale, "{id:?}");
+        assert_eq!(shipped.frame_rate, built_in.frame_rate, "{id:?}");
+        assert_eq!(shipped.rows, built_in.rows, "{id:?}");
+        assert_eq!(shipped.variants, built_in.variants, "{id:?}");
+        assert_eq!(shipped.cues, built_in.cues, "{id:?}");
+    }
+}
+
+#[test]
+fn manifest_with_a_bad_entry_is_rejected() {
+    let contents = std::fs::read_to_string("assets/ships.ron").unwrap();
+    let broken = contents.replacen("scale: 5.5", "scale: 0.0", 1);
+    assert!(matches!(
+        ShipManifest::from_ron(&broken),
+        Err(ShipManifestError::Invalid(problem)) if problem.starts_with("Boss")
+    ));
+
+    let missing = contents.replacen("Tank:", "Tonk:", 1);
+    assert!(ShipManifest::from_ron(&missing).is_err());
+}
+
+#[test]
+fn ship_whose_sheet_fails_to_load_goes_back_to_its_built_in_entry() {
+    let contents = std::fs::read_to_string("assets/ships.ron").unwrap();
+    let renamed = contents.replacen("images/tinyShip20.png", "images/missing.png", 1);
+    let mut manifest = ShipManifest::from_ron(&renamed).expect("valid manifest");
+    assert_eq!(
+        manifest.spec(ShipSpriteId::Boss).sheet,
+        "images/missing.png"
+    );
+
+    manifest.restore_built_in(ShipSpriteId::Boss);
+    assert_eq!(
+        manifest.spec(ShipSpriteId::Boss).sheet,
+        "images/tinyShip20.png"
+    );
+}
+
+fn player_row(app: &mut App) -> ShipRow {
+    app.world_mut()
+        .query_filtered::<&ShipAnimation, With<Player>>()
+        .single(app.world())
+        .row
+}
+
+fn hold(app: &mut App, key: Option<KeyCode>) {
+    let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keys.release_all();
+    if let Some(key) = key {
+        keys.press(key);
+    }
+}
+
+#[test]
+fn player_banks_while_strafing_and_levels_out() {
+    let mut app = start_default_run();
+    assert_eq!(player_row(&mut app), ShipRow::Idle);
+
+    hold(&mut app, Some(KeyCode::ArrowLeft));
+    run_fixed_updates(&mut app, 4);
+    assert_eq!(player_row(&mut app), ShipRow::BankLeft);
+
+    hold(&mut app, Some(KeyCode::ArrowRight));
+    run_fixed_updates(&mut app, 4);
+    assert_eq!(player_row(&mut app), ShipRow::BankRight);
+
+    hold(&mut app, None);
+    run_fixed_updates(&mut app, 4);
+    assert_eq!(player_row(&mut app), ShipRow::Idle);
+}
+
+// (variant, atlas index) for each grunt spawned at x, in order.
+fn spawn_grunts(app: &mut App, variants: &[(f32, Option<&str>)]) -> Vec<(Option<String>, usize)> {
+    for &(x, variant) in variants {
+        app.world_mut().send_event(SpawnEnemyEvent {
+            variant: variant.map(str::to_string),
+            ..enemy_event(EnemyKind::Grunt, Vec2::new(x, 200.0))
+        });
+    }
+    run_fixed_updates(app, 1);
+    let mut query = app
+        .world_mut()
+        .query_filtered::<(&Transform, &ShipAnimation, &TextureAtlas), With<Enemy>>();
+    variants
+        .iter()
+        .map(|&(x, _)| {
+            let (_, animation, atlas) = query
+                .iter(app.world())
+                .find(|(transform, ..)| transform.translation.x == x)
+                .expect("grunt spawned");
+            (animation.variant.clone(), atlas.index)
+        })
+        .collect()
+}
+
+#[test]
+fn enemy_variants_wear_their_recoloured_frames() {
+    let mut app = start_default_run();
+    let spawned = spawn_grunts(
+        &mut app,
+        &[(-100.0, None), (0.0, Some("Elite")), (100.0, Some("Nope"))],
+    );
+    assert_eq!(spawned[0].0, None);
+    assert_eq!(spawned[1].0.as_deref(), Some("Elite"));
+    assert_ne!(spawned[1].1, spawned[0].1);
+    // An unknown name falls back to the ship's usual colours.
+    assert_eq!(spawned[2], spawned[0]);
+
+    // The last level's grunts turn veteran unless a wave asks for elites.
+    app.world_mut().resource_mut::<WaveDirector>().level_index = 2;
+    let spawned = spawn_grunts(&mut app, &[(-200.0, None), (200.0, Some("Elite"))]);
+    assert_eq!(spawned[0].0.as_deref(), Some("Veteran"));
+    assert_eq!(spawned[1].0.as_deref(), Some("Elite"));
+}
+
+#[test]
+fn armed_enemies_fire_on_their_muzzle_flash_frame() {
+    let mut app = start_default_run();
+    spawn_enemy(&mut app, EnemyKind::Sine, Vec2::new(0.0, 200.0));
+    let fired = (0..480).find(|_| {
+        run_fixed_updates(&mut app, 1);
+        app.world_mut()
+            .query::<&EnemyProjectile>()
+            .iter(app.world())
+            .next()
+            .is_some()
+    });
+    // The weapon's 2.6 second timer readies the shot; the sprite decides when it goes.
+    let fired = fired.expect("the sine fired");
+    assert!(fired >= 312, "fired after {fired} ticks");
+    let frame = app
+        .world_mut()
+        .query_filtered::<&ShipAnimation, With<Enemy>>()
+        .single(app.world())
+        .frame;
+    assert_eq!(frame, 2);
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::{set_state, start_default_run, state};
+use sforce::{
+    game::{
+        AppState,
+        career::CareerStats,
+        unlocks::{Unlock, Unlocks},
+    },
+    headless::run_fixed_updates,
+};
+
+#[test]
+fn career_milestone_unlocks_and_is_announced_on_the_title_screen() {
+    let mut app = start_default_run();
+    app.world_mut().resource_mut::<CareerStats>().games_played = 5;
+    run_fixed_updates(&mut app, 1);
+
+    let unlocks = app.world().resource::<Unlocks>();
+    assert!(unlocks.has(Unlock::MirrorMode));
+    assert!(!unlocks.has(Unlock::BossRush));
+    assert!(unlocks.unseen.contains(&Unlock::MirrorMode));
+
+    set_state(&mut app, AppState::Title);
+    run_fixed_updates(&mut app, 3);
+    assert_eq!(state(&app), AppState::NewUnlock);
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use common::{enemies, run_until, set_state, start_default_run, start_run, state};
+use sforce::{
+    game::{
+        AppState,
+        cheats::Cheats,
+        config::{Difficulty, GameRng, GameSettings, RunDifficulty},
+        dialogue::DialogueScene,
+        enemies::Enemy,
+        medals::{Medal, NoMissStreak},
+        powerups::DropsPowerUp,
+        savegame::{ResumeRunEvent, SuspendRunEvent},
+        spawn::{
+            BonusProgress, BonusStage, SecretCondition, Storyboard, WaveDefinition, WaveDirector,
+        },
+    },
+    headless::run_fixed_updates,
+};
+
+#[test]
+fn first_wave_spawns_once_its_delay_elapses() {
+    let mut app = start_default_run();
+    assert_eq!(app.world().resource::<WaveDirector>().wave_index, 0);
+    assert!(enemies(&mut app).is_empty());
+
+    let started = run_until(&mut app, 1200, |app| {
+        app.world().resource::<WaveDirector>().wave_index == 1
+    });
+    assert!(started, "the first wave never started");
+    run_fixed_updates(&mut app, 2);
+    assert!(!enemies(&mut app).is_empty());
+}
+
+#[test]
+fn pausing_holds_the_wave_timer() {
+    let mut app = start_default_run();
+    run_fixed_updates(&mut app, 60);
+    let elapsed = app.world().resource::<WaveDirector>().timer.elapsed();
+
+    set_state(&mut app, AppState::Paused);
+    run_fixed_updates(&mut app, 120);
+    assert_eq!(state(&app), AppState::Paused);
+    assert_eq!(
+        app.world().resource::<WaveDirector>().timer.elapsed(),
+        elapsed
+    );
+
+    set_state(&mut app, AppState::Playing);
+    run_fixed_updates(&mut app, 10);
+    assert!(app.world().resource::<WaveDirector>().timer.elapsed() > elapsed);
+}
+
+#[test]
+fn same_seed_replays_the_same_run() {
+    let replay = |seed| {
+        let mut app = start_run(seed);
+        run_until(&mut app, 1200, |app| {
+            app.world().resource::<WaveDirector>().wave_index == 1
+        });
+        run_fixed_updates(&mut app, 60);
+        let positions: Vec<_> = enemies(&mut app)
+            .into_iter()
+            .map(|(_, kind, position)| (kind, position))
+            .collect();
+        let next_roll = app.world_mut().resource_mut::<GameRng>().next_u32();
+        (positions, next_roll)
+    };
+    let first = replay(7);
+    assert!(!first.0.is_empty());
+    assert_eq!(first, replay(7));
+}
+
+#[test]
+fn resumed_run_keeps_its_difficulty_without_changing_the_settings() {
+    let mut app = start_default_run();
+    app.world_mut().resource_mut::<GameSettings>().difficulty = Difficulty::Hard;
+    run_fixed_updates(&mut app, 1);
+    assert_eq!(
+        app.world().resource::<RunDifficulty>().difficulty,
+        Difficulty::Hard
+    );
+
+    set_state(&mut app, AppState::Paused);
+    run_fixed_updates(&mut app, 1);
+    app.world_mut().send_event(SuspendRunEvent);
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(state(&app), AppState::Title);
+
+    app.world_mut().resource_mut::<GameSettings>().difficulty = Difficulty::Easy;
+    app.world_mut().send_event(ResumeRunEvent);
+    run_fixed_updates(&mut app, 3);
+    assert_eq!(state(&app), AppState::Playing);
+    assert_eq!(
+        app.world().resource::<RunDifficulty>().difficulty,
+        Difficulty::Hard
+    );
+    assert_eq!(
+        app.world().resource::<GameSettings>().difficulty,
+        Difficulty::Easy
+    );
+}
+
+#[test]
+fn mirror_mode_reflects_the_first_wave() {
+    let first_wave = |mirror| {
+        let mut app = start_default_run();
+        app.world_mut().resource_mut::<GameSettings>().mirror_mode = mirror;
+        run_until(&mut app, 1200, |app| {
+            app.world().resource::<WaveDirector>().wave_index == 1
+        });
+        run_fixed_updates(&mut app, 2);
+        let mut positions: Vec<_> = enemies(&mut app)
+            .into_iter()
+            .map(|(_, kind, position)| (kind, position.x.round() as i32, position.y.round() as i32))
+            .collect();
+        positions.sort_by_key(|&(_, x, y)| (x, y));
+        positions
+    };
+    let normal = first_wave(false);
+    let mut reflected: Vec<_> = first_wave(true)
+        .into_iter()
+        .map(|(kind, x, y)| (kind, -x, y))
+        .collect();
+    reflected.sort_by_key(|&(_, x, y)| (x, y));
+    assert!(!normal.is_empty());
+    assert_eq!(normal, reflected);
+}
+
+#[test]
+fn flawless_wave_drops_a_medal() {
+    let mut app = start_default_run();
+    app.world_mut().resource_mut::<Cheats>().god_mode = true;
+    let second_wave = run_until(&mut app, 6000, |app| {
+        app.world().resource::<WaveDirector>().wave_index == 2
+    });
+    assert!(second_wave, "the second wave never started");
+    run_fixed_updates(&mut app, 1);
+
+    assert_eq!(app.world().resource::<NoMissStreak>().streak, 1);
+    let values: Vec<_> = app
+        .world_mut()
+        .query::<&Medal>()
+        .iter(app.world())
+        .map(|medal| medal.value)
+        .collect();
+    assert_eq!(values, vec![500]);
+}
+
+#[test]
+fn found_secret_plays_the_bonus_stage_then_resumes_the_level() {
+    let mut app = start_default_run();
+    app.world_mut()
+        .resource_mut::<Storyboard>()
+        .level_mut(0)
+        .unwrap()
+        .bonus_stage = Some(BonusStage {
+        name: "Test".into(),
+        secret: SecretCondition::Score { score: 0 },
+        waves: vec![WaveDefinition::default()],
+    });
+
+    let entered = run_until(&mut app, 1200, |app| {
+        app.world().resource::<WaveDirector>().in_bonus_stage()
+    });
+    assert!(entered, "the bonus stage never opened");
+    assert_eq!(app.world().resource::<WaveDirector>().wave_index, 0);
+
+    let spawned = run_until(&mut app, 1200, |app| !enemies(app).is_empty());
+    assert!(spawned, "the bonus wave never spawned");
+    let carriers: Vec<_> = app
+        .world_mut()
+        .query_filtered::<Option<&DropsPowerUp>, With<Enemy>>()
+        .iter(app.world())
+        .map(|drop| drop.is_some())
+        .collect();
+    assert_eq!(carriers, vec![true; 3]);
+
+    let resumed = run_until(&mut app, 1200, |app| {
+        app.world().resource::<WaveDirector>().wave_index == 1
+    });
+    assert!(resumed, "the level never resumed");
+    assert_eq!(
+        app.world().resource::<WaveDirector>().bonus,
+        BonusProgress::Done
+    );
+}
+
+#[test]
+fn event_wave_plays_its_scene_once_and_can_be_skipped() {
+    let mut app = start_default_run();
+    app.world_mut()
+        .resource_mut::<GameSettings>()
+        .story_dialogue = true;
+
+    let opened = run_until(&mut app, 1200, |app| state(app) == AppState::Dialogue);
+    assert!(opened, "the first wave's scene never played");
+    let expected = app
+        .world()
+        .resource::<Storyboard>()
+        .dialogue("level1_intro")
+        .map_or(0, <[_]>::len);
+    assert!(expected > 0);
+    assert_eq!(
+        app.world().resource::<DialogueScene>().lines.len(),
+        expected
+    );
+
+    // The run holds still until the scene is dismissed.
+    let wave_index = app.world().resource::<WaveDirector>().wave_index;
+    run_fixed_updates(&mut app, 120);
+    assert_eq!(state(&app), AppState::Dialogue);
+    assert_eq!(
+        app.world().resource::<WaveDirector>().wave_index,
+        wave_index
+    );
+
+    app.world_mut().send_event(KeyboardInput {
+        key_code: KeyCode::Escape,
+        logical_key: Key::Escape,
+        state: ButtonState::Pressed,
+        window: Entity::PLACEHOLDER,
+    });
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(state(&app), AppState::Playing);
+    assert!(!app.world().contains_resource::<DialogueScene>());
+
+    // Coming back round to the first wave doesn't replay it.
+    app.world_mut().resource_mut::<WaveDirector>().wave_index = 0;
+    let replayed = run_until(&mut app, 1200, |app| {
+        app.world().resource::<WaveDirector>().wave_index == 1
+    });
+    assert!(replayed);
+    run_fixed_updates(&mut app, 2);
+    assert_eq!(state(&app), AppState::Playing);
+}
+
+#[cfg(feature = "hot-reload")]
+#[test]
+fn saved_storyboard_is_swapped_in_unless_it_is_broken() {
+    use sforce::{
+        game::config::LaunchOptions,
+        headless::{DEFAULT_SEED, headless_app},
+    };
+    use std::{
+        fs::{self, File},
+        time::{Duration, SystemTime},
+    };
+
+    let path = std::env::temp_dir().join(format!("sforce-storyboard-{}.json", std::process::id()));
+    let contents = fs::read_to_string("assets/storyboard.json").unwrap();
+    fs::write(&path, &contents).unwrap();
+    let save = |contents: &str, age: u64| {
+        fs::write(&path, contents).unwrap();
+        let stamp = SystemTime::now() + Duration::from_secs(age);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(stamp)
+            .unwrap();
+    };
+    let level_name = |app: &App| {
+        app.world()
+            .resource::<Storyboard>()
+            .level(0)
+            .unwrap()
+            .name
+            .clone()
+    };
+
+    let launch = LaunchOptions {
+        storyboard: Some(path.clone()),
+        ..default()
+    };
+    let mut app = headless_app(DEFAULT_SEED, launch).expect("assets load");
+    // A second of frames lets the watcher note the file before it changes.
+    run_fixed_updates(&mut app, 120);
+    assert_eq!(level_name(&app), "Default");
+
+    save(&contents.replacen("\"Default\"", "\"Reloaded\"", 1), 10);
+    run_fixed_updates(&mut app, 120);
+    assert_eq!(level_name(&app), "Reloaded");
+
+    save("{ \"levels\": ", 20);
+    run_fixed_updates(&mut app, 120);
+    assert_eq!(level_name(&app), "Reloaded");
+    fs::remove_file(&path).ok();
+}